@@ -0,0 +1,205 @@
+//! Expression benchmarks: parsing, visiting, and printing.
+//!
+//! The realistic corpora are the outermost expressions of the [benchmark sources](get_srcs). The
+//! pathological corpora stress deep nesting, wide argument lists, many small calls, and many
+//! literals.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use solar_bench::{get_srcs, Source};
+use solar_parse::{
+    ast::{self, visit::Visit, Arena},
+    interface::{source_map::FileName, Session},
+    Parser,
+};
+use std::{hint::black_box, ops::ControlFlow, path::PathBuf, time::Duration};
+
+struct Corpus {
+    name: String,
+    exprs: Vec<String>,
+}
+
+impl Corpus {
+    fn bytes(&self) -> usize {
+        self.exprs.iter().map(String::len).sum()
+    }
+}
+
+fn get_corpora() -> Vec<Corpus> {
+    let mut corpora = get_srcs()
+        .iter()
+        .filter(|s| !s.src.is_empty())
+        .map(|s| Corpus { name: s.name.to_string(), exprs: extract_exprs(s) })
+        .filter(|c| !c.exprs.is_empty())
+        .collect::<Vec<_>>();
+    corpora.extend(pathological());
+    corpora
+}
+
+/// Returns the source code of the outermost expressions in the given source that can be parsed as
+/// standalone expressions.
+fn extract_exprs(s: &Source) -> Vec<String> {
+    struct Collector<'a> {
+        sess: &'a Session,
+        exprs: Vec<String>,
+    }
+
+    impl<'ast> Visit<'ast> for Collector<'_> {
+        type BreakValue = ();
+
+        fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+            if let Ok(snippet) = self.sess.source_map().span_to_snippet(expr.span) {
+                self.exprs.push(snippet);
+            }
+            // Only the outermost expressions are collected.
+            ControlFlow::Continue(())
+        }
+    }
+
+    let sess = session();
+    let exprs = sess.enter(|| {
+        let arena = Arena::new();
+        let filename = PathBuf::from(s.path);
+        let mut parser =
+            Parser::from_source_code(&sess, &arena, filename.into(), s.src.into()).unwrap();
+        let ast = parser.parse_file().map_err(|e| e.emit()).unwrap();
+        let mut collector = Collector { sess: &sess, exprs: Vec::new() };
+        let _ = collector.visit_source_unit(&ast);
+        collector.exprs
+    });
+    exprs.into_iter().filter(|src| parse_exprs(&Arena::new(), [src.as_str()]).is_some()).collect()
+}
+
+fn pathological() -> Vec<Corpus> {
+    let n = 256;
+    let mk = |name: &str, src: String| Corpus { name: name.to_string(), exprs: vec![src] };
+    vec![
+        mk("deep_parens", format!("{}x{}", "(".repeat(n), ")".repeat(n))),
+        mk("deep_unary", format!("{}x", "-!~".repeat(n / 3))),
+        mk(
+            "deep_ternary",
+            format!("{}x", (0..n).map(|i| format!("c{i} ? a{i} : ")).collect::<String>()),
+        ),
+        mk("deep_index", format!("m{}", (0..n).map(|i| format!("[k{i}]")).collect::<String>())),
+        mk("long_binary", (0..n * 4).map(|i| format!("a{i}")).collect::<Vec<_>>().join(" + ")),
+        mk("mixed_precedence", {
+            let ops = ["+", "*", "<<", "&", "==", "||", "**", "-", "/", "&&"];
+            let terms = (0..n * 4).map(|i| format!("a{i} {} ", ops[i % ops.len()]));
+            format!("{}z", terms.collect::<String>())
+        }),
+        mk(
+            "wide_call",
+            format!("f({})", (0..n * 4).map(|i| format!("a{i}")).collect::<Vec<_>>().join(", ")),
+        ),
+        mk("many_small_calls", {
+            let calls = (0..n * 4).map(|i| match i % 4 {
+                0 => format!("f{i}()"),
+                1 => format!("f{i}(a)"),
+                2 => format!("f{i}(a, b)"),
+                _ => format!("x.f{i}(a, b, c)"),
+            });
+            format!("[{}]", calls.collect::<Vec<_>>().join(", "))
+        }),
+        mk("wide_named_call", {
+            let args = (0..n * 4).map(|i| format!("k{i}: a{i}")).collect::<Vec<_>>();
+            format!("f({{{}}})", args.join(", "))
+        }),
+        mk("many_literals", {
+            let lits = (0..n * 16).map(|i| match i % 4 {
+                0 => format!("{i}"),
+                1 => format!("0x{i:x}"),
+                2 => format!("{i}e{}", i % 8),
+                _ => format!("{i} ether"),
+            });
+            format!("[{}]", lits.collect::<Vec<_>>().join(", "))
+        }),
+        mk("many_strings", {
+            let lits = (0..n * 16).map(|i| format!("\"string {i}\""));
+            format!("abi.encodePacked({})", lits.collect::<Vec<_>>().join(", "))
+        }),
+    ]
+}
+
+fn expr_benches(c: &mut Criterion) {
+    let corpora = get_corpora();
+
+    let mut g = c.benchmark_group("expr");
+    g.warm_up_time(Duration::from_secs(3));
+    g.measurement_time(Duration::from_secs(10));
+    g.sample_size(20);
+    g.noise_threshold(0.05);
+
+    solar_parse::interface::enter(|| {
+        for corpus in &corpora {
+            let name = &corpus.name;
+            let srcs = || corpus.exprs.iter().map(String::as_str);
+            g.throughput(Throughput::Bytes(corpus.bytes() as u64));
+
+            g.bench_function(format!("{name}/parse"), |b| {
+                b.iter(|| black_box(parse_exprs(&Arena::new(), srcs()).unwrap().len()))
+            });
+
+            let arena = Arena::new();
+            let exprs = parse_exprs(&arena, srcs()).unwrap();
+            g.bench_function(format!("{name}/visit"), |b| {
+                b.iter(|| {
+                    let mut counter = NodeCounter(0);
+                    for &expr in &exprs {
+                        let _ = counter.visit_expr(expr);
+                    }
+                    black_box(counter.0)
+                })
+            });
+
+            g.bench_function(format!("{name}/round_trip"), |b| {
+                b.iter(|| {
+                    let printed = exprs.iter().map(|expr| expr.to_minified().unwrap());
+                    let printed = printed.collect::<Vec<_>>();
+                    let arena = Arena::new();
+                    black_box(
+                        parse_exprs(&arena, printed.iter().map(String::as_str)).unwrap().len(),
+                    )
+                })
+            });
+        }
+    });
+
+    g.finish();
+}
+
+/// Counts the expressions in a tree.
+struct NodeCounter(usize);
+
+impl<'ast> Visit<'ast> for NodeCounter {
+    type BreakValue = ();
+
+    fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        self.0 += 1;
+        self.walk_expr(expr)
+    }
+}
+
+/// Parses every source as a standalone expression. Returns `None` if any of them fails to parse.
+fn parse_exprs<'ast, 'a>(
+    arena: &'ast Arena,
+    srcs: impl IntoIterator<Item = &'a str>,
+) -> Option<Vec<&'ast ast::Expr<'ast>>> {
+    let sess = session();
+    sess.enter(|| {
+        let mut exprs = Vec::new();
+        for src in srcs {
+            let mut parser =
+                Parser::from_source_code(&sess, arena, FileName::Stdin, src.to_string()).ok()?;
+            let expr = parser.parse_standalone_expr().map_err(|e| e.cancel()).ok()?;
+            exprs.push(&*expr);
+        }
+        sess.dcx.has_errors().ok()?;
+        Some(exprs)
+    })
+}
+
+fn session() -> Session {
+    Session::builder().with_silent_emitter(None).single_threaded().build()
+}
+
+criterion_group!(benches, expr_benches);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+use super::{CallArgs, Expr, ExprKind, IndexKind};
+
+impl Expr<'_> {
+    /// Canonicalizes the expression in place, so that expressions that only differ in the order
+    /// of the operands of [commutative](super::BinOpKind::is_commutative) operators become
+    /// structurally equal, e.g. `a + b` and `b + a`.
+    ///
+    /// The operands are sorted by their span-free [`debug_tree`](Self::debug_tree)
+    /// representation. They are only reordered if both are [pure](Self::is_pure), since
+    /// reordering would otherwise change the order in which their side effects happen, like in
+    /// `f() + g()`. Expressions nested in types, like array sizes, are not canonicalized.
+    pub fn canonicalize(&mut self) {
+        match &mut self.kind {
+            ExprKind::Array(exprs) => exprs.iter_mut().for_each(|e| e.canonicalize()),
+            ExprKind::Assign(lhs, _, rhs) => {
+                lhs.canonicalize();
+                rhs.canonicalize();
+            }
+            ExprKind::Binary(lhs, op, rhs) => {
+                lhs.canonicalize();
+                rhs.canonicalize();
+                if op.kind.is_commutative()
+                    && lhs.is_pure()
+                    && rhs.is_pure()
+                    && sort_key(lhs) > sort_key(rhs)
+                {
+                    std::mem::swap(lhs, rhs);
+                }
+            }
+            ExprKind::Call(expr, args) => {
+                expr.canonicalize();
+                canonicalize_args(args);
+            }
+            ExprKind::CallOptions(expr, args) => {
+                expr.canonicalize();
+                args.iter_mut().for_each(|arg| arg.value.canonicalize());
+            }
+            ExprKind::Delete(expr) | ExprKind::Member(expr, _) | ExprKind::Unary(_, expr) => {
+                expr.canonicalize()
+            }
+            ExprKind::Index(expr, kind) => {
+                expr.canonicalize();
+                match kind {
+                    IndexKind::Index(index) => index.iter_mut().for_each(|e| e.canonicalize()),
+                    IndexKind::Range(start, end) => {
+                        start.iter_mut().chain(end).for_each(|e| e.canonicalize())
+                    }
+                }
+            }
+            ExprKind::Payable(args) => canonicalize_args(args),
+            ExprKind::Ternary(cond, true_, false_) => {
+                cond.canonicalize();
+                true_.canonicalize();
+                false_.canonicalize();
+            }
+            ExprKind::Tuple(exprs) => exprs.iter_mut().flatten().for_each(|e| e.canonicalize()),
+            ExprKind::Err(_)
+            | ExprKind::Ident(_)
+            | ExprKind::Lit(..)
+            | ExprKind::New(_)
+            | ExprKind::TypeCall(_)
+            | ExprKind::Type(_) => {}
+        }
+    }
+}
+
+fn canonicalize_args(args: &mut CallArgs<'_>) {
+    args.exprs_mut().for_each(|e| e.canonicalize());
+}
+
+fn sort_key(expr: &Expr<'_>) -> String {
+    format!("{:?}", expr.debug_tree())
+}
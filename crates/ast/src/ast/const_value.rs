@@ -0,0 +1,1347 @@
+use super::{BinOpKind, Expr, ExprKind, Lit, LitKind, StrKind, SubDenomination, UnOpKind};
+use alloy_primitives::{I256, U256};
+use num_bigint::{BigInt, Sign};
+use num_rational::BigRational;
+use solar_interface::Span;
+use std::{fmt, sync::Arc};
+
+/// The maximum number of bits of the numerator or denominator of a rational number produced by
+/// exponentiation.
+const MAX_RATIONAL_BITS: u64 = 4096;
+
+/// A compile-time constant value.
+///
+/// Values compare and hash by value and type: rationals are always in lowest terms, strings and
+/// bytes are compared by contents, and integers of different types, like `uint8(1)` and
+/// `uint256(1)`, are different values.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConstValue {
+    /// An integer of a specific integer type.
+    Int(ConstInt),
+    /// A boolean.
+    Bool(bool),
+    /// The contents of a string. Note that this may contain invalid UTF-8 sequences.
+    String(Arc<[u8]>),
+    /// A byte array.
+    Bytes(Arc<[u8]>),
+    /// A rational number that is not an integer.
+    ///
+    /// Rational values that evaluate to integers are represented as [`Int`](Self::Int).
+    Rational(BigRational),
+    /// The elements of an array literal. See [`Expr::as_constant_array`].
+    Array(Arc<[ConstValue]>),
+}
+
+impl fmt::Display for ConstValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int(int) => int.fmt(f),
+            Self::Bool(b) => b.fmt(f),
+            Self::String(s) => write!(f, "\"{}\"", s.escape_ascii()),
+            Self::Bytes(bytes) => write!(f, "hex\"{}\"", alloy_primitives::hex::encode(bytes)),
+            Self::Rational(r) => r.fmt(f),
+            Self::Array(values) => {
+                f.write_str("[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    value.fmt(f)?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+impl ConstValue {
+    /// Creates a new integer value of the given type.
+    ///
+    /// Returns an error if the value does not fit in the type.
+    pub fn int(value: BigInt, ty: IntType) -> Result<Self, ConstValueError> {
+        ConstInt::new(value, ty).map(Self::Int)
+    }
+
+    /// Creates a new `uint256` value.
+    pub fn uint256(value: U256) -> Self {
+        let value = BigInt::from_bytes_be(Sign::Plus, &value.to_be_bytes::<32>());
+        Self::Int(ConstInt { value, ty: IntType::UINT256 })
+    }
+
+    /// Creates a new `int256` value.
+    pub fn int256(value: I256) -> Self {
+        let mut value = BigInt::from_bytes_be(Sign::Plus, &value.into_raw().to_be_bytes::<32>());
+        if value > IntType::INT256.max() {
+            value -= modulus(256);
+        }
+        Self::Int(ConstInt { value, ty: IntType::INT256 })
+    }
+
+    /// Creates a new rational value.
+    ///
+    /// If the value is an integer, it is converted to a `uint256` if it is positive, or to an
+    /// `int256` otherwise. Returns an error if the value does not fit in either of these types.
+    pub fn rational(value: BigRational) -> Result<Self, ConstValueError> {
+        if !value.is_integer() {
+            return Ok(Self::Rational(value));
+        }
+        let value = value.to_integer();
+        let ty = if value.sign() == Sign::Minus { IntType::INT256 } else { IntType::UINT256 };
+        Self::int(value, ty)
+    }
+
+    /// Returns the description of the value's kind.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Int(_) => "integer",
+            Self::Bool(_) => "boolean",
+            Self::String(_) => "string",
+            Self::Bytes(_) => "bytes",
+            Self::Rational(_) => "rational",
+            Self::Array(_) => "array",
+        }
+    }
+
+    /// Returns the integer value, if this is an integer.
+    pub fn as_int(&self) -> Option<&ConstInt> {
+        match self {
+            Self::Int(int) => Some(int),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean value, if this is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Self::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a `uint256`, if this is a non-negative integer.
+    pub fn as_u256(&self) -> Option<U256> {
+        self.as_int()?.as_u256()
+    }
+
+    /// Returns the value as an `int256`, if this is an integer in the range of `int256`.
+    pub fn as_i256(&self) -> Option<I256> {
+        self.as_int()?.as_i256()
+    }
+
+    /// Applies the given unary operation to this value.
+    ///
+    /// Increments and decrements, both prefix and postfix, evaluate to the updated value.
+    pub fn unop(&self, op: UnOpKind) -> Result<Self, ConstValueError> {
+        match (op, self) {
+            (UnOpKind::Not, Self::Bool(b)) => Ok(Self::Bool(!b)),
+            (UnOpKind::Neg, Self::Rational(r)) => Ok(Self::Rational(-r)),
+            (_, Self::Int(int)) => int.unop(op).map(Self::Int),
+            _ => Err(ConstValueError::UnsupportedUnaryOp(op, self.description())),
+        }
+    }
+
+    /// Applies the given binary operation to this value.
+    pub fn binop(&self, r: &Self, op: BinOpKind) -> Result<Self, ConstValueError> {
+        use BinOpKind::*;
+
+        let l = self;
+        match (l, r) {
+            (Self::Int(l), Self::Int(r)) => l.binop(r, op),
+            (Self::Rational(_), Self::Int(_) | Self::Rational(_))
+            | (Self::Int(_), Self::Rational(_)) => {
+                rational_binop(&l.to_rational().unwrap(), &r.to_rational().unwrap(), op)
+            }
+            (Self::Bool(l), Self::Bool(r)) => match op {
+                And => Ok(Self::Bool(*l && *r)),
+                Or => Ok(Self::Bool(*l || *r)),
+                Eq => Ok(Self::Bool(l == r)),
+                Ne => Ok(Self::Bool(l != r)),
+                _ => Err(ConstValueError::UnsupportedBinaryOp(op, self.description())),
+            },
+            (Self::String(l), Self::String(r)) | (Self::Bytes(l), Self::Bytes(r)) => match op {
+                Eq => Ok(Self::Bool(l == r)),
+                Ne => Ok(Self::Bool(l != r)),
+                _ => Err(ConstValueError::UnsupportedBinaryOp(op, self.description())),
+            },
+            _ => Err(ConstValueError::TypeMismatch(l.description(), r.description())),
+        }
+    }
+
+    fn to_rational(&self) -> Option<BigRational> {
+        match self {
+            Self::Int(int) => Some(BigRational::from_integer(int.value.clone())),
+            Self::Rational(r) => Some(r.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Lit {
+    /// Converts the literal to its constant value, for example to use it as the key of a map of
+    /// constants.
+    ///
+    /// The value is canonical, so literals that are spelled differently but have the same value
+    /// are equal and hash the same: `1`, `0x01`, `1 wei`, and `10e-1` are all the `uint256` one,
+    /// and `"a"`, `'a'`, `"\x61"`, and `unicode"a"` are all the string `a`. Hex string
+    /// literals are [bytes](ConstValue::Bytes) and never equal to string literals, like in
+    /// [`Expr::const_eval`].
+    ///
+    /// `sub` is the sub-denomination following the literal, if any. The parser has already
+    /// applied it to the value of the literal, so it is only checked to be on a number.
+    ///
+    /// Returns `None` if the literal has no constant value, like address literals and literals
+    /// that failed to parse, or if it has a sub-denomination but is not a number.
+    pub fn to_const_value(&self, sub: Option<SubDenomination>) -> Option<ConstValue> {
+        if sub.is_some() && !matches!(self.kind, LitKind::Number(_) | LitKind::Rational(_)) {
+            return None;
+        }
+        lit_value(self)?.into_const_value()
+    }
+}
+
+impl Expr<'_> {
+    /// Evaluates the expression at compile time, if it only consists of literals, parentheses,
+    /// operators, and conditionals: `1 - 1`, `2 ** 8 > 255 ? 1 ether : 0`.
+    ///
+    /// Like in Solidity, number literals are evaluated with arbitrary precision, so intermediate
+    /// values are not bounded and `2 ** 256 - 1` is a valid constant. Only the final value is
+    /// converted with [`ConstValue::rational`]. Sub-denominations are applied, so `1 gwei`
+    /// evaluates to `1000000000`.
+    ///
+    /// Returns `None` if the expression is not constant, for example because it references an
+    /// identifier, or if its evaluation fails, for example because of a division by zero.
+    pub fn const_eval(&self) -> Option<ConstValue> {
+        eval(self)?.into_const_value()
+    }
+
+    /// Evaluates the expression at compile time, and returns its value if it is a non-negative
+    /// integer that fits in `uint256`, like an array size.
+    ///
+    /// See [`const_eval`](Self::const_eval) for the supported expressions.
+    pub fn try_into_int_const(&self) -> Result<U256, ConstError> {
+        let err = |kind| ConstError { span: self.span, kind };
+        match eval(self).ok_or_else(|| err(ConstErrorKind::NotConstant))? {
+            Value::Num(n) if n.is_integer() => {
+                let n = n.to_integer();
+                if n.sign() == Sign::Minus {
+                    return Err(err(ConstErrorKind::Negative(n)));
+                }
+                U256::try_from_be_slice(&n.to_bytes_be().1)
+                    .ok_or_else(|| err(ConstErrorKind::TooLarge))
+            }
+            value => Err(err(ConstErrorKind::Mismatch {
+                expected: "integer",
+                found: value.description(),
+            })),
+        }
+    }
+
+    /// Evaluates the expression at compile time, and returns its value if it is a boolean.
+    ///
+    /// See [`const_eval`](Self::const_eval) for the supported expressions.
+    pub fn try_into_bool_const(&self) -> Result<bool, ConstError> {
+        let err = |kind| ConstError { span: self.span, kind };
+        match eval(self).ok_or_else(|| err(ConstErrorKind::NotConstant))? {
+            Value::Bool(b) => Ok(b),
+            value => Err(err(ConstErrorKind::Mismatch {
+                expected: "boolean",
+                found: value.description(),
+            })),
+        }
+    }
+
+    /// Returns the set of constant values that the expression can evaluate to.
+    ///
+    /// This is the value of [`const_eval`](Self::const_eval), or the values of both branches of a
+    /// conditional whose condition is not constant but whose branches are: `c ? 1 : 2` can
+    /// evaluate to `1` or `2`.
+    ///
+    /// Returns `None` if any of the values is not constant.
+    pub fn const_value_set(&self) -> Option<ValueSet> {
+        let expr = self.strip_parens();
+        if let ExprKind::Ternary(cond, true_, false_) = &expr.kind {
+            if eval(cond).is_none() {
+                let mut set = true_.const_value_set()?;
+                set.extend(false_.const_value_set()?);
+                return Some(set);
+            }
+        }
+        expr.const_eval().map(ValueSet::from)
+    }
+
+    /// Evaluates the elements of an array literal at compile time, if they are all constant:
+    /// `[1, 2, 3]`, `[1 ether, 2 ** 8]`.
+    ///
+    /// Each element is evaluated on its own with [`const_eval`](Self::const_eval), so integer
+    /// elements are not converted to the common type of the array. Elements that are array
+    /// literals themselves are evaluated recursively, into [arrays](ConstValue::Array):
+    /// `[[1, 2], [3, 4]]` evaluates to two arrays of two integers.
+    ///
+    /// Returns `None` if the expression is not an array literal, ignoring parentheses, or if any
+    /// of its elements is not constant.
+    pub fn as_constant_array(&self) -> Option<Vec<ConstValue>> {
+        let ExprKind::Array(exprs) = &self.strip_parens().kind else { return None };
+        exprs
+            .iter()
+            .map(|expr| match expr.as_constant_array() {
+                Some(values) => Some(ConstValue::Array(values.into())),
+                None => expr.const_eval(),
+            })
+            .collect()
+    }
+
+    /// Infers the smallest integer type that all elements of an array literal fit in, if they are
+    /// all integer literals, optionally negated: `[1, 2, 300]` is `uint16[3]`, and `[-1, 200]` is
+    /// `int16[2]`.
+    ///
+    /// Non-negative elements are unsigned, so the type is signed only if at least one element is
+    /// negative. Parentheses and sub-denominations are allowed, so `[(1), 1 gwei]` is an array of
+    /// integer literals, but other constant expressions like `1 + 1` are not.
+    ///
+    /// Returns `None` if the expression is not an array literal, ignoring parentheses, if it is
+    /// empty, if any of its elements is not an integer literal, or if the elements do not fit in
+    /// `uint256` or `int256`.
+    pub fn infer_array_literal_type(&self) -> Option<InferredIntType> {
+        let ExprKind::Array(exprs) = &self.strip_parens().kind else { return None };
+        if exprs.is_empty() {
+            return None;
+        }
+        let values =
+            exprs.iter().map(|expr| int_literal_value(expr)).collect::<Option<Vec<_>>>()?;
+        let negative = values.iter().filter(|value| value.sign() == Sign::Minus).count();
+        let signed = negative > 0;
+        let ty = (1..=32)
+            .map(|bytes| IntType::new(signed, bytes * 8))
+            .find(|ty| values.iter().all(|value| ty.contains(value)))?;
+        Some(InferredIntType { ty, mixed_sign: signed && negative < values.len() })
+    }
+}
+
+/// Returns the value of an integer literal, looking through parentheses and negations.
+fn int_literal_value(expr: &Expr<'_>) -> Option<BigInt> {
+    match &expr.strip_parens().kind {
+        ExprKind::Unary(op, inner) if op.kind == UnOpKind::Neg => {
+            int_literal_value(inner).map(|value| -value)
+        }
+        ExprKind::Lit(lit, _) => match &lit.kind {
+            LitKind::Number(value) => Some(value.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The inferred type of the elements of an array literal. See
+/// [`Expr::infer_array_literal_type`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InferredIntType {
+    /// The smallest integer type that all elements fit in.
+    pub ty: IntType,
+    /// Whether the array has both negative and non-negative elements, like `[-1, 1]`.
+    ///
+    /// Solidity gives the non-negative literals unsigned types, so such an array literal is
+    /// rejected unless its elements are converted explicitly: `[int8(-1), 1]`.
+    pub mixed_sign: bool,
+}
+
+/// A set of constant values. See [`Expr::const_value_set`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValueSet {
+    values: Vec<ConstValue>,
+}
+
+impl From<ConstValue> for ValueSet {
+    fn from(value: ConstValue) -> Self {
+        Self { values: vec![value] }
+    }
+}
+
+impl FromIterator<ConstValue> for ValueSet {
+    fn from_iter<I: IntoIterator<Item = ConstValue>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<ConstValue> for ValueSet {
+    fn extend<I: IntoIterator<Item = ConstValue>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl IntoIterator for ValueSet {
+    type Item = ConstValue;
+    type IntoIter = std::vec::IntoIter<ConstValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter()
+    }
+}
+
+impl ValueSet {
+    /// Creates a new, empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value into the set. Returns `true` if the value was not already in the set.
+    pub fn insert(&mut self, value: ConstValue) -> bool {
+        if self.contains(&value) {
+            return false;
+        }
+        self.values.push(value);
+        true
+    }
+
+    /// Returns `true` if the set contains the value.
+    pub fn contains(&self, value: &ConstValue) -> bool {
+        self.values.contains(value)
+    }
+
+    /// Returns `true` if the two sets have at least one value in common.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.values.iter().any(|value| other.contains(value))
+    }
+
+    /// Returns the value if the set contains exactly one value.
+    pub fn as_single(&self) -> Option<&ConstValue> {
+        match &self.values[..] {
+            [value] => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of values in the set.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns an iterator over the values, in insertion order.
+    pub fn iter(&self) -> std::slice::Iter<'_, ConstValue> {
+        self.values.iter()
+    }
+}
+
+/// An error returned when an expression is not a constant of the required kind.
+///
+/// See [`Expr::try_into_int_const`] and [`Expr::try_into_bool_const`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstError {
+    /// The span of the expression.
+    pub span: Span,
+    /// The kind of error.
+    pub kind: ConstErrorKind,
+}
+
+impl fmt::Display for ConstError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind.fmt(f)
+    }
+}
+
+impl std::error::Error for ConstError {}
+
+/// A kind of [`ConstError`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstErrorKind {
+    /// The expression is not a compile-time constant, or its evaluation failed.
+    NotConstant,
+    /// The expression is a constant of another kind.
+    Mismatch {
+        /// The description of the required kind.
+        expected: &'static str,
+        /// The description of the kind of the value.
+        found: &'static str,
+    },
+    /// A non-negative integer is required, but the value is negative.
+    Negative(BigInt),
+    /// The integer is larger than the maximum value of `uint256`.
+    TooLarge,
+}
+
+impl fmt::Display for ConstErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotConstant => f.write_str("expression is not a compile-time constant"),
+            Self::Mismatch { expected, found } => {
+                write!(f, "expected {expected} constant, found {found}")
+            }
+            Self::Negative(value) => {
+                write!(f, "expected non-negative integer constant, found {value}")
+            }
+            Self::TooLarge => f.write_str("integer constant does not fit in `uint256`"),
+        }
+    }
+}
+
+/// An intermediate value of [`Expr::const_eval`].
+enum Value {
+    /// A number of arbitrary precision.
+    Num(BigRational),
+    Bool(bool),
+    Str(StrKind, Arc<[u8]>),
+}
+
+impl Value {
+    /// Returns the description of the kind of this value, like [`ConstValue::description`].
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Num(n) if n.is_integer() => "integer",
+            Self::Num(_) => "rational",
+            Self::Bool(_) => "boolean",
+            Self::Str(StrKind::Hex, _) => "bytes",
+            Self::Str(..) => "string",
+        }
+    }
+
+    fn into_const_value(self) -> Option<ConstValue> {
+        Some(match self {
+            Self::Num(value) => return ConstValue::rational(value).ok(),
+            Self::Bool(b) => ConstValue::Bool(b),
+            Self::Str(StrKind::Hex, bytes) => ConstValue::Bytes(bytes),
+            Self::Str(_, s) => ConstValue::String(s),
+        })
+    }
+}
+
+fn lit_value(lit: &Lit) -> Option<Value> {
+    Some(match &lit.kind {
+        LitKind::Number(n) => Value::Num(BigRational::from_integer(n.clone())),
+        LitKind::Rational(r) => Value::Num(r.clone()),
+        LitKind::Bool(b) => Value::Bool(*b),
+        LitKind::Str(kind, s) => Value::Str(*kind, s.clone()),
+        LitKind::Address(_) | LitKind::Err(_) => return None,
+    })
+}
+
+fn eval(expr: &Expr<'_>) -> Option<Value> {
+    Some(match &expr.strip_parens().kind {
+        // Sub-denominations have already been applied to the literal value by the parser.
+        ExprKind::Lit(lit, _) => lit_value(lit)?,
+        ExprKind::Unary(op, operand) => match (op.kind, eval(operand)?) {
+            (UnOpKind::Neg, Value::Num(n)) => Value::Num(-n),
+            (UnOpKind::BitNot, Value::Num(n)) if n.is_integer() => {
+                Value::Num(BigRational::from_integer(!n.to_integer()))
+            }
+            (UnOpKind::Not, Value::Bool(b)) => Value::Bool(!b),
+            _ => return None,
+        },
+        ExprKind::Binary(lhs, op, rhs) => eval_binop(eval(lhs)?, op.kind, eval(rhs)?)?,
+        // Both branches must be constant for the conditional to be constant.
+        ExprKind::Ternary(cond, true_, false_) => {
+            let (true_, false_) = (eval(true_)?, eval(false_)?);
+            match eval(cond)? {
+                Value::Bool(true) => true_,
+                Value::Bool(false) => false_,
+                _ => return None,
+            }
+        }
+        _ => return None,
+    })
+}
+
+fn eval_binop(l: Value, op: BinOpKind, r: Value) -> Option<Value> {
+    use BinOpKind::*;
+
+    let (l, r) = match (l, r) {
+        (Value::Num(l), Value::Num(r)) => (l, r),
+        (Value::Bool(l), Value::Bool(r)) => {
+            return Some(Value::Bool(match op {
+                And => l && r,
+                Or => l || r,
+                Eq => l == r,
+                Ne => l != r,
+                _ => return None,
+            }))
+        }
+        _ => return None,
+    };
+    let value = match op {
+        Lt => return Some(Value::Bool(l < r)),
+        Le => return Some(Value::Bool(l <= r)),
+        Gt => return Some(Value::Bool(l > r)),
+        Ge => return Some(Value::Bool(l >= r)),
+        Eq => return Some(Value::Bool(l == r)),
+        Ne => return Some(Value::Bool(l != r)),
+        Add => l + r,
+        Sub => l - r,
+        Mul => l * r,
+        Div if r.numer().sign() == Sign::NoSign => return None,
+        Div => l / r,
+        Pow => rational_pow(&l, &r).ok()?,
+        Rem | BitAnd | BitOr | BitXor | Shl | Shr | Sar => {
+            if !l.is_integer() || !r.is_integer() {
+                return None;
+            }
+            let (l, r) = (l.to_integer(), r.to_integer());
+            BigRational::from_integer(match op {
+                Rem if r.sign() == Sign::NoSign => return None,
+                // `BigInt` remainders have the sign of the dividend, like in Solidity.
+                Rem => l % r,
+                BitAnd => l & r,
+                BitOr => l | r,
+                BitXor => l ^ r,
+                _ => {
+                    if r.sign() == Sign::Minus {
+                        return None;
+                    }
+                    let amount = u32::try_from(&r).ok()?;
+                    if op == Shl {
+                        if l.bits().saturating_add(u64::from(amount)) > MAX_RATIONAL_BITS {
+                            return None;
+                        }
+                        l << amount
+                    } else {
+                        // Rounds towards negative infinity.
+                        l >> amount
+                    }
+                }
+            })
+        }
+        And | Or => return None,
+    };
+    Some(Value::Num(value))
+}
+
+/// An integer constant of a specific integer type.
+///
+/// The value is stored as a mathematical integer, and is guaranteed to be in the range of the
+/// type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConstInt {
+    value: BigInt,
+    ty: IntType,
+}
+
+impl fmt::Display for ConstInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl ConstInt {
+    /// Creates a new integer of the given type.
+    ///
+    /// Returns an error if the value does not fit in the type.
+    pub fn new(value: BigInt, ty: IntType) -> Result<Self, ConstValueError> {
+        ty.check(&value)?;
+        Ok(Self { value, ty })
+    }
+
+    /// Creates a new integer of the given type, wrapping the value around in two's complement if
+    /// it does not fit in the type.
+    pub fn new_wrapping(value: BigInt, ty: IntType) -> Self {
+        Self { value: ty.wrap(value), ty }
+    }
+
+    /// Returns the value.
+    pub fn value(&self) -> &BigInt {
+        &self.value
+    }
+
+    /// Returns the type.
+    pub fn ty(&self) -> IntType {
+        self.ty
+    }
+
+    /// Returns `true` if the value is zero.
+    pub fn is_zero(&self) -> bool {
+        self.value.sign() == Sign::NoSign
+    }
+
+    /// Returns `true` if the value is negative.
+    pub fn is_negative(&self) -> bool {
+        self.value.sign() == Sign::Minus
+    }
+
+    /// Returns the value as a `uint256`, if it is non-negative.
+    pub fn as_u256(&self) -> Option<U256> {
+        if self.is_negative() {
+            return None;
+        }
+        U256::try_from_be_slice(&self.value.to_bytes_be().1)
+    }
+
+    /// Returns the value as an `int256`, if it is in the range of `int256`.
+    pub fn as_i256(&self) -> Option<I256> {
+        if !IntType::INT256.contains(&self.value) {
+            return None;
+        }
+        let raw = IntType::UINT256.wrap(self.value.clone());
+        U256::try_from_be_slice(&raw.to_bytes_be().1).map(I256::from_raw)
+    }
+
+    /// Returns the value in two's complement representation as an unsigned integer, truncated
+    /// to `bits` bits.
+    pub fn to_twos_complement(&self, bits: u16) -> BigInt {
+        IntType::new(false, bits).wrap(self.value.clone())
+    }
+
+    /// Applies the given unary operation to this value.
+    ///
+    /// Increments and decrements, both prefix and postfix, evaluate to the updated value.
+    pub fn unop(&self, op: UnOpKind) -> Result<Self, ConstValueError> {
+        let value = match op {
+            UnOpKind::PreInc | UnOpKind::PostInc => &self.value + 1,
+            UnOpKind::PreDec | UnOpKind::PostDec => &self.value - 1,
+            UnOpKind::Neg => -&self.value,
+            UnOpKind::BitNot if self.ty.signed => !&self.value,
+            UnOpKind::BitNot => self.ty.max() - &self.value,
+            UnOpKind::Not => return Err(ConstValueError::UnsupportedUnaryOp(op, "integer")),
+        };
+        Self::new(value, self.ty).map_err(|e| e.with_op(|| ConstOp::Unary(op, self.to_rational())))
+    }
+
+    /// Applies the given binary operation to this value.
+    ///
+    /// The operands of arithmetic, bitwise, and comparison operators are converted to their
+    /// common type, which is also the type of the result. Shifts and exponentiation have the type
+    /// of the left operand. Shifts wrap around in two's complement, while all other arithmetic
+    /// operations are checked.
+    pub fn binop(&self, r: &Self, op: BinOpKind) -> Result<ConstValue, ConstValueError> {
+        use BinOpKind::*;
+
+        let l = self;
+        match op {
+            Shl | Shr | Sar => return l.shift(r, op).map(ConstValue::Int),
+            Pow => return l.pow(r).map(ConstValue::Int),
+            Or | And => return Err(ConstValueError::UnsupportedBinaryOp(op, "integer")),
+            _ => {}
+        }
+
+        let ty = l.ty.common_type(r.ty).ok_or(ConstValueError::IntTypeMismatch(l.ty, r.ty))?;
+        let operation = || ConstOp::Binary(l.to_rational(), op, r.to_rational());
+        let (l, r) = (&l.value, &r.value);
+        let value = match op {
+            Lt => return Ok(ConstValue::Bool(l < r)),
+            Le => return Ok(ConstValue::Bool(l <= r)),
+            Gt => return Ok(ConstValue::Bool(l > r)),
+            Ge => return Ok(ConstValue::Bool(l >= r)),
+            Eq => return Ok(ConstValue::Bool(l == r)),
+            Ne => return Ok(ConstValue::Bool(l != r)),
+            // Bitwise operations on `BigInt` behave as if the values were in two's complement
+            // with infinite sign extension, so the result is always in range of the common type.
+            BitAnd => l & r,
+            BitOr => l | r,
+            BitXor => l ^ r,
+            Add => l + r,
+            Sub => l - r,
+            Mul => l * r,
+            // `BigInt` division truncates towards zero, and the remainder has the sign of the
+            // dividend, just like `SDIV` and `SMOD`.
+            Div | Rem if r.sign() == Sign::NoSign => return Err(ConstValueError::DivisionByZero),
+            Div => l / r,
+            Rem => l % r,
+            Shl | Shr | Sar | Pow | Or | And => unreachable!(),
+        };
+        Self::new(value, ty).map(ConstValue::Int).map_err(|e| e.with_op(operation))
+    }
+
+    fn shift(&self, r: &Self, op: BinOpKind) -> Result<Self, ConstValueError> {
+        if r.is_negative() {
+            return Err(ConstValueError::NegativeShift);
+        }
+        // Shifting by the bit width or more is the same as shifting by exactly the bit width.
+        let bits = self.ty.bits as usize;
+        let amount = usize::try_from(&r.value).unwrap_or(bits).min(bits);
+        Ok(match op {
+            BinOpKind::Shl => Self::new_wrapping(&self.value << amount, self.ty),
+            // `BigInt` right shifts round towards negative infinity, which is an arithmetic shift
+            // for signed values and a logical shift for unsigned values.
+            BinOpKind::Shr | BinOpKind::Sar => Self { value: &self.value >> amount, ty: self.ty },
+            _ => unreachable!(),
+        })
+    }
+
+    fn pow(&self, r: &Self) -> Result<Self, ConstValueError> {
+        if r.is_negative() {
+            return Err(ConstValueError::NegativeExponent);
+        }
+        let operation = || ConstOp::Binary(self.to_rational(), BinOpKind::Pow, r.to_rational());
+        let base = &self.value;
+        let value = if r.is_zero() {
+            BigInt::from(1)
+        } else if *base == BigInt::from(0) || *base == BigInt::from(1) {
+            base.clone()
+        } else if *base == BigInt::from(-1) {
+            if r.value.bit(0) {
+                base.clone()
+            } else {
+                BigInt::from(1)
+            }
+        } else {
+            // `|base| >= 2`, so any exponent larger than the bit width overflows.
+            match u32::try_from(&r.value) {
+                Ok(exp) if exp <= u32::from(self.ty.bits) => base.pow(exp),
+                _ if base.sign() == Sign::Minus && r.value.bit(0) => {
+                    return Err(ConstValueError::underflow(operation(), Some(self.ty)))
+                }
+                _ => return Err(ConstValueError::overflow(operation(), Some(self.ty))),
+            }
+        };
+        Self::new(value, self.ty).map_err(|e| e.with_op(operation))
+    }
+
+    fn to_rational(&self) -> BigRational {
+        BigRational::from_integer(self.value.clone())
+    }
+}
+
+fn rational_binop(
+    l: &BigRational,
+    r: &BigRational,
+    op: BinOpKind,
+) -> Result<ConstValue, ConstValueError> {
+    use BinOpKind::*;
+
+    let value = match op {
+        Lt => return Ok(ConstValue::Bool(l < r)),
+        Le => return Ok(ConstValue::Bool(l <= r)),
+        Gt => return Ok(ConstValue::Bool(l > r)),
+        Ge => return Ok(ConstValue::Bool(l >= r)),
+        Eq => return Ok(ConstValue::Bool(l == r)),
+        Ne => return Ok(ConstValue::Bool(l != r)),
+        Add => l + r,
+        Sub => l - r,
+        Mul => l * r,
+        Div if r.numer().sign() == Sign::NoSign => return Err(ConstValueError::DivisionByZero),
+        Div => l / r,
+        Pow => rational_pow(l, r)?,
+        Or | And | Shr | Shl | Sar | BitAnd | BitOr | BitXor | Rem => {
+            return Err(ConstValueError::UnsupportedBinaryOp(op, "rational"))
+        }
+    };
+    ConstValue::rational(value).map_err(|e| e.with_op(|| ConstOp::Binary(l.clone(), op, r.clone())))
+}
+
+fn rational_pow(base: &BigRational, exp: &BigRational) -> Result<BigRational, ConstValueError> {
+    if !exp.is_integer() {
+        return Err(ConstValueError::UnsupportedBinaryOp(BinOpKind::Pow, "rational"));
+    }
+    let operation = || ConstOp::Binary(base.clone(), BinOpKind::Pow, exp.clone());
+    let exp = exp.to_integer();
+    let base = match exp.sign() {
+        Sign::Minus if base.numer().sign() == Sign::NoSign => {
+            return Err(ConstValueError::DivisionByZero)
+        }
+        Sign::Minus => base.recip(),
+        _ => base.clone(),
+    };
+    let max_bits = base.numer().bits().max(base.denom().bits());
+    let exp = u32::try_from(exp.magnitude())
+        .ok()
+        .filter(|&exp| {
+            max_bits <= 1 || max_bits.saturating_mul(u64::from(exp)) <= MAX_RATIONAL_BITS
+        })
+        .ok_or_else(|| ConstValueError::overflow(operation(), None))?;
+    Ok(BigRational::new(base.numer().pow(exp), base.denom().pow(exp)))
+}
+
+/// An integer type: `uintN` or `intN`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct IntType {
+    /// Whether the type is signed.
+    pub signed: bool,
+    /// The size of the type in bits. Must be a multiple of 8 in the range `8..=256`.
+    pub bits: u16,
+}
+
+impl fmt::Display for IntType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}int{}", if self.signed { "" } else { "u" }, self.bits)
+    }
+}
+
+impl IntType {
+    /// `uint256`.
+    pub const UINT256: Self = Self::new(false, 256);
+    /// `int256`.
+    pub const INT256: Self = Self::new(true, 256);
+
+    /// Creates a new integer type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not a multiple of 8 in the range `8..=256`.
+    #[track_caller]
+    pub const fn new(signed: bool, bits: u16) -> Self {
+        assert!(bits % 8 == 0 && matches!(bits, 8..=256), "invalid integer type size");
+        Self { signed, bits }
+    }
+
+    /// Returns the minimum value of the type.
+    pub fn min(self) -> BigInt {
+        if self.signed {
+            -(modulus(self.bits - 1))
+        } else {
+            BigInt::from(0)
+        }
+    }
+
+    /// Returns the maximum value of the type.
+    pub fn max(self) -> BigInt {
+        modulus(if self.signed { self.bits - 1 } else { self.bits }) - 1
+    }
+
+    /// Returns `true` if the value is in the range of the type.
+    pub fn contains(self, value: &BigInt) -> bool {
+        self.check(value).is_ok()
+    }
+
+    /// Returns the type that both `self` and `other` can be implicitly converted to, if any.
+    ///
+    /// Integer types can be implicitly converted to types of the same signedness that are at
+    /// least as large, and unsigned types can be converted to strictly larger signed types.
+    pub fn common_type(self, other: Self) -> Option<Self> {
+        if self.is_convertible_to(other) {
+            Some(other)
+        } else if other.is_convertible_to(self) {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the type can be implicitly converted to `other`.
+    pub fn is_convertible_to(self, other: Self) -> bool {
+        match (self.signed, other.signed) {
+            (false, true) => self.bits < other.bits,
+            (true, false) => false,
+            _ => self.bits <= other.bits,
+        }
+    }
+
+    fn check(self, value: &BigInt) -> Result<(), ConstValueError> {
+        if *value > self.max() {
+            Err(ConstValueError::overflow(ConstOp::Value(value.clone()), Some(self)))
+        } else if *value < self.min() {
+            Err(ConstValueError::underflow(ConstOp::Value(value.clone()), Some(self)))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Wraps the value around to the range of the type in two's complement.
+    fn wrap(self, value: BigInt) -> BigInt {
+        let modulus = modulus(self.bits);
+        let mut value = value % &modulus;
+        if value.sign() == Sign::Minus {
+            value += &modulus;
+        }
+        if self.signed && value > self.max() {
+            value -= modulus;
+        }
+        value
+    }
+}
+
+/// Returns `2**bits`.
+fn modulus(bits: u16) -> BigInt {
+    BigInt::from(1) << bits as usize
+}
+
+/// An error that occurred while operating on a [`ConstValue`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstValueError {
+    /// The result is larger than the maximum value of its type.
+    Overflow(Box<ConstOverflow>),
+    /// The result is smaller than the minimum value of its type.
+    Underflow(Box<ConstOverflow>),
+    /// Division or modulo by zero.
+    DivisionByZero,
+    /// Exponentiation with a negative exponent.
+    NegativeExponent,
+    /// Shift by a negative amount.
+    NegativeShift,
+    /// The operands are of different kinds, e.g. an integer and a boolean.
+    TypeMismatch(&'static str, &'static str),
+    /// The integer operands have no common type.
+    IntTypeMismatch(IntType, IntType),
+    /// The unary operator is not supported for the operand kind.
+    UnsupportedUnaryOp(UnOpKind, &'static str),
+    /// The binary operator is not supported for the operand kind.
+    UnsupportedBinaryOp(BinOpKind, &'static str),
+}
+
+impl fmt::Display for ConstValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow(overflow) => overflow.fmt(f, "overflows"),
+            Self::Underflow(underflow) => underflow.fmt(f, "underflows"),
+            Self::DivisionByZero => f.write_str("division by zero"),
+            Self::NegativeExponent => f.write_str("exponent cannot be negative"),
+            Self::NegativeShift => f.write_str("shift amount cannot be negative"),
+            Self::TypeMismatch(l, r) => write!(f, "mismatched operand kinds: {l} and {r}"),
+            Self::IntTypeMismatch(l, r) => write!(f, "no common type for `{l}` and `{r}`"),
+            Self::UnsupportedUnaryOp(op, kind) => {
+                write!(f, "unary operator `{}` is not supported for {kind} values", op.to_str())
+            }
+            Self::UnsupportedBinaryOp(op, kind) => {
+                write!(f, "binary operator `{}` is not supported for {kind} values", op.to_str())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConstValueError {}
+
+impl ConstValueError {
+    fn overflow(op: ConstOp, ty: Option<IntType>) -> Self {
+        Self::Overflow(Box::new(ConstOverflow { op, ty }))
+    }
+
+    fn underflow(op: ConstOp, ty: Option<IntType>) -> Self {
+        Self::Underflow(Box::new(ConstOverflow { op, ty }))
+    }
+
+    /// Sets the operation that produced the out of range value of an overflow or underflow.
+    fn with_op(mut self, op: impl FnOnce() -> ConstOp) -> Self {
+        if let Self::Overflow(overflow) | Self::Underflow(overflow) = &mut self {
+            overflow.op = op();
+        }
+        self
+    }
+}
+
+/// The details of an [overflow](ConstValueError::Overflow) or
+/// [underflow](ConstValueError::Underflow).
+///
+/// Displayed as the operation and the type: `` `200 + 56` overflows `uint8` ``.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstOverflow {
+    /// The operation whose result is out of range, with the values of its operands.
+    pub op: ConstOp,
+    /// The type of the result, or `None` if the result is a rational number that is too large to
+    /// be computed, like `(1/2) ** 100000`.
+    pub ty: Option<IntType>,
+}
+
+impl ConstOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>, flows: &str) -> fmt::Result {
+        let Self { op, ty } = self;
+        match ty {
+            Some(ty) => write!(f, "`{op}` {flows} `{ty}`"),
+            None => write!(f, "`{op}` is too large"),
+        }
+    }
+}
+
+/// An operation on constant values, with the values of its operands. See [`ConstOverflow`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConstOp {
+    /// The value itself, when it does not fit in the type it is created with, like `256` as a
+    /// `uint8`.
+    Value(BigInt),
+    /// A unary operation: `-x`, `x++`.
+    Unary(UnOpKind, BigRational),
+    /// A binary operation: `x + y`.
+    Binary(BigRational, BinOpKind, BigRational),
+}
+
+impl fmt::Display for ConstOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Negative and fractional operands are parenthesized: `-(-128)`, `(1/2) ** 3`.
+        let operand = |value: &BigRational| {
+            if value.is_integer() && value.numer().sign() != Sign::Minus {
+                value.to_string()
+            } else {
+                format!("({value})")
+            }
+        };
+        match self {
+            Self::Value(value) => value.fmt(f),
+            Self::Unary(op, value) if op.is_postfix() => {
+                write!(f, "{}{}", operand(value), op.to_str())
+            }
+            Self::Unary(op, value) => write!(f, "{}{}", op.to_str(), operand(value)),
+            Self::Binary(l, op, r) => write!(f, "{} {} {}", operand(l), op.to_str(), operand(r)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ConstValueError as E;
+
+    fn uint(bits: u16, value: i64) -> ConstValue {
+        ConstValue::int(BigInt::from(value), IntType::new(false, bits)).unwrap()
+    }
+
+    fn int(bits: u16, value: i64) -> ConstValue {
+        ConstValue::int(BigInt::from(value), IntType::new(true, bits)).unwrap()
+    }
+
+    fn rational(numer: i64, denom: i64) -> ConstValue {
+        ConstValue::rational(BigRational::new(numer.into(), denom.into())).unwrap()
+    }
+
+    fn binop(l: &ConstValue, op: BinOpKind, r: &ConstValue) -> Result<ConstValue, E> {
+        l.binop(r, op)
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(IntType::new(false, 8).min(), BigInt::from(0));
+        assert_eq!(IntType::new(false, 8).max(), BigInt::from(255));
+        assert_eq!(IntType::new(true, 8).min(), BigInt::from(-128));
+        assert_eq!(IntType::new(true, 8).max(), BigInt::from(127));
+        assert_eq!(IntType::UINT256.max().bits(), 256);
+        assert_eq!(IntType::INT256.max().bits(), 255);
+
+        let ty = IntType::new(true, 8);
+        assert!(matches!(ConstValue::int(BigInt::from(128), ty), Err(E::Overflow(_))));
+        assert!(matches!(ConstValue::int(BigInt::from(-129), ty), Err(E::Underflow(_))));
+        assert!(matches!(
+            ConstValue::int(BigInt::from(-1), IntType::UINT256),
+            Err(E::Underflow(_))
+        ));
+        assert_eq!(IntType::new(true, 16).to_string(), "int16");
+        assert_eq!(IntType::UINT256.to_string(), "uint256");
+    }
+
+    #[test]
+    fn common_type() {
+        let u8 = IntType::new(false, 8);
+        let u16 = IntType::new(false, 16);
+        let i8 = IntType::new(true, 8);
+        let i16 = IntType::new(true, 16);
+        assert_eq!(u8.common_type(u16), Some(u16));
+        assert_eq!(i16.common_type(i8), Some(i16));
+        assert_eq!(u8.common_type(i16), Some(i16));
+        assert_eq!(u8.common_type(i8), None);
+        assert_eq!(u16.common_type(i8), None);
+
+        assert_eq!(binop(&uint(8, 200), BinOpKind::Add, &uint(16, 100)), Ok(uint(16, 300)));
+        assert_eq!(binop(&uint(8, 1), BinOpKind::Add, &int(8, 1)), Err(E::IntTypeMismatch(u8, i8)));
+        assert_eq!(
+            binop(&uint(8, 1), BinOpKind::Add, &ConstValue::Bool(true)),
+            Err(E::TypeMismatch("integer", "boolean"))
+        );
+    }
+
+    #[test]
+    fn unsigned_arithmetic() {
+        use BinOpKind::*;
+
+        assert_eq!(binop(&uint(8, 200), Add, &uint(8, 55)), Ok(uint(8, 255)));
+        assert!(matches!(binop(&uint(8, 200), Add, &uint(8, 56)), Err(E::Overflow(_))));
+        assert_eq!(binop(&uint(8, 1), Sub, &uint(8, 1)), Ok(uint(8, 0)));
+        assert!(matches!(binop(&uint(8, 1), Sub, &uint(8, 2)), Err(E::Underflow(_))));
+        assert_eq!(binop(&uint(8, 15), Mul, &uint(8, 17)), Ok(uint(8, 255)));
+        assert!(matches!(binop(&uint(8, 16), Mul, &uint(8, 16)), Err(E::Overflow(_))));
+        assert_eq!(binop(&uint(8, 7), Div, &uint(8, 2)), Ok(uint(8, 3)));
+        assert_eq!(binop(&uint(8, 7), Rem, &uint(8, 2)), Ok(uint(8, 1)));
+        assert_eq!(binop(&uint(8, 7), Div, &uint(8, 0)), Err(E::DivisionByZero));
+        assert_eq!(binop(&uint(8, 7), Rem, &uint(8, 0)), Err(E::DivisionByZero));
+
+        let max = ConstValue::uint256(U256::MAX);
+        let one = ConstValue::uint256(U256::from(1));
+        assert!(matches!(binop(&max, Add, &one), Err(E::Overflow(_))));
+        assert_eq!(binop(&max, Sub, &max), Ok(ConstValue::uint256(U256::ZERO)));
+        assert_eq!(binop(&max, Mul, &one), Ok(max.clone()));
+        assert_eq!(max.as_u256(), Some(U256::MAX));
+        assert_eq!(max.as_i256(), None);
+    }
+
+    #[test]
+    fn signed_arithmetic() {
+        use BinOpKind::*;
+
+        assert_eq!(binop(&int(8, 100), Add, &int(8, 27)), Ok(int(8, 127)));
+        assert!(matches!(binop(&int(8, 100), Add, &int(8, 28)), Err(E::Overflow(_))));
+        assert_eq!(binop(&int(8, -100), Sub, &int(8, 28)), Ok(int(8, -128)));
+        assert!(matches!(binop(&int(8, -100), Sub, &int(8, 29)), Err(E::Underflow(_))));
+        assert_eq!(binop(&int(8, -16), Mul, &int(8, 8)), Ok(int(8, -128)));
+        assert!(matches!(binop(&int(8, -16), Mul, &int(8, -8)), Err(E::Overflow(_))));
+        assert!(matches!(binop(&int(8, -16), Mul, &int(8, 9)), Err(E::Underflow(_))));
+
+        // Division truncates towards zero, and the remainder has the sign of the dividend.
+        assert_eq!(binop(&int(8, -7), Div, &int(8, 2)), Ok(int(8, -3)));
+        assert_eq!(binop(&int(8, 7), Div, &int(8, -2)), Ok(int(8, -3)));
+        assert_eq!(binop(&int(8, -7), Rem, &int(8, 2)), Ok(int(8, -1)));
+        assert_eq!(binop(&int(8, 7), Rem, &int(8, -2)), Ok(int(8, 1)));
+        assert!(matches!(binop(&int(8, -128), Div, &int(8, -1)), Err(E::Overflow(_))));
+        assert_eq!(binop(&int(8, -128), Rem, &int(8, -1)), Ok(int(8, 0)));
+
+        let min = ConstValue::int256(I256::MIN);
+        let max = ConstValue::int256(I256::MAX);
+        assert_eq!(min.as_i256(), Some(I256::MIN));
+        assert_eq!(max.as_i256(), Some(I256::MAX));
+        assert_eq!(min.as_u256(), None);
+        assert!(matches!(binop(&min, Sub, &ConstValue::int256(I256::ONE)), Err(E::Underflow(_))));
+        assert!(matches!(
+            binop(&min, Div, &ConstValue::int256(I256::MINUS_ONE)),
+            Err(E::Overflow(_))
+        ));
+        assert_eq!(binop(&min, Add, &max), Ok(ConstValue::int256(I256::MINUS_ONE)));
+        assert_eq!(int(8, -1).as_i256(), Some(I256::MINUS_ONE));
+    }
+
+    #[test]
+    fn unary() {
+        use UnOpKind::*;
+
+        assert_eq!(int(8, 127).unop(Neg), Ok(int(8, -127)));
+        assert!(matches!(int(8, -128).unop(Neg), Err(E::Overflow(_))));
+        assert_eq!(uint(8, 0).unop(Neg), Ok(uint(8, 0)));
+        assert!(matches!(uint(8, 1).unop(Neg), Err(E::Underflow(_))));
+        assert_eq!(uint(8, 0).unop(BitNot), Ok(uint(8, 255)));
+        assert_eq!(uint(8, 0x0f).unop(BitNot), Ok(uint(8, 0xf0)));
+        assert_eq!(int(8, 0).unop(BitNot), Ok(int(8, -1)));
+        assert_eq!(int(8, -128).unop(BitNot), Ok(int(8, 127)));
+        assert_eq!(uint(8, 254).unop(PreInc), Ok(uint(8, 255)));
+        assert!(matches!(uint(8, 255).unop(PostInc), Err(E::Overflow(_))));
+        assert!(matches!(uint(8, 0).unop(PreDec), Err(E::Underflow(_))));
+        assert_eq!(int(8, -127).unop(PostDec), Ok(int(8, -128)));
+        assert_eq!(ConstValue::Bool(true).unop(Not), Ok(ConstValue::Bool(false)));
+        assert_eq!(uint(8, 0).unop(Not), Err(E::UnsupportedUnaryOp(Not, "integer")));
+        assert_eq!(rational(1, 2).unop(Neg), Ok(rational(-1, 2)));
+    }
+
+    #[test]
+    fn bitwise() {
+        use BinOpKind::*;
+
+        assert_eq!(binop(&uint(8, 0b1100), BitAnd, &uint(8, 0b1010)), Ok(uint(8, 0b1000)));
+        assert_eq!(binop(&uint(8, 0b1100), BitOr, &uint(8, 0b1010)), Ok(uint(8, 0b1110)));
+        assert_eq!(binop(&uint(8, 0b1100), BitXor, &uint(8, 0b1010)), Ok(uint(8, 0b0110)));
+        // Two's complement: -1 is all ones, -128 is `0b1000_0000`.
+        assert_eq!(binop(&int(8, -1), BitAnd, &int(8, 0x55)), Ok(int(8, 0x55)));
+        assert_eq!(binop(&int(8, -128), BitOr, &int(8, 1)), Ok(int(8, -127)));
+        assert_eq!(binop(&int(8, -1), BitXor, &int(8, 127)), Ok(int(8, -128)));
+        assert_eq!(binop(&int(8, -2), BitAnd, &int(8, -3)), Ok(int(8, -4)));
+    }
+
+    #[test]
+    fn shifts() {
+        use BinOpKind::*;
+
+        assert_eq!(binop(&uint(8, 1), Shl, &uint(8, 7)), Ok(uint(8, 128)));
+        // Left shifts are not checked and discard the higher order bits.
+        assert_eq!(binop(&uint(8, 0xff), Shl, &uint(8, 4)), Ok(uint(8, 0xf0)));
+        assert_eq!(binop(&uint(8, 1), Shl, &uint(8, 8)), Ok(uint(8, 0)));
+        assert_eq!(binop(&uint(8, 1), Shl, &uint(256, 1000)), Ok(uint(8, 0)));
+        assert_eq!(binop(&int(8, 1), Shl, &uint(8, 7)), Ok(int(8, -128)));
+        assert_eq!(binop(&int(8, -1), Shl, &uint(8, 7)), Ok(int(8, -128)));
+        assert_eq!(binop(&int(8, 0x41), Shl, &uint(8, 1)), Ok(int(8, -126)));
+
+        // Right shifts of signed values are arithmetic, rounding towards negative infinity.
+        assert_eq!(binop(&uint(8, 0xf0), Shr, &uint(8, 4)), Ok(uint(8, 0x0f)));
+        assert_eq!(binop(&int(8, -128), Shr, &uint(8, 4)), Ok(int(8, -8)));
+        assert_eq!(binop(&int(8, -7), Shr, &uint(8, 1)), Ok(int(8, -4)));
+        assert_eq!(binop(&int(8, -1), Shr, &uint(8, 200)), Ok(int(8, -1)));
+        assert_eq!(binop(&int(8, 127), Sar, &uint(8, 200)), Ok(int(8, 0)));
+
+        assert_eq!(binop(&uint(8, 1), Shl, &int(8, -1)), Err(E::NegativeShift));
+        // The type of the right operand does not affect the result type.
+        assert_eq!(binop(&int(8, 1), Shl, &uint(256, 1)), Ok(int(8, 2)));
+    }
+
+    #[test]
+    fn pow() {
+        use BinOpKind::*;
+
+        assert_eq!(binop(&uint(8, 2), Pow, &uint(8, 7)), Ok(uint(8, 128)));
+        assert!(matches!(binop(&uint(8, 2), Pow, &uint(8, 8)), Err(E::Overflow(_))));
+        assert_eq!(binop(&uint(8, 3), Pow, &uint(8, 0)), Ok(uint(8, 1)));
+        assert_eq!(binop(&uint(8, 0), Pow, &uint(8, 0)), Ok(uint(8, 1)));
+        assert_eq!(binop(&int(8, -2), Pow, &uint(8, 7)), Ok(int(8, -128)));
+        assert!(matches!(binop(&int(8, 2), Pow, &uint(8, 7)), Err(E::Overflow(_))));
+        assert!(matches!(binop(&int(8, -3), Pow, &uint(8, 5)), Err(E::Underflow(_))));
+        assert_eq!(binop(&int(8, -1), Pow, &uint(256, 1001)), Ok(int(8, -1)));
+        assert_eq!(binop(&int(8, -1), Pow, &uint(256, 1000)), Ok(int(8, 1)));
+        assert_eq!(binop(&uint(8, 0), Pow, &uint(256, 1000)), Ok(uint(8, 0)));
+        assert_eq!(binop(&uint(8, 1), Pow, &uint(256, 1000)), Ok(uint(8, 1)));
+        assert_eq!(binop(&int(8, 2), Pow, &int(8, -1)), Err(E::NegativeExponent));
+        let huge = ConstValue::uint256(U256::MAX);
+        assert!(matches!(binop(&uint(256, 2), Pow, &huge), Err(E::Overflow(_))));
+        assert!(matches!(binop(&int(256, -2), Pow, &huge), Err(E::Underflow(_))));
+
+        let two = uint(256, 2);
+        assert_eq!(
+            binop(&two, Pow, &uint(256, 255)).unwrap().as_u256(),
+            Some(U256::from(1) << 255)
+        );
+        assert!(matches!(binop(&two, Pow, &uint(256, 256)), Err(E::Overflow(_))));
+    }
+
+    #[test]
+    fn comparisons() {
+        use BinOpKind::*;
+
+        let t = ConstValue::Bool(true);
+        let f = ConstValue::Bool(false);
+        assert_eq!(binop(&int(8, -1), Lt, &int(16, 1)), Ok(t.clone()));
+        assert_eq!(binop(&int(8, -1), Ge, &int(8, 1)), Ok(f.clone()));
+        assert_eq!(binop(&uint(8, 1), Eq, &uint(256, 1)), Ok(t.clone()));
+        assert_eq!(binop(&uint(8, 1), Ne, &uint(8, 1)), Ok(f.clone()));
+        assert_eq!(binop(&t, And, &f), Ok(f.clone()));
+        assert_eq!(binop(&t, Or, &f), Ok(t.clone()));
+        assert_eq!(binop(&t, Ne, &f), Ok(t.clone()));
+        assert_eq!(binop(&t, Add, &f), Err(E::UnsupportedBinaryOp(Add, "boolean")));
+        assert_eq!(
+            binop(&uint(8, 1), And, &uint(8, 1)),
+            Err(E::UnsupportedBinaryOp(And, "integer"))
+        );
+
+        let s = |s: &str| ConstValue::String(s.as_bytes().into());
+        assert_eq!(binop(&s("a"), Eq, &s("a")), Ok(t.clone()));
+        assert_eq!(binop(&s("a"), Ne, &s("b")), Ok(t.clone()));
+        assert_eq!(binop(&s("a"), Lt, &s("b")), Err(E::UnsupportedBinaryOp(Lt, "string")));
+        let b = ConstValue::Bytes(b"a"[..].into());
+        assert_eq!(binop(&s("a"), Eq, &b), Err(E::TypeMismatch("string", "bytes")));
+    }
+
+    #[test]
+    fn rationals() {
+        use BinOpKind::*;
+
+        let half = rational(1, 2);
+        assert!(matches!(half, ConstValue::Rational(_)));
+        assert_eq!(rational(4, 2), uint(256, 2));
+        assert_eq!(rational(-4, 2), int(256, -2));
+        assert_eq!(binop(&half, Add, &half), Ok(uint(256, 1)));
+        assert_eq!(binop(&half, Mul, &uint(8, 3)), Ok(rational(3, 2)));
+        assert_eq!(binop(&uint(8, 1), Div, &half), Ok(uint(256, 2)));
+        assert_eq!(binop(&half, Sub, &uint(8, 1)), Ok(rational(-1, 2)));
+        assert_eq!(binop(&half, Div, &uint(8, 0)), Err(E::DivisionByZero));
+        assert_eq!(binop(&half, Lt, &uint(8, 1)), Ok(ConstValue::Bool(true)));
+        assert_eq!(binop(&half, Pow, &uint(8, 2)), Ok(rational(1, 4)));
+        assert_eq!(binop(&half, Pow, &int(8, -2)), Ok(uint(256, 4)));
+        assert_eq!(binop(&half, Pow, &half), Err(E::UnsupportedBinaryOp(Pow, "rational")));
+        assert!(matches!(binop(&half, Pow, &uint(256, 100_000)), Err(E::Overflow(_))));
+        assert_eq!(binop(&half, Rem, &half), Err(E::UnsupportedBinaryOp(Rem, "rational")));
+        assert_eq!(half.as_u256(), None);
+        assert_eq!(half.to_string(), "1/2");
+    }
+
+    #[test]
+    fn overflow_messages() {
+        use BinOpKind::*;
+
+        let msg = |result: Result<ConstValue, E>| result.unwrap_err().to_string();
+        assert_eq!(msg(binop(&uint(8, 16), Mul, &uint(8, 16))), "`16 * 16` overflows `uint8`");
+        assert_eq!(msg(binop(&uint(8, 1), Sub, &uint(16, 2))), "`1 - 2` underflows `uint16`");
+        assert_eq!(msg(binop(&int(8, -3), Pow, &uint(8, 5))), "`(-3) ** 5` underflows `int8`");
+        assert_eq!(msg(int(8, -128).unop(UnOpKind::Neg)), "`-(-128)` overflows `int8`");
+        assert_eq!(msg(uint(8, 255).unop(UnOpKind::PostInc)), "`255++` overflows `uint8`");
+        let value = ConstValue::int(BigInt::from(-1), IntType::UINT256);
+        assert_eq!(msg(value), "`-1` underflows `uint256`");
+        let pow = binop(&rational(1, 2), Pow, &uint(256, 100_000));
+        assert_eq!(msg(pow), "`(1/2) ** 100000` is too large");
+
+        let Err(E::Overflow(overflow)) = binop(&uint(8, 200), Add, &uint(8, 56)) else { panic!() };
+        let r = |value: i64| BigRational::from_integer(value.into());
+        assert_eq!(overflow.op, ConstOp::Binary(r(200), Add, r(56)));
+        assert_eq!(overflow.ty, Some(IntType::new(false, 8)));
+    }
+}
@@ -0,0 +1,156 @@
+use super::{
+    CallArgs, CallArgsKind, Expr, ExprKind, IndexKind, NamedArg, Type, TypeKind, VariableDefinition,
+};
+use std::fmt;
+
+/// Compact [`Debug`](fmt::Debug) formatting of an AST node that omits all spans.
+///
+/// Created by [`Expr::debug_tree`] and [`Type::debug_tree`]. This is mostly useful for snapshot
+/// tests, since the output is stable across changes in whitespace.
+pub struct DebugTree<'a, T: ?Sized>(&'a T);
+
+impl Expr<'_> {
+    /// Returns a [`Debug`](fmt::Debug) implementation that renders the expression tree without
+    /// spans, e.g. `Binary(Ident("a"), Add, Ident("b"))`.
+    pub fn debug_tree(&self) -> DebugTree<'_, Self> {
+        DebugTree(self)
+    }
+}
+
+impl Type<'_> {
+    /// Returns a [`Debug`](fmt::Debug) implementation that renders the type tree without spans.
+    pub fn debug_tree(&self) -> DebugTree<'_, Self> {
+        DebugTree(self)
+    }
+}
+
+impl fmt::Debug for DebugTree<'_, Expr<'_>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0.kind {
+            ExprKind::Array(exprs) => {
+                f.debug_tuple("Array").field(&DebugTree::<[&mut Expr<'_>]>(exprs)).finish()
+            }
+            ExprKind::Assign(lhs, op, rhs) => f
+                .debug_tuple("Assign")
+                .field(&lhs.debug_tree())
+                .field(&op.map(|op| op.kind))
+                .field(&rhs.debug_tree())
+                .finish(),
+            ExprKind::Binary(lhs, op, rhs) => f
+                .debug_tuple("Binary")
+                .field(&lhs.debug_tree())
+                .field(&op.kind)
+                .field(&rhs.debug_tree())
+                .finish(),
+            ExprKind::Call(callee, args) => {
+                f.debug_tuple("Call").field(&callee.debug_tree()).field(&DebugTree(args)).finish()
+            }
+            ExprKind::CallOptions(callee, args) => f
+                .debug_tuple("CallOptions")
+                .field(&callee.debug_tree())
+                .field(&DebugTree::<[NamedArg<'_>]>(args))
+                .finish(),
+            ExprKind::Delete(expr) => f.debug_tuple("Delete").field(&expr.debug_tree()).finish(),
+            ExprKind::Err(_) => f.write_str("Err"),
+            ExprKind::Ident(ident) => f.debug_tuple("Ident").field(&ident.as_str()).finish(),
+            ExprKind::Index(expr, IndexKind::Index(index)) => {
+                f.debug_tuple("Index").field(&expr.debug_tree()).field(&opt(index)).finish()
+            }
+            ExprKind::Index(expr, IndexKind::Range(start, end)) => f
+                .debug_tuple("Slice")
+                .field(&expr.debug_tree())
+                .field(&opt(start))
+                .field(&opt(end))
+                .finish(),
+            ExprKind::Lit(lit, sub) => {
+                let mut t = f.debug_tuple("Lit");
+                t.field(&lit.symbol.as_str());
+                if let Some(sub) = sub {
+                    t.field(&format_args!("{sub}"));
+                }
+                t.finish()
+            }
+            ExprKind::Member(expr, member) => {
+                f.debug_tuple("Member").field(&expr.debug_tree()).field(&member.as_str()).finish()
+            }
+            ExprKind::New(ty) => f.debug_tuple("New").field(&ty.debug_tree()).finish(),
+            ExprKind::Payable(args) => f.debug_tuple("Payable").field(&DebugTree(args)).finish(),
+            ExprKind::Ternary(cond, true_, false_) => f
+                .debug_tuple("Ternary")
+                .field(&cond.debug_tree())
+                .field(&true_.debug_tree())
+                .field(&false_.debug_tree())
+                .finish(),
+            ExprKind::Tuple(exprs) => {
+                f.debug_tuple("Tuple").field(&exprs.iter().map(opt).collect::<Vec<_>>()).finish()
+            }
+            ExprKind::TypeCall(ty) => f.debug_tuple("TypeCall").field(&ty.debug_tree()).finish(),
+            ExprKind::Type(ty) => f.debug_tuple("Type").field(&ty.debug_tree()).finish(),
+            ExprKind::Unary(op, expr) => {
+                f.debug_tuple("Unary").field(&op.kind).field(&expr.debug_tree()).finish()
+            }
+        }
+    }
+}
+
+impl fmt::Debug for DebugTree<'_, Type<'_>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0.kind {
+            TypeKind::Elementary(ty) => fmt::Debug::fmt(ty, f),
+            TypeKind::Array(array) => f
+                .debug_tuple("Array")
+                .field(&array.element.debug_tree())
+                .field(&array.size.as_deref().map(Expr::debug_tree))
+                .finish(),
+            TypeKind::Function(function) => f
+                .debug_tuple("Function")
+                .field(&params(&function.parameters))
+                .field(&function.visibility)
+                .field(&function.state_mutability)
+                .field(&params(&function.returns))
+                .finish(),
+            TypeKind::Mapping(mapping) => f
+                .debug_tuple("Mapping")
+                .field(&mapping.key.debug_tree())
+                .field(&mapping.value.debug_tree())
+                .finish(),
+            TypeKind::Custom(path) => {
+                f.debug_tuple("Custom").field(&format_args!("{path}")).finish()
+            }
+            TypeKind::Var => f.write_str("Var"),
+        }
+    }
+}
+
+fn opt<'a, 'ast>(expr: &'a Option<&'ast mut Expr<'ast>>) -> Option<DebugTree<'a, Expr<'ast>>> {
+    expr.as_deref().map(Expr::debug_tree)
+}
+
+fn params<'a, 'ast>(params: &'a [VariableDefinition<'ast>]) -> Vec<DebugTree<'a, Type<'ast>>> {
+    params.iter().map(|param| param.ty.debug_tree()).collect()
+}
+
+impl fmt::Debug for DebugTree<'_, CallArgs<'_>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0.kind {
+            CallArgsKind::Unnamed(exprs) => {
+                fmt::Debug::fmt(&DebugTree::<[&mut Expr<'_>]>(exprs), f)
+            }
+            CallArgsKind::Named(args, _) => fmt::Debug::fmt(&DebugTree::<[NamedArg<'_>]>(args), f),
+        }
+    }
+}
+
+impl fmt::Debug for DebugTree<'_, [&mut Expr<'_>]> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.0.iter().map(|expr| expr.debug_tree())).finish()
+    }
+}
+
+impl fmt::Debug for DebugTree<'_, [NamedArg<'_>]> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|arg| (arg.name.as_str(), arg.value.debug_tree())))
+            .finish()
+    }
+}
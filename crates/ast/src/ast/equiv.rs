@@ -0,0 +1,207 @@
+use super::{BinOpKind, Expr, ExprKind, IndexKind, LitKind, UnOpKind};
+use num_bigint::Sign;
+use num_rational::BigRational;
+
+impl Expr<'_> {
+    /// Returns `true` if the two expressions are provably equivalent.
+    ///
+    /// This is a stronger check than comparing the [canonicalized](Self::canonicalize)
+    /// expressions: operands of associative and commutative operators are flattened and sorted,
+    /// and number literals are folded with arbitrary precision, so that `a + 1 + 2` is equivalent
+    /// to `3 + a`. Arithmetic overflow and the types of the operands are not taken into account.
+    ///
+    /// Expressions with side effects are never equivalent, not even to themselves, since
+    /// evaluating them twice may produce different results. Expressions containing errors are
+    /// never equivalent either.
+    ///
+    /// The expressions are traversed with an explicit worklist, so this does not overflow the
+    /// stack on deeply nested expressions.
+    pub fn equiv(&self, other: &Self) -> bool {
+        match (normalize(self), normalize(other)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// The normal form of a pure expression.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum Norm {
+    /// A folded number.
+    Num(BigRational),
+    /// A flattened chain of an associative and commutative operator, with sorted operands.
+    Assoc(&'static str, Vec<Norm>),
+    /// Any other node, with a description of the node that does not include its children.
+    Node(String, Vec<Norm>),
+}
+
+enum Task<'a, 'ast> {
+    /// Pushes the children of the expression, followed by a `Build` task.
+    Visit(&'a Expr<'ast>),
+    /// Pops the given number of normalized children and builds the normal form of the
+    /// expression.
+    Build(&'a Expr<'ast>, usize),
+}
+
+/// Returns the normal form of the expression, or `None` if it is impure or contains errors.
+fn normalize(expr: &Expr<'_>) -> Option<Norm> {
+    let mut tasks = vec![Task::Visit(expr)];
+    let mut out = Vec::new();
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(expr) => {
+                let expr = expr.strip_parens();
+                let children = children(expr)?;
+                tasks.push(Task::Build(expr, children.len()));
+                tasks.extend(children.into_iter().rev().map(Task::Visit));
+            }
+            Task::Build(expr, n) => {
+                let children = out.split_off(out.len() - n);
+                out.push(build(expr, children));
+            }
+        }
+    }
+    debug_assert_eq!(out.len(), 1);
+    out.pop()
+}
+
+/// Returns the children of the expression, or `None` if the node itself is impure or an error.
+fn children<'a, 'ast>(expr: &'a Expr<'ast>) -> Option<Vec<&'a Expr<'ast>>> {
+    Some(match &expr.kind {
+        ExprKind::Assign(..) | ExprKind::Call(..) | ExprKind::Delete(_) | ExprKind::Err(_) => {
+            return None
+        }
+        ExprKind::Unary(op, _) if op.kind.is_modifying() => return None,
+        ExprKind::Array(exprs) => exprs.iter().map(|e| &**e).collect(),
+        ExprKind::Binary(lhs, _, rhs) => vec![&**lhs, &**rhs],
+        ExprKind::CallOptions(expr, args) => {
+            std::iter::once(&**expr).chain(args.iter().map(|arg| &*arg.value)).collect()
+        }
+        ExprKind::Index(expr, kind) => {
+            let (start, end) = match kind {
+                IndexKind::Index(index) => (index, &None),
+                IndexKind::Range(start, end) => (start, end),
+            };
+            std::iter::once(&**expr).chain(start.as_deref()).chain(end.as_deref()).collect()
+        }
+        ExprKind::Member(expr, _) | ExprKind::Unary(_, expr) => vec![&**expr],
+        ExprKind::Payable(args) => args.exprs().collect(),
+        ExprKind::Ternary(cond, true_, false_) => vec![&**cond, &**true_, &**false_],
+        ExprKind::Tuple(exprs) => exprs.iter().flatten().map(|e| &**e).collect(),
+        ExprKind::Ident(_)
+        | ExprKind::Lit(..)
+        | ExprKind::New(_)
+        | ExprKind::TypeCall(_)
+        | ExprKind::Type(_) => Vec::new(),
+    })
+}
+
+fn build(expr: &Expr<'_>, children: Vec<Norm>) -> Norm {
+    let desc = match &expr.kind {
+        ExprKind::Binary(_, op, _) => return binary(op.kind, children),
+        ExprKind::Unary(op, _) if op.kind == UnOpKind::Neg => {
+            if let [Norm::Num(value)] = &children[..] {
+                return Norm::Num(-value);
+            }
+            "unary -".to_string()
+        }
+        ExprKind::Lit(lit, None) => match &lit.kind {
+            LitKind::Number(value) => return Norm::Num(BigRational::from_integer(value.clone())),
+            LitKind::Rational(value) => return Norm::Num(value.clone()),
+            kind => format!("lit {kind:?}"),
+        },
+        ExprKind::Lit(lit, Some(sub)) => format!("lit {:?} {sub}", lit.kind),
+        ExprKind::Array(_) => "array".to_string(),
+        ExprKind::CallOptions(_, args) => {
+            let names = args.iter().map(|arg| arg.name.as_str()).collect::<Vec<_>>();
+            format!("options {}", names.join(" "))
+        }
+        ExprKind::Ident(ident) => format!("ident {ident}"),
+        ExprKind::Index(_, IndexKind::Index(index)) => format!("index {}", index.is_some()),
+        ExprKind::Index(_, IndexKind::Range(start, end)) => {
+            format!("slice {} {}", start.is_some(), end.is_some())
+        }
+        ExprKind::Member(_, member) => format!("member {member}"),
+        ExprKind::New(ty) => format!("new {}", ty.to_sexpr()),
+        ExprKind::Payable(_) => "payable".to_string(),
+        ExprKind::Ternary(..) => "ternary".to_string(),
+        ExprKind::Tuple(exprs) => {
+            let components = exprs.iter().map(|e| if e.is_some() { " x" } else { " _" });
+            format!("tuple{}", components.collect::<String>())
+        }
+        ExprKind::TypeCall(ty) => format!("type-call {}", ty.to_sexpr()),
+        ExprKind::Type(ty) => format!("type {}", ty.to_sexpr()),
+        ExprKind::Unary(op, _) => format!("unary {}", op.kind.to_str()),
+        ExprKind::Assign(..) | ExprKind::Call(..) | ExprKind::Delete(_) | ExprKind::Err(_) => {
+            unreachable!("impure or erroneous expressions are rejected by `children`")
+        }
+    };
+    Norm::Node(desc, children)
+}
+
+fn binary(op: BinOpKind, children: Vec<Norm>) -> Norm {
+    let Ok([lhs, rhs]) = <[Norm; 2]>::try_from(children) else { unreachable!() };
+
+    if is_associative(op) {
+        let mut operands = Vec::new();
+        for operand in [lhs, rhs] {
+            match operand {
+                Norm::Assoc(kind, nested) if kind == op.to_str() => {
+                    if operands.is_empty() {
+                        operands = nested;
+                    } else {
+                        operands.extend(nested);
+                    }
+                }
+                operand => operands.push(operand),
+            }
+        }
+        if matches!(op, BinOpKind::Add | BinOpKind::Mul) {
+            operands = fold(op, operands);
+        }
+        operands.sort();
+        if operands.len() == 1 {
+            return operands.pop().unwrap();
+        }
+        return Norm::Assoc(op.to_str(), operands);
+    }
+
+    if let (Norm::Num(l), Norm::Num(r)) = (&lhs, &rhs) {
+        match op {
+            BinOpKind::Sub => return Norm::Num(l - r),
+            BinOpKind::Div if r.numer().sign() != Sign::NoSign => return Norm::Num(l / r),
+            _ => {}
+        }
+    }
+    let mut operands = vec![lhs, rhs];
+    if op.is_commutative() {
+        operands.sort();
+    }
+    Norm::Node(op.to_str().to_string(), operands)
+}
+
+/// Folds all the numbers of an addition or multiplication chain into one.
+fn fold(op: BinOpKind, operands: Vec<Norm>) -> Vec<Norm> {
+    let mut acc = None;
+    let mut rest = Vec::with_capacity(operands.len());
+    for operand in operands {
+        match operand {
+            Norm::Num(value) => {
+                acc = Some(match acc {
+                    None => value,
+                    Some(acc) if op == BinOpKind::Add => acc + value,
+                    Some(acc) => acc * value,
+                });
+            }
+            operand => rest.push(operand),
+        }
+    }
+    rest.extend(acc.map(Norm::Num));
+    rest
+}
+
+/// Returns `true` if the operator is both associative and commutative, so that chains of it can
+/// be flattened and reordered.
+fn is_associative(op: BinOpKind) -> bool {
+    op.is_commutative() && !matches!(op, BinOpKind::Eq | BinOpKind::Ne)
+}
@@ -1,7 +1,15 @@
-use super::{Box, Lit, SubDenomination, Type};
+use super::{
+    AstPath, Box, ElementaryType, Lit, LitKind, PathSegment, SubDenomination, Type, TypeKind,
+};
 use either::Either;
-use solar_interface::{Ident, Span};
-use std::fmt;
+use num_bigint::Sign;
+use solar_interface::{
+    diagnostics::ErrorGuaranteed,
+    kw,
+    source_map::{DisplayWithContext, SourceMap},
+    sym, Ident, Span, Symbol,
+};
+use std::{collections::HashSet, fmt, hash::BuildHasher, ops::ControlFlow};
 
 /// A list of named arguments: `{a: "1", b: 2}`.
 pub type NamedArgList<'ast> = Box<'ast, [NamedArg<'ast>]>;
@@ -21,6 +29,24 @@ impl AsRef<Self> for Expr<'_> {
     }
 }
 
+/// Renders the source code of the expression, as written.
+///
+/// If the source code is not available, like for expressions with a dummy span, the expression is
+/// rendered with [`Expr::to_minified`] instead, or as its span if it contains errors.
+impl DisplayWithContext for Expr<'_> {
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, ctx: &SourceMap) -> fmt::Result {
+        if !self.span.is_dummy() && ctx.contains_span(self.span) {
+            if let Ok(snippet) = ctx.span_to_snippet(self.span) {
+                return f.write_str(&snippet);
+            }
+        }
+        match self.to_minified() {
+            Some(minified) => f.write_str(&minified),
+            None => self.span.fmt_with(f, ctx),
+        }
+    }
+}
+
 impl<'ast> Expr<'ast> {
     /// Creates a new expression from an identifier.
     pub fn from_ident(ident: Ident) -> Self {
@@ -31,6 +57,731 @@ impl<'ast> Expr<'ast> {
     pub fn from_ty(ty: Type<'ast>) -> Self {
         Self { span: ty.span, kind: ExprKind::Type(ty) }
     }
+
+    /// Returns the innermost expression wrapped in any number of parentheses, e.g. `x` for
+    /// `((x))`.
+    ///
+    /// Parenthesized expressions are represented as [tuples](ExprKind::Tuple) with a single
+    /// component. Genuine tuples, with multiple or missing components, are not unwrapped.
+    pub fn strip_parens(&self) -> &Self {
+        let mut expr = self;
+        while let Some(inner) = expr.paren_inner() {
+            expr = inner;
+        }
+        expr
+    }
+
+    /// Mutable version of [`strip_parens`](Self::strip_parens).
+    pub fn strip_parens_mut(&mut self) -> &mut Self {
+        if self.paren_inner().is_none() {
+            return self;
+        }
+        let ExprKind::Tuple(exprs) = &mut self.kind else { unreachable!() };
+        exprs[0].as_mut().unwrap().strip_parens_mut()
+    }
+
+    /// Returns `true` if this is a parenthesized expression: `(x)`.
+    ///
+    /// The parser records parentheses as written, even redundant ones, so this can be used to
+    /// preserve them when printing the expression. See [`MinifyOptions`](super::MinifyOptions).
+    pub fn is_parenthesized(&self) -> bool {
+        self.paren_inner().is_some()
+    }
+
+    /// Returns the expression wrapped in parentheses, if this is a parenthesized expression.
+    fn paren_inner(&self) -> Option<&Self> {
+        match &self.kind {
+            ExprKind::Tuple(exprs) => match &**exprs {
+                [Some(inner)] => Some(inner),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the name and the optional arguments if this expression has the shape of a modifier
+    /// invocation: either a bare identifier, `onlyOwner`, or a call of one, `onlyRole(ADMIN)`.
+    ///
+    /// Note that modifier invocations in function headers are represented by
+    /// [`Modifier`](super::Modifier) instead.
+    pub fn as_modifier_invocation(&self) -> Option<(Ident, Option<&CallArgs<'ast>>)> {
+        match &self.kind {
+            ExprKind::Ident(ident) => Some((*ident, None)),
+            ExprKind::Call(callee, args) => match &callee.kind {
+                ExprKind::Ident(ident) => Some((*ident, Some(args))),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the kind, the type, and the arguments if this expression is a call of a `new`
+    /// expression, with or without call options: `new C(a, b)`, `new C{salt: s}()`,
+    /// `new uint[](len)`.
+    ///
+    /// Returns `None` if the type cannot be created with `new`, like in `new uint(1)`.
+    pub fn as_new_call(&self) -> Option<(NewKind, &Type<'ast>, &CallArgs<'ast>)> {
+        let ExprKind::Call(callee, args) = &self.kind else { return None };
+        let callee = match &callee.kind {
+            ExprKind::CallOptions(callee, _) => callee,
+            _ => callee,
+        };
+        let ExprKind::New(ty) = &callee.kind else { return None };
+        Some((NewKind::of(ty)?, ty, args))
+    }
+
+    /// Returns the kind of the external call if this expression is one, with or without call
+    /// options: `addr.call{value: v}("")`, `payable(to).transfer(v)`,
+    /// `IERC20(token).approve(a, v)`.
+    ///
+    /// See [`ExternalCallKind`] for the recognized forms.
+    pub fn as_external_call(&self) -> Option<ExternalCallKind> {
+        let ExprKind::Call(callee, call_args) = &self.kind else { return None };
+        let callee = match &callee.kind {
+            ExprKind::CallOptions(callee, _) => callee,
+            _ => callee,
+        };
+        let ExprKind::Member(receiver, member) = &callee.kind else { return None };
+        if let Some(kind) = ExternalCallKind::from_member(member.name) {
+            // The ether `transfer` and `send` take a single amount, unlike the token functions of
+            // the same names, like `transfer(to, amount)`.
+            let is_ether_transfer =
+                matches!(kind, ExternalCallKind::Transfer | ExternalCallKind::Send);
+            if !is_ether_transfer
+                || matches!(&call_args.kind, CallArgsKind::Unnamed(args) if args.len() == 1)
+            {
+                return Some(kind);
+            }
+        }
+        let ExprKind::Call(conversion, args) = &receiver.strip_parens().kind else { return None };
+        let is_conversion = matches!(conversion.kind, ExprKind::Ident(_))
+            && matches!(&args.kind, CallArgsKind::Unnamed(args) if args.len() == 1);
+        is_conversion.then_some(ExternalCallKind::Contract)
+    }
+
+    /// Returns all the [external calls](Self::as_external_call) in this expression, including
+    /// this expression itself, in pre-order.
+    ///
+    /// Every call is a node of the tree, so `a.call(abi.encode(b.send(1)))` contains two external
+    /// calls.
+    pub fn external_calls(&self) -> Vec<&Self> {
+        let mut calls = Vec::new();
+        self.walk_with_path(|expr, _| {
+            if expr.as_external_call().is_some() {
+                calls.push(expr);
+            }
+        });
+        calls
+    }
+
+    /// Returns the callee and the arguments if this expression is a call: `f(a, b)`.
+    pub fn as_call(&self) -> Option<(&Self, &CallArgs<'ast>)> {
+        match &self.kind {
+            ExprKind::Call(callee, args) => Some((callee, args)),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of the revert if this expression is a call of the `revert` builtin:
+    /// `revert()` or `revert("reason")`.
+    ///
+    /// Reverts with custom errors, `revert CustomError(args)`, are statements, and are returned by
+    /// [`Stmt::as_revert`](super::Stmt::as_revert) instead.
+    pub fn as_revert(&self) -> Option<RevertKind<'_, 'ast>> {
+        let ExprKind::Call(callee, args) = &self.kind else { return None };
+        if !matches!(callee.kind, ExprKind::Ident(ident) if ident.name == kw::Revert) {
+            return None;
+        }
+        match &args.kind {
+            CallArgsKind::Unnamed(args) => match &args[..] {
+                [] => Some(RevertKind::Bare),
+                [reason] => Some(RevertKind::Reason(reason)),
+                _ => None,
+            },
+            CallArgsKind::Named(..) => None,
+        }
+    }
+
+    /// Returns the base expression and the member name if this expression is a member access:
+    /// `a.b`.
+    pub fn as_member(&self) -> Option<(&Self, &Ident)> {
+        match &self.kind {
+            ExprKind::Member(base, member) => Some((base, member)),
+            _ => None,
+        }
+    }
+
+    /// Returns the type argument and the member name if this expression is a member access of a
+    /// `type(T)` expression: `type(C).creationCode`.
+    ///
+    /// See [`TypeMember`] for the members that exist.
+    pub fn as_type_member(&self) -> Option<(&Type<'ast>, &Ident)> {
+        let (base, member) = self.as_member()?;
+        match &base.strip_parens().kind {
+            ExprKind::TypeCall(ty) => Some((ty, member)),
+            _ => None,
+        }
+    }
+
+    /// Returns the innermost indexed expression and the index and slice operations applied to it,
+    /// in source order, if this expression is an index or a slice: `a[i][l:r]` returns `a`, and
+    /// `[i]` and `[l:r]`.
+    ///
+    /// Returns `None` if this expression is not an index or a slice. Parentheses around the
+    /// indexed expressions are not looked through.
+    pub fn index_chain(&self) -> Option<(&Self, Vec<&IndexKind<'ast>>)> {
+        let mut indices = Vec::new();
+        let mut expr = self;
+        while let ExprKind::Index(base, kind) = &expr.kind {
+            indices.push(kind);
+            expr = base;
+        }
+        if indices.is_empty() {
+            return None;
+        }
+        indices.reverse();
+        Some((expr, indices))
+    }
+
+    /// Returns the operands and the operator if this expression is a binary operation: `a + b`.
+    ///
+    /// Compound assignments, like `a += b`, are [assignments](ExprKind::Assign) and are not
+    /// included.
+    pub fn as_binary(&self) -> Option<(&Self, BinOpKind, &Self)> {
+        match &self.kind {
+            ExprKind::Binary(lhs, op, rhs) => Some((lhs, op.kind, rhs)),
+            _ => None,
+        }
+    }
+
+    /// Returns the identifier if this expression is an identifier: `a`.
+    pub fn as_ident(&self) -> Option<&Ident> {
+        match &self.kind {
+            ExprKind::Ident(ident) => Some(ident),
+            _ => None,
+        }
+    }
+
+    /// Returns the special identifier if this expression is one: `this`, `super`, `msg`, `tx`, or
+    /// `block`.
+    ///
+    /// This only looks at the name, so identifiers that refer to a declaration that shadows the
+    /// builtin, like a local variable named `msg`, are classified too. After name resolution, use
+    /// the HIR expression's `special_identifier` instead, which does not have this issue.
+    pub fn special_identifier(&self) -> Option<SpecialIdent> {
+        SpecialIdent::from_name(self.as_ident()?.name)
+    }
+
+    /// Returns `true` if this expression is `this`, or `this` converted to an address: `this`,
+    /// `address(this)`, `payable(this)`, or `payable(address(this))`. Parentheses are looked
+    /// through.
+    ///
+    /// Like [`special_identifier`](Self::special_identifier), this only looks at the name.
+    pub fn is_addressable_this(&self) -> bool {
+        let expr = self.strip_parens();
+        let only_arg = |args: &CallArgs<'_>| match &args.kind {
+            CallArgsKind::Unnamed(args) if args.len() == 1 => args[0].is_addressable_this(),
+            _ => false,
+        };
+        match &expr.kind {
+            ExprKind::Ident(_) => expr.special_identifier() == Some(SpecialIdent::This),
+            ExprKind::Call(callee, args) => {
+                matches!(
+                    callee.kind,
+                    ExprKind::Type(Type {
+                        kind: TypeKind::Elementary(ElementaryType::Address(_)),
+                        ..
+                    })
+                ) && only_arg(args)
+            }
+            ExprKind::Payable(args) => only_arg(args),
+            _ => false,
+        }
+    }
+
+    /// Returns the literal and its sub-denomination if this expression is a literal: `1 ether`.
+    pub fn as_lit(&self) -> Option<(&Lit, Option<SubDenomination>)> {
+        match &self.kind {
+            ExprKind::Lit(lit, sub) => Some((lit, *sub)),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the expression is a number literal equal to zero, in any spelling and
+    /// with any sub-denomination: `0`, `0x0`, `0.0`, `0 ether`, `-0`.
+    ///
+    /// Parentheses and leading negations are looked through. Address literals are not numbers
+    /// and are not considered zero.
+    pub fn is_zero_literal(&self) -> bool {
+        let mut expr = self.strip_parens();
+        while let ExprKind::Unary(op, inner) = &expr.kind {
+            if op.kind != UnOpKind::Neg {
+                break;
+            }
+            expr = inner.strip_parens();
+        }
+        match &expr.kind {
+            ExprKind::Lit(lit, _) => match &lit.kind {
+                LitKind::Number(value) => value.sign() == Sign::NoSign,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the expression is a number literal equal to one, in any spelling: `1`,
+    /// `0x01`, `1e0`, `1 wei`.
+    ///
+    /// Parentheses are looked through, but negations are not. Sub-denominations other than `wei`
+    /// and `seconds` scale the value, so `1 ether` is not one.
+    pub fn is_one_literal(&self) -> bool {
+        match &self.strip_parens().kind {
+            ExprKind::Lit(lit, sub) => match &lit.kind {
+                LitKind::Number(value) => {
+                    value.sign() == Sign::Plus
+                        && value.bits() == 1
+                        && sub.is_none_or(|sub| sub.value() == 1)
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the expression is the `true` literal, looking through parentheses.
+    pub fn is_true_literal(&self) -> bool {
+        self.as_bool_literal() == Some(true)
+    }
+
+    /// Returns `true` if the expression is the `false` literal, looking through parentheses.
+    pub fn is_false_literal(&self) -> bool {
+        self.as_bool_literal() == Some(false)
+    }
+
+    fn as_bool_literal(&self) -> Option<bool> {
+        match &self.strip_parens().kind {
+            ExprKind::Lit(lit, None) => match lit.kind {
+                LitKind::Bool(value) => Some(value),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the expression is an empty string literal of any kind, looking through
+    /// parentheses: `""`, `unicode""`, `hex""`.
+    pub fn is_empty_string_literal(&self) -> bool {
+        match &self.strip_parens().kind {
+            ExprKind::Lit(lit, None) => match &lit.kind {
+                LitKind::Str(_, value) => value.is_empty(),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if evaluating the expression has no side effects.
+    ///
+    /// This is a conservative syntactic check: calls, assignments, increments, decrements, and
+    /// `delete` are always considered impure, even if the call is a type conversion or a call to a
+    /// `pure` function. Reverting, for example on overflow, is not considered a side effect.
+    pub fn is_pure(&self) -> bool {
+        let opt = |expr: &Option<Box<'ast, Self>>| expr.as_ref().is_none_or(|e| e.is_pure());
+        match &self.kind {
+            ExprKind::Assign(..) | ExprKind::Call(..) | ExprKind::Delete(_) => false,
+            ExprKind::Unary(op, expr) => !op.kind.is_modifying() && expr.is_pure(),
+            ExprKind::Array(exprs) => exprs.iter().all(|e| e.is_pure()),
+            ExprKind::Binary(lhs, _, rhs) => lhs.is_pure() && rhs.is_pure(),
+            ExprKind::CallOptions(expr, args) => {
+                expr.is_pure() && args.iter().all(|arg| arg.value.is_pure())
+            }
+            ExprKind::Index(expr, kind) => {
+                expr.is_pure()
+                    && match kind {
+                        IndexKind::Index(index) => opt(index),
+                        IndexKind::Range(start, end) => opt(start) && opt(end),
+                    }
+            }
+            ExprKind::Member(expr, _) => expr.is_pure(),
+            ExprKind::Payable(args) => args.exprs().all(Self::is_pure),
+            ExprKind::Ternary(cond, true_, false_) => {
+                cond.is_pure() && true_.is_pure() && false_.is_pure()
+            }
+            ExprKind::Tuple(exprs) => exprs.iter().all(opt),
+            ExprKind::Err(_)
+            | ExprKind::Ident(_)
+            | ExprKind::Lit(..)
+            | ExprKind::New(_)
+            | ExprKind::TypeCall(_)
+            | ExprKind::Type(_) => true,
+        }
+    }
+
+    /// Returns the identifiers that the expression reads, in pre-order and source order, with
+    /// duplicates: `a`, `f`, `b`, and `a` for `a.x + f(b, a)`.
+    ///
+    /// Member names and the names of named arguments are not identifier expressions, and are not
+    /// included. Expressions nested in types, like array sizes, are not visited.
+    pub fn free_identifiers(&self) -> Vec<&Ident> {
+        let mut idents = Vec::new();
+        self.walk_with_path(|expr, _| {
+            if let ExprKind::Ident(ident) = &expr.kind {
+                idents.push(ident);
+            }
+        });
+        idents
+    }
+
+    /// Returns `true` if the expression evaluates to the same value every time it is evaluated,
+    /// as long as none of the variables in `modified` change: it is [pure](Self::is_pure), and
+    /// none of its [identifiers](Self::free_identifiers) are in `modified`.
+    ///
+    /// For loop-invariant code motion, `modified` must contain all the variables that may be
+    /// modified in the loop, including state variables modified by calls. Note that assigning to
+    /// a member or an element of a variable, like `a[i] = x`, modifies the whole variable `a`.
+    pub fn is_invariant<S: BuildHasher>(&self, modified: &HashSet<Symbol, S>) -> bool {
+        self.is_pure()
+            && self.free_identifiers().iter().all(|ident| !modified.contains(&ident.name))
+    }
+
+    /// Returns the outermost [invariant](Self::is_invariant) sub-expressions of the expression,
+    /// including the expression itself, in source order. These are the candidates for hoisting
+    /// out of a loop in which the variables in `modified` are modified.
+    ///
+    /// Identifiers, literals, and types are not candidates, since they are as cheap to evaluate as
+    /// a hoisted value, so `x * 2` is a candidate in `y + x * 2` with `y` modified, but `x` and `2`
+    /// alone are not.
+    pub fn invariant_subexprs<S: BuildHasher>(&self, modified: &HashSet<Symbol, S>) -> Vec<&Self> {
+        let mut candidates = Vec::new();
+        // The depth of the last candidate: its sub-expressions are visited right after it.
+        let mut candidate_depth = None;
+        self.walk_with_path(|expr, path| {
+            if candidate_depth.is_some_and(|depth| path.len() > depth) {
+                return;
+            }
+            candidate_depth = None;
+            let is_leaf = matches!(
+                expr.kind,
+                ExprKind::Ident(_)
+                    | ExprKind::Lit(..)
+                    | ExprKind::New(_)
+                    | ExprKind::Type(_)
+                    | ExprKind::TypeCall(_)
+                    | ExprKind::Err(_)
+            );
+            if !is_leaf && expr.is_invariant(modified) {
+                candidates.push(expr);
+                candidate_depth = Some(path.len());
+            }
+        });
+        candidates
+    }
+
+    /// Returns `true` if the expression can syntactically be assigned to: an identifier, a member
+    /// access, an index access, or a [tuple of them](Self::is_tuple_assignment_target), possibly
+    /// parenthesized.
+    ///
+    /// Whether the expression refers to a mutable location can only be determined after name
+    /// resolution and type checking.
+    pub fn is_lvalue(&self) -> bool {
+        match &self.strip_parens().kind {
+            ExprKind::Ident(_)
+            | ExprKind::Member(..)
+            | ExprKind::Index(_, IndexKind::Index(Some(_))) => true,
+            ExprKind::Tuple(_) => self.is_tuple_assignment_target(),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the expression is a tuple that can be the target of a destructuring
+    /// assignment: `(a, , b.c) = t`.
+    ///
+    /// Components may be missing, in which case the corresponding value is not assigned. The
+    /// components that are present must be [lvalues](Self::is_lvalue), and may be tuples
+    /// themselves.
+    pub fn is_tuple_assignment_target(&self) -> bool {
+        match &self.strip_parens().kind {
+            ExprKind::Tuple(exprs) => {
+                !exprs.is_empty() && exprs.iter().flatten().all(|expr| expr.is_lvalue())
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the identifiers at the root of the lvalue, which name the variables modified by
+    /// assigning to it: `a` for `a.b[i].c`, and `x` and `y` for `(x, , y.z)`.
+    ///
+    /// Roots that are not identifiers, like `f()` in `f().x`, are skipped, so the result is
+    /// empty for `f().x`. Use [`lvalue_non_variable_roots`](Self::lvalue_non_variable_roots) to
+    /// find them.
+    pub fn lvalue_roots(&self) -> Vec<&Ident> {
+        let mut roots = Vec::new();
+        self.collect_lvalue_roots(&mut roots);
+        roots.into_iter().filter_map(Self::as_ident).collect()
+    }
+
+    /// Returns the roots of the lvalue that are not identifiers, like `f()` in `f().x` or
+    /// `[a, b]` in `[a, b][i]`.
+    ///
+    /// The modified variables of these lvalues cannot be determined syntactically. See
+    /// [`lvalue_roots`](Self::lvalue_roots).
+    pub fn lvalue_non_variable_roots(&self) -> Vec<&Self> {
+        let mut roots = Vec::new();
+        self.collect_lvalue_roots(&mut roots);
+        roots.retain(|root| root.as_ident().is_none());
+        roots
+    }
+
+    fn collect_lvalue_roots<'a>(&'a self, roots: &mut Vec<&'a Self>) {
+        let expr = self.strip_parens();
+        match &expr.kind {
+            ExprKind::Member(base, _) | ExprKind::Index(base, _) => {
+                base.collect_lvalue_roots(roots)
+            }
+            ExprKind::Tuple(exprs) => {
+                for expr in exprs.iter().flatten() {
+                    expr.collect_lvalue_roots(roots);
+                }
+            }
+            _ => roots.push(expr),
+        }
+    }
+
+    /// Returns the types referenced by the expression and its sub-expressions, in source order.
+    ///
+    /// This includes the types of elementary type expressions, like `uint8` in `uint8(x)`, of
+    /// `new` expressions, like `new C()`, and of `type` expressions, like
+    /// `type(I).interfaceId`. Conversions to user-defined types, like `C(addr)`, cannot be told
+    /// apart from function calls without name resolution, and are not included. Expressions nested
+    /// in types, like array sizes, are not visited.
+    pub fn referenced_types(&self) -> Vec<&Type<'ast>> {
+        let mut types = Vec::new();
+        self.collect_referenced_types(&mut types);
+        types
+    }
+
+    fn collect_referenced_types<'a>(&'a self, types: &mut Vec<&'a Type<'ast>>) {
+        let opt = |expr: &'a Option<Box<'ast, Self>>, types: &mut Vec<&'a Type<'ast>>| {
+            if let Some(expr) = expr {
+                expr.collect_referenced_types(types);
+            }
+        };
+        match &self.kind {
+            ExprKind::New(ty) | ExprKind::TypeCall(ty) | ExprKind::Type(ty) => types.push(ty),
+            ExprKind::Array(exprs) => exprs.iter().for_each(|e| e.collect_referenced_types(types)),
+            ExprKind::Assign(lhs, _, rhs) | ExprKind::Binary(lhs, _, rhs) => {
+                lhs.collect_referenced_types(types);
+                rhs.collect_referenced_types(types);
+            }
+            ExprKind::Call(expr, args) => {
+                expr.collect_referenced_types(types);
+                args.exprs().for_each(|e| e.collect_referenced_types(types));
+            }
+            ExprKind::CallOptions(expr, args) => {
+                expr.collect_referenced_types(types);
+                args.iter().for_each(|arg| arg.value.collect_referenced_types(types));
+            }
+            ExprKind::Delete(expr) | ExprKind::Member(expr, _) | ExprKind::Unary(_, expr) => {
+                expr.collect_referenced_types(types)
+            }
+            ExprKind::Index(expr, kind) => {
+                expr.collect_referenced_types(types);
+                match kind {
+                    IndexKind::Index(index) => opt(index, types),
+                    IndexKind::Range(start, end) => {
+                        opt(start, types);
+                        opt(end, types);
+                    }
+                }
+            }
+            ExprKind::Payable(args) => args.exprs().for_each(|e| e.collect_referenced_types(types)),
+            ExprKind::Ternary(cond, true_, false_) => {
+                cond.collect_referenced_types(types);
+                true_.collect_referenced_types(types);
+                false_.collect_referenced_types(types);
+            }
+            ExprKind::Tuple(exprs) => {
+                exprs.iter().flatten().for_each(|e| e.collect_referenced_types(types))
+            }
+            ExprKind::Err(_) | ExprKind::Ident(_) | ExprKind::Lit(..) => {}
+        }
+    }
+
+    /// Returns the total cost of the expression and its sub-expressions, given the cost `f` of
+    /// each node.
+    ///
+    /// `f` is called exactly once for every expression node, including parentheses, which are
+    /// [tuples](ExprKind::Tuple) of one element. Expressions nested in types, like array sizes,
+    /// are not visited. The total saturates at `u64::MAX`.
+    pub fn cost<F: Fn(&ExprKind<'ast>) -> u64>(&self, f: F) -> u64 {
+        let mut total = 0u64;
+        self.walk_with_path(|expr, _| total = total.saturating_add(f(&expr.kind)));
+        total
+    }
+
+    /// Returns the number of expression nodes in the expression, including itself.
+    ///
+    /// This is the [`cost`](Self::cost) of the expression with a cost of one per node.
+    pub fn node_count(&self) -> usize {
+        self.cost(|_| 1) as usize
+    }
+
+    /// Returns the first non-`None` result of `f` on the expression and its sub-expressions,
+    /// stopping the traversal there.
+    ///
+    /// The expressions are visited in pre-order, like in [`walk_with_path`](Self::walk_with_path):
+    /// an expression is visited before its sub-expressions, which are visited in source order, so
+    /// the first match is the leftmost outermost one. Parentheses, which are
+    /// [tuples](ExprKind::Tuple) of one element, are visited like any other expression.
+    /// Expressions nested in types, like array sizes, are not visited.
+    pub fn find_map<T>(&self, mut f: impl FnMut(&Self) -> Option<T>) -> Option<T> {
+        self.try_walk_with_path(|expr, _| match f(expr) {
+            Some(value) => ControlFlow::Break(value),
+            None => ControlFlow::Continue(()),
+        })
+        .break_value()
+    }
+
+    /// Returns `true` if `pred` returns `true` for the expression or any of its sub-expressions.
+    ///
+    /// This stops at the first match, in the order of [`find_map`](Self::find_map).
+    pub fn any(&self, mut pred: impl FnMut(&Self) -> bool) -> bool {
+        self.find_map(|e| pred(e).then_some(())).is_some()
+    }
+
+    /// Appends the spans in the expression to `out`, in pre-order and source order.
+    ///
+    /// This is the span of every expression, followed by the spans of its parts: operators,
+    /// member names, argument lists and the names of named arguments. A part is left out if it
+    /// has the same span as its expression, like the name in an identifier expression, so no
+    /// span is appended twice. Like in [`find_map`](Self::find_map), expressions nested in types
+    /// are not visited.
+    pub fn collect_spans(&self, out: &mut Vec<Span>) {
+        // The ancestors of the visited expression, from this expression to its parent.
+        let mut ancestors = Vec::<&Self>::new();
+        // The spans of the parts that follow the sub-expression at the given depth, appended once
+        // the walk leaves it. The spans of a sub-expression are in reverse order.
+        let mut deferred = Vec::<(usize, Span)>::new();
+        self.walk_with_path(|expr, path| {
+            let depth = path.len();
+            while let Some(&(_, span)) = deferred.last().filter(|&&(d, _)| d >= depth) {
+                out.push(span);
+                deferred.pop();
+            }
+            ancestors.truncate(depth);
+
+            // The parts of the parent that are between its sub-expressions.
+            if let (Some(parent), Some(&segment)) = (ancestors.last(), path.last()) {
+                match (&parent.kind, segment) {
+                    (ExprKind::Assign(_, Some(op), _), PathSegment::AssignRhs)
+                    | (ExprKind::Binary(_, op, _), PathSegment::BinaryRhs) => out.push(op.span),
+                    (ExprKind::Call(_, args), PathSegment::Callee) => {
+                        if let CallArgsKind::Named(_, span) = &args.kind {
+                            deferred.push((depth, *span));
+                        }
+                        deferred.push((depth, args.span));
+                    }
+                    (ExprKind::Call(_, args), PathSegment::CallArg(i))
+                    | (ExprKind::Payable(args), PathSegment::PayableArg(i)) => {
+                        if let CallArgsKind::Named(args, _) = &args.kind {
+                            out.push(args[i].name.span);
+                        }
+                    }
+                    (ExprKind::CallOptions(_, options), PathSegment::CallOption(i)) => {
+                        out.push(options[i].name.span);
+                    }
+                    (ExprKind::Member(_, member), PathSegment::MemberBase) => {
+                        deferred.push((depth, member.span));
+                    }
+                    (ExprKind::Unary(op, _), PathSegment::UnaryOperand) => {
+                        if op.kind.is_postfix() {
+                            deferred.push((depth, op.span));
+                        } else {
+                            out.push(op.span);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            out.push(expr.span);
+            match &expr.kind {
+                ExprKind::Ident(Ident { span, .. })
+                | ExprKind::Lit(Lit { span, .. }, _)
+                | ExprKind::New(Type { span, .. })
+                | ExprKind::TypeCall(Type { span, .. })
+                | ExprKind::Type(Type { span, .. }) => {
+                    if *span != expr.span {
+                        out.push(*span);
+                    }
+                }
+                ExprKind::Payable(args) => {
+                    out.push(args.span);
+                    if let CallArgsKind::Named(_, span) = &args.kind {
+                        out.push(*span);
+                    }
+                }
+                _ => {}
+            }
+            ancestors.push(expr);
+        });
+        out.extend(deferred.into_iter().rev().map(|(_, span)| span));
+    }
+
+    /// Returns an estimate of the number of bytes used by this expression and all of its
+    /// sub-nodes.
+    ///
+    /// This includes the size of every node, the slices holding their children, and the heap data
+    /// owned by literals, such as string contents and big integer digits. Since nodes are
+    /// allocated in an [`Arena`](super::Arena), slices have no spare capacity. Interned symbols
+    /// are shared by the whole session and are not counted.
+    pub fn approx_memory_bytes(&self) -> usize {
+        size_of::<Self>() + self.heap_bytes()
+    }
+
+    /// Returns the number of bytes used by the nodes that this expression points to.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        let opt =
+            |expr: &Option<Box<'ast, Self>>| expr.as_ref().map_or(0, |e| e.approx_memory_bytes());
+        match &self.kind {
+            ExprKind::Array(exprs) => exprs_memory_bytes(exprs),
+            ExprKind::Assign(lhs, _, rhs) | ExprKind::Binary(lhs, _, rhs) => {
+                lhs.approx_memory_bytes() + rhs.approx_memory_bytes()
+            }
+            ExprKind::Call(expr, args) => expr.approx_memory_bytes() + args.heap_bytes(),
+            ExprKind::CallOptions(expr, args) => {
+                expr.approx_memory_bytes() + named_args_memory_bytes(args)
+            }
+            ExprKind::Delete(expr) | ExprKind::Member(expr, _) | ExprKind::Unary(_, expr) => {
+                expr.approx_memory_bytes()
+            }
+            ExprKind::Err(_) | ExprKind::Ident(_) => 0,
+            ExprKind::Index(expr, kind) => {
+                expr.approx_memory_bytes()
+                    + match kind {
+                        IndexKind::Index(index) => opt(index),
+                        IndexKind::Range(start, end) => opt(start) + opt(end),
+                    }
+            }
+            ExprKind::Lit(lit, _) => lit.approx_memory_bytes(),
+            ExprKind::New(ty) | ExprKind::TypeCall(ty) | ExprKind::Type(ty) => ty.heap_bytes(),
+            ExprKind::Payable(args) => args.heap_bytes(),
+            ExprKind::Ternary(cond, true_, false_) => {
+                cond.approx_memory_bytes()
+                    + true_.approx_memory_bytes()
+                    + false_.approx_memory_bytes()
+            }
+            ExprKind::Tuple(exprs) => size_of_val(&**exprs) + exprs.iter().map(opt).sum::<usize>(),
+        }
+    }
+}
+
+fn exprs_memory_bytes(exprs: &[Box<'_, Expr<'_>>]) -> usize {
+    size_of_val(exprs) + exprs.iter().map(|e| e.approx_memory_bytes()).sum::<usize>()
+}
+
+fn named_args_memory_bytes(args: &[NamedArg<'_>]) -> usize {
+    size_of_val(args) + args.iter().map(|arg| arg.value.approx_memory_bytes()).sum::<usize>()
 }
 
 /// A kind of expression.
@@ -54,6 +805,12 @@ pub enum ExprKind<'ast> {
     /// A unary `delete` expression: `delete vector`.
     Delete(Box<'ast, Expr<'ast>>),
 
+    /// A placeholder for an expression that could not be parsed, which has been emitted as an
+    /// error.
+    ///
+    /// This is created when recovering from missing operands, like in `a + ` or `f(a, )`.
+    Err(ErrorGuaranteed),
+
     /// An identifier: `foo`.
     Ident(Ident),
 
@@ -66,7 +823,11 @@ pub enum ExprKind<'ast> {
     /// Access of a named member: `obj.k`.
     Member(Box<'ast, Expr<'ast>>, Ident),
 
-    /// A `new` expression: `new Contract`.
+    /// A `new` expression: `new Contract`, `new uint[]`.
+    ///
+    /// Like in `solc`, this only contains the type: the constructor arguments of a contract, or
+    /// the length of a memory allocation, are the arguments of the wrapping
+    /// [`Call`](Self::Call). See [`Expr::as_new_call`] and [`NewKind`].
     New(Type<'ast>),
 
     /// A `payable` expression: `payable(address(0x...))`.
@@ -202,6 +963,92 @@ impl BinOpKind {
             | Self::Pow => false,
         }
     }
+
+    /// Returns `true` if the operator is a comparison operator: `<`, `<=`, `>`, `>=`, `==`, `!=`.
+    pub const fn is_comparison(self) -> bool {
+        matches!(self, Self::Lt | Self::Le | Self::Gt | Self::Ge | Self::Eq | Self::Ne)
+    }
+
+    /// Returns the comparison operator with the opposite result, if this is a comparison
+    /// operator: `a < b` is `!(a >= b)`.
+    pub const fn negate(self) -> Option<Self> {
+        match self {
+            Self::Lt => Some(Self::Ge),
+            Self::Le => Some(Self::Gt),
+            Self::Gt => Some(Self::Le),
+            Self::Ge => Some(Self::Lt),
+            Self::Eq => Some(Self::Ne),
+            Self::Ne => Some(Self::Eq),
+            _ => None,
+        }
+    }
+
+    /// Returns the comparison operator with the same result when the operands are swapped, if
+    /// this is a comparison operator: `a < b` is `b > a`.
+    pub const fn flip_comparison(self) -> Option<Self> {
+        match self {
+            Self::Lt => Some(Self::Gt),
+            Self::Le => Some(Self::Ge),
+            Self::Gt => Some(Self::Lt),
+            Self::Ge => Some(Self::Le),
+            Self::Eq | Self::Ne => Some(self),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the operands of the operator can be swapped without changing the result:
+    /// `+`, `*`, `&`, `|`, `^`, `==`, `!=`, `&&`, `||`.
+    ///
+    /// Note that this does not account for the evaluation order of the operands, see
+    /// [`Expr::is_pure`].
+    pub const fn is_commutative(self) -> bool {
+        matches!(
+            self,
+            Self::Add
+                | Self::Mul
+                | Self::BitAnd
+                | Self::BitOr
+                | Self::BitXor
+                | Self::Eq
+                | Self::Ne
+                | Self::And
+                | Self::Or
+        )
+    }
+
+    /// Returns a rough estimate of the EVM gas cost of evaluating the operator, excluding the cost
+    /// of evaluating its operands.
+    ///
+    /// The estimate is based on the cost of the underlying opcodes, and does not account for
+    /// checked arithmetic, stack manipulation, or differences between EVM versions. It should
+    /// only be used for relative comparisons.
+    ///
+    /// `**` is special in that its actual cost grows with the size of the exponent (`EXP`
+    /// costs 10 gas plus 50 gas per byte of the exponent). The value returned here is the upper
+    /// bound for a 32-byte exponent.
+    pub const fn approx_gas(self) -> u32 {
+        match self {
+            // `LT`, `GT`, `EQ`.
+            Self::Lt | Self::Gt | Self::Eq => 3,
+            // `GT`/`LT`/`EQ` + `ISZERO`.
+            Self::Le | Self::Ge | Self::Ne => 6,
+            // `ISZERO` + `JUMPI` for short-circuiting.
+            Self::Or | Self::And => 13,
+            // `SHR`, `SHL`, `SAR`, `AND`, `OR`, `XOR`, `ADD`, `SUB`.
+            Self::Shr
+            | Self::Shl
+            | Self::Sar
+            | Self::BitAnd
+            | Self::BitOr
+            | Self::BitXor
+            | Self::Add
+            | Self::Sub => 3,
+            // `MUL`, `DIV`/`SDIV`, `MOD`/`SMOD`.
+            Self::Mul | Self::Div | Self::Rem => 5,
+            // `EXP` with a 32-byte exponent.
+            Self::Pow => 10 + 50 * 32,
+        }
+    }
 }
 
 /// A unary operation: `!x`, `-x`, `x++`.
@@ -263,25 +1110,303 @@ impl UnOpKind {
     pub const fn is_postfix(self) -> bool {
         !self.is_prefix()
     }
+
+    /// Returns `true` if the operator modifies its operand: `++` and `--`.
+    pub const fn is_modifying(self) -> bool {
+        matches!(self, Self::PreInc | Self::PreDec | Self::PostInc | Self::PostDec)
+    }
+
+    /// Returns a rough estimate of the EVM gas cost of evaluating the operator, excluding the cost
+    /// of evaluating its operand.
+    ///
+    /// See [`BinOpKind::approx_gas`] for more details.
+    pub const fn approx_gas(self) -> u32 {
+        match self {
+            // `ISZERO`, `NOT`, `SUB` from zero.
+            Self::Not | Self::BitNot | Self::Neg => 3,
+            // `PUSH1 1` + `ADD`/`SUB`.
+            Self::PreInc | Self::PreDec | Self::PostInc | Self::PostDec => 6,
+        }
+    }
+}
+
+/// The kind of a [`New`](ExprKind::New) expression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NewKind {
+    /// A contract creation: `new C(a, b)`. The call arguments are the constructor arguments.
+    Contract,
+    /// A memory allocation of a dynamic array, `bytes`, or `string`: `new uint[](len)`,
+    /// `new bytes(32)`. The only call argument is the length.
+    Allocation,
+}
+
+impl NewKind {
+    /// Returns the kind of a `new` expression creating the given type, or `None` if the type
+    /// cannot be created with `new`.
+    ///
+    /// Custom types are assumed to be contracts, since this is the only kind of user-defined type
+    /// that can be created with `new`.
+    pub fn of(ty: &Type<'_>) -> Option<Self> {
+        match &ty.kind {
+            TypeKind::Custom(_) => Some(Self::Contract),
+            TypeKind::Array(array) if array.size.is_none() => Some(Self::Allocation),
+            TypeKind::Elementary(ElementaryType::Bytes | ElementaryType::String) => {
+                Some(Self::Allocation)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The kind of an [external call](Expr::as_external_call).
+///
+/// External calls are recognized syntactically, since the type of the receiver is not known
+/// without type checking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExternalCallKind {
+    /// A low-level `call`: `addr.call(data)`.
+    Call,
+    /// A low-level `delegatecall`: `addr.delegatecall(data)`.
+    Delegatecall,
+    /// A low-level `staticcall`: `addr.staticcall(data)`.
+    Staticcall,
+    /// An ether transfer that reverts on failure: `addr.transfer(amount)`.
+    ///
+    /// Only calls with a single unnamed argument are ether transfers. Others, like
+    /// `IERC20(t).transfer(to, amount)`, are [contract calls](Self::Contract) if the receiver is
+    /// a conversion.
+    Transfer,
+    /// An ether transfer that returns `false` on failure: `addr.send(amount)`.
+    ///
+    /// Like for [`Transfer`](Self::Transfer), only calls with a single unnamed argument are
+    /// ether transfers.
+    Send,
+    /// A call of a function of a contract or interface, on an explicit conversion of an address:
+    /// `IERC20(token).approve(spender, amount)`.
+    ///
+    /// Calls on variables of contract types, like `token.approve(spender, amount)`, cannot be
+    /// told apart from internal library calls without type checking, and are not included.
+    /// Single-argument function calls, like `f(x).g()`, are assumed to be conversions.
+    Contract,
+}
+
+impl ExternalCallKind {
+    /// Returns the kind of an external call of the given address member, or `None` if it is not a
+    /// low-level call or transfer member.
+    ///
+    /// This only looks at the name, so a call of `transfer` or `send` may still be a contract
+    /// call. See [`Expr::as_external_call`].
+    pub fn from_member(name: Symbol) -> Option<Self> {
+        match name {
+            kw::Call => Some(Self::Call),
+            kw::Delegatecall => Some(Self::Delegatecall),
+            kw::Staticcall => Some(Self::Staticcall),
+            sym::transfer => Some(Self::Transfer),
+            sym::send => Some(Self::Send),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this is a low-level call: `call`, `delegatecall` or `staticcall`.
+    pub fn is_low_level(self) -> bool {
+        matches!(self, Self::Call | Self::Delegatecall | Self::Staticcall)
+    }
+}
+
+/// A revert, as returned by [`Expr::as_revert`] and [`Stmt::as_revert`](super::Stmt::as_revert).
+#[derive(Clone, Copy, Debug)]
+pub enum RevertKind<'a, 'ast> {
+    /// A revert without data: `revert()`.
+    Bare,
+    /// A revert with a reason string, encoded as an `Error(string)`: `revert("reason")`.
+    Reason(&'a Expr<'ast>),
+    /// A revert with a custom error: `revert CustomError(args)`. The arguments may be empty.
+    CustomError(&'a AstPath<'ast>, &'a CallArgs<'ast>),
+}
+
+/// A builtin global identifier with a special meaning in contracts.
+///
+/// See [`Expr::special_identifier`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpecialIdent {
+    /// `this`: the current contract. Calls of its functions, like `this.f()`, are external calls.
+    This,
+    /// `super`: the contract before the current one in the inheritance linearization.
+    Super,
+    /// `msg`: the current message call, like `msg.sender`.
+    Msg,
+    /// `tx`: the current transaction, like `tx.origin`.
+    Tx,
+    /// `block`: the current block, like `block.timestamp`.
+    Block,
+}
+
+impl SpecialIdent {
+    /// Returns the special identifier with the given name, or `None` if the name is not special.
+    pub fn from_name(name: Symbol) -> Option<Self> {
+        match name {
+            sym::this => Some(Self::This),
+            sym::super_ => Some(Self::Super),
+            sym::msg => Some(Self::Msg),
+            sym::tx => Some(Self::Tx),
+            sym::block => Some(Self::Block),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the identifier.
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::This => "this",
+            Self::Super => "super",
+            Self::Msg => "msg",
+            Self::Tx => "tx",
+            Self::Block => "block",
+        }
+    }
+}
+
+/// A member of a `type(T)` expression, like `type(C).creationCode`.
+///
+/// Which members are available depends on what `T` is, which is not known without name
+/// resolution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TypeMember {
+    /// The creation bytecode of a contract: `type(C).creationCode`.
+    CreationCode,
+    /// The runtime bytecode of a contract: `type(C).runtimeCode`.
+    RuntimeCode,
+    /// The name of a contract or interface: `type(C).name`.
+    Name,
+    /// The EIP-165 interface identifier of an interface: `type(I).interfaceId`.
+    InterfaceId,
+    /// The smallest value of an integer or enum type: `type(uint8).min`.
+    Min,
+    /// The largest value of an integer or enum type: `type(uint8).max`.
+    Max,
+}
+
+impl TypeMember {
+    /// Returns the member with the given name, or `None` if no `type(T)` has such a member.
+    pub fn from_member(name: Symbol) -> Option<Self> {
+        match name {
+            sym::creationCode => Some(Self::CreationCode),
+            sym::runtimeCode => Some(Self::RuntimeCode),
+            sym::name => Some(Self::Name),
+            sym::interfaceId => Some(Self::InterfaceId),
+            sym::min => Some(Self::Min),
+            sym::max => Some(Self::Max),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this member exists on `type(T)` for an elementary type `T`: `min` and
+    /// `max` for integer types.
+    pub fn applies_to_elementary(self, ty: ElementaryType) -> bool {
+        matches!(self, Self::Min | Self::Max)
+            && matches!(ty, ElementaryType::Int(_) | ElementaryType::UInt(_))
+    }
 }
 
 /// A list of function call arguments.
 #[derive(Debug)]
-pub enum CallArgs<'ast> {
+pub struct CallArgs<'ast> {
+    /// The span of the arguments, including the parentheses: `(1, 2, 3)`.
+    ///
+    /// If the arguments are omitted, like in modifier invocations without parentheses, this is
+    /// an empty span right after the callee.
+    pub span: Span,
+    /// The arguments.
+    pub kind: CallArgsKind<'ast>,
+}
+
+impl Default for CallArgs<'_> {
+    fn default() -> Self {
+        Self::empty(Span::DUMMY)
+    }
+}
+
+impl<'ast> CallArgs<'ast> {
+    /// Creates a new empty list of unnamed arguments.
+    pub fn empty(span: Span) -> Self {
+        Self { span, kind: CallArgsKind::empty() }
+    }
+
+    /// Returns the length of the arguments.
+    pub fn len(&self) -> usize {
+        self.kind.len()
+    }
+
+    /// Returns `true` if the list of arguments is empty.
+    pub fn is_empty(&self) -> bool {
+        self.kind.is_empty()
+    }
+
+    /// Returns the number of bytes used by the argument list's slice and expressions.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        self.kind.heap_bytes()
+    }
+
+    /// Returns an iterator over the expressions.
+    pub fn exprs(
+        &self,
+    ) -> impl ExactSizeIterator<Item = &Expr<'ast>> + DoubleEndedIterator + Clone {
+        self.kind.exprs()
+    }
+
+    /// Returns an iterator over the expressions.
+    pub fn exprs_mut(
+        &mut self,
+    ) -> impl ExactSizeIterator<Item = &mut Box<'ast, Expr<'ast>>> + DoubleEndedIterator {
+        self.kind.exprs_mut()
+    }
+
+    /// Pairs each argument of an event emission with whether the parameter it is passed to is
+    /// `indexed`, that is whether it is logged as a topic.
+    ///
+    /// `params` are the name and `indexed` flag of each parameter of the event, in declaration
+    /// order, like `event.parameters.iter().map(|p| (p.name.map(|n| n.name), p.indexed))` for an
+    /// [`ItemEvent`](super::ItemEvent). Unnamed arguments are matched with the parameters by
+    /// position, and named arguments by name. The arguments are returned in source order, and
+    /// arguments that do not match any parameter are paired with `None`.
+    pub fn with_indexed<'a>(
+        &'a self,
+        params: &'a [(Option<Symbol>, bool)],
+    ) -> impl ExactSizeIterator<Item = (&'a Expr<'ast>, Option<bool>)> + Clone + 'a {
+        match &self.kind {
+            CallArgsKind::Unnamed(exprs) => Either::Left(
+                exprs
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, expr)| (&**expr, params.get(i).map(|p| p.1))),
+            ),
+            CallArgsKind::Named(args, _) => Either::Right(args.iter().map(move |arg| {
+                let param = params.iter().find(|p| p.0 == Some(arg.name.name));
+                (&*arg.value, param.map(|p| p.1))
+            })),
+        }
+    }
+}
+
+/// A list of function call arguments.
+#[derive(Debug)]
+pub enum CallArgsKind<'ast> {
     /// A list of unnamed arguments: `(1, 2, 3)`.
     Unnamed(Box<'ast, [Box<'ast, Expr<'ast>>]>),
 
     /// A list of named arguments: `({x: 1, y: 2, z: 3})`.
-    Named(NamedArgList<'ast>),
+    ///
+    /// The span covers the braces: `{x: 1, y: 2, z: 3}`.
+    Named(NamedArgList<'ast>, Span),
 }
 
-impl Default for CallArgs<'_> {
+impl Default for CallArgsKind<'_> {
     fn default() -> Self {
         Self::empty()
     }
 }
 
-impl<'ast> CallArgs<'ast> {
+impl<'ast> CallArgsKind<'ast> {
     /// Creates a new empty list of unnamed arguments.
     pub fn empty() -> Self {
         Self::Unnamed(Box::default())
@@ -291,7 +1416,7 @@ impl<'ast> CallArgs<'ast> {
     pub fn len(&self) -> usize {
         match self {
             Self::Unnamed(exprs) => exprs.len(),
-            Self::Named(args) => args.len(),
+            Self::Named(args, _) => args.len(),
         }
     }
 
@@ -300,13 +1425,21 @@ impl<'ast> CallArgs<'ast> {
         self.len() == 0
     }
 
+    /// Returns the number of bytes used by the argument list's slice and expressions.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        match self {
+            Self::Unnamed(exprs) => exprs_memory_bytes(exprs),
+            Self::Named(args, _) => named_args_memory_bytes(args),
+        }
+    }
+
     /// Returns an iterator over the expressions.
     pub fn exprs(
         &self,
     ) -> impl ExactSizeIterator<Item = &Expr<'ast>> + DoubleEndedIterator + Clone {
         match self {
             Self::Unnamed(exprs) => Either::Left(exprs.iter().map(|expr| &**expr)),
-            Self::Named(args) => Either::Right(args.iter().map(|arg| &*arg.value)),
+            Self::Named(args, _) => Either::Right(args.iter().map(|arg| &*arg.value)),
         }
     }
 
@@ -316,7 +1449,7 @@ impl<'ast> CallArgs<'ast> {
     ) -> impl ExactSizeIterator<Item = &mut Box<'ast, Expr<'ast>>> + DoubleEndedIterator {
         match self {
             Self::Unnamed(exprs) => Either::Left(exprs.iter_mut()),
-            Self::Named(args) => Either::Right(args.iter_mut().map(|arg| &mut arg.value)),
+            Self::Named(args, _) => Either::Right(args.iter_mut().map(|arg| &mut arg.value)),
         }
     }
 }
@@ -325,6 +1458,8 @@ impl<'ast> CallArgs<'ast> {
 #[derive(Debug)]
 pub struct NamedArg<'ast> {
     pub name: Ident,
+    /// The span of the `:` between the name and the value.
+    pub colon_span: Span,
     pub value: Box<'ast, Expr<'ast>>,
 }
 
@@ -261,9 +261,25 @@ pub struct UsingDirective<'ast> {
     pub list: UsingList<'ast>,
     /// The type for which this `using` directive applies. This is `*` if the value is `None`.
     pub ty: Option<Type<'ast>>,
+    /// Whether the directive is `global`, attaching the functions and operators to the type in
+    /// every file that uses the type, instead of only in the scope of the directive.
     pub global: bool,
 }
 
+impl<'ast> UsingDirective<'ast> {
+    /// Returns the user-defined operators bound by this directive, along with the paths of the
+    /// functions that implement them.
+    ///
+    /// Operators can only be bound by [`global`](Self::global) directives.
+    pub fn operators(&self) -> impl Iterator<Item = (&AstPath<'ast>, UserDefinableOperator)> + '_ {
+        let paths = match &self.list {
+            UsingList::Single(_) => &[][..],
+            UsingList::Multiple(paths) => &paths[..],
+        };
+        paths.iter().filter_map(|(path, op)| Some((path, (*op)?)))
+    }
+}
+
 /// The path list of a `using` directive.
 #[derive(Debug)]
 pub enum UsingList<'ast> {
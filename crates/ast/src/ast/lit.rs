@@ -16,6 +16,89 @@ pub struct Lit {
     pub kind: LitKind,
 }
 
+impl Lit {
+    /// Returns an estimate of the number of bytes used by this literal, including the heap data
+    /// owned by its value.
+    pub fn approx_memory_bytes(&self) -> usize {
+        let heap = match &self.kind {
+            LitKind::Str(_, bytes) => 2 * size_of::<usize>() + bytes.len(),
+            LitKind::Number(n) => bigint_memory_bytes(n),
+            LitKind::Rational(r) => bigint_memory_bytes(r.numer()) + bigint_memory_bytes(r.denom()),
+            LitKind::Address(_) | LitKind::Bool(_) | LitKind::Err(_) => 0,
+        };
+        size_of::<Self>() + heap
+    }
+
+    /// Formats an integer literal with the given base and digit separators, for use in source
+    /// code formatters.
+    ///
+    /// `sub` is the sub-denomination following the literal, if any, since hexadecimal literals
+    /// cannot have one: integers with a sub-denomination are never converted to hexadecimal.
+    ///
+    /// Returns `None` if the literal should be written as is: string, boolean and address
+    /// literals, since addresses must be written as checksummed hexadecimal numbers, and
+    /// rational or scientific notation number literals, like `1.5` and `1e18`.
+    pub fn format_int(&self, sub: Option<SubDenomination>, format: IntFormat) -> Option<String> {
+        let LitKind::Number(n) = &self.kind else { return None };
+        let original = self.symbol.as_str();
+        let (is_hex, original_digits) = match original.strip_prefix("0x") {
+            Some(digits) => (true, digits),
+            None => (false, original),
+        };
+        if !is_hex && !original_digits.bytes().all(|b| b.is_ascii_digit() || b == b'_') {
+            return None;
+        }
+
+        let hex = match format.base {
+            IntBase::Preserve => is_hex,
+            IntBase::Decimal => false,
+            IntBase::Hexadecimal => is_hex || sub.is_none(),
+        };
+        let digits = if hex == is_hex {
+            match format.separators {
+                DigitSeparators::Preserve => return Some(original.to_string()),
+                _ => original_digits.replace('_', ""),
+            }
+        } else if hex {
+            let digits = n.to_str_radix(16);
+            // 40 hexadecimal digits are an address literal.
+            if digits.len() == 40 {
+                format!("0{digits}")
+            } else {
+                digits
+            }
+        } else {
+            n.to_str_radix(10)
+        };
+
+        let group = match format.separators {
+            DigitSeparators::Group { decimal, hexadecimal } => {
+                if hex {
+                    hexadecimal
+                } else {
+                    decimal
+                }
+            }
+            DigitSeparators::Preserve | DigitSeparators::Remove => 0,
+        };
+        let mut s = String::with_capacity(2 + digits.len() + digits.len() / group.max(1));
+        if hex {
+            s.push_str("0x");
+        }
+        for (i, c) in digits.chars().enumerate() {
+            if group != 0 && i != 0 && (digits.len() - i) % group == 0 {
+                s.push('_');
+            }
+            s.push(c);
+        }
+        Some(s)
+    }
+}
+
+fn bigint_memory_bytes(n: &num_bigint::BigInt) -> usize {
+    n.bits().div_ceil(64) as usize * size_of::<u64>()
+}
+
 /// A kind of literal.
 #[derive(Clone, Debug)]
 pub enum LitKind {
@@ -268,3 +351,43 @@ impl Base {
         }
     }
 }
+
+/// Options to [format integer literals](Lit::format_int).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntFormat {
+    /// The base to write integers in.
+    pub base: IntBase,
+    /// How to write the digit separators.
+    pub separators: DigitSeparators,
+}
+
+/// The base of a [formatted integer literal](Lit::format_int).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum IntBase {
+    /// Keep the base of the original literal.
+    #[default]
+    Preserve,
+    /// Write integers in decimal: `255`.
+    Decimal,
+    /// Write integers in hexadecimal: `0xff`.
+    Hexadecimal,
+}
+
+/// The digit separators of a [formatted integer literal](Lit::format_int).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum DigitSeparators {
+    /// Keep the separators of the original literal, if its base is not changed. Otherwise, the
+    /// literal is written without separators.
+    #[default]
+    Preserve,
+    /// Remove all separators: `1000000`.
+    Remove,
+    /// Separate groups of the given number of digits, starting from the least significant digit:
+    /// `1_000_000`, `0xde_ad_be_ef`. A group size of zero writes no separators for that base.
+    Group {
+        /// The number of digits per group in decimal literals.
+        decimal: usize,
+        /// The number of digits per group in hexadecimal literals.
+        hexadecimal: usize,
+    },
+}
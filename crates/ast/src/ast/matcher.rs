@@ -0,0 +1,418 @@
+//! Composable expression patterns, for writing lints without deeply nested `match`es.
+//!
+//! Patterns are built from the combinators in this module, and matched against an expression with
+//! [`ExprPattern::match_expr`]. Sub-expressions matched by a [`capture`] pattern are returned
+//! in the [`Captures`] of a successful match, in the order in which the capture patterns appear.
+//! The [`expr_matches!`](crate::expr_matches) macro is a lighter alternative for patterns over
+//! the kind of a single expression.
+//!
+//! For example, this matches comparisons to a boolean literal, like `x == true` or `false == x`,
+//! and captures the compared value and the literal:
+//!
+//! ```
+//! use solar_ast::{matcher::*, BinOpKind, ExprKind};
+//! # use solar_ast::{Arena, BinOp, Expr, Lit, LitKind};
+//! # use solar_interface::{kw, Ident, Span};
+//!
+//! # solar_interface::enter(|| {
+//! # let (arena, span) = (Arena::new(), Span::DUMMY);
+//! # let x = arena.alloc(Expr { span, kind: ExprKind::Ident(Ident::from_str("x")) });
+//! # let lit = arena.literals.alloc(Lit { span, symbol: kw::True, kind: LitKind::Bool(true) });
+//! # let lit = arena.alloc(Expr { span, kind: ExprKind::Lit(lit, None) });
+//! # let op = BinOp { span, kind: BinOpKind::Eq };
+//! // `x == true`
+//! # let expr = &Expr { span, kind: ExprKind::Binary(x, op, lit) };
+//! let pattern = bin_op_commutative(
+//!     BinOpKind::Eq,
+//!     capture(not(strip_parens(any_lit_bool()))),
+//!     strip_parens(capture(any_lit_bool())),
+//! );
+//! let captures = pattern.match_expr(expr).unwrap();
+//! let (value, lit) = (captures[0], captures[1]);
+//! assert!(matches!(&value.kind, ExprKind::Ident(x) if x.as_str() == "x"));
+//! assert!(matches!(&lit.kind, ExprKind::Lit(..)));
+//! # });
+//! ```
+
+use super::{BinOpKind, Expr, ExprKind, LitKind, UnOpKind};
+use solar_interface::Symbol;
+use std::ops::Index;
+
+/// The sub-expressions captured by a successful [match](ExprPattern::match_expr), in the order
+/// of their [`capture`] patterns.
+#[derive(Clone, Debug, Default)]
+pub struct Captures<'a, 'ast> {
+    exprs: Vec<&'a Expr<'ast>>,
+}
+
+impl<'a, 'ast> Captures<'a, 'ast> {
+    /// Returns the number of captured expressions.
+    pub fn len(&self) -> usize {
+        self.exprs.len()
+    }
+
+    /// Returns `true` if no expression was captured.
+    pub fn is_empty(&self) -> bool {
+        self.exprs.is_empty()
+    }
+
+    /// Returns the captured expression at the given index.
+    pub fn get(&self, index: usize) -> Option<&'a Expr<'ast>> {
+        self.exprs.get(index).copied()
+    }
+
+    /// Returns an iterator over the captured expressions.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &'a Expr<'ast>> + '_ {
+        self.exprs.iter().copied()
+    }
+
+    fn push(&mut self, expr: &'a Expr<'ast>) {
+        self.exprs.push(expr);
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.exprs.truncate(len);
+    }
+}
+
+impl<'a, 'ast> Index<usize> for Captures<'a, 'ast> {
+    type Output = &'a Expr<'ast>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.exprs[index]
+    }
+}
+
+/// A pattern that can be matched against an expression.
+pub trait ExprPattern {
+    /// Matches the pattern against the expression, pushing the captured sub-expressions to
+    /// `captures`.
+    ///
+    /// Some sub-expressions may have been captured even if the pattern does not match.
+    fn matches_into<'a, 'ast>(
+        &self,
+        expr: &'a Expr<'ast>,
+        captures: &mut Captures<'a, 'ast>,
+    ) -> bool;
+
+    /// Matches the pattern against the expression, and returns the captured sub-expressions if
+    /// it matches.
+    fn match_expr<'a, 'ast>(&self, expr: &'a Expr<'ast>) -> Option<Captures<'a, 'ast>> {
+        let mut captures = Captures::default();
+        self.matches_into(expr, &mut captures).then_some(captures)
+    }
+
+    /// Returns `true` if the pattern matches the expression.
+    fn is_match(&self, expr: &Expr<'_>) -> bool {
+        self.match_expr(expr).is_some()
+    }
+}
+
+impl<P: ExprPattern + ?Sized> ExprPattern for &P {
+    fn matches_into<'a, 'ast>(
+        &self,
+        expr: &'a Expr<'ast>,
+        captures: &mut Captures<'a, 'ast>,
+    ) -> bool {
+        (**self).matches_into(expr, captures)
+    }
+}
+
+struct FnPattern<F>(F);
+
+impl<F> ExprPattern for FnPattern<F>
+where
+    F: for<'a, 'ast> Fn(&'a Expr<'ast>, &mut Captures<'a, 'ast>) -> bool,
+{
+    fn matches_into<'a, 'ast>(
+        &self,
+        expr: &'a Expr<'ast>,
+        captures: &mut Captures<'a, 'ast>,
+    ) -> bool {
+        (self.0)(expr, captures)
+    }
+}
+
+fn from_fn<F>(f: F) -> impl ExprPattern
+where
+    F: for<'a, 'ast> Fn(&'a Expr<'ast>, &mut Captures<'a, 'ast>) -> bool,
+{
+    FnPattern(f)
+}
+
+/// Matches any expression.
+pub fn any() -> impl ExprPattern {
+    from_fn(|_, _| true)
+}
+
+/// Matches the expressions for which the predicate returns `true`.
+pub fn predicate(f: impl Fn(&Expr<'_>) -> bool) -> impl ExprPattern {
+    from_fn(move |expr, _| f(expr))
+}
+
+/// Matches the same expressions as `pattern`, and captures the matched expression before the
+/// ones captured by `pattern` itself.
+pub fn capture(pattern: impl ExprPattern) -> impl ExprPattern {
+    from_fn(move |expr, captures| {
+        captures.push(expr);
+        pattern.matches_into(expr, captures)
+    })
+}
+
+/// Matches the expressions that `pattern` does not match. Nothing is captured.
+pub fn not(pattern: impl ExprPattern) -> impl ExprPattern {
+    from_fn(move |expr, captures| {
+        let len = captures.len();
+        let matches = pattern.matches_into(expr, captures);
+        captures.truncate(len);
+        !matches
+    })
+}
+
+/// Matches the expressions that `first` or `second` match, trying `first` first. Only the
+/// sub-expressions of the matching pattern are captured.
+pub fn either(first: impl ExprPattern, second: impl ExprPattern) -> impl ExprPattern {
+    from_fn(move |expr, captures| {
+        let len = captures.len();
+        if first.matches_into(expr, captures) {
+            return true;
+        }
+        captures.truncate(len);
+        second.matches_into(expr, captures)
+    })
+}
+
+/// Matches `pattern` against the expression with its [parentheses stripped](Expr::strip_parens).
+pub fn strip_parens(pattern: impl ExprPattern) -> impl ExprPattern {
+    from_fn(move |expr, captures| pattern.matches_into(expr.strip_parens(), captures))
+}
+
+/// Matches a binary operation with the given operator: `lhs op rhs`.
+pub fn bin_op(op: BinOpKind, lhs: impl ExprPattern, rhs: impl ExprPattern) -> impl ExprPattern {
+    from_fn(move |expr, captures| match &expr.kind {
+        ExprKind::Binary(l, bin, r) if bin.kind == op => {
+            lhs.matches_into(l, captures) && rhs.matches_into(r, captures)
+        }
+        _ => false,
+    })
+}
+
+/// Matches a binary operation with the given operator, with the operands in either order:
+/// `a op b` or `b op a`.
+///
+/// The sub-expressions are captured in the order of the patterns, regardless of the order of the
+/// operands.
+pub fn bin_op_commutative(
+    op: BinOpKind,
+    a: impl ExprPattern,
+    b: impl ExprPattern,
+) -> impl ExprPattern {
+    from_fn(move |expr, captures| match &expr.kind {
+        ExprKind::Binary(l, bin, r) if bin.kind == op => {
+            let len = captures.len();
+            if a.matches_into(l, captures) && b.matches_into(r, captures) {
+                return true;
+            }
+            captures.truncate(len);
+            a.matches_into(r, captures) && b.matches_into(l, captures)
+        }
+        _ => false,
+    })
+}
+
+/// Matches a unary operation with the given operator: `op operand` or `operand op`.
+pub fn unary(op: UnOpKind, operand: impl ExprPattern) -> impl ExprPattern {
+    from_fn(move |expr, captures| match &expr.kind {
+        ExprKind::Unary(un, e) if un.kind == op => operand.matches_into(e, captures),
+        _ => false,
+    })
+}
+
+/// Matches a call with any arguments: `callee(...)`.
+pub fn call(callee: impl ExprPattern) -> impl ExprPattern {
+    from_fn(move |expr, captures| match &expr.kind {
+        ExprKind::Call(e, _) => callee.matches_into(e, captures),
+        _ => false,
+    })
+}
+
+/// Matches an access of the given member: `base.name`.
+pub fn member(base: impl ExprPattern, name: Symbol) -> impl ExprPattern {
+    from_fn(move |expr, captures| match &expr.kind {
+        ExprKind::Member(e, member) if member.name == name => base.matches_into(e, captures),
+        _ => false,
+    })
+}
+
+/// Matches the given identifier: `name`.
+pub fn ident(name: Symbol) -> impl ExprPattern {
+    predicate(move |expr| expr.as_ident().is_some_and(|ident| ident.name == name))
+}
+
+/// Matches the given boolean literal: `true` or `false`.
+pub fn lit_bool(value: bool) -> impl ExprPattern {
+    predicate(move |expr| as_bool_lit(expr) == Some(value))
+}
+
+/// Matches any boolean literal.
+pub fn any_lit_bool() -> impl ExprPattern {
+    predicate(|expr| as_bool_lit(expr).is_some())
+}
+
+fn as_bool_lit(expr: &Expr<'_>) -> Option<bool> {
+    match expr.as_lit()?.0.kind {
+        LitKind::Bool(b) => Some(b),
+        _ => None,
+    }
+}
+
+/// Matches the number literals equal to zero. See [`Expr::is_zero_literal`].
+pub fn lit_zero() -> impl ExprPattern {
+    predicate(|expr| expr.is_zero_literal())
+}
+
+/// Matches an expression against a pattern over its [kind](ExprKind), like `matches!`, without
+/// spelling out the nested `match` and the [`Box`](super::Box) dereferences.
+///
+/// `expr_matches!(expr, Pattern)` returns `true` if `expr` matches, and
+/// `expr_matches!(expr, Pattern => value)` returns `Some(value)` if it matches and `None`
+/// otherwise. Patterns may be followed by an `if` guard, like in a `match` arm.
+///
+/// Patterns are a variant of [`ExprKind`] with patterns for its fields. Sub-expressions are
+/// matched and bound as `&Expr`, so they can be matched further with struct patterns, or with
+/// nested `expr_matches!` calls in the guard or the value. Operators are written as the name of
+/// their [`BinOpKind`] or [`UnOpKind`] variant, or `_` for any operator. In assignments, `=`
+/// matches plain assignments, and an operator matches compound assignments: `Assign(l, Add, r)`
+/// matches `l += r`. Other variants bind their fields by reference, as they are.
+///
+/// Parentheses are not stripped.
+///
+/// # Examples
+///
+/// ```
+/// use solar_ast::expr_matches;
+/// # use solar_ast::{Arena, BinOp, BinOpKind, Expr, ExprKind, UnOp, UnOpKind};
+/// # use solar_interface::{Ident, Span};
+///
+/// # solar_interface::enter(|| {
+/// # let (arena, span) = (Arena::new(), Span::DUMMY);
+/// # let ident = |name| arena.alloc(Expr { span, kind: ExprKind::Ident(Ident::from_str(name)) });
+/// # let not = |operand| arena.alloc(Expr {
+/// #     span,
+/// #     kind: ExprKind::Unary(UnOp { span, kind: UnOpKind::Not }, operand),
+/// # });
+/// # let eq = BinOp { span, kind: BinOpKind::Eq };
+/// // `x == x`
+/// # let expr = &Expr { span, kind: ExprKind::Binary(ident("x"), eq, ident("x")) };
+/// assert!(expr_matches!(expr, Binary(lhs, Eq, rhs) if lhs.equiv(rhs)));
+/// assert!(!expr_matches!(expr, Binary(_, Ne, _)));
+///
+/// // `!!x`
+/// # let expr = &*not(not(ident("x")));
+/// let inner = expr_matches!(expr, Unary(Not, inner) => inner)
+///     .and_then(|inner| expr_matches!(inner.strip_parens(), Unary(Not, x) => x));
+/// assert!(expr_matches!(inner.unwrap(), Ident(x) if x.as_str() == "x"));
+///
+/// // `a.length`
+/// # let expr = &Expr { span, kind: ExprKind::Member(ident("a"), Ident::from_str("length")) };
+/// let array = expr_matches!(expr, Member(array, name) if name.as_str() == "length" => array);
+/// assert!(array.is_some_and(|array| expr_matches!(array, Ident(_))));
+/// # });
+/// ```
+#[macro_export]
+macro_rules! expr_matches {
+    ($expr:expr, $variant:ident $fields:tt $(if $guard:expr)? => $value:expr $(,)?) => {
+        $crate::expr_matches!(@match $expr, $variant $fields, ($($guard)?), Some($value), None)
+    };
+    ($expr:expr, $variant:ident $fields:tt $(if $guard:expr)? $(,)?) => {
+        $crate::expr_matches!(@match $expr, $variant $fields, ($($guard)?), true, false)
+    };
+
+    (@match $expr:expr, Binary($lhs:pat, _, $rhs:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Binary(l, _, r) => (&**l, &**r),
+            ($lhs, $rhs), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Binary($lhs:pat, $op:ident, $rhs:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr,
+            $crate::ExprKind::Binary(l, op, r) if op.kind == $crate::BinOpKind::$op => (&**l, &**r),
+            ($lhs, $rhs), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Assign($lhs:pat, _, $rhs:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Assign(l, _, r) => (&**l, &**r),
+            ($lhs, $rhs), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Assign($lhs:pat, =, $rhs:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Assign(l, None, r) => (&**l, &**r),
+            ($lhs, $rhs), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Assign($lhs:pat, $op:ident, $rhs:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr,
+            $crate::ExprKind::Assign(l, Some(op), r) if op.kind == $crate::BinOpKind::$op
+                => (&**l, &**r),
+            ($lhs, $rhs), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Unary(_, $operand:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Unary(_, e) => (&**e,),
+            ($operand,), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Unary($op:ident, $operand:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr,
+            $crate::ExprKind::Unary(op, e) if op.kind == $crate::UnOpKind::$op => (&**e,),
+            ($operand,), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Call($callee:pat, $args:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Call(e, args) => (&**e, args),
+            ($callee, $args), $guard, $yes, $no)
+    };
+    (@match $expr:expr, CallOptions($callee:pat, $args:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr,
+            $crate::ExprKind::CallOptions(e, args) => (&**e, &**args),
+            ($callee, $args), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Delete($operand:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Delete(e) => (&**e,),
+            ($operand,), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Index($base:pat, $index:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Index(e, kind) => (&**e, kind),
+            ($base, $index), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Lit($lit:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Lit(lit, _) => (&**lit,),
+            ($lit,), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Lit($lit:pat, $sub:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Lit(lit, sub) => (&**lit, sub),
+            ($lit, $sub), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Member($base:pat, $member:pat), $guard:tt, $yes:expr, $no:expr) => {
+        $crate::expr_matches!(@kind $expr, $crate::ExprKind::Member(e, m) => (&**e, m),
+            ($base, $member), $guard, $yes, $no)
+    };
+    (@match $expr:expr, Ternary($cond:pat, $true_:pat, $false_:pat), $guard:tt, $yes:expr,
+        $no:expr) => {
+        $crate::expr_matches!(@kind $expr,
+            $crate::ExprKind::Ternary(c, t, f) => (&**c, &**t, &**f),
+            ($cond, $true_, $false_), $guard, $yes, $no)
+    };
+    (@match $expr:expr, $variant:ident($($field:pat),* $(,)?), ($($guard:expr)?), $yes:expr,
+        $no:expr) => {
+        match &$expr.kind {
+            $crate::ExprKind::$variant($($field),*) $(if $guard)? => $yes,
+            _ => $no,
+        }
+    };
+
+    // Matches the kind with `$kind`, then matches the fields it evaluates to with `$fields`.
+    (@kind $expr:expr, $kind:pat $(if $kind_guard:expr)? => $bind:expr, $fields:pat,
+        ($($guard:expr)?), $yes:expr, $no:expr) => {
+        match &$expr.kind {
+            $kind $(if $kind_guard)? => match $bind {
+                $fields $(if $guard)? => $yes,
+                #[allow(unreachable_patterns)]
+                _ => $no,
+            },
+            _ => $no,
+        }
+    };
+}
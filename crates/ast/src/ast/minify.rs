@@ -0,0 +1,510 @@
+use super::{
+    CallArgs, CallArgsKind, ElementaryType, Expr, ExprKind, IndexKind, Lit, LitKind, NamedArg,
+    StrKind, Type, TypeKind, VariableDefinition,
+};
+use std::fmt::Write;
+
+impl Expr<'_> {
+    /// Returns the expression as Solidity source code with as little whitespace as possible,
+    /// e.g. `a+b*c` or `f(x,y)`.
+    ///
+    /// A space is only inserted between two tokens that would otherwise be lexed differently, such
+    /// as two identifiers or keywords (`delete x`), or two operators that would be glued together
+    /// (`a- -b` instead of `a--b`). Parentheses are printed as they appear in the tree, so the
+    /// output parses back to the same tree.
+    ///
+    /// String literals are re-escaped from their value, and adjacent string literals are printed
+    /// as a single literal.
+    ///
+    /// Returns `None` if the expression contains errors.
+    pub fn to_minified(&self) -> Option<String> {
+        self.to_minified_with(MinifyOptions::default())
+    }
+
+    /// Returns the expression as Solidity source code with as little whitespace as possible,
+    /// using the given options.
+    ///
+    /// See [`to_minified`](Self::to_minified) for more details.
+    pub fn to_minified_with(&self, options: MinifyOptions) -> Option<String> {
+        let mut m = Minifier { options, ..Default::default() };
+        m.expr(self)?;
+        Some(m.out)
+    }
+
+    /// Returns the expression as [minified](Self::to_minified) source code, truncated with an
+    /// ellipsis if it is longer than `max_len` bytes, e.g. `f(a,b,...)`.
+    ///
+    /// This is meant for logs and diagnostics, not for parsing back. The output is only ever cut
+    /// between two tokens, and the brackets that are open at that point are closed after the
+    /// ellipsis. With them, it is at most `max_len` bytes long, unless `max_len` is too short for
+    /// the ellipsis itself. Errors in the expression are printed as `<error>`.
+    pub fn to_string_truncated(&self, max_len: usize) -> String {
+        let mut m = Minifier { lossy: true, ..Default::default() };
+        let _ = m.expr(self);
+        if m.out.len() <= max_len {
+            return m.out;
+        }
+        let truncation = Truncation { max_len, closers: Vec::new(), done: false };
+        let mut m = Minifier { lossy: true, truncation: Some(truncation), ..Default::default() };
+        let _ = m.expr(self);
+        m.out
+    }
+}
+
+impl Type<'_> {
+    /// Returns the type as Solidity source code with as little whitespace as possible.
+    ///
+    /// See [`Expr::to_minified`] for more details.
+    pub fn to_minified(&self) -> Option<String> {
+        let mut m = Minifier::default();
+        m.ty(self)?;
+        Some(m.out)
+    }
+}
+
+/// Options for [`Expr::to_minified_with`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinifyOptions {
+    /// Whether to remove the parentheses that do not change how the expression is parsed, like
+    /// in `(a + b)` or `f((x))`. Otherwise, parentheses are printed as written.
+    ///
+    /// Parentheses around operations are only removed where they are redundant regardless of
+    /// operator precedence, so some redundant parentheses are kept, like in `(a * b) + c`.
+    pub strip_redundant_parens: bool,
+    /// Whether to print the named arguments of calls in alphabetical order of their names, like
+    /// `f({a: x, b: y})` for `f({b: y, a: x})`. Otherwise, they are printed in source order.
+    ///
+    /// This does not change the meaning of the call, since named arguments are matched to the
+    /// parameters by name, and evaluated in the order of the parameters. Unnamed arguments and
+    /// call options, like `{value: v}`, are always printed in source order.
+    pub sort_named_args: bool,
+}
+
+#[derive(Default)]
+struct Minifier {
+    out: String,
+    options: MinifyOptions,
+    /// Whether the last token is a number literal, which must not be followed by a `.`.
+    after_number: bool,
+    /// Whether to print errors as `<error>`, instead of failing.
+    lossy: bool,
+    /// The truncation of the output, if it is limited in length.
+    truncation: Option<Truncation>,
+}
+
+/// The ellipsis that replaces the end of a truncated output.
+const ELLIPSIS: &str = "...";
+
+/// The state of a [`Minifier`] with an output of limited length.
+struct Truncation {
+    /// The maximum length of the output, in bytes.
+    max_len: usize,
+    /// The closing brackets of the brackets that are currently open, innermost last.
+    closers: Vec<char>,
+    /// Whether the output has been truncated, after which no more tokens are appended.
+    done: bool,
+}
+
+/// The position of an expression, which determines whether it must be parenthesized.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Position {
+    /// Anywhere a full expression is expected, like a call argument or a ternary branch.
+    Any,
+    /// An operand of a binary operation, the condition of a ternary, or the left-hand side of an
+    /// assignment.
+    Operand,
+    /// The operand of a prefix unary operation or of `delete`.
+    Prefix,
+    /// The operand of a postfix unary operation, or the base of a call, member access, or index
+    /// access.
+    Base,
+}
+
+impl Position {
+    /// Returns `true` if the expression can be printed without parentheses at this position.
+    fn allows(self, expr: &Expr<'_>) -> bool {
+        match &expr.kind {
+            ExprKind::Assign(..) | ExprKind::Binary(..) | ExprKind::Ternary(..) => {
+                self == Self::Any
+            }
+            // `new C.x` would be parsed as creating `C.x`.
+            ExprKind::Unary(..) | ExprKind::Delete(_) | ExprKind::New(_) => self != Self::Base,
+            _ => true,
+        }
+    }
+}
+
+impl Minifier {
+    /// Appends a token, preceded by a space if it would otherwise be lexed together with the
+    /// previous token.
+    fn token(&mut self, token: &str) {
+        let space = match (self.out.chars().next_back(), token.chars().next()) {
+            (Some(prev), Some(next)) => {
+                needs_space(prev, next) || (self.after_number && next == '.')
+            }
+            _ => false,
+        };
+        if let Some(truncation) = &mut self.truncation {
+            if truncation.done {
+                return;
+            }
+            let open = truncation.closers.len();
+            let open = match token {
+                "(" | "[" | "{" => open + 1,
+                ")" | "]" | "}" => open.saturating_sub(1),
+                _ => open,
+            };
+            // Keep room for the ellipsis and the closing brackets after this token.
+            let len = self.out.len() + usize::from(space) + token.len() + ELLIPSIS.len() + open;
+            if len > truncation.max_len {
+                truncation.done = true;
+                self.out.push_str(ELLIPSIS);
+                self.out.extend(truncation.closers.iter().rev());
+                return;
+            }
+            match token {
+                "(" => truncation.closers.push(')'),
+                "[" => truncation.closers.push(']'),
+                "{" => truncation.closers.push('}'),
+                ")" | "]" | "}" => {
+                    truncation.closers.pop();
+                }
+                _ => {}
+            }
+        }
+        if space {
+            self.out.push(' ');
+        }
+        self.out.push_str(token);
+        self.after_number = false;
+    }
+
+    /// Prints an error as `<error>` if the minifier is lossy. Otherwise, fails.
+    fn error(&mut self) -> Option<()> {
+        self.lossy.then(|| self.token("<error>"))
+    }
+
+    fn expr(&mut self, expr: &Expr<'_>) -> Option<()> {
+        self.expr_at(expr, Position::Any)
+    }
+
+    fn expr_at(&mut self, expr: &Expr<'_>, position: Position) -> Option<()> {
+        match &expr.kind {
+            ExprKind::Array(exprs) => {
+                self.token("[");
+                self.exprs(exprs.iter().map(|e| &**e))?;
+                self.token("]");
+            }
+            ExprKind::Assign(lhs, op, rhs) => {
+                self.expr_at(lhs, Position::Operand)?;
+                match op {
+                    Some(op) => self.token(&format!("{}=", op.kind.to_str())),
+                    None => self.token("="),
+                }
+                self.expr(rhs)?;
+            }
+            ExprKind::Binary(lhs, op, rhs) => {
+                self.expr_at(lhs, Position::Operand)?;
+                self.token(op.kind.to_str());
+                self.expr_at(rhs, Position::Operand)?;
+            }
+            ExprKind::Call(callee, args) => {
+                self.expr_at(callee, Position::Base)?;
+                self.args(args)?;
+            }
+            ExprKind::CallOptions(callee, args) => {
+                self.expr_at(callee, Position::Base)?;
+                self.named_args(args.iter())?;
+            }
+            ExprKind::Delete(expr) => {
+                self.token("delete");
+                self.expr_at(expr, Position::Prefix)?;
+            }
+            ExprKind::Err(_) => self.error()?,
+            ExprKind::Ident(ident) => self.token(ident.as_str()),
+            ExprKind::Index(expr, kind) => {
+                self.expr_at(expr, Position::Base)?;
+                self.token("[");
+                match kind {
+                    IndexKind::Index(index) => self.opt(index.as_deref())?,
+                    IndexKind::Range(start, end) => {
+                        self.opt(start.as_deref())?;
+                        self.token(":");
+                        self.opt(end.as_deref())?;
+                    }
+                }
+                self.token("]");
+            }
+            ExprKind::Lit(lit, sub) => {
+                self.lit(lit)?;
+                if let Some(sub) = sub {
+                    self.token(&sub.to_string());
+                }
+            }
+            ExprKind::Member(expr, member) => {
+                self.expr_at(expr, Position::Base)?;
+                self.token(".");
+                self.token(member.as_str());
+            }
+            ExprKind::New(ty) => {
+                self.token("new");
+                self.ty(ty)?;
+            }
+            ExprKind::Payable(args) => {
+                self.token("payable");
+                self.args(args)?;
+            }
+            ExprKind::Ternary(cond, true_, false_) => {
+                self.expr_at(cond, Position::Operand)?;
+                self.token("?");
+                self.expr(true_)?;
+                self.token(":");
+                self.expr(false_)?;
+            }
+            ExprKind::Tuple(_)
+                if self.options.strip_redundant_parens && expr.is_parenthesized() =>
+            {
+                let inner = expr.strip_parens();
+                if position.allows(inner) {
+                    return self.expr_at(inner, position);
+                }
+                self.token("(");
+                self.expr(inner)?;
+                self.token(")");
+            }
+            ExprKind::Tuple(exprs) => {
+                self.token("(");
+                for (i, expr) in exprs.iter().enumerate() {
+                    if i > 0 {
+                        self.token(",");
+                    }
+                    self.opt(expr.as_deref())?;
+                }
+                self.token(")");
+            }
+            ExprKind::TypeCall(ty) => {
+                self.token("type");
+                self.token("(");
+                self.ty(ty)?;
+                self.token(")");
+            }
+            ExprKind::Type(ty) => self.ty(ty)?,
+            ExprKind::Unary(op, expr) => {
+                if op.kind.is_postfix() {
+                    self.expr_at(expr, Position::Base)?;
+                    self.token(op.kind.to_str());
+                } else {
+                    self.token(op.kind.to_str());
+                    self.expr_at(expr, Position::Prefix)?;
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn exprs<'a, 'ast: 'a>(
+        &mut self,
+        exprs: impl IntoIterator<Item = &'a Expr<'ast>>,
+    ) -> Option<()> {
+        for (i, expr) in exprs.into_iter().enumerate() {
+            if i > 0 {
+                self.token(",");
+            }
+            self.expr(expr)?;
+        }
+        Some(())
+    }
+
+    fn opt(&mut self, expr: Option<&Expr<'_>>) -> Option<()> {
+        match expr {
+            Some(expr) => self.expr(expr),
+            None => Some(()),
+        }
+    }
+
+    fn args(&mut self, args: &CallArgs<'_>) -> Option<()> {
+        self.token("(");
+        match &args.kind {
+            CallArgsKind::Unnamed(exprs) => self.exprs(exprs.iter().map(|e| &**e))?,
+            CallArgsKind::Named(args, _) if self.options.sort_named_args => {
+                let mut args = args.iter().collect::<Vec<_>>();
+                args.sort_by(|a, b| a.name.as_str().cmp(b.name.as_str()));
+                self.named_args(args)?
+            }
+            CallArgsKind::Named(args, _) => self.named_args(args.iter())?,
+        }
+        self.token(")");
+        Some(())
+    }
+
+    fn named_args<'a, 'ast: 'a>(
+        &mut self,
+        args: impl IntoIterator<Item = &'a NamedArg<'ast>>,
+    ) -> Option<()> {
+        self.token("{");
+        for (i, arg) in args.into_iter().enumerate() {
+            if i > 0 {
+                self.token(",");
+            }
+            self.token(arg.name.as_str());
+            self.token(":");
+            self.expr(&arg.value)?;
+        }
+        self.token("}");
+        Some(())
+    }
+
+    fn lit(&mut self, lit: &Lit) -> Option<()> {
+        match &lit.kind {
+            LitKind::Str(kind, value) => self.token(&str_lit(*kind, value)),
+            LitKind::Number(_) | LitKind::Rational(_) => {
+                self.token(lit.symbol.as_str());
+                self.after_number = true;
+            }
+            LitKind::Address(_) | LitKind::Bool(_) => self.token(lit.symbol.as_str()),
+            LitKind::Err(_) => self.error()?,
+        }
+        Some(())
+    }
+
+    fn ty(&mut self, ty: &Type<'_>) -> Option<()> {
+        match &ty.kind {
+            TypeKind::Elementary(ty) => {
+                let mut s = String::new();
+                ty.write_abi_str(&mut s).unwrap();
+                self.token(&s);
+                if let ElementaryType::Address(true) = ty {
+                    self.token("payable");
+                }
+            }
+            TypeKind::Array(array) => {
+                self.ty(&array.element)?;
+                self.token("[");
+                self.opt(array.size.as_deref())?;
+                self.token("]");
+            }
+            TypeKind::Function(function) => {
+                self.token("function");
+                self.params(&function.parameters)?;
+                if let Some(visibility) = function.visibility {
+                    self.token(&visibility.to_string());
+                }
+                if !function.state_mutability.is_non_payable() {
+                    self.token(&function.state_mutability.to_string());
+                }
+                if !function.returns.is_empty() {
+                    self.token("returns");
+                    self.params(&function.returns)?;
+                }
+            }
+            TypeKind::Mapping(mapping) => {
+                self.token("mapping");
+                self.token("(");
+                self.ty(&mapping.key)?;
+                if let Some(name) = mapping.key_name {
+                    self.token(name.as_str());
+                }
+                self.token("=>");
+                self.ty(&mapping.value)?;
+                if let Some(name) = mapping.value_name {
+                    self.token(name.as_str());
+                }
+                self.token(")");
+            }
+            TypeKind::Custom(path) => {
+                for (i, segment) in path.segments().iter().enumerate() {
+                    if i > 0 {
+                        self.token(".");
+                    }
+                    self.token(segment.as_str());
+                }
+            }
+            TypeKind::Var => self.token("var"),
+        }
+        Some(())
+    }
+
+    fn params(&mut self, params: &[VariableDefinition<'_>]) -> Option<()> {
+        self.token("(");
+        for (i, param) in params.iter().enumerate() {
+            if i > 0 {
+                self.token(",");
+            }
+            self.ty(&param.ty)?;
+            if let Some(location) = param.data_location {
+                self.token(&location.to_string());
+            }
+            if let Some(name) = param.name {
+                self.token(name.as_str());
+            }
+        }
+        self.token(")");
+        Some(())
+    }
+}
+
+/// Returns `true` if the two characters, at the end of a token and at the start of the next one,
+/// must be separated by a space to be lexed as two tokens.
+fn needs_space(prev: char, next: char) -> bool {
+    let is_word = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '$');
+    if is_word(prev) {
+        // Also keep string literals from becoming prefixed literals, like `hex"00"`.
+        return is_word(next) || next == '"';
+    }
+    matches!(
+        (prev, next),
+        ('=', '=' | '>')
+            | ('<', '<' | '=')
+            | ('>', '>' | '=')
+            | ('!' | ':' | '%' | '^', '=')
+            | ('+', '+' | '=')
+            | ('-', '-' | '=' | '>')
+            | ('*', '*' | '=')
+            | ('/', '/' | '*' | '=')
+            | ('&', '&' | '=')
+            | ('|', '|' | '=')
+    )
+}
+
+/// Returns the source code of a string literal with the given value.
+fn str_lit(kind: StrKind, value: &[u8]) -> String {
+    let mut s = String::with_capacity(value.len() + 2);
+    match kind {
+        StrKind::Hex => {
+            s.push_str("hex\"");
+            for byte in value {
+                write!(s, "{byte:02x}").unwrap();
+            }
+        }
+        StrKind::Str => {
+            s.push('"');
+            for &byte in value {
+                escape(&mut s, byte as char, byte >= 0x80);
+            }
+        }
+        StrKind::Unicode => {
+            s.push_str("unicode\"");
+            match std::str::from_utf8(value) {
+                Ok(value) => value.chars().for_each(|c| escape(&mut s, c, false)),
+                Err(_) => value.iter().for_each(|&byte| escape(&mut s, byte as char, byte >= 0x80)),
+            }
+        }
+    }
+    s.push('"');
+    s
+}
+
+/// Pushes a character of a string literal, escaping it if necessary. Non-ASCII characters are
+/// only escaped, as a single byte, if `escape_non_ascii` is `true`.
+fn escape(s: &mut String, c: char, escape_non_ascii: bool) {
+    match c {
+        '"' => s.push_str("\\\""),
+        '\\' => s.push_str("\\\\"),
+        '\n' => s.push_str("\\n"),
+        '\r' => s.push_str("\\r"),
+        '\t' => s.push_str("\\t"),
+        ' '..='~' => s.push(c),
+        _ if !c.is_ascii() && !escape_non_ascii => s.push(c),
+        _ => write!(s, "\\x{:02x}", c as u32).unwrap(),
+    }
+}
@@ -1,11 +1,21 @@
 //! Solidity AST.
 
-use solar_data_structures::{index::IndexSlice, newtype_index, BumpExt};
+use solar_data_structures::{index::IndexSlice, newtype_index, sync::Lock, BumpExt};
 use std::fmt;
 
 pub use crate::token::CommentKind;
 pub use solar_interface::{Ident, Span, Symbol};
 
+mod canonicalize;
+
+mod const_value;
+pub use const_value::*;
+
+mod debug_tree;
+pub use debug_tree::*;
+
+mod equiv;
+
 mod expr;
 pub use expr::*;
 
@@ -15,18 +25,33 @@ pub use item::*;
 mod lit;
 pub use lit::*;
 
+pub mod matcher;
+
+mod minify;
+pub use minify::MinifyOptions;
+
 mod path;
 pub use path::*;
 
+mod pool;
+pub use pool::*;
+
 mod semver;
 pub use semver::*;
 
+mod sexpr;
+
+mod simplify;
+
 mod stmt;
 pub use stmt::*;
 
 mod ty;
 pub use ty::*;
 
+mod walk;
+pub use walk::*;
+
 pub mod yul;
 
 pub type Box<'ast, T> = &'ast mut T;
@@ -52,6 +77,17 @@ impl Arena {
     pub fn used_bytes(&self) -> usize {
         self.bump.used_bytes() + self.literals.len() * std::mem::size_of::<Lit>()
     }
+
+    /// Drops everything that was allocated in the arena, so that it can be reused.
+    ///
+    /// This is cheaper than dropping the arena and creating a new one, since the largest chunk of
+    /// memory is kept and reused by the next allocations. Since this takes `&mut self`, no
+    /// reference to the dropped nodes can be alive.
+    pub fn reset(&mut self) {
+        self.bump.reset();
+        // Literals own heap data that must be dropped, and the typed arena cannot be cleared.
+        self.literals = typed_arena::Arena::with_capacity(self.literals.len());
+    }
 }
 
 impl Default for Arena {
@@ -69,6 +105,54 @@ impl std::ops::Deref for Arena {
     }
 }
 
+/// A pool of [`Arena`]s, so that long-running programs can reuse arenas instead of creating a
+/// new one for every source.
+///
+/// The pool can be shared between threads, each checking arenas out with [`take`](Self::take)
+/// and back in with [`put`](Self::put).
+#[derive(Default)]
+pub struct ArenaPool {
+    arenas: Lock<Vec<Arena>>,
+}
+
+impl ArenaPool {
+    /// Creates a new, empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes an arena out of the pool, or creates a new one if the pool is empty.
+    pub fn take(&self) -> Arena {
+        self.arenas.lock().pop().unwrap_or_default()
+    }
+
+    /// [Resets](Arena::reset) the arena and puts it back into the pool.
+    pub fn put(&self, mut arena: Arena) {
+        arena.reset();
+        self.arenas.lock().push(arena);
+    }
+
+    /// Calls `f` with an arena taken out of the pool, and puts it back afterwards.
+    ///
+    /// The result of `f` cannot borrow from the arena.
+    pub fn with<R>(&self, f: impl FnOnce(&Arena) -> R) -> R {
+        let arena = self.take();
+        let r = f(&arena);
+        self.put(arena);
+        r
+    }
+
+    /// Returns the number of arenas in the pool.
+    pub fn len(&self) -> usize {
+        self.arenas.lock().len()
+    }
+
+    /// Returns `true` if the pool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.arenas.lock().is_empty()
+    }
+}
+
 /// A list of doc-comments.
 #[derive(Default)]
 pub struct DocComments<'ast>(pub Box<'ast, [DocComment]>);
@@ -162,4 +246,33 @@ mod tests {
         assert_no_drop::<Item<'_>>();
         assert_no_drop::<SourceUnit<'_>>();
     }
+
+    #[test]
+    fn approx_memory_bytes() {
+        let arena = Arena::new();
+        let ident = || Expr::from_ident(Ident::DUMMY);
+
+        let n = 1000;
+        let mut expr = arena.alloc(ident());
+        for _ in 0..n {
+            let op = BinOp { span: Span::DUMMY, kind: BinOpKind::Add };
+            let rhs = arena.alloc(ident());
+            expr = arena.alloc(Expr { span: Span::DUMMY, kind: ExprKind::Binary(expr, op, rhs) });
+        }
+        let nodes = 2 * n + 1;
+        assert_eq!(expr.approx_memory_bytes(), nodes * std::mem::size_of::<Expr<'_>>());
+
+        let contents = vec![0u8; 4096];
+        let lit = arena.literals.alloc(Lit {
+            span: Span::DUMMY,
+            symbol: Symbol::DUMMY,
+            kind: LitKind::Str(StrKind::Str, contents.into()),
+        });
+        let lit = arena.alloc(Expr { span: Span::DUMMY, kind: ExprKind::Lit(lit, None) });
+        let elems = arena.alloc_array([expr, lit]);
+        let array = Expr { span: Span::DUMMY, kind: ExprKind::Array(elems) };
+        let lower_bound =
+            (nodes + 2) * std::mem::size_of::<Expr<'_>>() + std::mem::size_of::<Lit>() + 4096;
+        assert!(array.approx_memory_bytes() >= lower_bound);
+    }
 }
@@ -0,0 +1,232 @@
+use super::{
+    BinOpKind, CallArgsKind, Expr, ExprKind, IndexKind, LitKind, StrKind, SubDenomination, UnOpKind,
+};
+use solar_data_structures::{map::FxIndexSet, newtype_index};
+use solar_interface::{Span, Symbol};
+
+newtype_index! {
+    /// A [pooled expression](PooledExprKind) ID. Only used in [`ExprPool`].
+    pub struct PooledExprId;
+}
+
+/// A kind of pooled expression. Sub-expressions are referred to by their ID in the [`ExprPool`].
+///
+/// This mirrors the pure subset of [`ExprKind`], without spans.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PooledExprKind {
+    /// An array literal expression: `[a, b, c, d]`.
+    Array(Box<[PooledExprId]>),
+    /// A binary operation: `a + b`.
+    Binary(PooledExprId, BinOpKind, PooledExprId),
+    /// Function call options: `foo.bar{ value: 1, gas: 2 }`.
+    CallOptions(PooledExprId, Box<[(Symbol, PooledExprId)]>),
+    /// An identifier: `foo`.
+    Ident(Symbol),
+    /// An indexing expression: `vector[index]`.
+    Index(PooledExprId, Option<PooledExprId>),
+    /// A literal, as written in the source code, with its string kind if it is a string
+    /// literal: `hex"1234"`, `5.6 ether`.
+    Lit(Symbol, Option<StrKind>, Option<SubDenomination>),
+    /// Access of a named member: `obj.k`.
+    Member(PooledExprId, Symbol),
+    /// A `new` expression, with the type as an s-expression: `new Contract`.
+    New(Box<str>),
+    /// A `payable` expression with unnamed arguments: `payable(address(0x...))`.
+    Payable(Box<[PooledExprId]>),
+    /// A slice expression: `slice[l:r]`.
+    Slice(PooledExprId, Option<PooledExprId>, Option<PooledExprId>),
+    /// A ternary expression: `foo ? bar : baz`.
+    Ternary(PooledExprId, PooledExprId, PooledExprId),
+    /// A tuple expression, including parenthesized expressions: `(a,,, b, c, d)`, `(a)`.
+    Tuple(Box<[Option<PooledExprId>]>),
+    /// A `type()` expression, with the type as an s-expression: `type(uint256)`.
+    TypeCall(Box<str>),
+    /// An elementary type name, as an s-expression: `uint256`.
+    Type(Box<str>),
+    /// A non-modifying unary operation: `!x`, `-x`.
+    Unary(UnOpKind, PooledExprId),
+}
+
+/// A pool of immutable, deduplicated expressions.
+///
+/// AST nodes are uniquely owned by their parent, so identical sub-expressions cannot be shared in
+/// the AST itself. Instead, [interning](Self::intern) an expression into the pool converts it to a
+/// directed acyclic graph in which structurally identical sub-expressions, from the same or from
+/// different interned expressions, are stored only once. This makes the pool much smaller than
+/// the AST of highly repetitive expressions, such as the ones found in generated code, and the
+/// AST can be dropped once it has been interned.
+///
+/// Only pure expressions can be interned, since identical expressions with side effects are not
+/// interchangeable. A pooled expression only records the span of its first occurrence.
+#[derive(Debug, Default)]
+pub struct ExprPool {
+    nodes: FxIndexSet<PooledExprKind>,
+    spans: Vec<Span>,
+}
+
+impl ExprPool {
+    /// Creates a new, empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns the expression and all of its sub-expressions, and returns the ID of the pooled
+    /// expression.
+    ///
+    /// Returns `None`, without modifying the pool, if the expression has side effects or contains
+    /// errors. Named `payable` arguments are not supported either.
+    pub fn intern(&mut self, expr: &Expr<'_>) -> Option<PooledExprId> {
+        let mut poolable = true;
+        expr.walk_with_path(|expr, _| poolable &= is_poolable(expr));
+        if !poolable {
+            return None;
+        }
+
+        let mut tasks = vec![(expr, false)];
+        let mut ids = Vec::new();
+        while let Some((expr, built)) = tasks.pop() {
+            if built {
+                let n = children(expr).len();
+                let kind = build(expr, &ids[ids.len() - n..]);
+                ids.truncate(ids.len() - n);
+                ids.push(self.insert(kind, expr.span));
+            } else {
+                tasks.push((expr, true));
+                tasks.extend(children(expr).into_iter().rev().map(|child| (child, false)));
+            }
+        }
+        debug_assert_eq!(ids.len(), 1);
+        ids.pop()
+    }
+
+    fn insert(&mut self, kind: PooledExprKind, span: Span) -> PooledExprId {
+        let (index, new) = self.nodes.insert_full(kind);
+        if new {
+            self.spans.push(span);
+        }
+        PooledExprId::from_usize(index)
+    }
+
+    /// Returns the kind of the pooled expression.
+    pub fn get(&self, id: PooledExprId) -> &PooledExprKind {
+        &self.nodes[id.index()]
+    }
+
+    /// Returns the span of the first occurrence of the pooled expression.
+    pub fn span(&self, id: PooledExprId) -> Span {
+        self.spans[id.index()]
+    }
+
+    /// Returns the number of distinct expressions in the pool.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the pool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns an estimate of the number of bytes used by the pool.
+    ///
+    /// This includes the storage of the nodes, their hash table, their spans, and the slices and
+    /// strings owned by the nodes. Interned symbols are shared by the whole session and are not
+    /// counted.
+    pub fn approx_memory_bytes(&self) -> usize {
+        // Each entry stores the node and its hash, and is indexed from the hash table.
+        let entry = size_of::<PooledExprKind>() + 2 * size_of::<usize>();
+        let heap = self
+            .nodes
+            .iter()
+            .map(|kind| match kind {
+                PooledExprKind::Array(ids) | PooledExprKind::Payable(ids) => size_of_val(&**ids),
+                PooledExprKind::CallOptions(_, args) => size_of_val(&**args),
+                PooledExprKind::Tuple(ids) => size_of_val(&**ids),
+                PooledExprKind::New(ty)
+                | PooledExprKind::TypeCall(ty)
+                | PooledExprKind::Type(ty) => ty.len(),
+                _ => 0,
+            })
+            .sum::<usize>();
+        self.nodes.capacity() * entry + self.spans.capacity() * size_of::<Span>() + heap
+    }
+}
+
+/// Returns `true` if the node itself, ignoring its children, can be interned.
+fn is_poolable(expr: &Expr<'_>) -> bool {
+    match &expr.kind {
+        ExprKind::Assign(..) | ExprKind::Call(..) | ExprKind::Delete(_) | ExprKind::Err(_) => false,
+        ExprKind::Unary(op, _) => !op.kind.is_modifying(),
+        ExprKind::Payable(args) => matches!(args.kind, CallArgsKind::Unnamed(_)),
+        _ => true,
+    }
+}
+
+/// Returns the children of a poolable expression, in source order.
+fn children<'a, 'ast>(expr: &'a Expr<'ast>) -> Vec<&'a Expr<'ast>> {
+    match &expr.kind {
+        ExprKind::Array(exprs) => exprs.iter().map(|e| &**e).collect(),
+        ExprKind::Binary(lhs, _, rhs) => vec![&**lhs, &**rhs],
+        ExprKind::CallOptions(expr, args) => {
+            std::iter::once(&**expr).chain(args.iter().map(|arg| &*arg.value)).collect()
+        }
+        ExprKind::Index(expr, kind) => {
+            let (start, end) = match kind {
+                IndexKind::Index(index) => (index, &None),
+                IndexKind::Range(start, end) => (start, end),
+            };
+            std::iter::once(&**expr).chain(start.as_deref()).chain(end.as_deref()).collect()
+        }
+        ExprKind::Member(expr, _) | ExprKind::Unary(_, expr) => vec![&**expr],
+        ExprKind::Payable(args) => args.exprs().collect(),
+        ExprKind::Ternary(cond, true_, false_) => vec![&**cond, &**true_, &**false_],
+        ExprKind::Tuple(exprs) => exprs.iter().flatten().map(|e| &**e).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Builds the pooled kind of a poolable expression from the IDs of its children.
+fn build(expr: &Expr<'_>, ids: &[PooledExprId]) -> PooledExprKind {
+    let mut ids_iter = ids.iter().copied();
+    let mut next = || ids_iter.next().unwrap();
+    match &expr.kind {
+        ExprKind::Array(_) => PooledExprKind::Array(ids.into()),
+        ExprKind::Binary(_, op, _) => PooledExprKind::Binary(next(), op.kind, next()),
+        ExprKind::CallOptions(_, args) => {
+            let callee = next();
+            PooledExprKind::CallOptions(
+                callee,
+                args.iter().map(|arg| (arg.name.name, next())).collect(),
+            )
+        }
+        ExprKind::Ident(ident) => PooledExprKind::Ident(ident.name),
+        ExprKind::Index(_, IndexKind::Index(index)) => {
+            PooledExprKind::Index(next(), index.as_ref().map(|_| next()))
+        }
+        ExprKind::Index(_, IndexKind::Range(start, end)) => PooledExprKind::Slice(
+            next(),
+            start.as_ref().map(|_| next()),
+            end.as_ref().map(|_| next()),
+        ),
+        ExprKind::Lit(lit, sub) => {
+            let str_kind = match lit.kind {
+                LitKind::Str(kind, _) => Some(kind),
+                _ => None,
+            };
+            PooledExprKind::Lit(lit.symbol, str_kind, *sub)
+        }
+        ExprKind::Member(_, member) => PooledExprKind::Member(next(), member.name),
+        ExprKind::New(ty) => PooledExprKind::New(ty.to_sexpr().into()),
+        ExprKind::Payable(_) => PooledExprKind::Payable(ids.into()),
+        ExprKind::Ternary(..) => PooledExprKind::Ternary(next(), next(), next()),
+        ExprKind::Tuple(exprs) => {
+            PooledExprKind::Tuple(exprs.iter().map(|e| e.as_ref().map(|_| next())).collect())
+        }
+        ExprKind::TypeCall(ty) => PooledExprKind::TypeCall(ty.to_sexpr().into()),
+        ExprKind::Type(ty) => PooledExprKind::Type(ty.to_sexpr().into()),
+        ExprKind::Unary(op, _) => PooledExprKind::Unary(op.kind, next()),
+        ExprKind::Assign(..) | ExprKind::Call(..) | ExprKind::Delete(_) | ExprKind::Err(_) => {
+            unreachable!("non-poolable expressions are rejected by `ExprPool::intern`")
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use super::{
+    CallArgs, CallArgsKind, ElementaryType, Expr, ExprKind, IndexKind, Lit, LitKind, NamedArg,
+    StrKind, Type, TypeKind, VariableDefinition,
+};
+use std::fmt::{self, Write};
+
+impl Expr<'_> {
+    /// Returns a compact S-expression representation of the expression tree, without spans,
+    /// e.g. `(binary + (ident a) (ident b))`.
+    ///
+    /// Literals are inlined as written in the source code, and missing tuple components or
+    /// slice bounds are rendered as `_`. Parenthesized expressions are rendered as single-element
+    /// tuples: `(tuple (ident a))`.
+    pub fn to_sexpr(&self) -> String {
+        let mut s = Writer::default();
+        write_expr(&mut s, self).unwrap();
+        s.s
+    }
+
+    /// Returns `true` if the two expressions have the same structure, ignoring spans and
+    /// parentheses: `(a + b) * c` and `((a + b)) * (c)` are structurally equal, but `a + b * c`
+    /// is not.
+    ///
+    /// To also compare parentheses, compare the [S-expressions](Self::to_sexpr) instead.
+    pub fn struct_eq(&self, other: &Self) -> bool {
+        let sexpr = |expr: &Self| {
+            let mut s = Writer { s: String::new(), strip_parens: true };
+            write_expr(&mut s, expr).unwrap();
+            s.s
+        };
+        sexpr(self) == sexpr(other)
+    }
+}
+
+impl Type<'_> {
+    /// Returns a compact S-expression representation of the type tree, without spans.
+    ///
+    /// See [`Expr::to_sexpr`] for more details.
+    pub fn to_sexpr(&self) -> String {
+        let mut s = Writer::default();
+        write_ty(&mut s, self).unwrap();
+        s.s
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    s: String,
+    /// Whether to write parenthesized expressions without their parentheses.
+    strip_parens: bool,
+}
+
+impl std::ops::Deref for Writer {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.s
+    }
+}
+
+impl std::ops::DerefMut for Writer {
+    fn deref_mut(&mut self) -> &mut String {
+        &mut self.s
+    }
+}
+
+impl Write for Writer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.s.write_str(s)
+    }
+}
+
+fn write_expr(s: &mut Writer, expr: &Expr<'_>) -> fmt::Result {
+    let expr = if s.strip_parens { expr.strip_parens() } else { expr };
+    match &expr.kind {
+        ExprKind::Array(exprs) => {
+            s.push_str("(array");
+            write_exprs(s, exprs.iter().map(|e| &**e))?;
+        }
+        ExprKind::Assign(lhs, op, rhs) => {
+            let op = op.map_or("", |op| op.kind.to_str());
+            write!(s, "(assign {op}= ")?;
+            write_expr(s, lhs)?;
+            s.push(' ');
+            write_expr(s, rhs)?;
+        }
+        ExprKind::Binary(lhs, op, rhs) => {
+            write!(s, "(binary {} ", op.kind.to_str())?;
+            write_expr(s, lhs)?;
+            s.push(' ');
+            write_expr(s, rhs)?;
+        }
+        ExprKind::Call(callee, args) => {
+            s.push_str("(call ");
+            write_expr(s, callee)?;
+            write_args(s, args)?;
+        }
+        ExprKind::CallOptions(callee, args) => {
+            s.push_str("(options ");
+            write_expr(s, callee)?;
+            write_named_args(s, args)?;
+        }
+        ExprKind::Delete(expr) => {
+            s.push_str("(delete ");
+            write_expr(s, expr)?;
+        }
+        ExprKind::Err(_) => s.push_str("(err"),
+        ExprKind::Ident(ident) => write!(s, "(ident {ident}")?,
+        ExprKind::Index(expr, kind) => {
+            s.push_str(match kind {
+                IndexKind::Index(_) => "(index ",
+                IndexKind::Range(..) => "(slice ",
+            });
+            write_expr(s, expr)?;
+            match kind {
+                IndexKind::Index(index) => write_opt(s, index.as_deref())?,
+                IndexKind::Range(start, end) => {
+                    write_opt(s, start.as_deref())?;
+                    write_opt(s, end.as_deref())?;
+                }
+            }
+        }
+        ExprKind::Lit(lit, None) => return write_lit(s, lit),
+        ExprKind::Lit(lit, Some(sub)) => {
+            s.push_str("(lit ");
+            write_lit(s, lit)?;
+            write!(s, " {sub}")?;
+        }
+        ExprKind::Member(expr, member) => {
+            s.push_str("(member ");
+            write_expr(s, expr)?;
+            write!(s, " {member}")?;
+        }
+        ExprKind::New(ty) => {
+            s.push_str("(new ");
+            write_ty(s, ty)?;
+        }
+        ExprKind::Payable(args) => {
+            s.push_str("(payable");
+            write_args(s, args)?;
+        }
+        ExprKind::Ternary(cond, true_, false_) => {
+            s.push_str("(ternary");
+            write_exprs(s, [&**cond, &**true_, &**false_])?;
+        }
+        ExprKind::Tuple(exprs) => {
+            s.push_str("(tuple");
+            for expr in exprs.iter() {
+                write_opt(s, expr.as_deref())?;
+            }
+        }
+        ExprKind::TypeCall(ty) => {
+            s.push_str("(type-call ");
+            write_ty(s, ty)?;
+        }
+        ExprKind::Type(ty) => {
+            s.push_str("(type ");
+            write_ty(s, ty)?;
+        }
+        ExprKind::Unary(op, expr) => {
+            let kind = if op.kind.is_postfix() { "postfix" } else { "unary" };
+            write!(s, "({kind} {} ", op.kind.to_str())?;
+            write_expr(s, expr)?;
+        }
+    }
+    s.push(')');
+    Ok(())
+}
+
+fn write_exprs<'a, 'ast: 'a>(
+    s: &mut Writer,
+    exprs: impl IntoIterator<Item = &'a Expr<'ast>>,
+) -> fmt::Result {
+    for expr in exprs {
+        s.push(' ');
+        write_expr(s, expr)?;
+    }
+    Ok(())
+}
+
+fn write_opt(s: &mut Writer, expr: Option<&Expr<'_>>) -> fmt::Result {
+    s.push(' ');
+    match expr {
+        Some(expr) => write_expr(s, expr),
+        None => {
+            s.push('_');
+            Ok(())
+        }
+    }
+}
+
+fn write_args(s: &mut Writer, args: &CallArgs<'_>) -> fmt::Result {
+    match &args.kind {
+        CallArgsKind::Unnamed(exprs) => write_exprs(s, exprs.iter().map(|e| &**e)),
+        CallArgsKind::Named(args, _) => write_named_args(s, args),
+    }
+}
+
+fn write_named_args(s: &mut Writer, args: &[NamedArg<'_>]) -> fmt::Result {
+    for arg in args {
+        write!(s, " (arg {} ", arg.name)?;
+        write_expr(s, &arg.value)?;
+        s.push(')');
+    }
+    Ok(())
+}
+
+fn write_lit(s: &mut Writer, lit: &Lit) -> fmt::Result {
+    match &lit.kind {
+        LitKind::Str(kind, _) => {
+            let prefix = match kind {
+                StrKind::Str => "",
+                StrKind::Unicode => "unicode",
+                StrKind::Hex => "hex",
+            };
+            write!(s, "{prefix}\"{}\"", lit.symbol)
+        }
+        _ => write!(s, "{}", lit.symbol),
+    }
+}
+
+fn write_ty(s: &mut Writer, ty: &Type<'_>) -> fmt::Result {
+    match &ty.kind {
+        TypeKind::Elementary(ElementaryType::Address(true)) => s.push_str("(address payable)"),
+        TypeKind::Elementary(ty) => ty.write_abi_str(s)?,
+        TypeKind::Array(array) => {
+            s.push_str("(array-type ");
+            write_ty(s, &array.element)?;
+            write_opt(s, array.size.as_deref())?;
+            s.push(')');
+        }
+        TypeKind::Function(function) => {
+            s.push_str("(function (");
+            write_params(s, &function.parameters)?;
+            s.push(')');
+            if let Some(visibility) = function.visibility {
+                write!(s, " {visibility}")?;
+            }
+            if !function.state_mutability.is_non_payable() {
+                write!(s, " {}", function.state_mutability)?;
+            }
+            s.push_str(" (");
+            write_params(s, &function.returns)?;
+            s.push_str("))");
+        }
+        TypeKind::Mapping(mapping) => {
+            s.push_str("(mapping ");
+            write_ty(s, &mapping.key)?;
+            s.push(' ');
+            write_ty(s, &mapping.value)?;
+            s.push(')');
+        }
+        TypeKind::Custom(path) => write!(s, "{path}")?,
+        TypeKind::Var => s.push_str("var"),
+    }
+    Ok(())
+}
+
+fn write_params(s: &mut Writer, params: &[VariableDefinition<'_>]) -> fmt::Result {
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            s.push(' ');
+        }
+        write_ty(s, &param.ty)?;
+    }
+    Ok(())
+}
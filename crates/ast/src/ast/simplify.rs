@@ -0,0 +1,184 @@
+use super::{Arena, BinOpKind, Box, CallArgs, Expr, ExprKind, IndexKind, UnOp, UnOpKind};
+use std::mem;
+
+impl<'ast> Expr<'ast> {
+    /// Simplifies the logical negations in the expression in place:
+    /// - `!!x` becomes `x`;
+    /// - `!(a == b)` becomes `(a != b)`, and likewise for the other comparison operators, with
+    ///   the [negated](BinOpKind::negate) operator;
+    /// - `!(a && b)` becomes `(!a || !b)`, and `!(a || b)` becomes `(!a && !b)` (De Morgan's
+    ///   laws), and the new negations are simplified in turn.
+    ///
+    /// These rewrites preserve the semantics of the expression, including its side effects: the
+    /// operands are evaluated in the same order, and De Morgan's laws preserve short-circuiting,
+    /// since `b` is evaluated in `!a || !b` exactly when it is in `!(a && b)`, that is when `a` is
+    /// `true`.
+    ///
+    /// Parentheses are kept or added where needed, so that the expression still prints as it is
+    /// structured. Nodes that are created, like the negations of the operands of `&&` and `||`,
+    /// are allocated in `arena`, and have the span of the expression they replace. Expressions
+    /// nested in types, like array sizes, are not simplified.
+    pub fn simplify_negations(&mut self, arena: &'ast Arena) {
+        while self.simplify_negation(arena) {}
+        match &mut self.kind {
+            ExprKind::Array(exprs) => exprs.iter_mut().for_each(|e| e.simplify_negations(arena)),
+            ExprKind::Assign(lhs, _, rhs) | ExprKind::Binary(lhs, _, rhs) => {
+                lhs.simplify_negations(arena);
+                rhs.simplify_negations(arena);
+            }
+            ExprKind::Call(expr, args) => {
+                expr.simplify_negations(arena);
+                simplify_args(args, arena);
+            }
+            ExprKind::CallOptions(expr, args) => {
+                expr.simplify_negations(arena);
+                args.iter_mut().for_each(|arg| arg.value.simplify_negations(arena));
+            }
+            ExprKind::Delete(expr) | ExprKind::Member(expr, _) | ExprKind::Unary(_, expr) => {
+                expr.simplify_negations(arena)
+            }
+            ExprKind::Index(expr, kind) => {
+                expr.simplify_negations(arena);
+                match kind {
+                    IndexKind::Index(index) => {
+                        index.iter_mut().for_each(|e| e.simplify_negations(arena))
+                    }
+                    IndexKind::Range(start, end) => {
+                        start.iter_mut().chain(end).for_each(|e| e.simplify_negations(arena))
+                    }
+                }
+            }
+            ExprKind::Payable(args) => simplify_args(args, arena),
+            ExprKind::Ternary(cond, true_, false_) => {
+                cond.simplify_negations(arena);
+                true_.simplify_negations(arena);
+                false_.simplify_negations(arena);
+            }
+            ExprKind::Tuple(exprs) => {
+                exprs.iter_mut().flatten().for_each(|e| e.simplify_negations(arena))
+            }
+            ExprKind::Err(_)
+            | ExprKind::Ident(_)
+            | ExprKind::Lit(..)
+            | ExprKind::New(_)
+            | ExprKind::TypeCall(_)
+            | ExprKind::Type(_) => {}
+        }
+    }
+
+    /// Applies one of the rewrites of [`simplify_negations`](Self::simplify_negations) to this
+    /// expression, if it is a negation. Returns `true` if the expression was rewritten.
+    fn simplify_negation(&mut self, arena: &'ast Arena) -> bool {
+        let ExprKind::Unary(not, operand) = &mut self.kind else { return false };
+        if not.kind != UnOpKind::Not {
+            return false;
+        }
+        let not = *not;
+        match &mut operand.strip_parens_mut().kind {
+            // `!!x` -> `x`
+            ExprKind::Unary(op, x) if op.kind == UnOpKind::Not => {
+                self.kind = take_kind(x);
+            }
+            // `!(a == b)` -> `(a != b)`
+            ExprKind::Binary(_, op, _) if op.kind.negate().is_some() => {
+                op.kind = op.kind.negate().unwrap();
+                self.kind = take_kind(operand);
+            }
+            // `!(a && b)` -> `(!a || !b)`
+            ExprKind::Binary(lhs, op, rhs) if matches!(op.kind, BinOpKind::And | BinOpKind::Or) => {
+                op.kind = if op.kind == BinOpKind::And { BinOpKind::Or } else { BinOpKind::And };
+                negate_in_place(lhs, not, arena);
+                negate_in_place(rhs, not, arena);
+                self.kind = take_kind(operand);
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl<'ast> Expr<'ast> {
+    /// Returns the expansion of the compound assignment, if the expression is one: `a += b`
+    /// expands to `a = a + b`, and `x[i] <<= n` to `x[i] = x[i] << n`. The expression itself is
+    /// left untouched.
+    ///
+    /// The left-hand side is duplicated, so it is only expanded if it is pure and only consists of
+    /// identifiers, literals, member and index accesses, parentheses, and non-modifying
+    /// operators. Evaluating it twice then has the same effect as evaluating it once, which is
+    /// not the case for `a[f()] += 1` or `a[i++] += 1`. The right-hand side must be pure too,
+    /// since the expansion reads the left-hand side before evaluating the right-hand side,
+    /// instead of after: in `x += f()`, `f` could modify `x`.
+    ///
+    /// The right-hand side is parenthesized where needed, so that `a *= b + c` expands to
+    /// `a = a * (b + c)`. The nodes of the expansion are allocated in `arena`. The copies of the
+    /// operands keep their spans, and the other new nodes have the span of the assignment.
+    pub fn expand_compound_assign(&self, arena: &'ast Arena) -> Option<Self> {
+        let ExprKind::Assign(lhs, Some(op), rhs) = &self.kind else { return None };
+        let span = self.span;
+        let target = clone_duplicable(lhs, arena)?;
+        let read = clone_duplicable(lhs, arena)?;
+        let mut rhs = clone_duplicable(rhs, arena)?;
+        if matches!(rhs.kind, ExprKind::Binary(..) | ExprKind::Ternary(..)) {
+            let exprs = arena.alloc_slice_fill_iter([Some(rhs)]);
+            rhs = arena.alloc(Expr { span, kind: ExprKind::Tuple(exprs) });
+        }
+        let value = arena.alloc(Expr { span, kind: ExprKind::Binary(read, *op, rhs) });
+        Some(Self { span, kind: ExprKind::Assign(target, None, value) })
+    }
+}
+
+/// Copies the expression into `arena`, if it can be evaluated twice with the same effect as once.
+/// See [`Expr::expand_compound_assign`].
+fn clone_duplicable<'ast>(expr: &Expr<'ast>, arena: &'ast Arena) -> Option<Box<'ast, Expr<'ast>>> {
+    let kind = match &expr.kind {
+        ExprKind::Ident(ident) => ExprKind::Ident(*ident),
+        ExprKind::Lit(lit, sub) => ExprKind::Lit(arena.literals.alloc((**lit).clone()), *sub),
+        ExprKind::Member(base, member) => ExprKind::Member(clone_duplicable(base, arena)?, *member),
+        ExprKind::Index(base, IndexKind::Index(Some(index))) => ExprKind::Index(
+            clone_duplicable(base, arena)?,
+            IndexKind::Index(Some(clone_duplicable(index, arena)?)),
+        ),
+        ExprKind::Unary(op, operand) if !op.kind.is_modifying() => {
+            ExprKind::Unary(*op, clone_duplicable(operand, arena)?)
+        }
+        ExprKind::Binary(lhs, op, rhs) => {
+            ExprKind::Binary(clone_duplicable(lhs, arena)?, *op, clone_duplicable(rhs, arena)?)
+        }
+        ExprKind::Ternary(cond, true_, false_) => ExprKind::Ternary(
+            clone_duplicable(cond, arena)?,
+            clone_duplicable(true_, arena)?,
+            clone_duplicable(false_, arena)?,
+        ),
+        ExprKind::Tuple(exprs) => match &**exprs {
+            [Some(inner)] => {
+                let inner = clone_duplicable(inner, arena)?;
+                ExprKind::Tuple(arena.alloc_slice_fill_iter([Some(inner)]))
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(arena.alloc(Expr { span: expr.span, kind }))
+}
+
+fn simplify_args<'ast>(args: &mut CallArgs<'ast>, arena: &'ast Arena) {
+    args.exprs_mut().for_each(|e| e.simplify_negations(arena));
+}
+
+/// Replaces `expr` with `!expr`, parenthesizing it if needed.
+fn negate_in_place<'ast>(expr: &mut Expr<'ast>, not: UnOp, arena: &'ast Arena) {
+    let span = expr.span;
+    let needs_parens =
+        matches!(expr.kind, ExprKind::Assign(..) | ExprKind::Binary(..) | ExprKind::Ternary(..));
+    let mut operand = arena.alloc(Expr { span, kind: take_kind(expr) });
+    if needs_parens {
+        let exprs = arena.alloc_slice_fill_iter([Some(operand)]);
+        operand = arena.alloc(Expr { span, kind: ExprKind::Tuple(exprs) });
+    }
+    expr.kind = ExprKind::Unary(not, operand);
+}
+
+/// Takes the kind out of the expression, leaving an empty tuple in its place.
+fn take_kind<'ast>(expr: &mut Expr<'ast>) -> ExprKind<'ast> {
+    mem::replace(&mut expr.kind, ExprKind::Tuple(Default::default()))
+}
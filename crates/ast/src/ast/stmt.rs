@@ -1,8 +1,8 @@
 use super::{
-    yul, AstPath, Box, CallArgs, DocComments, Expr, ParameterList, PathSlice, StrLit,
+    yul, AstPath, Box, CallArgs, DocComments, Expr, ParameterList, PathSlice, RevertKind, StrLit,
     VariableDefinition,
 };
-use solar_interface::{Ident, Span};
+use solar_interface::{sym, Ident, Span};
 
 /// A block of statements.
 pub type Block<'ast> = Box<'ast, [Stmt<'ast>]>;
@@ -17,6 +17,20 @@ pub struct Stmt<'ast> {
     pub kind: StmtKind<'ast>,
 }
 
+impl<'ast> Stmt<'ast> {
+    /// Returns the kind of the revert if this statement is a revert: either a
+    /// [`Revert`](StmtKind::Revert) statement with a custom error, `revert CustomError(args);`, or
+    /// an expression statement calling the `revert` builtin, `revert("reason");`. See
+    /// [`Expr::as_revert`].
+    pub fn as_revert(&self) -> Option<RevertKind<'_, 'ast>> {
+        match &self.kind {
+            StmtKind::Revert(path, args) => Some(RevertKind::CustomError(path, args)),
+            StmtKind::Expr(expr) => expr.as_revert(),
+            _ => None,
+        }
+    }
+}
+
 /// A kind of statement.
 #[derive(Debug)]
 pub enum StmtKind<'ast> {
@@ -66,6 +80,11 @@ pub enum StmtKind<'ast> {
     /// A revert statement: `revert Foo.bar(42);`.
     Revert(AstPath<'ast>, CallArgs<'ast>),
 
+    /// A throw statement: `throw;`.
+    ///
+    /// Only produced when parsing for a language version older than 0.5.0.
+    Throw,
+
     /// A try statement: `try fooBar(42) returns (...) { ... } catch (...) { ... }`.
     Try(Box<'ast, StmtTry<'ast>>),
 
@@ -90,6 +109,18 @@ pub struct StmtAssembly<'ast> {
     pub block: yul::Block<'ast>,
 }
 
+impl StmtAssembly<'_> {
+    /// Returns `true` if the block has the given flag, such as `"memory-safe"`.
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f.value.as_str() == flag)
+    }
+
+    /// Returns `true` if the block is annotated as memory-safe: `assembly ("memory-safe") { ... }`.
+    pub fn is_memory_safe(&self) -> bool {
+        self.has_flag("memory-safe")
+    }
+}
+
 /// A try statement: `try fooBar(42) returns (...) { ... } catch (...) { ... }`.
 ///
 /// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.tryStatement>
@@ -101,6 +132,19 @@ pub struct StmtTry<'ast> {
     pub clauses: Box<'ast, [TryCatchClause<'ast>]>,
 }
 
+impl<'ast> StmtTry<'ast> {
+    /// Returns the clause that runs if the call succeeds: `returns (...) { ... }`, or just the
+    /// block if there is no `returns`.
+    pub fn returns_clause(&self) -> &TryCatchClause<'ast> {
+        &self.clauses[0]
+    }
+
+    /// Returns the `catch` clauses, in order.
+    pub fn catch_clauses(&self) -> &[TryCatchClause<'ast>] {
+        &self.clauses[1..]
+    }
+}
+
 /// Clause of a try/catch block: `returns/catch (...) { ... }`.
 ///
 /// Includes both the successful case and the unsuccessful cases.
@@ -113,3 +157,30 @@ pub struct TryCatchClause<'ast> {
     pub args: ParameterList<'ast>,
     pub block: Block<'ast>,
 }
+
+impl TryCatchClause<'_> {
+    /// Returns the kind of errors that this clause catches, if it is a `catch` clause.
+    pub fn catch_kind(&self) -> CatchKind {
+        match self.name {
+            None => CatchKind::Any,
+            Some(name) if name.name == sym::Error => CatchKind::Error,
+            Some(name) if name.name == sym::Panic => CatchKind::Panic,
+            Some(name) => CatchKind::Custom(name),
+        }
+    }
+}
+
+/// The kind of errors caught by a `catch` clause. See [`TryCatchClause::catch_kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CatchKind {
+    /// `catch Error(string memory reason) { ... }`: reverts with a reason string, like
+    /// `require(false, "reason")`.
+    Error,
+    /// `catch Panic(uint code) { ... }`: panics, like failed assertions and arithmetic overflows.
+    Panic,
+    /// `catch (bytes memory data) { ... }` or `catch { ... }`: any error, including the ones that
+    /// are not caught by the other clauses.
+    Any,
+    /// `catch Name(...) { ... }` with any other name, which is not valid Solidity.
+    Custom(Ident),
+}
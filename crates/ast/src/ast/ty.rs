@@ -1,4 +1,4 @@
-use super::{AstPath, Box, Expr, ParameterList, StateMutability, Visibility};
+use super::{AstPath, Box, Expr, ParameterList, StateMutability, VariableDefinition, Visibility};
 use solar_interface::{kw, Ident, Span, Symbol};
 use std::{borrow::Cow, fmt};
 
@@ -29,6 +29,43 @@ impl Type<'_> {
     pub fn is_function(&self) -> bool {
         matches!(self.kind, TypeKind::Function(_))
     }
+
+    /// Returns an estimate of the number of bytes used by this type and all of its sub-nodes.
+    ///
+    /// See [`Expr::approx_memory_bytes`] for more details.
+    pub fn approx_memory_bytes(&self) -> usize {
+        size_of::<Self>() + self.heap_bytes()
+    }
+
+    /// Returns the number of bytes used by the nodes that this type points to.
+    pub(crate) fn heap_bytes(&self) -> usize {
+        let params = |params: &[VariableDefinition<'_>]| {
+            size_of_val(params)
+                + params
+                    .iter()
+                    .map(|param| {
+                        param.ty.heap_bytes()
+                            + param.initializer.as_ref().map_or(0, |e| e.approx_memory_bytes())
+                    })
+                    .sum::<usize>()
+        };
+        match &self.kind {
+            TypeKind::Elementary(_) => 0,
+            TypeKind::Array(array) => {
+                size_of::<TypeArray<'_>>()
+                    + array.element.heap_bytes()
+                    + array.size.as_ref().map_or(0, |e| e.approx_memory_bytes())
+            }
+            TypeKind::Function(f) => {
+                size_of::<TypeFunction<'_>>() + params(&f.parameters) + params(&f.returns)
+            }
+            TypeKind::Mapping(mapping) => {
+                size_of::<TypeMapping<'_>>() + mapping.key.heap_bytes() + mapping.value.heap_bytes()
+            }
+            TypeKind::Custom(path) => size_of_val(&***path),
+            TypeKind::Var => 0,
+        }
+    }
 }
 
 /// The kind of a type.
@@ -45,6 +82,11 @@ pub enum TypeKind<'ast> {
 
     /// A custom type.
     Custom(AstPath<'ast>),
+
+    /// `var`, a type inferred from the initializer.
+    ///
+    /// Only produced when parsing for a language version older than 0.5.0.
+    Var,
 }
 
 impl fmt::Debug for TypeKind<'_> {
@@ -55,6 +97,7 @@ impl fmt::Debug for TypeKind<'_> {
             Self::Function(ty) => ty.fmt(f),
             Self::Mapping(ty) => ty.fmt(f),
             Self::Custom(path) => write!(f, "Custom({path:?})"),
+            Self::Var => f.write_str("Var"),
         }
     }
 }
@@ -368,6 +411,20 @@ pub struct TypeFunction<'ast> {
     pub returns: ParameterList<'ast>,
 }
 
+impl TypeFunction<'_> {
+    /// Returns `true` if this is an external function type.
+    pub fn is_external(&self) -> bool {
+        self.visibility == Some(Visibility::External)
+    }
+
+    /// Returns `true` if this is an internal function type.
+    ///
+    /// Function types are internal by default.
+    pub fn is_internal(&self) -> bool {
+        matches!(self.visibility, None | Some(Visibility::Internal))
+    }
+}
+
 /// A mapping type.
 ///
 /// Reference: <https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.mappingType>
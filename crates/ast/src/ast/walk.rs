@@ -0,0 +1,230 @@
+use super::{Expr, ExprKind, IndexKind, Stmt, StmtAssembly};
+use crate::visit::Visit;
+use solar_data_structures::Never;
+use std::{fmt, ops::ControlFlow};
+
+/// A step from an expression to one of its direct sub-expressions.
+///
+/// See [`Expr::walk_with_path`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// The element at the given index of an array expression: `[a, b]`.
+    ArrayElement(usize),
+    /// The left-hand side of an assignment: `a = b`.
+    AssignLhs,
+    /// The right-hand side of an assignment: `a = b`.
+    AssignRhs,
+    /// The left operand of a binary expression: `a + b`.
+    BinaryLhs,
+    /// The right operand of a binary expression: `a + b`.
+    BinaryRhs,
+    /// The called expression of a call or of call options: `f(a)`, `f{value: 1}`.
+    Callee,
+    /// The argument at the given index of a call: `f(a, b)`, `f({x: a, y: b})`.
+    CallArg(usize),
+    /// The call option at the given index: `f{value: a, gas: b}`.
+    CallOption(usize),
+    /// The operand of a `delete` expression: `delete a`.
+    DeleteOperand,
+    /// The indexed expression of an index or slice expression: `a[i]`, `a[i:j]`.
+    IndexBase,
+    /// The index of an index expression: `a[i]`.
+    Index,
+    /// The start of a slice expression: `a[i:j]`.
+    SliceStart,
+    /// The end of a slice expression: `a[i:j]`.
+    SliceEnd,
+    /// The expression whose member is accessed: `a.b`.
+    MemberBase,
+    /// The argument at the given index of a `payable` expression: `payable(a)`.
+    PayableArg(usize),
+    /// The condition of a ternary expression: `c ? a : b`.
+    TernaryCond,
+    /// The expression evaluated if the condition of a ternary expression is true.
+    TernaryTrue,
+    /// The expression evaluated if the condition of a ternary expression is false.
+    TernaryFalse,
+    /// The component at the given index of a tuple expression: `(a, , b)`.
+    TupleElement(usize),
+    /// The operand of a unary expression: `-a`, `a++`.
+    UnaryOperand,
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::ArrayElement(i) => write!(f, "element {i} of the array"),
+            Self::AssignLhs => f.write_str("left-hand side of the assignment"),
+            Self::AssignRhs => f.write_str("right-hand side of the assignment"),
+            Self::BinaryLhs => f.write_str("left operand"),
+            Self::BinaryRhs => f.write_str("right operand"),
+            Self::Callee => f.write_str("called expression"),
+            Self::CallArg(i) => write!(f, "argument {i} of the call"),
+            Self::CallOption(i) => write!(f, "call option {i}"),
+            Self::DeleteOperand => f.write_str("operand of `delete`"),
+            Self::IndexBase => f.write_str("indexed expression"),
+            Self::Index => f.write_str("index"),
+            Self::SliceStart => f.write_str("start of the slice"),
+            Self::SliceEnd => f.write_str("end of the slice"),
+            Self::MemberBase => f.write_str("base of the member access"),
+            Self::PayableArg(i) => write!(f, "argument {i} of `payable`"),
+            Self::TernaryCond => f.write_str("condition"),
+            Self::TernaryTrue => f.write_str("true branch"),
+            Self::TernaryFalse => f.write_str("false branch"),
+            Self::TupleElement(i) => write!(f, "component {i} of the tuple"),
+            Self::UnaryOperand => f.write_str("operand"),
+        }
+    }
+}
+
+impl<'ast> Expr<'ast> {
+    /// Calls `f` on this expression and all of its sub-expressions in pre-order, together with
+    /// the path of [segments](PathSegment) leading from this expression to the node.
+    ///
+    /// The path is empty for this expression itself. The traversal is iterative and reuses a
+    /// single path buffer, so it does not allocate per node and does not overflow the stack on
+    /// deeply nested expressions. Expressions nested in types, like array sizes, are not visited.
+    pub fn walk_with_path<'a>(&'a self, mut f: impl FnMut(&'a Expr<'ast>, &[PathSegment])) {
+        let _ = self.try_walk_with_path(|expr, path| -> ControlFlow<Never> {
+            f(expr, path);
+            ControlFlow::Continue(())
+        });
+    }
+
+    /// Like [`walk_with_path`](Self::walk_with_path), but stops the traversal at the first
+    /// [`Break`](ControlFlow::Break) returned by `f`, and returns it.
+    pub(super) fn try_walk_with_path<'a, T>(
+        &'a self,
+        mut f: impl FnMut(&'a Expr<'ast>, &[PathSegment]) -> ControlFlow<T>,
+    ) -> ControlFlow<T> {
+        let mut path = Vec::new();
+        let mut stack = vec![(self, 0, None)];
+        while let Some((expr, depth, segment)) = stack.pop() {
+            path.truncate(depth);
+            path.extend(segment);
+            f(expr, &path)?;
+
+            let depth = path.len();
+            let start = stack.len();
+            let mut push = |expr: &'a Expr<'ast>, segment| stack.push((expr, depth, Some(segment)));
+            match &expr.kind {
+                ExprKind::Array(exprs) => {
+                    for (i, e) in exprs.iter().enumerate() {
+                        push(e, PathSegment::ArrayElement(i));
+                    }
+                }
+                ExprKind::Assign(lhs, _, rhs) => {
+                    push(lhs, PathSegment::AssignLhs);
+                    push(rhs, PathSegment::AssignRhs);
+                }
+                ExprKind::Binary(lhs, _, rhs) => {
+                    push(lhs, PathSegment::BinaryLhs);
+                    push(rhs, PathSegment::BinaryRhs);
+                }
+                ExprKind::Call(callee, args) => {
+                    push(callee, PathSegment::Callee);
+                    for (i, e) in args.exprs().enumerate() {
+                        push(e, PathSegment::CallArg(i));
+                    }
+                }
+                ExprKind::CallOptions(callee, args) => {
+                    push(callee, PathSegment::Callee);
+                    for (i, arg) in args.iter().enumerate() {
+                        push(&arg.value, PathSegment::CallOption(i));
+                    }
+                }
+                ExprKind::Delete(e) => push(e, PathSegment::DeleteOperand),
+                ExprKind::Index(base, kind) => {
+                    push(base, PathSegment::IndexBase);
+                    match kind {
+                        IndexKind::Index(Some(index)) => push(index, PathSegment::Index),
+                        IndexKind::Index(None) => {}
+                        IndexKind::Range(start, end) => {
+                            if let Some(start) = start {
+                                push(start, PathSegment::SliceStart);
+                            }
+                            if let Some(end) = end {
+                                push(end, PathSegment::SliceEnd);
+                            }
+                        }
+                    }
+                }
+                ExprKind::Member(base, _) => push(base, PathSegment::MemberBase),
+                ExprKind::Payable(args) => {
+                    for (i, e) in args.exprs().enumerate() {
+                        push(e, PathSegment::PayableArg(i));
+                    }
+                }
+                ExprKind::Ternary(cond, true_, false_) => {
+                    push(cond, PathSegment::TernaryCond);
+                    push(true_, PathSegment::TernaryTrue);
+                    push(false_, PathSegment::TernaryFalse);
+                }
+                ExprKind::Tuple(exprs) => {
+                    for (i, e) in exprs.iter().enumerate() {
+                        if let Some(e) = e {
+                            push(e, PathSegment::TupleElement(i));
+                        }
+                    }
+                }
+                ExprKind::Unary(_, e) => push(e, PathSegment::UnaryOperand),
+                ExprKind::Err(_)
+                | ExprKind::Ident(_)
+                | ExprKind::Lit(..)
+                | ExprKind::New(_)
+                | ExprKind::TypeCall(_)
+                | ExprKind::Type(_) => {}
+            }
+            // Visit the children in source order.
+            stack[start..].reverse();
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Calls `f` on every expression in the statements, including in nested statements, in source
+/// order. See [`Stmt::walk_exprs`].
+///
+/// This lets expression-level analyses run over whole blocks, like function bodies.
+pub fn walk_exprs_in_statements<'ast>(
+    stmts: &'ast [Stmt<'ast>],
+    f: impl FnMut(&'ast Expr<'ast>, &[PathSegment]),
+) {
+    let mut walker = ExprWalker(f);
+    for stmt in stmts {
+        let _ = walker.visit_stmt(stmt);
+    }
+}
+
+impl<'ast> Stmt<'ast> {
+    /// Calls `f` on every expression in this statement, including in nested statements, in
+    /// source order.
+    ///
+    /// Each of the outermost expressions of the statements, like the condition of an `if`, the
+    /// initializer of a variable declaration, or an argument of an `emit`, is walked with
+    /// [`Expr::walk_with_path`], so `f` is called with the path from the outermost expression.
+    /// Expressions in inline assembly, which are Yul expressions, and in types are not visited.
+    pub fn walk_exprs(&'ast self, f: impl FnMut(&'ast Expr<'ast>, &[PathSegment])) {
+        let _ = ExprWalker(f).visit_stmt(self);
+    }
+}
+
+/// Visits the statements, and walks each of their outermost expressions with
+/// [`Expr::walk_with_path`] instead of visiting it.
+struct ExprWalker<F>(F);
+
+impl<'ast, F: FnMut(&'ast Expr<'ast>, &[PathSegment])> Visit<'ast> for ExprWalker<F> {
+    type BreakValue = Never;
+
+    fn visit_expr(&mut self, expr: &'ast Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        expr.walk_with_path(&mut self.0);
+        ControlFlow::Continue(())
+    }
+
+    fn visit_stmt_assembly(
+        &mut self,
+        _assembly: &'ast StmtAssembly<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        ControlFlow::Continue(())
+    }
+}
@@ -12,5 +12,7 @@ pub use solar_interface as interface;
 mod ast;
 pub use ast::*;
 
+#[cfg(feature = "proptest")]
+pub mod strategy;
 pub mod token;
 pub mod visit;
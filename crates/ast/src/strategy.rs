@@ -0,0 +1,173 @@
+//! [`proptest`] strategies for operators and small, valid expressions.
+//!
+//! Expressions are generated as owned [`ExprTree`]s, which can be printed as Solidity source
+//! code and parsed into an AST. Shrinking reduces the trees toward fewer nodes and literal leaves,
+//! and never produces tuples with empty components, empty arrays, or increments and decrements of
+//! non-identifiers.
+
+use crate::{BinOpKind, UnOpKind};
+use proptest::{prelude::*, sample::Select};
+use std::fmt;
+
+/// All binary operators, from the simplest to the most unusual.
+const BIN_OPS: &[BinOpKind] = {
+    use BinOpKind::*;
+    &[
+        Add, Sub, Mul, Div, Rem, Pow, Lt, Le, Gt, Ge, Eq, Ne, And, Or, BitAnd, BitOr, BitXor, Shl,
+        Shr,
+    ]
+};
+
+/// All unary operators that do not modify their operand.
+const PURE_UN_OPS: &[UnOpKind] = &[UnOpKind::Not, UnOpKind::Neg, UnOpKind::BitNot];
+
+/// All unary operators that modify their operand.
+const MODIFYING_UN_OPS: &[UnOpKind] =
+    &[UnOpKind::PreInc, UnOpKind::PreDec, UnOpKind::PostInc, UnOpKind::PostDec];
+
+const IDENTS: &[&str] = &["a", "b", "c", "x", "y"];
+
+const MEMBERS: &[&str] = &["length", "balance", "value"];
+
+/// Returns a strategy for binary operators.
+///
+/// `>>>` is not generated, since it is not supported by recent versions of Solidity.
+pub fn bin_op_kind() -> Select<BinOpKind> {
+    prop::sample::select(BIN_OPS)
+}
+
+/// Returns a strategy for unary operators.
+pub fn un_op_kind() -> impl Strategy<Value = UnOpKind> {
+    prop_oneof![prop::sample::select(PURE_UN_OPS), prop::sample::select(MODIFYING_UN_OPS)]
+}
+
+impl Arbitrary for BinOpKind {
+    type Parameters = ();
+    type Strategy = Select<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        bin_op_kind()
+    }
+}
+
+impl Arbitrary for UnOpKind {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        un_op_kind().boxed()
+    }
+}
+
+/// An owned expression, generated by [`expr_tree`].
+///
+/// The [`Display`](fmt::Display) implementation prints the expression as Solidity source code,
+/// with every operation parenthesized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprTree {
+    /// A number literal: `42`.
+    Number(u64),
+    /// A boolean literal: `true`.
+    Bool(bool),
+    /// An identifier: `x`.
+    Ident(&'static str),
+    /// A unary operation: `(!x)`. Modifying operators are only applied to identifiers.
+    Unary(UnOpKind, Box<ExprTree>),
+    /// A binary operation: `(a + b)`.
+    Binary(Box<ExprTree>, BinOpKind, Box<ExprTree>),
+    /// A ternary expression: `(c ? a : b)`.
+    Ternary(Box<ExprTree>, Box<ExprTree>, Box<ExprTree>),
+    /// A tuple with at least two components: `(a, b)`.
+    Tuple(Vec<ExprTree>),
+    /// A non-empty array literal: `[a, b]`.
+    Array(Vec<ExprTree>),
+    /// A call with unnamed arguments: `f(a, b)`.
+    Call(Box<ExprTree>, Vec<ExprTree>),
+    /// A member access: `x.length`.
+    Member(Box<ExprTree>, &'static str),
+    /// An index access: `x[i]`.
+    Index(Box<ExprTree>, Box<ExprTree>),
+}
+
+impl fmt::Display for ExprTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Ident(name) => f.write_str(name),
+            Self::Unary(op, expr) if op.is_postfix() => write!(f, "({expr}{op})", op = op.to_str()),
+            Self::Unary(op, expr) => write!(f, "({op}{expr})", op = op.to_str()),
+            Self::Binary(lhs, op, rhs) => write!(f, "({lhs} {op} {rhs})", op = op.to_str()),
+            Self::Ternary(cond, true_, false_) => write!(f, "({cond} ? {true_} : {false_})"),
+            Self::Tuple(exprs) => write!(f, "({})", List(exprs)),
+            Self::Array(exprs) => write!(f, "[{}]", List(exprs)),
+            Self::Call(callee, args) => write!(f, "{}({})", Base(callee), List(args)),
+            Self::Member(expr, member) => write!(f, "{}.{member}", Base(expr)),
+            Self::Index(expr, index) => write!(f, "{}[{index}]", Base(expr)),
+        }
+    }
+}
+
+/// Prints a comma-separated list of expressions.
+struct List<'a>(&'a [ExprTree]);
+
+impl fmt::Display for List<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, expr) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            expr.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the base of a call, member access, or index access, parenthesized unless it is an
+/// identifier, so that `1.length` is not lexed as a number.
+struct Base<'a>(&'a ExprTree);
+
+impl fmt::Display for Base<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ExprTree::Ident(name) => f.write_str(name),
+            expr => write!(f, "({expr})"),
+        }
+    }
+}
+
+/// Returns a strategy for identifiers.
+fn ident() -> Select<&'static str> {
+    prop::sample::select(IDENTS)
+}
+
+/// Returns a strategy for small, syntactically valid expressions.
+///
+/// The generated trees have a depth of at most 4, and around 32 nodes.
+pub fn expr_tree() -> impl Strategy<Value = ExprTree> {
+    let leaf = prop_oneof![
+        any::<u64>().prop_map(ExprTree::Number),
+        any::<bool>().prop_map(ExprTree::Bool),
+        ident().prop_map(ExprTree::Ident),
+    ];
+    leaf.prop_recursive(4, 32, 4, |inner| {
+        let boxed = || inner.clone().prop_map(Box::new);
+        prop_oneof![
+            (prop::sample::select(PURE_UN_OPS), boxed())
+                .prop_map(|(op, expr)| ExprTree::Unary(op, expr)),
+            (prop::sample::select(MODIFYING_UN_OPS), ident())
+                .prop_map(|(op, name)| ExprTree::Unary(op, Box::new(ExprTree::Ident(name)))),
+            (boxed(), bin_op_kind(), boxed())
+                .prop_map(|(lhs, op, rhs)| ExprTree::Binary(lhs, op, rhs)),
+            (boxed(), boxed(), boxed())
+                .prop_map(|(cond, true_, false_)| ExprTree::Ternary(cond, true_, false_)),
+            prop::collection::vec(inner.clone(), 2..=4).prop_map(ExprTree::Tuple),
+            prop::collection::vec(inner.clone(), 1..=4).prop_map(ExprTree::Array),
+            (boxed(), prop::collection::vec(inner.clone(), 0..=3))
+                .prop_map(|(callee, args)| ExprTree::Call(callee, args)),
+            (boxed(), prop::sample::select(MEMBERS))
+                .prop_map(|(expr, member)| ExprTree::Member(expr, member)),
+            (boxed(), boxed()).prop_map(|(expr, index)| ExprTree::Index(expr, index)),
+        ]
+    })
+}
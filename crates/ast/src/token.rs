@@ -261,6 +261,12 @@ pub enum TokenKind {
     /// similarly to symbols in string literal tokens.
     Comment(bool /* is_doc */, CommentKind, Symbol),
 
+    /// A run of whitespace characters.
+    ///
+    /// Only emitted by lexers that keep trivia, for tooling such as syntax highlighters. It is
+    /// never passed to the parser.
+    Whitespace,
+
     /// End of file marker.
     Eof,
 }
@@ -317,6 +323,7 @@ impl TokenKind {
                 symbol.as_str()
             }
 
+            Self::Whitespace => "<whitespace>",
             Self::Eof => "<eof>",
         }
     }
@@ -343,9 +350,8 @@ impl TokenKind {
             | PlusPlus | MinusMinus | StarStar | BinOp(_) | BinOpEq(_) | At | Dot | Comma
             | Colon | Arrow | FatArrow | Question => true,
 
-            OpenDelim(..) | CloseDelim(..) | Literal(..) | Comment(..) | Ident(..) | Semi | Eof => {
-                false
-            }
+            OpenDelim(..) | CloseDelim(..) | Literal(..) | Comment(..) | Ident(..) | Whitespace
+            | Semi | Eof => false,
         }
     }
 
@@ -411,6 +417,12 @@ impl TokenKind {
         matches!(self, Self::Comment(..))
     }
 
+    /// Returns `true` if the token kind is trivia: whitespace, a comment, or a doc-comment.
+    #[inline]
+    pub const fn is_trivia(&self) -> bool {
+        matches!(self, Self::Whitespace | Self::Comment(..))
+    }
+
     /// Glues two token kinds together.
     pub const fn glue(&self, other: &Self) -> Option<Self> {
         use BinOpToken::*;
@@ -458,9 +470,8 @@ impl TokenKind {
 
             Le | EqEq | Ne | Ge | AndAnd | OrOr | Tilde | Walrus | PlusPlus | MinusMinus
             | StarStar | BinOpEq(_) | At | Dot | Comma | Semi | Arrow | FatArrow | Question
-            | OpenDelim(_) | CloseDelim(_) | Literal(..) | Ident(_) | Comment(..) | Eof => {
-                return None
-            }
+            | OpenDelim(_) | CloseDelim(_) | Literal(..) | Ident(_) | Comment(..) | Whitespace
+            | Eof => return None,
         })
     }
 }
@@ -558,6 +569,12 @@ impl Token {
         self.kind.is_op()
     }
 
+    /// Returns `true` if the token is trivia: whitespace, a comment, or a doc-comment.
+    #[inline]
+    pub const fn is_trivia(&self) -> bool {
+        self.kind.is_trivia()
+    }
+
     /// Returns the token as a unary operator, if any.
     #[inline]
     pub fn as_unop(&self, is_postfix: bool) -> Option<UnOp> {
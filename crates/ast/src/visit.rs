@@ -6,6 +6,22 @@ use solar_interface::{Ident, Span};
 use solar_macros::declare_visitors;
 use std::ops::ControlFlow;
 
+/// The kind of a lexical scope, passed to the [`enter_scope`](Visit::enter_scope) and
+/// [`exit_scope`](Visit::exit_scope) visitor hooks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ScopeKind {
+    /// A function, with its parameters, modifiers, return parameters, and body.
+    ///
+    /// The body is a [`Block`](Self::Block) scope nested in this one.
+    Function,
+    /// A block of statements, including function bodies and `unchecked` blocks.
+    Block,
+    /// A `for` statement, with the variables declared in its initializer.
+    For,
+    /// A clause of a `try` statement, with its parameters and block.
+    TryCatchClause,
+}
+
 declare_visitors! {
     /// AST traversal.
     pub trait Visit VisitMut <'ast> {
@@ -15,6 +31,21 @@ declare_visitors! {
         /// should never break.
         type BreakValue;
 
+        /// Called when entering a lexical scope, before visiting its contents.
+        ///
+        /// Every call is matched by a call to `exit_scope` with the same kind once the contents are
+        /// visited, even if the traversal breaks inside the scope.
+        fn enter_scope(&mut self, kind: ScopeKind) {
+            // noop by default.
+            let _ = kind;
+        }
+
+        /// Called when exiting a lexical scope entered with `enter_scope`.
+        fn exit_scope(&mut self, kind: ScopeKind) {
+            // noop by default.
+            let _ = kind;
+        }
+
         fn visit_source_unit(&mut self, source_unit: &#mut SourceUnit<'ast>) -> ControlFlow<Self::BreakValue> {
             // TODO: SAFETY: Idk
             let source_unit = unsafe { trustme::decouple_lt #_mut(source_unit) };
@@ -109,10 +140,13 @@ declare_visitors! {
 
         fn visit_item_function(&mut self, function: &'ast #mut ItemFunction<'ast>) -> ControlFlow<Self::BreakValue> {
             let ItemFunction { kind: _, header, body, body_span } = function;
-            self.visit_function_header #_mut(header)?;
-            if let Some(body) = body {
-                self.visit_block #_mut(body)?;
+            self.enter_scope #_mut(ScopeKind::Function);
+            let mut flow = self.visit_function_header #_mut(header);
+            if let (true, Some(body)) = (flow.is_continue(), body) {
+                flow = self.visit_block #_mut(body);
             }
+            self.exit_scope #_mut(ScopeKind::Function);
+            flow?;
             self.visit_span #_mut(body_span)?;
             ControlFlow::Continue(())
         }
@@ -207,6 +241,7 @@ declare_visitors! {
                 TypeKind::Custom(path) => {
                     self.visit_path #_mut(path)?;
                 }
+                TypeKind::Var => {}
             }
             ControlFlow::Continue(())
         }
@@ -241,11 +276,14 @@ declare_visitors! {
         }
 
         fn visit_call_args(&mut self, args: &'ast #mut CallArgs<'ast>) -> ControlFlow<Self::BreakValue> {
-            match args {
-                CallArgs::Named(named) => {
+            let CallArgs { span, kind } = args;
+            self.visit_span #_mut(span)?;
+            match kind {
+                CallArgsKind::Named(named, braces) => {
+                    self.visit_span #_mut(braces)?;
                     self.visit_named_args #_mut(named)?;
                 }
-                CallArgs::Unnamed(unnamed) => {
+                CallArgsKind::Unnamed(unnamed) => {
                     for arg in unnamed.iter #_mut() {
                         self.visit_expr #_mut(arg)?;
                     }
@@ -255,8 +293,9 @@ declare_visitors! {
         }
 
         fn visit_named_args(&mut self, args: &'ast #mut NamedArgList<'ast>) -> ControlFlow<Self::BreakValue> {
-            for NamedArg { name, value } in args.iter #_mut() {
+            for NamedArg { name, colon_span, value } in args.iter #_mut() {
                 self.visit_ident #_mut(name)?;
+                self.visit_span #_mut(colon_span)?;
                 self.visit_expr #_mut(value)?;
             }
             ControlFlow::Continue(())
@@ -296,16 +335,22 @@ declare_visitors! {
                     self.visit_expr #_mut(expr)?;
                 }
                 StmtKind::For { init, cond, next, body } => {
+                    self.enter_scope #_mut(ScopeKind::For);
+                    let mut flow = ControlFlow::Continue(());
                     if let Some(init) = init {
-                        self.visit_stmt #_mut(init)?;
+                        flow = self.visit_stmt #_mut(init);
+                    }
+                    if let (true, Some(cond)) = (flow.is_continue(), cond) {
+                        flow = self.visit_expr #_mut(cond);
                     }
-                    if let Some(cond) = cond {
-                        self.visit_expr #_mut(cond)?;
+                    if let (true, Some(next)) = (flow.is_continue(), next) {
+                        flow = self.visit_expr #_mut(next);
                     }
-                    if let Some(next) = next {
-                        self.visit_expr #_mut(next)?;
+                    if flow.is_continue() {
+                        flow = self.visit_stmt #_mut(body);
                     }
-                    self.visit_stmt #_mut(body)?;
+                    self.exit_scope #_mut(ScopeKind::For);
+                    flow?;
                 }
                 StmtKind::If(cond, then, else_) => {
                     self.visit_expr #_mut(cond)?;
@@ -333,6 +378,7 @@ declare_visitors! {
                     self.visit_expr #_mut(cond)?;
                     self.visit_stmt #_mut(stmt)?;
                 }
+                StmtKind::Throw => {}
                 StmtKind::Placeholder => {}
             }
             ControlFlow::Continue(())
@@ -358,16 +404,26 @@ declare_visitors! {
             if let Some(name) = name {
                 self.visit_ident #_mut(name)?;
             }
-            self.visit_parameter_list #_mut(args)?;
-            self.visit_block #_mut(block)?;
-            ControlFlow::Continue(())
+            self.enter_scope #_mut(ScopeKind::TryCatchClause);
+            let mut flow = self.visit_parameter_list #_mut(args);
+            if flow.is_continue() {
+                flow = self.visit_block #_mut(block);
+            }
+            self.exit_scope #_mut(ScopeKind::TryCatchClause);
+            flow
         }
 
         fn visit_block(&mut self, block: &'ast #mut Block<'ast>) -> ControlFlow<Self::BreakValue> {
+            self.enter_scope #_mut(ScopeKind::Block);
+            let mut flow = ControlFlow::Continue(());
             for stmt in block.iter #_mut() {
-                self.visit_stmt #_mut(stmt)?;
+                flow = self.visit_stmt #_mut(stmt);
+                if flow.is_break() {
+                    break;
+                }
             }
-            ControlFlow::Continue(())
+            self.exit_scope #_mut(ScopeKind::Block);
+            flow
         }
 
         fn visit_expr(&mut self, expr: &'ast #mut Expr<'ast>) -> ControlFlow<Self::BreakValue> {
@@ -398,6 +454,7 @@ declare_visitors! {
                 ExprKind::Delete(expr) => {
                     self.visit_expr #_mut(expr)?;
                 }
+                ExprKind::Err(_guar) => {}
                 ExprKind::Ident(ident) => {
                     self.visit_ident #_mut(ident)?;
                 }
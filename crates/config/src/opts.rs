@@ -158,6 +158,11 @@ pub struct UnstableOpts {
     #[cfg_attr(feature = "clap", arg(long))]
     pub ast_stats: bool,
 
+    /// Limits the number of AST nodes parsed per file, to bound the memory used by enormous
+    /// inputs.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "N"))]
+    pub max_ast_nodes: Option<usize>,
+
     /// Print help.
     #[cfg_attr(feature = "clap", arg(long, action = clap::ArgAction::Help))]
     pub help: (),
@@ -1,6 +1,6 @@
 use super::{
-    BugAbort, Diag, DiagCtxt, DiagId, DiagMsg, ErrorGuaranteed, ExplicitBug, FatalAbort, Level,
-    MultiSpan, Style,
+    Applicability, BugAbort, Diag, DiagCtxt, DiagId, DiagMsg, ErrorGuaranteed, ExplicitBug,
+    FatalAbort, Level, MultiSpan, Style,
 };
 use crate::Span;
 use solar_data_structures::Never;
@@ -205,6 +205,12 @@ impl<G: EmissionGuarantee> DiagBuilder<'_, G> {
 
         pub fn span_label(span: Span, label: impl Into<DiagMsg>);
         pub fn span_labels(spans: impl IntoIterator<Item = Span>, label: impl Into<DiagMsg>);
+        pub fn span_suggestion(
+            span: Span,
+            msg: impl Into<DiagMsg>,
+            replacement: impl Into<String>,
+            applicability: Applicability,
+        );
 
         pub fn warn(msg: impl Into<DiagMsg>);
         pub fn span_warn(span: impl Into<MultiSpan>, msg: impl Into<DiagMsg>);
@@ -0,0 +1,84 @@
+//! Registry of diagnostic codes.
+//!
+//! Every diagnostic code emitted by the compiler is defined here, so that codes are unique and
+//! documented in a single place. Codes are stable across releases: once released, a code must not
+//! be changed or reused for a different diagnostic, even if the original diagnostic is removed.
+//!
+//! Codes in the range `0001-0999` are specific to Solar:
+//! - `04xx`: parser diagnostics;
+//! - `05xx`: lints.
+//!
+//! All other codes are shared with `solc`, and have the same meaning as the `solc` error ID with
+//! the same number.
+
+use super::DiagId;
+use crate::error_code;
+
+// Parser.
+
+/// An operand is missing in an expression: `a + ;`, `f(a, )`.
+pub const EXPECTED_EXPRESSION: DiagId = error_code!(401);
+/// An array expression has an empty component: `[a, , b]`.
+pub const EMPTY_ARRAY_COMPONENT: DiagId = error_code!(402);
+/// Unary plus is used: `+a`.
+pub const UNARY_PLUS: DiagId = error_code!(403);
+/// An operator is used that is disabled in the parser dialect.
+pub const OPERATOR_NOT_IN_DIALECT: DiagId = error_code!(404);
+/// Syntax is used that was removed before the language version targeted by the parser dialect:
+/// `var x = 1;`, `throw;`.
+pub const REMOVED_SYNTAX: DiagId = error_code!(405);
+/// A literal has more than one sub-denomination: `5 days seconds`.
+pub const MULTIPLE_SUBDENOMINATIONS: DiagId = error_code!(406);
+/// A sub-denomination is applied to a literal that is not a number: `"a" ether`.
+pub const SUBDENOMINATION_ON_NON_NUMBER: DiagId = error_code!(407);
+
+// Lints.
+
+/// Comparison operators are chained: `a < b < c`.
+pub const CHAINED_COMPARISON: DiagId = error_code!(501);
+/// A lint suppression comment does not suppress any lint.
+pub const UNUSED_SUPPRESSION: DiagId = error_code!(502);
+/// A value is raised to a small constant power: `x ** 2`.
+pub const SMALL_POWER: DiagId = error_code!(503);
+/// A value is compared to a boolean literal: `x == true`.
+pub const BOOL_COMPARISON: DiagId = error_code!(504);
+/// A value is compared to a bound of its type, making the comparison always true or always false:
+/// `x < 0` for an unsigned `x`.
+pub const TAUTOLOGICAL_COMPARISON: DiagId = error_code!(505);
+/// A logical negation can be simplified by removing it: `!(a == b)`, `!!x`.
+pub const SIMPLIFIABLE_NEGATION: DiagId = error_code!(506);
+/// A bitwise operator is used where a logical one is likely intended: `if (a & b)`,
+/// `(a < b) | (c > d)`.
+pub const BITWISE_LOGICAL_OPERATION: DiagId = error_code!(507);
+/// An assignment is used as a condition where a comparison is likely intended: `if (a = b)`.
+pub const ASSIGNMENT_IN_CONDITION: DiagId = error_code!(508);
+/// A comparison or an arithmetic operation mixes signed and unsigned integers: `x < y` for an
+/// `int256` `x` and a `uint256` `y`.
+pub const MIXED_SIGNEDNESS: DiagId = error_code!(509);
+/// An explicit conversion converts a value to the type that it already has: `uint256(x)` for a
+/// `uint256` `x`.
+pub const REDUNDANT_CONVERSION: DiagId = error_code!(510);
+/// A builtin name that is shadowed by a local declaration is used: `require(x)` in a function
+/// that declares a local `require`.
+pub const SHADOWED_BUILTIN: DiagId = error_code!(511);
+
+// `solc`.
+
+/// A declaration shadows a builtin global: `uint block;`.
+pub const BUILTIN_SHADOWING: DiagId = error_code!(2319);
+/// A free function is named `fallback` or `receive`.
+pub const FUNCTION_NAMED_FALLBACK_OR_RECEIVE: DiagId = error_code!(3445);
+/// A `catch` clause is named something other than `Error` or `Panic`: `catch Failure(bytes data)`.
+pub const INVALID_CATCH_CLAUSE_NAME: DiagId = error_code!(3542);
+/// The argument of a `type(T)` expression is not a contract, enum, or integer type:
+/// `type(bool)`.
+pub const INVALID_TYPE_ARGUMENT: DiagId = error_code!(4259);
+/// A parameter of a function type is named.
+pub const NAMED_FUNCTION_TYPE_PARAMETER: DiagId = error_code!(6162);
+/// A hexadecimal literal looks like an address, but is not a valid one: it does not have exactly
+/// 40 digits, or does not have a valid EIP-55 checksum.
+pub const INVALID_ADDRESS_LITERAL: DiagId = error_code!(9429);
+/// A member does not exist in the accessed type: `type(uint256).creationCode`.
+pub const MEMBER_NOT_FOUND: DiagId = error_code!(9582);
+/// A Yul `switch` statement has only a default case.
+pub const SWITCH_ONLY_DEFAULT_CASE: DiagId = error_code!(9592);
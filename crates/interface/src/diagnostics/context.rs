@@ -16,6 +16,11 @@ pub struct DiagCtxtFlags {
     pub treat_err_as_bug: Option<NonZeroUsize>,
     /// If true, identical diagnostics are reported only once.
     pub deduplicate_diagnostics: bool,
+    /// If true, diagnostics are considered identical when their level, code, messages, and
+    /// primary spans are equal, even if their secondary labels, notes, or suggestions differ.
+    ///
+    /// Only used if `deduplicate_diagnostics` is true.
+    pub deduplicate_by_primary: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`, and by default in debug
     /// builds.
     pub track_diagnostics: bool,
@@ -27,6 +32,7 @@ impl Default for DiagCtxtFlags {
             can_emit_warnings: true,
             treat_err_as_bug: None,
             deduplicate_diagnostics: true,
+            deduplicate_by_primary: true,
             track_diagnostics: cfg!(debug_assertions),
         }
     }
@@ -290,7 +296,11 @@ impl DiagCtxtInner {
             diagnostic.level = Level::Bug;
         }
 
-        let already_emitted = self.insert_diagnostic(diagnostic);
+        let already_emitted = if self.flags.deduplicate_by_primary {
+            self.insert_diagnostic(&diagnostic.primary_keys())
+        } else {
+            self.insert_diagnostic(diagnostic)
+        };
         if !(self.flags.deduplicate_diagnostics && already_emitted) {
             // Remove duplicate `Once*` subdiagnostics.
             diagnostic.children.retain(|sub| {
@@ -391,3 +401,38 @@ impl DiagCtxtInner {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BytePos, Span};
+
+    fn emit_duplicates(dcx: &DiagCtxt) -> String {
+        let primary = Span::new(BytePos(0), BytePos(1));
+        let secondary = Span::new(BytePos(2), BytePos(3));
+        for label in ["first", "second", "second"] {
+            let _ = dcx.err("duplicate").span(primary).span_label(secondary, label).emit();
+        }
+        dcx.emitted_diagnostics().unwrap().to_string()
+    }
+
+    #[test]
+    fn deduplicate() {
+        let dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never);
+        let out = emit_duplicates(&dcx);
+        assert_eq!(out.matches("error: duplicate").count(), 1, "{out}");
+        assert_eq!(dcx.err_count(), 3);
+        assert_eq!(dcx.inner.lock().deduplicated_err_count, 1);
+
+        // Only exact duplicates.
+        let dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never)
+            .set_flags(|flags| flags.deduplicate_by_primary = false);
+        let out = emit_duplicates(&dcx);
+        assert_eq!(out.matches("error: duplicate").count(), 2, "{out}");
+
+        let dcx = DiagCtxt::with_buffer_emitter(None, ColorChoice::Never)
+            .set_flags(|flags| flags.deduplicate_diagnostics = false);
+        let out = emit_duplicates(&dcx);
+        assert_eq!(out.matches("error: duplicate").count(), 3, "{out}");
+    }
+}
@@ -1,6 +1,6 @@
 use super::{io_panic, rustc::FileWithAnnotatedLines, Diag, Emitter};
 use crate::{
-    diagnostics::{Level, MultiSpan, Style, SubDiagnostic},
+    diagnostics::{Level, MultiSpan, Style, SubDiagnostic, Suggestion},
     source_map::SourceFile,
     SourceMap,
 };
@@ -190,6 +190,7 @@ impl HumanEmitter {
             .iter()
             .filter(|sub| sub.span.is_dummy())
             .map(OwnedMessage::from_subdiagnostic)
+            .chain(diagnostic.suggestions.iter().map(OwnedMessage::from_suggestion))
             .collect();
 
         let snippet = title
@@ -286,6 +287,11 @@ impl OwnedMessage {
         Self { id: None, label: sub.label().into_owned(), level: to_as_level(sub.level) }
     }
 
+    fn from_suggestion(suggestion: &Suggestion) -> Self {
+        let label = format!("{}: `{}`", suggestion.msg.as_str(), suggestion.replacement);
+        Self { id: None, label, level: to_as_level(Level::Help) }
+    }
+
     fn as_ref(&self) -> Message<'_> {
         let mut msg = self.level.title(&self.label);
         if let Some(id) = &self.id {
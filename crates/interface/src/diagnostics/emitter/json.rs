@@ -1,6 +1,6 @@
 use super::{human::HumanBufferEmitter, io_panic, Emitter};
 use crate::{
-    diagnostics::{Level, MultiSpan, SpanLabel},
+    diagnostics::{Level, MultiSpan, SpanLabel, Suggestion},
     source_map::{LineInfo, SourceFile},
     SourceMap, Span,
 };
@@ -75,7 +75,12 @@ impl JsonEmitter {
             code: diagnostic.id().map(|code| DiagnosticCode { code, explanation: None }),
             level: diagnostic.level.to_str(),
             spans: self.spans(&diagnostic.span),
-            children: diagnostic.children.iter().map(|sub| self.sub_diagnostic(sub)).collect(),
+            children: diagnostic
+                .children
+                .iter()
+                .map(|sub| self.sub_diagnostic(sub))
+                .chain(diagnostic.suggestions.iter().map(|sugg| self.suggestion(sugg)))
+                .collect(),
             rendered: Some(self.emit_diagnostic_to_buffer(diagnostic)),
         }
     }
@@ -91,6 +96,23 @@ impl JsonEmitter {
         }
     }
 
+    fn suggestion(&self, suggestion: &Suggestion) -> Diag {
+        let label = SpanLabel { span: suggestion.span, is_primary: true, label: None };
+        let span = DiagnosticSpan {
+            suggested_replacement: Some(suggestion.replacement.clone()),
+            suggestion_applicability: Some(suggestion.applicability.to_str()),
+            ..self.span(&label)
+        };
+        Diag {
+            message: suggestion.msg.as_str().into(),
+            code: None,
+            level: Level::Help.to_str(),
+            spans: vec![span],
+            children: vec![],
+            rendered: None,
+        }
+    }
+
     fn spans(&self, msp: &MultiSpan) -> Vec<DiagnosticSpan> {
         msp.span_labels().iter().map(|label| self.span(label)).collect()
     }
@@ -111,6 +133,8 @@ impl JsonEmitter {
             is_primary: label.is_primary,
             text: self.span_lines(span),
             label: label.label.as_ref().map(|msg| msg.as_str().into()),
+            suggested_replacement: None,
+            suggestion_applicability: None,
         }
     }
 
@@ -250,9 +274,11 @@ struct DiagnosticSpan {
     text: Vec<DiagnosticSpanLine>,
     /// Label that should be placed at this location (if any)
     label: Option<String>,
-    // /// If we are suggesting a replacement, this will contain text
-    // /// that should be sliced in atop this span.
-    // suggested_replacement: Option<String>,
+    /// If we are suggesting a replacement, this will contain text
+    /// that should be sliced in atop this span.
+    suggested_replacement: Option<String>,
+    /// If the suggestion is approximate.
+    suggestion_applicability: Option<&'static str>,
 }
 
 #[derive(Serialize)]
@@ -9,6 +9,8 @@ use std::{borrow::Cow, fmt, panic::Location};
 mod builder;
 pub use builder::{DiagBuilder, EmissionGuarantee};
 
+pub mod codes;
+
 mod context;
 pub use context::{DiagCtxt, DiagCtxtFlags};
 
@@ -311,6 +313,45 @@ impl SubDiagnostic {
     }
 }
 
+/// The confidence that a [`Suggestion`] is correct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended, and can be applied automatically.
+    MachineApplicable,
+    /// The suggestion may be what the user intended, but it is uncertain, and should be reviewed
+    /// before being applied.
+    MaybeIncorrect,
+    /// The suggestion contains placeholders like `(...)`, and cannot be applied as is.
+    HasPlaceholders,
+    /// The applicability of the suggestion is unknown.
+    Unspecified,
+}
+
+impl Applicability {
+    /// Returns the string representation of the applicability.
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Self::MachineApplicable => "MachineApplicable",
+            Self::MaybeIncorrect => "MaybeIncorrect",
+            Self::HasPlaceholders => "HasPlaceholders",
+            Self::Unspecified => "Unspecified",
+        }
+    }
+}
+
+/// A suggestion to replace the source code at a span with new text.
+#[derive(Clone, Debug, PartialEq, Hash)]
+pub struct Suggestion {
+    /// The message describing the suggestion.
+    pub msg: DiagMsg,
+    /// The span to replace.
+    pub span: Span,
+    /// The text to replace the span with. If empty, the suggestion is to remove the span.
+    pub replacement: String,
+    /// The confidence that the suggestion is correct.
+    pub applicability: Applicability,
+}
+
 /// A compiler diagnostic.
 #[must_use]
 #[derive(Clone, Debug)]
@@ -320,6 +361,7 @@ pub struct Diag {
     pub messages: Vec<(DiagMsg, Style)>,
     pub span: MultiSpan,
     pub children: Vec<SubDiagnostic>,
+    pub suggestions: Vec<Suggestion>,
     pub code: Option<DiagId>,
 
     pub created_at: &'static Location<'static>,
@@ -353,7 +395,7 @@ impl Diag {
             code: None,
             span: MultiSpan::new(),
             children: vec![],
-            // suggestions: Ok(vec![]),
+            suggestions: vec![],
             // args: Default::default(),
             // sort_span: DUMMY_SP,
             // is_lint: false,
@@ -395,11 +437,17 @@ impl Diag {
             // self.args().collect(),
             &self.code,
             &self.span,
-            // &self.suggestions,
+            &self.suggestions,
             // (if self.is_lint { None } else { Some(&self.children) }),
             &self.children,
         )
     }
+
+    /// Fields used to deduplicate diagnostics with
+    /// [`deduplicate_by_primary`](DiagCtxtFlags::deduplicate_by_primary).
+    fn primary_keys(&self) -> impl std::hash::Hash + '_ {
+        (&self.level, &self.messages, &self.code, self.span.primary_spans())
+    }
 }
 
 /// Setters.
@@ -443,6 +491,26 @@ impl Diag {
         self
     }
 
+    /// Adds a suggestion to replace the source code at `span` with `replacement`.
+    ///
+    /// The suggestion is rendered as a help message, and is included in the JSON output together
+    /// with its applicability, so that tools can apply it automatically.
+    pub fn span_suggestion(
+        &mut self,
+        span: Span,
+        msg: impl Into<DiagMsg>,
+        replacement: impl Into<String>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.suggestions.push(Suggestion {
+            msg: msg.into(),
+            span,
+            replacement: replacement.into(),
+            applicability,
+        });
+        self
+    }
+
     /// Adds a note with the location where this diagnostic was created and emitted.
     pub(crate) fn locations_note(&mut self, emitted_at: &Location<'_>) -> &mut Self {
         let msg = format!(
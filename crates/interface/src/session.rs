@@ -455,4 +455,29 @@ mod tests {
         assert!(sess.dcx.emitted_errors().unwrap().unwrap_err().to_string().contains("test1"));
         assert!(sess.dcx.emitted_errors().unwrap().unwrap_err().to_string().contains("test2"));
     }
+
+    #[test]
+    fn suggestions() {
+        use crate::{diagnostics::Applicability, BytePos, Span};
+
+        let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).build();
+        let src = "x = a == true;";
+        sess.source_map().new_dummy_source_file("suggestion.sol".into(), src.into()).unwrap();
+        sess.enter(|| {
+            let span = Span::new(BytePos(4), BytePos(13));
+            let diag = sess.dcx.warn("redundant comparison").span(span).span_suggestion(
+                span,
+                "use the value directly",
+                "a",
+                Applicability::MachineApplicable,
+            );
+            let [suggestion] = &diag.suggestions[..] else { panic!("{:?}", diag.suggestions) };
+            assert_eq!(suggestion.span, span);
+            assert_eq!(suggestion.replacement, "a");
+            assert_eq!(suggestion.applicability, Applicability::MachineApplicable);
+            diag.emit();
+        });
+        let diags = sess.emitted_diagnostics().unwrap().to_string();
+        assert!(diags.contains("= help: use the value directly: `a`"), "{diags}");
+    }
 }
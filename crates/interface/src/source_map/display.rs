@@ -0,0 +1,66 @@
+use super::SourceMap;
+use crate::Span;
+use std::fmt;
+
+/// Formatting with access to a [`SourceMap`].
+///
+/// This is like [`Display`](fmt::Display), but for values that are more useful when rendered with
+/// the source code they refer to, like spans and AST nodes. Use
+/// [`display_with`](Self::display_with) to get a value that implements `Display`.
+///
+/// Implementations must not panic if the value does not refer to any source in the source map,
+/// like a dummy span, and should fall back to a less precise output instead.
+pub trait DisplayWithContext {
+    /// Formats the value using the given source map.
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, ctx: &SourceMap) -> fmt::Result;
+
+    /// Returns a value that implements [`Display`](fmt::Display) by calling
+    /// [`fmt_with`](Self::fmt_with) with the given source map.
+    #[inline]
+    fn display_with<'a>(&'a self, ctx: &'a SourceMap) -> WithContext<'a, Self> {
+        WithContext { value: self, ctx }
+    }
+}
+
+impl<T: DisplayWithContext + ?Sized> DisplayWithContext for &T {
+    #[inline]
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, ctx: &SourceMap) -> fmt::Result {
+        (**self).fmt_with(f, ctx)
+    }
+}
+
+/// A value rendered with a source map. See [`DisplayWithContext::display_with`].
+pub struct WithContext<'a, T: ?Sized> {
+    value: &'a T,
+    ctx: &'a SourceMap,
+}
+
+impl<T: DisplayWithContext + ?Sized> fmt::Display for WithContext<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt_with(f, self.ctx)
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for WithContext<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithContext").finish_non_exhaustive()
+    }
+}
+
+/// Renders the start of the span as `file:line:col`, with 1-based line and column numbers.
+///
+/// Dummy spans are rendered as `no-location`, and spans that are not in the source map as their
+/// raw byte positions, like `Span(12..34)`.
+impl DisplayWithContext for Span {
+    fn fmt_with(&self, f: &mut fmt::Formatter<'_>, ctx: &SourceMap) -> fmt::Result {
+        if self.is_dummy() {
+            return f.write_str("no-location");
+        }
+        if !ctx.contains_span(*self) {
+            return write!(f, "Span({lo}..{hi})", lo = self.lo().0, hi = self.hi().0);
+        }
+        let loc = ctx.lookup_char_pos(self.lo());
+        write!(f, "{}:{}:{}", loc.file.name.display(), loc.line, loc.col.to_usize() + 1)
+    }
+}
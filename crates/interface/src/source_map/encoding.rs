@@ -0,0 +1,286 @@
+use super::{file::BOM, SourceFile};
+use crate::{RelativeBytePos, Span};
+use std::{io, ops::Range};
+
+/// The encoding of a source that is not given as UTF-8 text.
+///
+/// Offsets in a source are counted in the code units of its encoding: bytes for UTF-8, 16-bit
+/// units for UTF-16, and 32-bit units, that is characters, for UTF-32.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SourceEncoding {
+    /// UTF-8.
+    Utf8,
+    /// UTF-16, in little-endian byte order when decoding bytes.
+    Utf16Le,
+    /// UTF-16, in big-endian byte order when decoding bytes.
+    Utf16Be,
+    /// UTF-32, in little-endian byte order when decoding bytes.
+    Utf32Le,
+    /// UTF-32, in big-endian byte order when decoding bytes.
+    Utf32Be,
+}
+
+impl SourceEncoding {
+    /// Returns the size of a code unit in bytes.
+    pub const fn unit_size(self) -> usize {
+        match self {
+            Self::Utf8 => 1,
+            Self::Utf16Le | Self::Utf16Be => 2,
+            Self::Utf32Le | Self::Utf32Be => 4,
+        }
+    }
+
+    /// Returns the number of code units of the character in this encoding.
+    fn char_units(self, c: char) -> usize {
+        match self {
+            Self::Utf8 => c.len_utf8(),
+            Self::Utf16Le | Self::Utf16Be => c.len_utf16(),
+            Self::Utf32Le | Self::Utf32Be => 1,
+        }
+    }
+}
+
+/// A source decoded to UTF-8, along with the mapping between its UTF-8 byte offsets and the code
+/// unit offsets of its original [encoding](SourceEncoding).
+///
+/// The decoded source is added to a [`SourceMap`](super::SourceMap) like any other source, and
+/// the spans into it can be converted back to the original code units with
+/// [`span_to_units`](Self::span_to_units). The conversions are exact in both directions for
+/// offsets at character boundaries.
+///
+/// A leading byte order mark is kept in the decoded source, and counted in its offsets, but it is
+/// removed from the [`SourceFile`] created from it. The conversions between spans and code units
+/// account for it.
+#[derive(Clone, Debug)]
+pub struct DecodedSource {
+    src: String,
+    encoding: SourceEncoding,
+    /// The characters that do not have the same number of UTF-8 bytes and code units, in order.
+    chars: Vec<WideChar>,
+}
+
+/// A character that takes a different number of UTF-8 bytes and code units.
+#[derive(Clone, Copy, Debug)]
+struct WideChar {
+    /// The UTF-8 byte offset of the character.
+    byte_pos: u32,
+    /// The code unit offset of the character.
+    unit_pos: u32,
+    /// The number of UTF-8 bytes of the character.
+    bytes: u8,
+    /// The number of code units of the character.
+    units: u8,
+}
+
+impl DecodedSource {
+    /// Decodes UTF-16 code units.
+    ///
+    /// Returns an error with the offset of the first unpaired surrogate if the source is not
+    /// valid UTF-16.
+    pub fn from_utf16(units: &[u16]) -> io::Result<Self> {
+        let mut src = String::with_capacity(units.len());
+        let mut offset = 0;
+        for c in char::decode_utf16(units.iter().copied()) {
+            let c = c.map_err(|e| {
+                invalid_data(format!(
+                    "source is not valid UTF-16: unpaired surrogate 0x{:04x} at unit {offset}",
+                    e.unpaired_surrogate()
+                ))
+            })?;
+            offset += c.len_utf16();
+            src.push(c);
+        }
+        Ok(Self::new(src, SourceEncoding::Utf16Le))
+    }
+
+    /// Decodes UTF-32 code units.
+    ///
+    /// Returns an error with the offset of the first invalid unit if the source is not valid
+    /// UTF-32.
+    pub fn from_utf32(units: &[u32]) -> io::Result<Self> {
+        let src = units
+            .iter()
+            .enumerate()
+            .map(|(i, &unit)| {
+                char::from_u32(unit).ok_or_else(|| {
+                    invalid_data(format!(
+                        "source is not valid UTF-32: invalid unit 0x{unit:08x} at unit {i}"
+                    ))
+                })
+            })
+            .collect::<io::Result<String>>()?;
+        Ok(Self::new(src, SourceEncoding::Utf32Le))
+    }
+
+    /// Decodes the bytes of a source in the given encoding.
+    ///
+    /// Returns an error if the length of the bytes is not a multiple of the code unit size, or
+    /// if the source is not valid in the encoding.
+    pub fn from_bytes(bytes: &[u8], encoding: SourceEncoding) -> io::Result<Self> {
+        let size = encoding.unit_size();
+        if bytes.len() % size != 0 {
+            return Err(invalid_data(format!(
+                "source length of {} bytes is not a multiple of the {size}-byte code unit size",
+                bytes.len()
+            )));
+        }
+        let mut decoded = match encoding {
+            SourceEncoding::Utf8 => {
+                let src = std::str::from_utf8(bytes).map_err(|e| {
+                    invalid_data(format!(
+                        "source is not valid UTF-8: invalid byte at offset {}",
+                        e.valid_up_to()
+                    ))
+                })?;
+                return Ok(Self::new(src.to_string(), encoding));
+            }
+            SourceEncoding::Utf16Le | SourceEncoding::Utf16Be => {
+                let le = encoding == SourceEncoding::Utf16Le;
+                let units = bytes
+                    .chunks_exact(2)
+                    .map(|b| {
+                        let b = [b[0], b[1]];
+                        if le {
+                            u16::from_le_bytes(b)
+                        } else {
+                            u16::from_be_bytes(b)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                Self::from_utf16(&units)?
+            }
+            SourceEncoding::Utf32Le | SourceEncoding::Utf32Be => {
+                let le = encoding == SourceEncoding::Utf32Le;
+                let units = bytes
+                    .chunks_exact(4)
+                    .map(|b| {
+                        let b = [b[0], b[1], b[2], b[3]];
+                        if le {
+                            u32::from_le_bytes(b)
+                        } else {
+                            u32::from_be_bytes(b)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                Self::from_utf32(&units)?
+            }
+        };
+        decoded.encoding = encoding;
+        Ok(decoded)
+    }
+
+    fn new(src: String, encoding: SourceEncoding) -> Self {
+        let mut chars = Vec::new();
+        let mut unit_pos = 0;
+        for (byte_pos, c) in src.char_indices() {
+            let (bytes, units) = (c.len_utf8(), encoding.char_units(c));
+            if bytes != units {
+                chars.push(WideChar {
+                    byte_pos: byte_pos as u32,
+                    unit_pos: unit_pos as u32,
+                    bytes: bytes as u8,
+                    units: units as u8,
+                });
+            }
+            unit_pos += units;
+        }
+        Self { src, encoding, chars }
+    }
+
+    /// Returns the decoded source.
+    pub fn as_str(&self) -> &str {
+        &self.src
+    }
+
+    /// Returns the decoded source.
+    pub fn into_string(self) -> String {
+        self.src
+    }
+
+    /// Returns the original encoding of the source.
+    pub fn encoding(&self) -> SourceEncoding {
+        self.encoding
+    }
+
+    /// Returns the length of the source in code units.
+    pub fn len_units(&self) -> usize {
+        self.byte_to_unit(RelativeBytePos::from_usize(self.src.len())).unwrap()
+    }
+
+    /// Converts a UTF-8 byte offset into the decoded source to a code unit offset.
+    ///
+    /// Returns `None` if the offset is out of bounds or inside a character.
+    pub fn byte_to_unit(&self, pos: RelativeBytePos) -> Option<usize> {
+        let pos = pos.to_u32();
+        if pos as usize > self.src.len() {
+            return None;
+        }
+        match self.chars.partition_point(|c| c.byte_pos < pos).checked_sub(1) {
+            None => Some(pos as usize),
+            Some(i) => {
+                let c = self.chars[i];
+                let end = c.byte_pos + c.bytes as u32;
+                if pos < end {
+                    return None;
+                }
+                Some((c.unit_pos + c.units as u32 + (pos - end)) as usize)
+            }
+        }
+    }
+
+    /// Converts a code unit offset to a UTF-8 byte offset into the decoded source.
+    ///
+    /// Returns `None` if the offset is out of bounds or inside a character.
+    pub fn unit_to_byte(&self, unit: usize) -> Option<RelativeBytePos> {
+        if unit > self.len_units() {
+            return None;
+        }
+        let unit = unit as u32;
+        match self.chars.partition_point(|c| c.unit_pos < unit).checked_sub(1) {
+            None => Some(RelativeBytePos::from_u32(unit)),
+            Some(i) => {
+                let c = self.chars[i];
+                let end = c.unit_pos + c.units as u32;
+                if unit < end {
+                    return None;
+                }
+                Some(RelativeBytePos::from_u32(c.byte_pos + c.bytes as u32 + (unit - end)))
+            }
+        }
+    }
+
+    /// Converts a span into `file`, which must have been created from this source, to a range of
+    /// code units.
+    ///
+    /// Returns `None` if the span is not in the file or does not start and end at character
+    /// boundaries.
+    pub fn span_to_units(&self, file: &SourceFile, span: Span) -> Option<Range<usize>> {
+        if !(file.start_pos <= span.lo() && span.hi() <= file.end_position()) {
+            return None;
+        }
+        let lo = self.byte_to_unit(file.original_relative_byte_pos(span.lo()))?;
+        let hi = self.byte_to_unit(file.original_relative_byte_pos(span.hi()))?;
+        Some(lo..hi)
+    }
+
+    /// Converts a range of code units to a span into `file`, which must have been created from
+    /// this source.
+    ///
+    /// Offsets before the end of the byte order mark, if any, map to the start of the file.
+    ///
+    /// Returns `None` if the range is out of bounds or does not start and end at character
+    /// boundaries.
+    pub fn units_to_span(&self, file: &SourceFile, units: Range<usize>) -> Option<Span> {
+        let to_file = |pos: RelativeBytePos| {
+            let bom = if file.has_bom { BOM.len() } else { 0 };
+            file.absolute_position(RelativeBytePos::from_usize(pos.to_usize().saturating_sub(bom)))
+        };
+        let lo = self.unit_to_byte(units.start)?;
+        let hi = self.unit_to_byte(units.end)?;
+        Some(Span::new(to_file(lo), to_file(hi)))
+    }
+}
+
+fn invalid_data(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -1,7 +1,7 @@
-use crate::{pos::RelativeBytePos, BytePos, CharPos};
+use crate::{pos::RelativeBytePos, BytePos, CharPos, Span};
 use std::{
     fmt, io,
-    ops::RangeInclusive,
+    ops::{Index, RangeInclusive},
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -221,6 +221,9 @@ pub struct SourceFile {
     pub multibyte_chars: Vec<MultiByteChar>,
     /// Width of characters that are not narrow in the source code.
     pub non_narrow_chars: Vec<NonNarrowChar>,
+    /// Whether the original source code started with a UTF-8 byte order mark, which has been
+    /// removed from [`src`](Self::src).
+    pub has_bom: bool,
     /// A hash of the filename & crate-id, used for uniquely identifying source
     /// files within the crate graph and for speeding up hashing in incremental
     /// compilation.
@@ -236,6 +239,7 @@ impl SourceFile {
         // Compute the file hash before any normalization.
         let src_hash = SourceFileHash::new(hash_kind, &src);
         // let normalized_pos = normalize_src(&mut src);
+        let has_bom = remove_bom(&mut src);
 
         let stable_id = StableSourceFileId::from_filename_in_current_crate(&name);
         let source_len = src.len();
@@ -253,6 +257,7 @@ impl SourceFile {
             lines,
             multibyte_chars,
             non_narrow_chars,
+            has_bom,
             stable_id,
         })
     }
@@ -424,9 +429,16 @@ impl SourceFile {
 
     /// Calculates the original byte position relative to the start of the file
     /// based on the given byte position.
+    ///
+    /// This accounts for the removed byte order mark, if any. Line and column numbers are
+    /// unaffected by it, since editors do not display it.
     pub fn original_relative_byte_pos(&self, pos: BytePos) -> RelativeBytePos {
         let pos = self.relative_position(pos);
-        RelativeBytePos::from_u32(pos.0)
+        if self.has_bom {
+            pos + RelativeBytePos::from_usize(BOM.len())
+        } else {
+            pos
+        }
     }
 }
 
@@ -439,6 +451,42 @@ pub enum SourceFileHashAlgorithm {
     // Sha256,
 }
 
+/// Slices the source code of the file with a span into it: `&file[span]`.
+///
+/// A [dummy](Span::is_dummy) span slices an empty string.
+///
+/// # Panics
+///
+/// Panics if the span is not in the file, or does not start and end at character boundaries. Use
+/// [`SourceMap::span_to_snippet`](super::SourceMap::span_to_snippet) to get the source code of a
+/// span without panicking.
+impl Index<Span> for SourceFile {
+    type Output = str;
+
+    fn index(&self, span: Span) -> &str {
+        if span.is_dummy() {
+            return "";
+        }
+        if !self.contains(span.lo()) || !self.contains(span.hi()) {
+            panic!(
+                "span {:?} is out of bounds of file `{}` with range {:?}",
+                span.to_range(),
+                self.name.display(),
+                self.start_pos.to_usize()..self.end_position().to_usize(),
+            );
+        }
+        let lo = self.relative_position(span.lo()).to_usize();
+        let hi = self.relative_position(span.hi()).to_usize();
+        self.src.get(lo..hi).unwrap_or_else(|| {
+            panic!(
+                "span {:?} of file `{}` does not start and end at character boundaries",
+                span.to_range(),
+                self.name.display(),
+            )
+        })
+    }
+}
+
 impl std::str::FromStr for SourceFileHashAlgorithm {
     type Err = ();
 
@@ -532,3 +580,18 @@ impl SourceFileHash {
         self.kind.hash_len()
     }
 }
+
+/// The UTF-8 byte order mark.
+pub(super) const BOM: &str = "\u{feff}";
+
+/// Removes the leading byte order mark from the source code, if any.
+///
+/// This is done before computing any positions, so that spans match the positions of the
+/// characters in editors, which hide the byte order mark.
+fn remove_bom(src: &mut String) -> bool {
+    let has_bom = src.starts_with(BOM);
+    if has_bom {
+        src.drain(..BOM.len());
+    }
+    has_bom
+}
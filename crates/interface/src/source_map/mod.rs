@@ -1,6 +1,6 @@
 //! SourceMap related types and operations.
 
-use crate::{BytePos, CharPos, Span};
+use crate::{BytePos, CharPos, RelativeBytePos, Span};
 use solar_data_structures::{
     map::FxBuildHasher,
     sync::{ReadGuard, RwLock},
@@ -13,6 +13,12 @@ use std::{
 
 mod analyze;
 
+mod display;
+pub use display::{DisplayWithContext, WithContext};
+
+mod encoding;
+pub use encoding::{DecodedSource, SourceEncoding};
+
 mod file;
 pub use file::*;
 
@@ -102,6 +108,32 @@ pub struct SourceMap {
     hash_kind: SourceFileHashAlgorithm,
 }
 
+/// Reads the entire contents of a source file into a string.
+///
+/// Unlike [`std::fs::read_to_string`], the error returned for invalid UTF-8 contains the location
+/// of the first invalid byte.
+pub fn read_to_string(path: &Path) -> io::Result<String> {
+    source_from_utf8(std::fs::read(path)?)
+}
+
+/// Converts the bytes of a source file to a string, returning an error with the line and column
+/// of the first invalid byte if the source is not valid UTF-8.
+fn source_from_utf8(bytes: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(bytes).map_err(|e| {
+        let bytes = e.as_bytes();
+        let valid_up_to = e.utf8_error().valid_up_to();
+        let valid = std::str::from_utf8(&bytes[..valid_up_to]).unwrap();
+        let valid = valid.strip_prefix('\u{feff}').unwrap_or(valid);
+        let line = valid.matches('\n').count() + 1;
+        let column = valid.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+        let msg = format!(
+            "source is not valid UTF-8: invalid byte 0x{:02x} at line {line}, column {column}",
+            bytes[valid_up_to]
+        );
+        io::Error::new(io::ErrorKind::InvalidData, msg)
+    })
+}
+
 impl Default for SourceMap {
     fn default() -> Self {
         Self::empty()
@@ -126,15 +158,15 @@ impl SourceMap {
     /// Loads a file from the given path.
     pub fn load_file(&self, path: &Path) -> io::Result<Arc<SourceFile>> {
         let filename = path.to_owned().into();
-        self.new_source_file(filename, || std::fs::read_to_string(path))
+        self.new_source_file(filename, || read_to_string(path))
     }
 
     /// Loads `stdin`.
     pub fn load_stdin(&self) -> io::Result<Arc<SourceFile>> {
         self.new_source_file(FileName::Stdin, || {
-            let mut src = String::new();
-            io::stdin().read_to_string(&mut src)?;
-            Ok(src)
+            let mut src = Vec::new();
+            io::stdin().read_to_end(&mut src)?;
+            source_from_utf8(src)
         })
     }
 
@@ -217,6 +249,14 @@ impl SourceMap {
         filename.display()
     }
 
+    /// Returns `true` if the given span is entirely contained in one of the files of the source
+    /// map.
+    pub fn contains_span(&self, span: Span) -> bool {
+        let files = self.files();
+        let idx = files.partition_point(|file| file.start_pos <= span.lo());
+        idx > 0 && span.hi() <= files[idx - 1].end_position()
+    }
+
     /// Returns `true` if the given span is multi-line.
     pub fn is_multiline(&self, span: Span) -> bool {
         let lo = self.lookup_source_file_idx(span.lo());
@@ -230,6 +270,25 @@ impl SourceMap {
         f.lookup_line(lo) != f.lookup_line(hi)
     }
 
+    /// Returns the first and the last lines that the given span occupies, as inclusive 0-based
+    /// line indices.
+    ///
+    /// The end of the span is exclusive, so a span that ends right after a newline ends on the line
+    /// of that newline, not on the next one. Empty spans occupy the line they start on. Spans that
+    /// cross the end of their file are clamped to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source map is empty.
+    pub fn span_line_range(&self, span: Span) -> (usize, usize) {
+        let sf = self.lookup_source_file(span.lo());
+        let lo = sf.relative_position(span.lo());
+        let hi = sf.relative_position(span.hi().min(sf.end_position()).max(span.lo()));
+        let last = if hi > lo { hi - 1 } else { lo };
+        let line = |pos| sf.lookup_line(pos).unwrap_or(0);
+        (line(lo), line(last))
+    }
+
     /// Returns the source snippet as `String` corresponding to the given `Span`.
     pub fn span_to_snippet(&self, span: Span) -> Result<String, SpanSnippetError> {
         self.span_to_source(span, |src, start_index, end_index| {
@@ -337,6 +396,39 @@ impl SourceMap {
         Ok(FileLines { file: lo.file, lines })
     }
 
+    /// Splits the span into one `(line_index, start_col, end_col)` segment per line that it
+    /// covers, so that multi-line spans can be underlined line by line.
+    ///
+    /// Line indices are 0-based. Columns are 0-based display columns, as in
+    /// [`Loc::col_display`]: tabs count as 4 columns and wide characters as 2. The end column is
+    /// exclusive, and every line but the last one extends to the end of the line.
+    ///
+    /// Returns an empty vector if the span is dummy or spans multiple files.
+    pub fn split_span_by_line(&self, sp: Span) -> Vec<(usize, usize, usize)> {
+        if sp.is_dummy() {
+            return Vec::new();
+        }
+        let Ok((lo, hi)) = self.is_valid_span(sp) else { return Vec::new() };
+        let file = &lo.file;
+        let line_width = |line_index: usize| {
+            let line = file.get_line(line_index).unwrap_or_default();
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let end = file.lines()[line_index] + RelativeBytePos::from_usize(line.len());
+            file.lookup_file_pos_with_col_display(file.absolute_position(end)).2
+        };
+
+        let lo_line = lo.line.saturating_sub(1);
+        let hi_line = hi.line.saturating_sub(1);
+        let mut segments = Vec::with_capacity(hi_line - lo_line + 1);
+        let mut start_col = lo.col_display;
+        for line_index in lo_line..hi_line {
+            segments.push((line_index, start_col, line_width(line_index)));
+            start_col = 0;
+        }
+        segments.push((hi_line, start_col, hi.col_display));
+        segments
+    }
+
     /// Extracts the source surrounding the given `Span` using the `extract_source` function. The
     /// extract function takes three arguments: a string slice containing the source, an index in
     /// the slice for the beginning of the span and an index in the slice for the end of the span.
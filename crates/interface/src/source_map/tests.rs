@@ -1,4 +1,5 @@
 use super::*;
+use crate::RelativeBytePos;
 use std::path::PathBuf;
 
 fn init_source_map() -> SourceMap {
@@ -148,6 +149,25 @@ fn t7() {
     assert_eq!(file_lines.lines[0].line_index, 1);
 }
 
+/// Tests rendering spans with `DisplayWithContext`.
+#[test]
+fn display_span_with_context() {
+    let sm = init_source_map();
+    let display = |lo, hi| Span::new(BytePos(lo), BytePos(hi)).display_with(&sm).to_string();
+
+    assert_eq!(display(0, 5), "blork.rs:1:1");
+    assert_eq!(display(12, 18), "blork.rs:2:1");
+    assert_eq!(display(31, 35), "blork2.rs:1:7");
+    assert_eq!(Span::DUMMY.display_with(&sm).to_string(), "no-location");
+    // Not in the source map.
+    assert_eq!(display(20, 30), "Span(20..30)");
+    assert_eq!(display(40, 100), "Span(40..100)");
+    assert_eq!(
+        Span::new(BytePos(3), BytePos(5)).display_with(&SourceMap::empty()).to_string(),
+        "Span(3..5)"
+    );
+}
+
 /// Given a string like " ~~~~~~~~~~~~ ", produces a span
 /// converting that range. The idea is that the string has the same
 /// length as the input, and we uncover the byte positions. Note
@@ -182,6 +202,34 @@ fn span_to_snippet_and_lines_spanning_multiple_lines() {
     assert_eq!(lines.lines, expected);
 }
 
+/// Tests `span_line_range` for single-line and multi-line spans.
+#[test]
+fn span_line_range() {
+    let sm = SourceMap::empty();
+    let inputtext = "aaaaa\nbbbbBB\nCCC\nDDDDDddddd\neee\n";
+    sm.new_dummy_source_file(Path::new("blork.rs").to_owned(), inputtext.to_string()).unwrap();
+    let range = |lo, hi| sm.span_line_range(Span::new(BytePos(lo), BytePos(hi)));
+
+    // Single line.
+    assert_eq!(range(1, 5), (0, 0));
+    assert_eq!(range(10, 12), (1, 1));
+    assert_eq!(range(30, 31), (4, 4));
+    // Ending right after a newline.
+    assert_eq!(range(7, 13), (1, 1));
+    assert_eq!(range(5, 6), (0, 0));
+    assert_eq!(range(28, 32), (4, 4));
+    // Empty.
+    assert_eq!(range(6, 6), (1, 1));
+    assert_eq!(range(12, 12), (1, 1));
+
+    // Multiple lines.
+    assert_eq!(range(10, 22), (1, 3));
+    assert_eq!(range(5, 7), (0, 1));
+    assert_eq!(range(1, 32), (0, 4));
+    // Clamped to the end of the file.
+    assert_eq!(range(28, 100), (4, 4));
+}
+
 /// Test span_to_snippet for a span ending at the end of a `SourceFile`.
 #[test]
 fn t8() {
@@ -216,6 +264,51 @@ fn span_merging_fail() {
     assert!(sm.merge_spans(span1, span2).is_none());
 }
 
+/// Tests that a leading byte order mark is removed without affecting line and column numbers.
+#[test]
+fn bom() {
+    let sm = SourceMap::empty();
+    let src = "\u{feff}contract C {}\nuint x;";
+    let file = sm.new_dummy_source_file(PathBuf::from("bom.sol"), src.to_string()).unwrap();
+    assert!(file.has_bom);
+    assert_eq!(file.src.as_str(), "contract C {}\nuint x;");
+    assert!(file.src_hash.matches(src), "src_hash should use the source before normalization");
+
+    let c = file.src.find('C').unwrap();
+    let c_pos = file.absolute_position(RelativeBytePos::from_usize(c));
+    let loc = sm.lookup_char_pos(c_pos);
+    assert_eq!((loc.line, loc.col), (1, CharPos(9)));
+    assert_eq!(file.original_relative_byte_pos(c_pos).to_usize(), c + 3);
+
+    let x = file.src.find('x').unwrap();
+    let lo = file.absolute_position(RelativeBytePos::from_usize(x));
+    let span = Span::new(lo, lo + BytePos(1));
+    assert_eq!(sm.span_to_snippet(span).as_deref(), Ok("x"));
+    assert_eq!(sm.span_to_diagnostic_string(span), "bom.sol:2:6: 2:7");
+
+    let file = sm.new_dummy_source_file(PathBuf::from("no_bom.sol"), "a\u{feff}".into()).unwrap();
+    assert!(!file.has_bom);
+    assert_eq!(file.src.as_str(), "a\u{feff}");
+}
+
+/// Tests that invalid UTF-8 is reported with the location of the first invalid byte.
+#[test]
+fn invalid_utf8() {
+    assert_eq!(source_from_utf8(b"\xef\xbb\xbfa\nb".to_vec()).unwrap(), "\u{feff}a\nb");
+
+    let err = source_from_utf8(b"\xef\xbb\xbfcontract C {}\n\xc3\xa9 \xff;".to_vec()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "source is not valid UTF-8: invalid byte 0xff at line 2, column 3");
+
+    let dir = std::env::temp_dir().join(format!("solar-invalid-utf8-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("invalid.sol");
+    std::fs::write(&path, b"\x80").unwrap();
+    let err = SourceMap::empty().load_file(&path).unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(err.to_string(), "source is not valid UTF-8: invalid byte 0x80 at line 1, column 1");
+}
+
 /// Tests loading an external source file that requires normalization.
 #[cfg(any())]
 #[test]
@@ -625,3 +718,208 @@ fn read_binary_file_handles_lying_stat() {
     let bin = RealFileLoader.read_binary_file(kernel_max).unwrap();
     assert_eq!(&real[..], &bin[..]);
 }
+
+#[test]
+fn split_span_by_line() {
+    let sm = SourceMap::empty();
+    let src = "x = c\n\t? f(a,\n\t\tb) : d;\n";
+    let sf = sm.new_dummy_source_file(PathBuf::from("split.sol"), src.to_string()).unwrap();
+    let pos = |s: &str| sf.start_pos + BytePos::from_usize(src.find(s).unwrap());
+
+    // From `c` to `b)`, across three lines.
+    let sp = Span::new(pos("c"), pos(" : d"));
+    assert_eq!(sm.split_span_by_line(sp), [(0, 4, 5), (1, 0, 10), (2, 0, 10)]);
+
+    // From `f` to `a`, on a single line.
+    let sp = Span::new(pos("f"), pos(","));
+    assert_eq!(sm.split_span_by_line(sp), [(1, 6, 9)]);
+
+    assert!(sm.split_span_by_line(Span::DUMMY).is_empty());
+}
+
+#[test]
+fn shrink_span() {
+    let sm = init_source_map();
+    let sf = sm.lookup_source_file(BytePos(0));
+    let sf2 = sm.files()[2].clone();
+    let pos =
+        |sf: &SourceFile, s: &str| sf.start_pos + BytePos::from_usize(sf.src.find(s).unwrap());
+
+    // `line` on the first line of `blork.rs`.
+    let sp = Span::new(pos(&sf, "line"), pos(&sf, "."));
+    let lo = sp.shrink_to_lo();
+    let hi = sp.shrink_to_hi();
+    assert_eq!((lo.lo(), lo.hi()), (sp.lo(), sp.lo()));
+    assert_eq!((hi.lo(), hi.hi()), (sp.hi(), sp.hi()));
+    assert_eq!(sm.span_to_snippet(lo), Ok(String::new()));
+    assert_eq!(sm.span_to_snippet(hi), Ok(String::new()));
+    assert_eq!(sm.lookup_char_pos(lo.lo()).col.to_usize(), 6);
+    assert_eq!(sm.lookup_char_pos(hi.lo()).col.to_usize(), 10);
+
+    // `second line` at the very end of `blork2.rs` stays in that file.
+    let sp = Span::new(pos(&sf2, "second"), sf2.start_pos + BytePos::from_usize(sf2.src.len()));
+    assert_eq!(sm.lookup_source_file(sp.shrink_to_lo().lo()).name, sf2.name);
+    assert_eq!(sm.lookup_source_file(sp.shrink_to_hi().hi()).name, sf2.name);
+    assert_eq!(sm.lookup_char_pos(sp.shrink_to_hi().lo()).line, 2);
+
+    // Empty spans are unchanged.
+    let empty = sp.shrink_to_hi();
+    assert_eq!(empty.shrink_to_lo(), empty);
+    assert_eq!(empty.shrink_to_hi(), empty);
+}
+
+#[test]
+fn decoded_source() {
+    // 1-byte, 2-byte, 3-byte, and 4-byte UTF-8 characters.
+    let src = "a = \"é€𝄞\"; // ü\nb\u{10348}";
+    let utf16 = src.encode_utf16().collect::<Vec<_>>();
+    let utf32 = src.chars().map(u32::from).collect::<Vec<_>>();
+    let sources = [
+        (DecodedSource::from_bytes(src.as_bytes(), SourceEncoding::Utf8).unwrap(), src.len()),
+        (DecodedSource::from_utf16(&utf16).unwrap(), utf16.len()),
+        (DecodedSource::from_utf32(&utf32).unwrap(), utf32.len()),
+    ];
+
+    let sm = SourceMap::empty();
+    sm.new_dummy_source_file(PathBuf::from("padding.sol"), "padding".to_string()).unwrap();
+    for (i, (decoded, len)) in sources.iter().enumerate() {
+        assert_eq!(decoded.as_str(), src);
+        assert_eq!(decoded.len_units(), *len);
+        let units = |s: &str| match decoded.encoding() {
+            SourceEncoding::Utf8 => s.len(),
+            SourceEncoding::Utf16Le | SourceEncoding::Utf16Be => s.encode_utf16().count(),
+            SourceEncoding::Utf32Le | SourceEncoding::Utf32Be => s.chars().count(),
+        };
+
+        let path = PathBuf::from(format!("{i}.sol"));
+        let file = sm.new_dummy_source_file(path, decoded.as_str().to_string()).unwrap();
+        let mut boundaries = src.char_indices().map(|(pos, _)| pos).collect::<Vec<_>>();
+        boundaries.push(src.len());
+        for &lo in &boundaries {
+            let unit = decoded.byte_to_unit(RelativeBytePos::from_usize(lo)).unwrap();
+            assert_eq!(unit, units(&src[..lo]), "{lo}");
+            assert_eq!(decoded.unit_to_byte(unit), Some(RelativeBytePos::from_usize(lo)));
+
+            for &hi in boundaries.iter().filter(|&&hi| hi >= lo) {
+                let span = Span::new(
+                    file.absolute_position(RelativeBytePos::from_usize(lo)),
+                    file.absolute_position(RelativeBytePos::from_usize(hi)),
+                );
+                let range = decoded.span_to_units(&file, span).unwrap();
+                assert_eq!(range, units(&src[..lo])..units(&src[..hi]));
+                assert_eq!(decoded.units_to_span(&file, range), Some(span));
+            }
+        }
+
+        // Offsets inside characters and out of bounds.
+        let euro = src.find('€').unwrap();
+        assert_eq!(decoded.byte_to_unit(RelativeBytePos::from_usize(euro + 1)), None);
+        assert_eq!(decoded.byte_to_unit(RelativeBytePos::from_usize(src.len() + 1)), None);
+        assert_eq!(decoded.unit_to_byte(*len + 1), None);
+    }
+
+    // Inside a surrogate pair.
+    let decoded = &sources[1].0;
+    let clef = src[..src.find('𝄞').unwrap()].encode_utf16().count();
+    assert!(decoded.unit_to_byte(clef).is_some());
+    assert_eq!(decoded.unit_to_byte(clef + 1), None);
+    assert!(decoded.unit_to_byte(clef + 2).is_some());
+}
+
+#[test]
+fn decoded_source_bytes() {
+    let src = "contract Ω { string s = \"日本\"; }";
+    let utf16 = src.encode_utf16().collect::<Vec<_>>();
+    let utf16_le = utf16.iter().flat_map(|u| u.to_le_bytes()).collect::<Vec<_>>();
+    let utf16_be = utf16.iter().flat_map(|u| u.to_be_bytes()).collect::<Vec<_>>();
+    let utf32_le = src.chars().flat_map(|c| u32::from(c).to_le_bytes()).collect::<Vec<_>>();
+    let utf32_be = src.chars().flat_map(|c| u32::from(c).to_be_bytes()).collect::<Vec<_>>();
+    for (bytes, encoding) in [
+        (&utf16_le, SourceEncoding::Utf16Le),
+        (&utf16_be, SourceEncoding::Utf16Be),
+        (&utf32_le, SourceEncoding::Utf32Le),
+        (&utf32_be, SourceEncoding::Utf32Be),
+    ] {
+        let decoded = DecodedSource::from_bytes(bytes, encoding).unwrap();
+        assert_eq!(decoded.as_str(), src, "{encoding:?}");
+        assert_eq!(decoded.encoding(), encoding);
+        assert_eq!(decoded.len_units(), bytes.len() / encoding.unit_size());
+    }
+
+    let err = DecodedSource::from_bytes(&utf16_le[1..], SourceEncoding::Utf16Le).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let err = DecodedSource::from_utf16(&[0x61, 0xd800, 0x62]).unwrap_err();
+    assert!(err.to_string().contains("unpaired surrogate 0xd800 at unit 1"), "{err}");
+    let err = DecodedSource::from_utf32(&[0x61, 0x110000]).unwrap_err();
+    assert!(err.to_string().contains("at unit 1"), "{err}");
+}
+
+#[test]
+fn decoded_source_bom() {
+    let src = "\u{feff}contract Ω {}";
+    let utf16 = src.encode_utf16().collect::<Vec<_>>();
+    let utf16_le = utf16.iter().flat_map(|u| u.to_le_bytes()).collect::<Vec<_>>();
+    let sources = [
+        DecodedSource::from_bytes(src.as_bytes(), SourceEncoding::Utf8).unwrap(),
+        DecodedSource::from_bytes(&utf16_le, SourceEncoding::Utf16Le).unwrap(),
+    ];
+
+    let sm = SourceMap::empty();
+    sm.new_dummy_source_file(PathBuf::from("padding.sol"), "padding".to_string()).unwrap();
+    for (i, decoded) in sources.iter().enumerate() {
+        assert_eq!(decoded.as_str(), src);
+        let path = PathBuf::from(format!("{i}.sol"));
+        let file = sm.new_dummy_source_file(path, decoded.as_str().to_string()).unwrap();
+        assert!(file.has_bom);
+        let utf8 = decoded.encoding() == SourceEncoding::Utf8;
+        let bom = decoded.byte_to_unit(RelativeBytePos::from_usize(3)).unwrap();
+        assert_eq!(bom, if utf8 { 3 } else { 1 });
+
+        // `Ω` follows the byte order mark and `contract `.
+        let omega = file.src.find('Ω').unwrap();
+        let span = file_span(&file, omega..omega + 'Ω'.len_utf8());
+        assert_eq!(&file[span], "Ω");
+        let units = bom + "contract ".len();
+        let width = if utf8 { 2 } else { 1 };
+        assert_eq!(decoded.span_to_units(&file, span), Some(units..units + width));
+        assert_eq!(decoded.units_to_span(&file, units..units + width), Some(span));
+
+        // The whole file, and offsets in the byte order mark.
+        let whole = file_span(&file, 0..file.src.len());
+        let len = decoded.len_units();
+        assert_eq!(decoded.span_to_units(&file, whole), Some(bom..len));
+        assert_eq!(decoded.units_to_span(&file, bom..len), Some(whole));
+        assert_eq!(decoded.units_to_span(&file, 0..len), Some(whole));
+    }
+}
+
+fn file_span(file: &SourceFile, range: std::ops::Range<usize>) -> Span {
+    Span::new(
+        file.absolute_position(RelativeBytePos::from_usize(range.start)),
+        file.absolute_position(RelativeBytePos::from_usize(range.end)),
+    )
+}
+
+#[test]
+fn index_source_file() {
+    let sm = init_source_map();
+    let files = sm.files();
+    let (first, empty, last) = (&files[0], &files[1], &files[2]);
+
+    assert_eq!(&first[file_span(first, 0..5)], "first");
+    assert_eq!(&first[file_span(first, 12..23)], "second line");
+    let span = file_span(last, 19..23);
+    assert_eq!(&last[span], "line");
+    assert_eq!(std::ops::Range::<usize>::from(span), 44..48);
+
+    assert_eq!(&empty[file_span(empty, 0..0)], "");
+    assert_eq!(&last[Span::DUMMY], "");
+}
+
+#[test]
+#[should_panic = "span 0..5 is out of bounds of file `blork2.rs` with range 25..48"]
+fn index_source_file_out_of_bounds() {
+    let sm = init_source_map();
+    let files = sm.files();
+    let _ = &files[2][file_span(&files[0], 0..5)];
+}
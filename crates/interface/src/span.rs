@@ -26,6 +26,13 @@ impl Default for &Span {
     }
 }
 
+impl From<Span> for Range<usize> {
+    #[inline]
+    fn from(span: Span) -> Self {
+        span.to_range()
+    }
+}
+
 impl fmt::Debug for Span {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Use the global `SourceMap` to print the span. If that's not
@@ -101,12 +108,27 @@ impl Span {
     }
 
     /// Creates a new span representing an empty span at the beginning of this span.
+    ///
+    /// This is useful to point at a position rather than a range, such as where some missing
+    /// code should be inserted.
+    ///
+    /// ```text
+    ///     ____
+    ///     self
+    ///     ^
+    /// ```
     #[inline]
     pub fn shrink_to_lo(self) -> Self {
         Self::new(self.lo(), self.lo())
     }
 
     /// Creates a new span representing an empty span at the end of this span.
+    ///
+    /// ```text
+    ///     ____
+    ///     self
+    ///         ^
+    /// ```
     #[inline]
     pub fn shrink_to_hi(self) -> Self {
         Self::new(self.hi(), self.hi())
@@ -205,3 +227,55 @@ impl Span {
         }
     }
 }
+
+/// Merges the given spans into sorted, non-overlapping `(lo, hi)` byte ranges, for example to
+/// highlight all the references to an item in an editor.
+///
+/// Spans that overlap, nest, or touch are merged into a single range. Empty spans are ignored.
+///
+/// Like spans, the ranges are offset by the file's starting position in the `SourceMap`.
+pub fn merge_spans_for_highlight(spans: &[Span]) -> Vec<(usize, usize)> {
+    let mut spans = spans.iter().filter(|span| span.lo() < span.hi()).copied().collect::<Vec<_>>();
+    spans.sort_unstable();
+    let mut ranges: Vec<(usize, usize)> = Vec::with_capacity(spans.len());
+    for span in spans {
+        let (lo, hi) = (span.lo().to_usize(), span.hi().to_usize());
+        match ranges.last_mut() {
+            Some(last) if lo <= last.1 => last.1 = last.1.max(hi),
+            _ => ranges.push((lo, hi)),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_for_highlight() {
+        let span = |lo, hi| Span::new(BytePos(lo), BytePos(hi));
+        assert!(merge_spans_for_highlight(&[]).is_empty());
+        assert!(merge_spans_for_highlight(&[span(3, 3), span(1, 1)]).is_empty());
+        assert_eq!(merge_spans_for_highlight(&[span(8, 10), span(1, 4)]), [(1, 4), (8, 10)]);
+        // Overlapping.
+        assert_eq!(merge_spans_for_highlight(&[span(5, 9), span(1, 6)]), [(1, 9)]);
+        // Touching.
+        assert_eq!(merge_spans_for_highlight(&[span(4, 6), span(1, 4)]), [(1, 6)]);
+        // Nested.
+        assert_eq!(merge_spans_for_highlight(&[span(2, 3), span(1, 10), span(4, 5)]), [(1, 10)]);
+        // Duplicates.
+        assert_eq!(merge_spans_for_highlight(&[span(1, 2), span(1, 2)]), [(1, 2)]);
+        assert_eq!(
+            merge_spans_for_highlight(&[
+                span(20, 25),
+                span(1, 3),
+                span(22, 30),
+                span(3, 5),
+                span(12, 14),
+                span(13, 13),
+            ]),
+            [(1, 5), (12, 14), (20, 30)]
+        );
+    }
+}
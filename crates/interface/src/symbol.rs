@@ -180,6 +180,20 @@ impl Ident {
     pub fn is_visibility_specifier(self) -> bool {
         self.name.is_visibility_specifier()
     }
+
+    /// Returns `true` if the identifier is a Solidity keyword that cannot be used as an
+    /// identifier. See [`Symbol::is_reserved_keyword`].
+    #[inline]
+    pub fn is_reserved_keyword(self) -> bool {
+        self.name.is_reserved_keyword()
+    }
+
+    /// Returns `true` if the identifier is the name of a builtin global that can be shadowed by a
+    /// declaration. See [`Symbol::is_builtin_global`].
+    #[inline]
+    pub fn is_builtin_global(self) -> bool {
+        self.name.is_builtin_global()
+    }
 }
 
 /// An interned string.
@@ -332,6 +346,47 @@ impl Symbol {
         matches!(self, kw::Public | kw::Private | kw::Internal | kw::External)
     }
 
+    /// Returns `true` if the symbol is a Solidity keyword, either currently in use or reserved for
+    /// possible future use, and cannot be used as an identifier.
+    ///
+    /// This is the same as [`is_reserved(false)`](Self::is_reserved).
+    #[inline]
+    pub fn is_reserved_keyword(self) -> bool {
+        self.is_reserved(false)
+    }
+
+    /// Returns `true` if the symbol is the name of a builtin global that can be shadowed by a
+    /// declaration, like `msg`, `this`, `require`, or `keccak256`.
+    ///
+    /// This includes the names of the global builtin variables and functions, the contract
+    /// builtins `this` and `super`, and the deprecated `now`. Unlike keywords, these are valid
+    /// identifiers, with the exception of `revert`.
+    #[inline]
+    pub fn is_builtin_global(self) -> bool {
+        matches!(
+            self,
+            sym::msg
+                | sym::block
+                | sym::tx
+                | sym::abi
+                | sym::now
+                | sym::this
+                | sym::super_
+                | kw::Blockhash
+                | kw::Blobhash
+                | sym::gasleft
+                | sym::assert
+                | sym::require
+                | kw::Revert
+                | kw::Addmod
+                | kw::Mulmod
+                | kw::Keccak256
+                | sym::sha256
+                | sym::ripemd160
+                | sym::ecrecover
+        )
+    }
+
     /// Returns `true` if the symbol was interned in the compiler's `symbols!` macro.
     #[inline]
     pub const fn is_preinterned(self) -> bool {
@@ -358,8 +413,18 @@ type InternerInner = LassoInterner;
 /// Symbol interner.
 ///
 /// Initialized in `SessionGlobals` with the `symbols!` macro's initial symbols.
+///
+/// The interner is thread-safe: it is shared by all the threads of the session's thread pool (see
+/// `Session::enter_parallel`), so symbols interned on any thread of the same session can be
+/// compared directly. Interning takes a lock on a single shard of the map, and resolving symbols is
+/// lock-free, so contention stays low when parsing many files in parallel.
 pub(crate) struct Interner(InternerInner);
 
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Interner>();
+};
+
 impl Interner {
     pub(crate) fn fresh() -> Self {
         Self(InternerInner::fresh())
@@ -845,6 +910,8 @@ symbols! {
     // There is currently no checking that all symbols are used; that would be
     // nice to have.
     Symbols {
+        Error,
+        Panic,
         X,
         __tmp_struct,
         abi,
@@ -874,11 +941,13 @@ symbols! {
         min,
         msg,
         name,
+        now,
         object,
         push,
         require,
         ripemd160,
         runtimeCode,
+        salt,
         selector,
         send,
         sender,
@@ -930,4 +999,23 @@ mod tests {
             assert_eq!(Ident::DUMMY.to_string(), "");
         });
     }
+
+    #[test]
+    fn reserved_and_builtin() {
+        assert!(kw::Contract.is_reserved_keyword());
+        assert!(kw::Alias.is_reserved_keyword());
+        assert!(!kw::Leave.is_reserved_keyword());
+        assert!(!sym::block.is_reserved_keyword());
+
+        for sym in [sym::msg, sym::block, sym::tx, sym::now, sym::this, sym::super_, sym::abi] {
+            assert!(sym.is_builtin_global(), "{sym:?}");
+            assert!(!sym.is_reserved_keyword(), "{sym:?}");
+        }
+        for sym in [sym::require, sym::assert, kw::Keccak256, sym::ecrecover, kw::Blockhash] {
+            assert!(sym.is_builtin_global(), "{sym:?}");
+            assert!(!sym.is_reserved_keyword(), "{sym:?}");
+        }
+        assert!(!sym::value.is_builtin_global());
+        assert!(!kw::Contract.is_builtin_global());
+    }
 }
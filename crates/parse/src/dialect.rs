@@ -0,0 +1,125 @@
+use solar_ast::{BinOpKind, UnOpKind};
+use std::fmt;
+
+/// Language dialect configuration for the [`Parser`](crate::Parser).
+///
+/// By default, all operators are available. Dialects can disable specific operators, in which
+/// case using them is reported as an error, but parsing continues as usual.
+///
+/// Dialects can also target an older [language version](LanguageVersion), in which case syntax
+/// that has since been removed from the language, like `var` declarations, is accepted. When no
+/// version is set, the latest version is assumed and such syntax is reported as an error.
+///
+/// # Examples
+///
+/// ```
+/// use solar_parse::{ast::BinOpKind, Dialect};
+///
+/// let dialect = Dialect::new().with_bin_op(BinOpKind::Pow, false);
+/// assert!(!dialect.is_bin_op_enabled(BinOpKind::Pow));
+/// assert!(dialect.is_bin_op_enabled(BinOpKind::Mul));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Dialect {
+    /// Bit set of the disabled binary operators, indexed by [`BinOpKind`].
+    disabled_bin_ops: u32,
+    /// Bit set of the disabled unary operators, indexed by [`UnOpKind`].
+    disabled_un_ops: u8,
+    /// The targeted language version, or `None` for the latest one.
+    language_version: Option<LanguageVersion>,
+}
+
+impl Dialect {
+    /// Creates a new dialect with all operators enabled.
+    pub const fn new() -> Self {
+        Self { disabled_bin_ops: 0, disabled_un_ops: 0, language_version: None }
+    }
+
+    /// Enables or disables the given binary operator.
+    ///
+    /// This also applies to the corresponding compound assignment operator, if any.
+    pub const fn with_bin_op(mut self, op: BinOpKind, enabled: bool) -> Self {
+        let bit = 1 << op as u32;
+        if enabled {
+            self.disabled_bin_ops &= !bit;
+        } else {
+            self.disabled_bin_ops |= bit;
+        }
+        self
+    }
+
+    /// Enables or disables the given unary operator.
+    pub const fn with_un_op(mut self, op: UnOpKind, enabled: bool) -> Self {
+        let bit = 1 << op as u8;
+        if enabled {
+            self.disabled_un_ops &= !bit;
+        } else {
+            self.disabled_un_ops |= bit;
+        }
+        self
+    }
+
+    /// Sets the targeted language version.
+    pub const fn with_language_version(mut self, version: LanguageVersion) -> Self {
+        self.language_version = Some(version);
+        self
+    }
+
+    /// Returns the targeted language version, or `None` if the latest version is targeted.
+    pub const fn language_version(&self) -> Option<LanguageVersion> {
+        self.language_version
+    }
+
+    /// Returns `true` if syntax that was removed in the given version is still accepted by the
+    /// targeted language version.
+    pub fn allows_removed_in(&self, removed_in: LanguageVersion) -> bool {
+        self.language_version.is_some_and(|version| version < removed_in)
+    }
+
+    /// Returns `true` if the given binary operator is available in this dialect.
+    pub const fn is_bin_op_enabled(&self, op: BinOpKind) -> bool {
+        self.disabled_bin_ops & (1 << op as u32) == 0
+    }
+
+    /// Returns `true` if the given unary operator is available in this dialect.
+    pub const fn is_un_op_enabled(&self, op: UnOpKind) -> bool {
+        self.disabled_un_ops & (1 << op as u8) == 0
+    }
+}
+
+/// A Solidity language version, without pre-release or build metadata: `0.4.26`.
+///
+/// # Examples
+///
+/// ```
+/// use solar_parse::LanguageVersion;
+///
+/// assert!(LanguageVersion::new(0, 4, 26) < LanguageVersion::V0_5_0);
+/// assert_eq!(LanguageVersion::V0_5_0.to_string(), "0.5.0");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LanguageVersion {
+    /// The major version.
+    pub major: u16,
+    /// The minor version.
+    pub minor: u16,
+    /// The patch version.
+    pub patch: u16,
+}
+
+impl fmt::Display for LanguageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { major, minor, patch } = self;
+        write!(f, "{major}.{minor}.{patch}")
+    }
+}
+
+impl LanguageVersion {
+    /// Version 0.5.0, which removed `var`, `throw`, `years`, `sha3` and `suicide`.
+    pub const V0_5_0: Self = Self::new(0, 5, 0);
+
+    /// Creates a new language version.
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self { major, minor, patch }
+    }
+}
@@ -46,6 +46,21 @@ pub struct Lexer<'sess, 'src> {
     /// in this file, it's safe to treat further occurrences of the non-breaking
     /// space character as whitespace.
     nbsp_is_whitespace: bool,
+
+    /// Whether to emit [`TokenKind::Whitespace`] tokens. See [`tokenize`].
+    keep_whitespace: bool,
+}
+
+/// Returns an iterator over all the tokens of the given source string, including trivia.
+///
+/// Unlike [`Lexer::new`], runs of whitespace are emitted as [`TokenKind::Whitespace`] tokens
+/// instead of being skipped, and comments are emitted as [`TokenKind::Comment`] tokens as usual.
+/// Use [`Token::is_trivia`] to tell them apart from the other tokens. This is intended for tooling
+/// that works on the token stream without parsing, such as syntax highlighters and formatters.
+///
+/// The tokens must not be passed to the [parser](crate::Parser).
+pub fn tokenize<'sess, 'src>(sess: &'sess Session, src: &'src str) -> Lexer<'sess, 'src> {
+    Lexer::new_inner(sess, src, BytePos(0), true)
 }
 
 impl<'sess, 'src> Lexer<'sess, 'src> {
@@ -63,6 +78,15 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
 
     /// Creates a new `Lexer` for the given source string and starting position.
     pub fn with_start_pos(sess: &'sess Session, src: &'src str, start_pos: BytePos) -> Self {
+        Self::new_inner(sess, src, start_pos, false)
+    }
+
+    fn new_inner(
+        sess: &'sess Session,
+        src: &'src str,
+        start_pos: BytePos,
+        keep_whitespace: bool,
+    ) -> Self {
         let mut lexer = Self {
             sess,
             start_pos,
@@ -71,6 +95,7 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
             cursor: Cursor::new(src),
             token: Token::DUMMY,
             nbsp_is_whitespace: false,
+            keep_whitespace,
         };
         (lexer.token, _) = lexer.bump();
         lexer
@@ -161,6 +186,7 @@ impl<'sess, 'src> Lexer<'sess, 'src> {
                     let content = self.str_from_to(content_start, content_end);
                     self.cook_doc_comment(content_start, content, is_doc, CommentKind::Block)
                 }
+                RawTokenKind::Whitespace if self.keep_whitespace => TokenKind::Whitespace,
                 RawTokenKind::Whitespace => {
                     preceded_by_whitespace = true;
                     continue;
@@ -722,4 +748,43 @@ mod tests {
             ("- -", &[(0..1, BinOp(Minus)), (2..3, BinOp(Minus))]),
         ]);
     }
+
+    #[test]
+    fn trivia() {
+        solar_interface::SessionGlobals::new().set(|| {
+            let sess = Session::builder().with_test_emitter().build();
+            let src = "a + b // c";
+            let tokens: Vec<_> = tokenize(&sess, src).collect();
+            sess.dcx.has_errors().unwrap();
+
+            let kinds: Vec<_> = tokens
+                .iter()
+                .map(|t| {
+                    (t.span.lo().to_usize()..t.span.hi().to_usize(), t.kind.clone(), t.is_trivia())
+                })
+                .collect();
+            let comment = Comment(false, CommentKind::Line, sym(" c"));
+            assert_eq!(
+                kinds,
+                [
+                    (0..1, id("a"), false),
+                    (1..2, Whitespace, true),
+                    (2..3, BinOp(Plus), false),
+                    (3..4, Whitespace, true),
+                    (4..5, id("b"), false),
+                    (5..6, Whitespace, true),
+                    (6..10, comment, true),
+                ]
+            );
+            assert_eq!(tokens[2].kind.as_binop(), Some(solar_ast::BinOpKind::Add));
+
+            // Adjacent tokens are still glued together, unless they are separated by whitespace.
+            let kinds: Vec<_> = tokenize(&sess, "a+=b + =").map(|t| t.kind).collect();
+            assert_eq!(
+                kinds,
+                [id("a"), BinOpEq(Plus), id("b"), Whitespace, BinOp(Plus), Whitespace, Eq]
+            );
+            assert_eq!(Lexer::new(&sess, src).filter(Token::is_trivia).count(), 1);
+        });
+    }
 }
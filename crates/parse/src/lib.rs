@@ -11,18 +11,38 @@ extern crate tracing;
 use solar_interface::diagnostics::{DiagBuilder, ErrorGuaranteed};
 
 pub mod lexer;
-pub use lexer::{unescape, Cursor, Lexer};
+pub use lexer::{tokenize, unescape, Cursor, Lexer};
+
+mod dialect;
+pub use dialect::{Dialect, LanguageVersion};
 
 mod parser;
 pub use parser::Parser;
 
+mod session;
+pub use session::ParseSession;
+
+mod standalone;
+pub use standalone::{parse_expr, parse_stmt, parse_type};
+
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+
 // Convenience re-exports.
 pub use bumpalo;
 pub use solar_ast::{self as ast, token};
 pub use solar_interface as interface;
 
 /// Parser error type.
+///
+/// This is a diagnostic that has not been emitted yet. It carries the message and the span of
+/// the offending token, and the [`Parser`] is left positioned at that token, so the caller can
+/// either [`cancel`](DiagBuilder::cancel) it and try another production, or
+/// [`emit`](DiagBuilder::emit) it and resume parsing after recovering. Dropping it without doing
+/// either is a bug.
 pub type PErr<'a> = DiagBuilder<'a, ErrorGuaranteed>;
 
 /// Parser result type. This is a shorthand for `Result<T, PErr<'a>>`.
+///
+/// All the parsing functions return this type, so that they compose with `?`.
 pub type PResult<'a, T> = Result<T, PErr<'a>>;
@@ -1,6 +1,8 @@
+use super::item::FunctionFlags;
 use crate::{PResult, Parser};
+use smallvec::SmallVec;
 use solar_ast::{token::*, *};
-use solar_interface::kw;
+use solar_interface::{diagnostics::codes, kw};
 
 impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// Parses an expression.
@@ -9,6 +11,12 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         self.parse_expr_with(None)
     }
 
+    /// Parses an expression, starting with the already parsed `with`, if any.
+    ///
+    /// Conditionals and assignments have the lowest precedence and are right-associative. Like in
+    /// solc, both branches of a conditional are full expressions, so they can be assignments:
+    /// `a ? b : c = d` is `a ? b : (c = d)`, and `a ? b = c : d` is `a ? (b = c) : d`. An
+    /// assignment to a conditional must be parenthesized: `(a ? b : c) = d`.
     #[instrument(name = "parse_expr", level = "debug", skip_all)]
     pub(super) fn parse_expr_with(
         &mut self,
@@ -29,8 +37,11 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             } else {
                 return Ok(expr);
             };
+            if let Some(op) = kind {
+                self.check_bin_op_dialect(op.kind, op.span, true);
+            }
             self.bump(); // binop token
-            let rhs = self.parse_expr()?;
+            let rhs = self.parse_expr_or_missing()?;
             let span = expr.span.to(self.prev_token.span);
             Ok(self.alloc(Expr { span, kind: ExprKind::Assign(expr, kind, rhs) }))
         }
@@ -56,13 +67,19 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 let token = self.token.clone();
                 self.bump(); // binop token
 
-                let rhs = self.parse_binary_expr(next_precedence, None)?;
+                let rhs = if self.check_expr_end() {
+                    self.recover_missing_expr()
+                } else {
+                    self.parse_binary_expr(next_precedence, None)?
+                };
 
                 let span = expr.span.to(self.prev_token.span);
 
                 let kind = if let Some(binop) = token.as_binop() {
+                    self.check_bin_op_dialect(binop.kind, binop.span, false);
                     ExprKind::Binary(expr, binop, rhs)
                 } else if let Some(binop_eq) = token.as_binop_eq() {
+                    self.check_bin_op_dialect(binop_eq.kind, binop_eq.span, true);
                     ExprKind::Assign(expr, Some(binop_eq), rhs)
                 } else if token.kind == TokenKind::Eq {
                     ExprKind::Assign(expr, None, rhs)
@@ -77,19 +94,99 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         Ok(expr)
     }
 
+    /// Parses an expression, or recovers with an [`ExprKind::Err`] placeholder if the current
+    /// token cannot start one. See [`recover_missing_expr`](Self::recover_missing_expr).
+    fn parse_expr_or_missing(&mut self) -> PResult<'sess, Box<'ast, Expr<'ast>>> {
+        if self.check_expr_end() {
+            Ok(self.recover_missing_expr())
+        } else {
+            self.parse_expr()
+        }
+    }
+
+    /// Returns `true` if the current token ends an expression, meaning that an expected operand
+    /// is missing.
+    fn check_expr_end(&self) -> bool {
+        matches!(
+            self.token.kind,
+            TokenKind::CloseDelim(_) | TokenKind::Comma | TokenKind::Semi | TokenKind::Eof
+        )
+    }
+
+    /// Emits an error for a missing expression and returns an [`ExprKind::Err`] placeholder.
+    ///
+    /// The placeholder has an empty span located right after the previous token, which is where
+    /// the expression is expected, so that tools can offer completions there.
+    #[must_use]
+    fn recover_missing_expr(&mut self) -> Box<'ast, Expr<'ast>> {
+        let span = self.prev_token.span.shrink_to_hi();
+        let msg = format!("expected expression, found {}", self.token.full_description());
+        let guar = self.dcx().err(msg).code(codes::EXPECTED_EXPRESSION).span(span).emit();
+        self.alloc(Expr { span, kind: ExprKind::Err(guar) })
+    }
+
+    /// Skips the rest of the expression that is being parsed once the
+    /// [node limit](Self::set_max_nodes) has been exceeded, up to the end of the statement or of
+    /// the enclosing delimiters.
+    fn skip_expr_after_node_limit(&mut self) {
+        self.skip_until(&[&TokenKind::Semi]);
+    }
+
+    /// Emits an error if the binary operator, or its compound assignment form if `assign` is
+    /// `true`, is disabled in the current [`Dialect`](crate::Dialect).
+    fn check_bin_op_dialect(&self, kind: BinOpKind, span: Span, assign: bool) {
+        if !self.dialect().is_bin_op_enabled(kind) {
+            let eq = if assign { "=" } else { "" };
+            let msg = format!("operator `{}{eq}` is not available in this dialect", kind.to_str());
+            self.dcx().err(msg).code(codes::OPERATOR_NOT_IN_DIALECT).span(span).emit();
+        }
+    }
+
+    /// Emits an error if the unary operator is disabled in the current
+    /// [`Dialect`](crate::Dialect).
+    fn check_un_op_dialect(&self, op: UnOp) {
+        if !self.dialect().is_un_op_enabled(op.kind) {
+            let msg = format!("operator `{}` is not available in this dialect", op.kind.to_str());
+            self.dcx().err(msg).code(codes::OPERATOR_NOT_IN_DIALECT).span(op.span).emit();
+        }
+    }
+
     /// Parses a unary expression.
     fn parse_unary_expr(
         &mut self,
         with: Option<Box<'ast, Expr<'ast>>>,
     ) -> PResult<'sess, Box<'ast, Expr<'ast>>> {
+        // Every operand is parsed here, so this tracks the nesting depth of the expression.
+        self.expr_depth += 1;
+        let expr = self.parse_unary_expr_inner(with);
+        self.expr_depth -= 1;
+        expr
+    }
+
+    fn parse_unary_expr_inner(
+        &mut self,
+        with: Option<Box<'ast, Expr<'ast>>>,
+    ) -> PResult<'sess, Box<'ast, Expr<'ast>>> {
+        if with.is_none() {
+            if let Some(guar) = self.node_limit_error() {
+                let span = self.token.span.shrink_to_lo();
+                self.skip_expr_after_node_limit();
+                return Ok(self.alloc(Expr { span, kind: ExprKind::Err(guar) }));
+            }
+        }
         if with.is_none() && self.eat(&TokenKind::BinOp(BinOpToken::Plus)) {
-            self.dcx().err("unary plus is not supported").span(self.prev_token.span).emit();
+            self.dcx()
+                .err("unary plus is not supported")
+                .code(codes::UNARY_PLUS)
+                .span(self.prev_token.span)
+                .emit();
         }
 
         let lo = with.as_ref().map(|e| e.span).unwrap_or(self.token.span);
         let parse_lhs = |this: &mut Self, with| {
             this.parse_lhs_expr(with, lo).map(|expr| {
                 if let Some(unop) = this.token.as_unop(true) {
+                    this.check_un_op_dialect(unop);
                     this.bump(); // unop
                     let span = lo.to(this.prev_token.span);
                     this.alloc(Expr { span, kind: ExprKind::Unary(unop, expr) })
@@ -106,6 +203,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 self.alloc(Expr { span, kind: ExprKind::Delete(expr) })
             })
         } else if let Some(unop) = self.token.as_unop(false) {
+            self.check_un_op_dialect(unop);
             self.bump(); // unop
             self.parse_unary_expr(None).map(|expr| {
                 let span = lo.to(self.prev_token.span);
@@ -138,6 +236,10 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             self.parse_primary_expr()
         }?;
         loop {
+            if self.check_node_limit() {
+                self.skip_expr_after_node_limit();
+                break;
+            }
             let kind = if self.eat(&TokenKind::Dot) {
                 // expr.member
                 let member = self.parse_ident_any()?;
@@ -188,27 +290,17 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 }
             }
             ExprKind::Type(ty)
+        } else if self.check_keyword(kw::Function) {
+            // Function type: `function (uint256) external returns (bool)`.
+            ExprKind::Type(self.parse_type_with(FunctionFlags::FUNCTION_TY_EXPR)?)
         } else if self.check_nr_ident() {
             let ident = self.parse_ident()?;
             ExprKind::Ident(ident)
-        } else if self.check(&TokenKind::OpenDelim(Delimiter::Parenthesis))
-            || self.check(&TokenKind::OpenDelim(Delimiter::Bracket))
-        {
-            // Array or tuple expression.
-            let TokenKind::OpenDelim(close_delim) = self.token.kind else { unreachable!() };
-            let is_array = close_delim == Delimiter::Bracket;
-            let list = self.parse_optional_items_seq(close_delim, Self::parse_expr)?;
-            if is_array {
-                if !list.iter().all(Option::is_some) {
-                    let msg = "array expression components cannot be empty";
-                    let span = lo.to(self.prev_token.span);
-                    return Err(self.dcx().err(msg).span(span));
-                }
-                // SAFETY: All elements are checked to be `Some` above.
-                ExprKind::Array(unsafe { option_boxes_unwrap_unchecked(list) })
-            } else {
-                ExprKind::Tuple(list)
-            }
+        } else if self.check(&TokenKind::OpenDelim(Delimiter::Parenthesis)) {
+            let list = self.parse_optional_items_seq(Delimiter::Parenthesis, Self::parse_expr)?;
+            ExprKind::Tuple(list)
+        } else if self.check(&TokenKind::OpenDelim(Delimiter::Bracket)) {
+            ExprKind::Array(self.parse_array_expr()?)
         } else {
             return self.unexpected();
         };
@@ -216,17 +308,50 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         Ok(self.alloc(Expr { span, kind }))
     }
 
+    /// Parses the components of an array expression: `[a, b, c]`.
+    ///
+    /// Unlike tuples, arrays cannot have empty components. These are reported individually and
+    /// recovered as [`ExprKind::Err`] placeholders.
+    #[allow(clippy::vec_box)]
+    fn parse_array_expr(&mut self) -> PResult<'sess, Box<'ast, [Box<'ast, Expr<'ast>>]>> {
+        let close = TokenKind::CloseDelim(Delimiter::Bracket);
+        self.expect(&TokenKind::OpenDelim(Delimiter::Bracket))?;
+        let mut out = SmallVec::<[_; 8]>::new();
+        if !self.eat(&close) {
+            loop {
+                if self.check(&TokenKind::Comma) || self.check(&close) {
+                    let span = self.prev_token.span.between(self.token.span);
+                    let msg = "array expression components cannot be empty";
+                    let guar =
+                        self.dcx().err(msg).code(codes::EMPTY_ARRAY_COMPONENT).span(span).emit();
+                    out.push(self.alloc(Expr { span, kind: ExprKind::Err(guar) }));
+                } else {
+                    out.push(self.parse_expr()?);
+                }
+                if self.eat(&close) {
+                    break;
+                }
+                self.expect(&TokenKind::Comma)?;
+            }
+        }
+        Ok(self.alloc_smallvec(out))
+    }
+
     /// Parses a list of function call arguments.
     #[track_caller]
     pub(super) fn parse_call_args(&mut self) -> PResult<'sess, CallArgs<'ast>> {
-        if self.look_ahead(1).kind == TokenKind::OpenDelim(Delimiter::Brace) {
+        let lo = self.token.span;
+        let kind = if self.look_ahead(1).kind == TokenKind::OpenDelim(Delimiter::Brace) {
             self.expect(&TokenKind::OpenDelim(Delimiter::Parenthesis))?;
-            let args = self.parse_named_args().map(CallArgs::Named)?;
+            let lo = self.token.span;
+            let args = self.parse_named_args()?;
+            let braces = lo.to(self.prev_token.span);
             self.expect(&TokenKind::CloseDelim(Delimiter::Parenthesis))?;
-            Ok(args)
+            CallArgsKind::Named(args, braces)
         } else {
-            self.parse_unnamed_args().map(CallArgs::Unnamed)
-        }
+            self.parse_unnamed_args().map(CallArgsKind::Unnamed)?
+        };
+        Ok(CallArgs { span: lo.to(self.prev_token.span), kind })
     }
 
     /// Parses a `[]` indexing expression.
@@ -265,15 +390,29 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     fn parse_named_arg(&mut self) -> PResult<'sess, NamedArg<'ast>> {
         let name = self.parse_ident()?;
         self.expect(&TokenKind::Colon)?;
+        let colon_span = self.prev_token.span;
         let value = self.parse_expr()?;
-        Ok(NamedArg { name, value })
+        Ok(NamedArg { name, colon_span, value })
     }
 
     /// Parses a list of expressions: `(a, b, c, ...)`.
+    ///
+    /// Missing arguments, like in `f(a, )`, are recovered as [`ExprKind::Err`] placeholders.
     #[allow(clippy::vec_box)]
     #[track_caller]
     fn parse_unnamed_args(&mut self) -> PResult<'sess, Box<'ast, [Box<'ast, Expr<'ast>>]>> {
-        self.parse_paren_comma_seq(true, Self::parse_expr)
+        self.expect(&TokenKind::OpenDelim(Delimiter::Parenthesis))?;
+        let mut args = SmallVec::<[_; 8]>::new();
+        if !self.check(&TokenKind::CloseDelim(Delimiter::Parenthesis)) {
+            loop {
+                args.push(self.parse_expr_or_missing()?);
+                if !self.eat(&TokenKind::Comma) {
+                    break;
+                }
+            }
+        }
+        self.expect(&TokenKind::CloseDelim(Delimiter::Parenthesis))?;
+        Ok(self.alloc_smallvec(args))
     }
 }
 
@@ -309,19 +448,1932 @@ fn token_precedence(t: &Token) -> usize {
     }
 }
 
-/// Converts a list of `Option<Box<'ast, T>>` into a list of `Box<'ast, T>`.
-///
-/// This only works because `Option<Box<'ast, T>>` is guaranteed to be a valid `Box<'ast, T>` when
-/// `Some` when `T: Sized`.
-///
-/// # Safety
-///
-/// All elements of the list must be `Some`.
-#[inline]
-unsafe fn option_boxes_unwrap_unchecked<'a, 'b, T>(
-    list: Box<'a, [Option<Box<'b, T>>]>,
-) -> Box<'a, [Box<'b, T>]> {
-    debug_assert!(list.iter().all(Option::is_some));
-    // SAFETY: Caller must ensure that all elements are `Some`.
-    unsafe { std::mem::transmute(list) }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, TestSession};
+    use solar_interface::{source_map::DisplayWithContext, Result, Session};
+
+    #[test]
+    fn standalone() {
+        test_util::enter(|t| -> Result {
+            let expr = t.parse_expr("a + b * c")?;
+            assert_eq!(t.range(expr.span), 0..9);
+            let ExprKind::Binary(lhs, op, rhs) = &expr.kind else { panic!("{expr:?}") };
+            assert_eq!(op.kind, BinOpKind::Add);
+            assert_eq!(t.snippet(lhs.span), "a");
+            let ExprKind::Binary(lhs, op, rhs) = &rhs.kind else { panic!("{rhs:?}") };
+            assert_eq!(op.kind, BinOpKind::Mul);
+            assert_eq!(t.snippet(lhs.span), "b");
+            assert_eq!(t.snippet(rhs.span), "c");
+
+            // The spans of the other files are offset in the source map.
+            let src = "mapping(uint => bool)[]";
+            let ty = t.parse_type(src)?;
+            assert!(matches!(ty.kind, TypeKind::Array(_)), "{ty:?}");
+            assert_eq!(t.range(ty.span), 0..src.len());
+            assert_ne!(ty.span.to_range(), 0..src.len());
+
+            let stmt = t.parse_stmt("return x;")?;
+            assert!(matches!(stmt.kind, StmtKind::Return(Some(_))), "{stmt:?}");
+            assert_eq!(t.snippet(stmt.span), "return x;");
+
+            t.has_errors()?;
+
+            assert!(t.parse_expr("a + b c").is_err());
+
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn debug_tree() {
+        use expect_test::{expect, Expect};
+
+        fn check(src: &str, expect: Expect) {
+            test_util::enter(|t| -> Result {
+                let expr = t.parse_expr(src)?;
+                expect.assert_eq(&format!("{:?}", expr.debug_tree()));
+                t.has_errors()
+            })
+            .unwrap();
+        }
+
+        check("a + b", expect![[r#"Binary(Ident("a"), Add, Ident("b"))"#]]);
+        check("  a\n    +   b ", expect![[r#"Binary(Ident("a"), Add, Ident("b"))"#]]);
+        check(
+            "a + b * c",
+            expect![[r#"Binary(Ident("a"), Add, Binary(Ident("b"), Mul, Ident("c")))"#]],
+        );
+        check(
+            "f(1 ether, x.y)[i]",
+            expect![[
+                r#"Index(Call(Ident("f"), [Lit("1", ether), Member(Ident("x"), "y")]), Some(Ident("i")))"#
+            ]],
+        );
+        check("g({a: 1})", expect![[r#"Call(Ident("g"), {"a": Lit("1")})"#]]);
+        check("new uint256[](n)", expect![[r#"Call(New(Array(UInt(256), None)), [Ident("n")])"#]]);
+    }
+
+    #[test]
+    fn approx_gas() {
+        use solar_ast::visit::Visit;
+        use solar_data_structures::Never;
+        use std::ops::ControlFlow;
+
+        struct GasCounter(u32);
+
+        impl<'ast> Visit<'ast> for GasCounter {
+            type BreakValue = Never;
+
+            fn visit_expr(&mut self, expr: &'ast Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+                match &expr.kind {
+                    ExprKind::Binary(_, op, _) | ExprKind::Assign(_, Some(op), _) => {
+                        self.0 += op.kind.approx_gas();
+                    }
+                    ExprKind::Unary(op, _) => self.0 += op.kind.approx_gas(),
+                    _ => {}
+                }
+                self.walk_expr(expr)
+            }
+        }
+
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a", 0),
+                ("a + b", 3),
+                ("a * b + c / d - ~e", 5 + 5 + 3 + 3 + 3),
+                ("x += y % z", 3 + 5),
+                ("a < b && !c", 3 + 13 + 3),
+                ("x ** y", 1610),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let mut counter = GasCounter(0);
+                let _ = counter.visit_expr(expr);
+                assert_eq!(counter.0, expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn missing_operand() {
+        test_util::enter(|t| -> Result {
+            let expr = t.parse_expr("a + ")?;
+            assert!(t.has_errors().is_err());
+            assert_eq!(format!("{:?}", expr.debug_tree()), r#"Binary(Ident("a"), Add, Err)"#);
+            let ExprKind::Binary(_, _, rhs) = &expr.kind else { unreachable!() };
+            assert_eq!(t.range(rhs.span), 3..3);
+
+            let expr = t.parse_expr("f(a, )")?;
+            assert_eq!(
+                format!("{:?}", expr.debug_tree()),
+                r#"Call(Ident("f"), [Ident("a"), Err])"#
+            );
+            let ExprKind::Call(_, CallArgs { kind: CallArgsKind::Unnamed(args), .. }) = &expr.kind
+            else {
+                unreachable!()
+            };
+            assert_eq!(t.range(args[1].span), 4..4);
+
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn array_holes() {
+        test_util::enter(|t| -> Result {
+            let expr = t.parse_expr("(a,,b)")?;
+            assert_eq!(
+                format!("{:?}", expr.debug_tree()),
+                r#"Tuple([Some(Ident("a")), None, Some(Ident("b"))])"#
+            );
+            let expr = t.parse_expr("[(a, b), (c, d)]")?;
+            assert_eq!(
+                format!("{:?}", expr.debug_tree()),
+                r#"Array([Tuple([Some(Ident("a")), Some(Ident("b"))]), Tuple([Some(Ident("c")), Some(Ident("d"))])])"#
+            );
+            let expr = t.parse_expr("[]")?;
+            assert_eq!(format!("{:?}", expr.debug_tree()), "Array([])");
+            t.has_errors()?;
+
+            let expr = t.parse_expr("[a,,b]")?;
+            assert!(t.has_errors().is_err());
+            assert_eq!(
+                format!("{:?}", expr.debug_tree()),
+                r#"Array([Ident("a"), Err, Ident("b")])"#
+            );
+            let ExprKind::Array(elems) = &expr.kind else { unreachable!() };
+            assert_eq!(t.range(elems[1].span), 3..3);
+
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn modifier_invocation() {
+        test_util::enter(|t| -> Result {
+            let tests: &[(&str, Option<(&str, Option<usize>)>)] = &[
+                ("onlyOwner", Some(("onlyOwner", None))),
+                ("onlyRole(x)", Some(("onlyRole", Some(1)))),
+                ("onlyRole()", Some(("onlyRole", Some(0)))),
+                ("a.b(x)", None),
+                ("a + b", None),
+            ];
+            for &(src, expected) in tests.iter() {
+                let expr = t.parse_expr(src)?;
+                let invocation = expr
+                    .as_modifier_invocation()
+                    .map(|(name, args)| (name.to_string(), args.map(CallArgs::len)));
+                let expected = expected.map(|(name, args)| (name.to_string(), args));
+                assert_eq!(invocation, expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn canonicalize() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a + b", "b + a", true),
+                ("x == 1", "1 == x", true),
+                ("a && b || c", "c || b && a", true),
+                ("(a * b) & c", "c & (b * a)", true),
+                ("f(a | b)", "f(b | a)", true),
+                ("a - b", "b - a", false),
+                ("a < b", "b < a", false),
+                ("f() + g()", "g() + f()", false),
+                ("a + i++", "i++ + a", false),
+                ("(x = 1) | y", "y | (x = 1)", false),
+            ];
+            for (a, b, equal) in tests {
+                let parse = |src: &str| -> Result<String> {
+                    let expr = t.parse_expr(src)?;
+                    expr.canonicalize();
+                    Ok(format!("{:?}", expr.debug_tree()))
+                };
+                let (a_tree, b_tree) = (parse(a)?, parse(b)?);
+                assert_eq!(a_tree == b_tree, equal, "{a:?} -> {a_tree}\n{b:?} -> {b_tree}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn sexpr() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a + b", "(binary + (ident a) (ident b))"),
+                (
+                    r#"x += -a[i:] * f(1 ether, (b, , "s"), g{value: 1}({k: 2}))[0]"#,
+                    r#"(assign += (ident x) (binary * (unary - (slice (ident a) (ident i) _)) (index (call (ident f) (lit 1 ether) (tuple (ident b) _ "s") (call (options (ident g) (arg value 1)) (arg k 2))) 0)))"#,
+                ),
+                (
+                    "i++ + type(uint256).max",
+                    "(binary + (postfix ++ (ident i)) (member (type-call uint256) max))",
+                ),
+                ("new uint[][2](n)", "(call (new (array-type (array-type uint256 _) 2)) (ident n))"),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(expr.to_sexpr(), expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn ternary_assign_precedence() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a ? b : c = d", "(ternary (ident a) (ident b) (assign = (ident c) (ident d)))"),
+                ("a ? b = c : d", "(ternary (ident a) (assign = (ident b) (ident c)) (ident d))"),
+                (
+                    "a = b ? c : d = e",
+                    "(assign = (ident a) (ternary (ident b) (ident c) (assign = (ident d) (ident e))))",
+                ),
+                (
+                    "a ? b : c ? d : e += f",
+                    "(ternary (ident a) (ident b) (ternary (ident c) (ident d) (assign += (ident e) (ident f))))",
+                ),
+                (
+                    "(a ? b : c) = d",
+                    "(assign = (tuple (ternary (ident a) (ident b) (ident c))) (ident d))",
+                ),
+                (
+                    "a || b ? c : d",
+                    "(ternary (binary || (ident a) (ident b)) (ident c) (ident d))",
+                ),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(expr.to_sexpr(), expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn dialect() {
+        use crate::Dialect;
+
+        test_util::enter(|t| -> Result {
+            let parse = |src: &str, dialect: Dialect| -> Result {
+                let mut parser = t.parser(src);
+                parser.set_dialect(dialect);
+                parser.parse_standalone_expr().map_err(|e| e.emit())?;
+                Ok(())
+            };
+
+            let no_pow = Dialect::new().with_bin_op(BinOpKind::Pow, false);
+            let no_sar = Dialect::new().with_bin_op(BinOpKind::Sar, false);
+            let no_inc = Dialect::new().with_un_op(UnOpKind::PostInc, false);
+
+            parse("a ** b", Dialect::new())?;
+            parse("a * b", no_pow.clone())?;
+            parse("a >>> b", no_pow.clone().with_bin_op(BinOpKind::Sar, true))?;
+            parse("a ** b", no_pow.clone().with_bin_op(BinOpKind::Pow, true))?;
+            parse("++a", no_inc.clone())?;
+            t.has_errors()?;
+
+            for (src, dialect) in [
+                ("a ** b", no_pow),
+                ("a >>> b", no_sar.clone()),
+                ("a >>>= b", no_sar),
+                ("a++", no_inc),
+            ] {
+                let errors = t.sess.dcx.err_count();
+                parse(src, dialect)?;
+                assert_eq!(t.sess.dcx.err_count(), errors + 1, "{src:?}");
+            }
+
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn strip_parens() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("x", r#"Ident("x")"#),
+                ("(((x)))", r#"Ident("x")"#),
+                ("((a + b))", r#"Binary(Ident("a"), Add, Ident("b"))"#),
+                ("(a, b)", r#"Tuple([Some(Ident("a")), Some(Ident("b"))])"#),
+                ("((a, b))", r#"Tuple([Some(Ident("a")), Some(Ident("b"))])"#),
+                ("((a,))", r#"Tuple([Some(Ident("a")), None])"#),
+                ("(f)(x)", r#"Call(Tuple([Some(Ident("f"))]), [Ident("x")])"#),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(format!("{:?}", expr.strip_parens().debug_tree()), expected, "{src:?}");
+                let span = expr.strip_parens().span;
+                assert_eq!(expr.strip_parens_mut().span, span, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn diagnostic_codes() {
+        use crate::Dialect;
+        use solar_interface::ColorChoice;
+
+        let tests = [
+            ("a + ", "error[0401]: expected expression, found "),
+            ("[a, , b]", "error[0402]: array expression components cannot be empty"),
+            ("+a", "error[0403]: unary plus is not supported"),
+            ("a ** b", "error[0404]: operator `**` is not available in this dialect"),
+        ];
+        for (src, expected) in tests {
+            let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).build();
+            TestSession::with_session(sess).enter(|t| {
+                let mut parser = t.parser(src);
+                parser.set_dialect(Dialect::new().with_bin_op(BinOpKind::Pow, false));
+                let _ = parser.parse_standalone_expr().map_err(|e| e.emit());
+                let diags = t.sess.emitted_diagnostics().unwrap().to_string();
+                assert!(diags.starts_with(expected), "{src:?}:\n{diags}");
+            });
+        }
+    }
+
+    #[test]
+    fn call_args_span() {
+        fn args_span(src: &str) -> std::ops::Range<usize> {
+            test_util::enter(|t| -> Result<_> {
+                let expr = t.parse_expr(src)?;
+                let ExprKind::Call(_, args) = &expr.kind else { panic!("not a call: {src:?}") };
+                Ok(t.range(args.span))
+            })
+            .unwrap()
+        }
+
+        assert_eq!(args_span("f()"), 1..3);
+        assert_eq!(args_span("f(a, b)"), 1..7);
+        assert_eq!(args_span("f({x: 1})"), 1..9);
+        assert_eq!(args_span("a.f ( 1 )"), 4..9);
+    }
+
+    #[test]
+    fn equiv() {
+        test_util::enter(|t| -> Result {
+            let long_sum = format!("a{}", " + 1".repeat(1000));
+            let tests = [
+                ("a + 1 + 2", "3 + a", true),
+                ("(a * b) * c", "c * (b * a)", true),
+                ("x == y + 0x10", "16 + y == x", true),
+                ("1 - 2 + a", "a + -1", true),
+                ("a * (2 ** 3)", "(2 ** 3) * a", true),
+                ("x[i + 1].y", "x[1 + i].y", true),
+                (long_sum.as_str(), "1000 + a", true),
+                ("a + 1", "a + 2", false),
+                ("a - b", "b - a", false),
+                ("a - 1 + 2", "a + 1", false),
+                ("f()", "f()", false),
+                ("f() + 1 + 2", "3 + f()", false),
+                ("a++ + b", "b + a++", false),
+                ("(x = 1) | y", "y | (x = 1)", false),
+            ];
+            for (a, b, equiv) in tests {
+                let (a_expr, b_expr) = (t.parse_expr(a)?, t.parse_expr(b)?);
+                assert_eq!(a_expr.equiv(&b_expr), equiv, "{a:?} <=> {b:?}");
+                assert_eq!(b_expr.equiv(&a_expr), equiv, "{b:?} <=> {a:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn referenced_types() {
+        test_util::enter(|t| -> Result {
+            let tests: [(&str, &[&str]); 6] = [
+                ("a + b", &[]),
+                ("C(addr).f()", &[]),
+                ("new MyContract{salt: s}(x)", &["MyContract"]),
+                (
+                    "f(type(MyInterface).interfaceId, uint8(x) + 1, new uint[](n), address(this))",
+                    &["MyInterface", "uint8", "(array-type uint256 _)", "address"],
+                ),
+                ("c ? T(x) : payable(address(y))", &["address"]),
+                ("a[int8(i)].b", &["int8"]),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let types =
+                    expr.referenced_types().iter().map(|ty| ty.to_sexpr()).collect::<Vec<_>>();
+                assert_eq!(types, expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn new_call() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                (
+                    "new C(a, b)",
+                    Some((NewKind::Contract, "C", 2)),
+                    r#"Call(New(Custom(C)), [Ident("a"), Ident("b")])"#,
+                ),
+                (
+                    "new a.C{salt: s}()",
+                    Some((NewKind::Contract, "a.C", 0)),
+                    r#"Call(CallOptions(New(Custom(a.C)), {"salt": Ident("s")}), [])"#,
+                ),
+                (
+                    "new uint256[](n)",
+                    Some((NewKind::Allocation, "(array-type uint256 _)", 1)),
+                    r#"Call(New(Array(UInt(256), None)), [Ident("n")])"#,
+                ),
+                (
+                    "new bytes(32)",
+                    Some((NewKind::Allocation, "bytes", 1)),
+                    r#"Call(New(Bytes), [Lit("32")])"#,
+                ),
+                (
+                    "new string(len)",
+                    Some((NewKind::Allocation, "string", 1)),
+                    r#"Call(New(String), [Ident("len")])"#,
+                ),
+                (
+                    "new uint256[2](n)",
+                    None,
+                    r#"Call(New(Array(UInt(256), Some(Lit("2")))), [Ident("n")])"#,
+                ),
+                ("new uint256(1)", None, r#"Call(New(UInt(256)), [Lit("1")])"#),
+                ("new C", None, r#"New(Custom(C))"#),
+            ];
+            for (src, expected, tree) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(format!("{:?}", expr.debug_tree()), tree, "{src:?}");
+                let new =
+                    expr.as_new_call().map(|(kind, ty, args)| (kind, ty.to_sexpr(), args.len()));
+                let expected = expected.map(|(kind, ty, len)| (kind, ty.to_string(), len));
+                assert_eq!(new, expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn walk_with_path() {
+        use PathSegment::*;
+
+        test_util::enter(|t| -> Result {
+            let src = "c && f(a, x ? y : z[b.m + (1, 2)])";
+            let expr = t.parse_expr(src)?;
+
+            let mut visited = Vec::new();
+            let mut paths = Vec::new();
+            expr.walk_with_path(|e, path| {
+                visited.push(e.to_sexpr());
+                paths.push(path.to_vec());
+            });
+            assert_eq!(visited[0], expr.to_sexpr());
+            assert!(paths[0].is_empty());
+            assert_eq!(visited[1], "(ident c)");
+            assert_eq!(paths[1], [BinaryLhs]);
+            assert_eq!(visited.len(), 16);
+
+            let i = visited.iter().position(|e| e == "2").unwrap();
+            assert_eq!(
+                paths[i],
+                [BinaryRhs, CallArg(1), TernaryFalse, Index, BinaryRhs, TupleElement(1)]
+            );
+            let i = visited.iter().position(|e| e == "(ident b)").unwrap();
+            assert_eq!(
+                paths[i],
+                [BinaryRhs, CallArg(1), TernaryFalse, Index, BinaryLhs, MemberBase]
+            );
+            let i = visited.iter().position(|e| e == "(ident f)").unwrap();
+            assert_eq!(paths[i], [BinaryRhs, Callee]);
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn function_type() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                (
+                    "function (uint256) external returns (bool)",
+                    Some(Visibility::External),
+                    StateMutability::NonPayable,
+                    "(type (function (uint256) external (bool)))",
+                ),
+                (
+                    "function () external payable",
+                    Some(Visibility::External),
+                    StateMutability::Payable,
+                    "(type (function () external payable ()))",
+                ),
+                (
+                    "function (uint256, bytes memory) internal view returns (uint256)",
+                    Some(Visibility::Internal),
+                    StateMutability::View,
+                    "(type (function (uint256 bytes) internal view (uint256)))",
+                ),
+                (
+                    "function (address) pure",
+                    None,
+                    StateMutability::Pure,
+                    "(type (function (address) pure ()))",
+                ),
+                (
+                    "function (uint256) external returns (bool)(f)",
+                    Some(Visibility::External),
+                    StateMutability::NonPayable,
+                    "(call (type (function (uint256) external (bool))) (ident f))",
+                ),
+                (
+                    "g = function () internal",
+                    Some(Visibility::Internal),
+                    StateMutability::NonPayable,
+                    "(assign = (ident g) (type (function () internal ())))",
+                ),
+                (
+                    "function () payable external",
+                    Some(Visibility::External),
+                    StateMutability::Payable,
+                    "(type (function () external payable ()))",
+                ),
+                (
+                    "function (uint256) external view returns (uint256)(f)",
+                    Some(Visibility::External),
+                    StateMutability::View,
+                    "(call (type (function (uint256) external view (uint256))) (ident f))",
+                ),
+                (
+                    "function () pure internal returns (bool)",
+                    Some(Visibility::Internal),
+                    StateMutability::Pure,
+                    "(type (function () internal pure (bool)))",
+                ),
+            ];
+            for (src, visibility, state_mutability, sexpr) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(expr.to_sexpr(), sexpr, "{src:?}");
+
+                let mut function = None;
+                expr.walk_with_path(|e, _| {
+                    if let ExprKind::Type(Type { kind: TypeKind::Function(f), .. }) = &e.kind {
+                        function = Some(&**f);
+                    }
+                });
+                let function = function.unwrap();
+                assert_eq!(function.visibility, visibility, "{src:?}");
+                assert_eq!(function.state_mutability, state_mutability, "{src:?}");
+                assert_eq!(function.is_external(), visibility == Some(Visibility::External));
+                assert_eq!(function.is_internal(), !function.is_external(), "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn function_type_conflicting_qualifiers() {
+        use solar_interface::ColorChoice;
+
+        // Source, the qualifiers that are kept, and the diagnostic.
+        let tests = [
+            (
+                "function () external internal",
+                Some(Visibility::External),
+                StateMutability::NonPayable,
+                "error: visibility already specified",
+            ),
+            (
+                "function () internal external returns (bool)",
+                Some(Visibility::Internal),
+                StateMutability::NonPayable,
+                "error: visibility already specified",
+            ),
+            (
+                "function () external pure view",
+                Some(Visibility::External),
+                StateMutability::Pure,
+                "error: state mutability already specified",
+            ),
+            (
+                "function () view external payable",
+                Some(Visibility::External),
+                StateMutability::View,
+                "error: state mutability already specified",
+            ),
+        ];
+        for (src, visibility, state_mutability, expected) in tests {
+            let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).build();
+            TestSession::with_session(sess).enter(|t| {
+                let expr = t.parse_expr(src).unwrap();
+                let ExprKind::Type(Type { kind: TypeKind::Function(f), .. }) = &expr.kind else {
+                    panic!("not a function type: {src:?}");
+                };
+                assert_eq!(f.visibility, visibility, "{src:?}");
+                assert_eq!(f.state_mutability, state_mutability, "{src:?}");
+                assert_eq!(t.sess.dcx.err_count(), 1, "{src:?}");
+                let diags = t.sess.emitted_diagnostics().unwrap().to_string();
+                assert!(diags.starts_with(expected), "{src:?}:\n{diags}");
+            });
+        }
+    }
+
+    #[test]
+    fn result_composition() {
+        /// Parses two comma-separated expressions.
+        fn parse_pair<'sess, 'ast>(
+            parser: &mut Parser<'sess, 'ast>,
+        ) -> PResult<'sess, (Box<'ast, Expr<'ast>>, Box<'ast, Expr<'ast>>)> {
+            let lhs = parser.parse_expr()?;
+            parser.expect(&TokenKind::Comma)?;
+            let rhs = parser.parse_expr()?;
+            Ok((lhs, rhs))
+        }
+
+        test_util::enter(|t| -> Result {
+            // The error points at the offending token, and the parser stays there so that
+            // parsing can resume after cancelling or emitting it.
+            let mut parser = t.parser("a + 1 b");
+            let err = parse_pair(&mut parser).unwrap_err();
+            let span = err.span.primary_span().unwrap();
+            assert_eq!(t.range(span), 6..7);
+            err.cancel();
+            assert_eq!(parser.parse_expr().map_err(|e| e.emit())?.to_sexpr(), "(ident b)");
+
+            let mut parser = t.parser("a + 1, b");
+            let (lhs, rhs) = parse_pair(&mut parser).map_err(|e| e.emit())?;
+            assert_eq!(lhs.to_sexpr(), "(binary + (ident a) 1)");
+            assert_eq!(rhs.to_sexpr(), "(ident b)");
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn literal_predicates() {
+        test_util::enter(|t| -> Result {
+            let predicates: [(&str, fn(&Expr<'_>) -> bool); 5] = [
+                ("zero", |e| e.is_zero_literal()),
+                ("one", |e| e.is_one_literal()),
+                ("true", |e| e.is_true_literal()),
+                ("false", |e| e.is_false_literal()),
+                ("empty string", |e| e.is_empty_string_literal()),
+            ];
+            let tests: &[(&str, Option<&str>)] = &[
+                ("0", Some("zero")),
+                ("0x0", Some("zero")),
+                ("0x00", Some("zero")),
+                ("0.0", Some("zero")),
+                ("0e10", Some("zero")),
+                ("0 ether", Some("zero")),
+                ("0 days", Some("zero")),
+                ("-0", Some("zero")),
+                ("(-(0))", Some("zero")),
+                ("1", Some("one")),
+                ("0x01", Some("one")),
+                ("1e0", Some("one")),
+                ("1.0", Some("one")),
+                ("1 wei", Some("one")),
+                ("(1)", Some("one")),
+                ("1 ether", None),
+                ("-1", None),
+                ("10", None),
+                ("0.1", None),
+                ("true", Some("true")),
+                ("((true))", Some("true")),
+                ("false", Some("false")),
+                ("!true", None),
+                ("\"\"", Some("empty string")),
+                ("''", Some("empty string")),
+                ("unicode\"\"", Some("empty string")),
+                ("hex\"\"", Some("empty string")),
+                ("(\"\")", Some("empty string")),
+                ("\" \"", None),
+                ("hex\"00\"", None),
+                ("0x0000000000000000000000000000000000000000", None),
+                ("x", None),
+                ("x - x", None),
+            ];
+            for &(src, expected) in tests.iter() {
+                let expr = t.parse_expr(src)?;
+                for (name, predicate) in predicates {
+                    assert_eq!(predicate(&expr), expected == Some(name), "{src:?} is {name}");
+                }
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn const_eval() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("1 - 1", Some("0")),
+                ("(1 - 2) * 3", Some("-3")),
+                ("2 ** 300 / 2 ** 200", Some("1267650600228229401496703205376")),
+                ("2 ** 256", None),
+                ("1 gwei", Some("1000000000")),
+                ("0.5 ether / 1e9", Some("500000000")),
+                ("1 / 2", Some("1/2")),
+                ("10 % 3", Some("1")),
+                ("-7 % 3", Some("-1")),
+                ("~0", Some("-1")),
+                ("1 << 8 | 1", Some("257")),
+                ("-3 >> 1", Some("-2")),
+                ("2 ** 8 > 255 ? 1 days : 0", Some("86400")),
+                ("!(1 == 2) && true", Some("true")),
+                ("hex\"01\"", Some("hex\"01\"")),
+                ("\"a\"", Some("\"a\"")),
+                ("1 / 0", None),
+                ("1 % 0", None),
+                ("0.5 % 1", None),
+                ("x - x", None),
+                ("true ? 1 : x", None),
+                ("1 + true", None),
+                ("f()", None),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let value = expr.const_eval().map(|v| v.to_string());
+                assert_eq!(value.as_deref(), expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn as_constant_array() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("[1, 2, 3]", Some("1, 2, 3")),
+                ("([1 - 2, 1 ether, 2 ** 8])", Some("-1, 1000000000000000000, 256")),
+                ("[true, !false]", Some("true, false")),
+                ("[\"a\", hex\"01\"]", Some("\"a\", hex\"01\"")),
+                ("[1 / 2]", Some("1/2")),
+                ("[[1, 2], [3, 4]]", Some("[1, 2], [3, 4]")),
+                ("[[[1], [2, 3]], ([4])]", Some("[[1], [2, 3]], [4]")),
+                ("[1, x, 3]", None),
+                ("[1, 1 / 0]", None),
+                ("[[1, 2], [3, x]]", None),
+                ("[f(), 2]", None),
+                ("(1, 2)", None),
+                ("1", None),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let values = expr.as_constant_array().map(|values| {
+                    values.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+                });
+                assert_eq!(values.as_deref(), expected, "{src:?}");
+            }
+
+            // Nested arrays are nested values.
+            let expr = t.parse_expr("[[1, 2], [3, 4]]")?;
+            let values = expr.as_constant_array().unwrap();
+            assert_eq!(values.len(), 2);
+            let ConstValue::Array(inner) = &values[1] else { panic!("{values:?}") };
+            let inner = inner.iter().map(|v| v.as_u256().unwrap().to::<u64>()).collect::<Vec<_>>();
+            assert_eq!(inner, [3, 4]);
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn infer_array_literal_type() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("[1, 2, 3]", Some(("uint8", false))),
+                ("[0, 255]", Some(("uint8", false))),
+                ("[1, 2, 300]", Some(("uint16", false))),
+                ("([256, (65535)])", Some(("uint16", false))),
+                ("[1 gwei]", Some(("uint32", false))),
+                (
+                    "[0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff]",
+                    Some(("uint256", false)),
+                ),
+                ("[-1, -128]", Some(("int8", false))),
+                ("[-129]", Some(("int16", false))),
+                ("[-1, 2]", Some(("int8", true))),
+                ("[-1, 0]", Some(("int8", true))),
+                ("[-1, 200]", Some(("int16", true))),
+                ("[1, -(2), 3]", Some(("int8", true))),
+                ("[-(-1), -2]", Some(("int8", true))),
+                (
+                    "[-0x8000000000000000000000000000000000000000000000000000000000000000]",
+                    Some(("int256", false)),
+                ),
+                ("[-1, 0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff]", None),
+                ("[1, x]", None),
+                ("[1 + 1]", None),
+                ("[1, true]", None),
+                ("[1.5]", None),
+                ("[[1, 2]]", None),
+                ("[]", None),
+                ("(1, 2)", None),
+                ("1", None),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let inferred = expr.infer_array_literal_type();
+                let inferred = inferred.as_ref().map(|i| (i.ty.to_string(), i.mixed_sign));
+                let expected = expected.map(|(ty, mixed_sign)| (ty.to_string(), mixed_sign));
+                assert_eq!(inferred, expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn display_with_context() {
+        test_util::enter(|t| -> Result {
+            let sm = t.sess.source_map();
+            let src = "f(a,  b)  +  1";
+            let expr = t.parse_expr(src)?;
+            assert_eq!(expr.display_with(sm).to_string(), src);
+            let ExprKind::Binary(lhs, _, rhs) = &expr.kind else { panic!("{expr:?}") };
+            assert_eq!(lhs.display_with(sm).to_string(), "f(a,  b)");
+            assert_eq!(lhs.span.display_with(sm).to_string(), "<test-0>:1:1");
+            assert_eq!(rhs.span.display_with(sm).to_string(), "<test-0>:1:14");
+
+            // Without source code, the expression is printed instead.
+            let expr = Expr { span: Span::DUMMY, kind: ExprKind::Ident(Ident::from_str("x")) };
+            assert_eq!(expr.display_with(sm).to_string(), "x");
+            assert_eq!(expr.span.display_with(sm).to_string(), "no-location");
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn try_into_const() {
+        use alloy_primitives::U256;
+
+        test_util::enter(|t| -> Result {
+            let mismatch = |expected, found| ConstErrorKind::Mismatch { expected, found };
+
+            let ints = [
+                ("2 * 3", Ok(U256::from(6))),
+                ("(0.5 ether)", Ok(U256::from(500_000_000_000_000_000u64))),
+                ("2 ** 256 - 1", Ok(U256::MAX)),
+                ("1 - 2", Err(ConstErrorKind::Negative((-1).into()))),
+                ("2 ** 256", Err(ConstErrorKind::TooLarge)),
+                ("1 / 2", Err(mismatch("integer", "rational"))),
+                ("1 < 2", Err(mismatch("integer", "boolean"))),
+                ("hex\"01\"", Err(mismatch("integer", "bytes"))),
+                ("n + 1", Err(ConstErrorKind::NotConstant)),
+                ("1 / 0", Err(ConstErrorKind::NotConstant)),
+            ];
+            for (src, expected) in ints {
+                let expr = t.parse_expr(src)?;
+                let expected = expected.map_err(|kind| ConstError { span: expr.span, kind });
+                assert_eq!(expr.try_into_int_const(), expected, "{src:?}");
+            }
+
+            let bools = [
+                ("!(1 > 2)", Ok(true)),
+                ("true && false", Ok(false)),
+                ("1", Err(mismatch("boolean", "integer"))),
+                ("\"true\"", Err(mismatch("boolean", "string"))),
+                ("flag", Err(ConstErrorKind::NotConstant)),
+            ];
+            for (src, expected) in bools {
+                let expr = t.parse_expr(src)?;
+                let expected = expected.map_err(|kind| ConstError { span: expr.span, kind });
+                assert_eq!(expr.try_into_bool_const(), expected, "{src:?}");
+            }
+
+            let err = t.parse_expr("3 - 5")?.try_into_int_const().unwrap_err();
+            assert_eq!(err.to_string(), "expected non-negative integer constant, found -2");
+            assert_eq!(t.range(err.span), 0..5);
+
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn named_args_spans() {
+        test_util::enter(|t| -> Result {
+            let src = "f( {a :1,\n  b\t:  x} )";
+            let expr = t.parse_expr(src)?;
+            let ExprKind::Call(_, args) = &expr.kind else { panic!("not a call: {expr:?}") };
+            let CallArgsKind::Named(named, braces) = &args.kind else {
+                panic!("not named arguments: {args:?}")
+            };
+            assert_eq!(t.range(args.span), 1..21);
+            assert_eq!(t.range(*braces), 3..19);
+            assert_eq!(&src[t.range(*braces)], "{a :1,\n  b\t:  x}");
+            let colons = named.iter().map(|arg| t.range(arg.colon_span)).collect::<Vec<_>>();
+            assert_eq!(colons, [6..7, 14..15]);
+            assert!(colons.iter().all(|colon| &src[colon.clone()] == ":"));
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn accessors() {
+        test_util::enter(|t| -> Result {
+            let src = "a.f(b + 2 ether, c)";
+            let expr = t.parse_expr(src)?;
+
+            let (callee, args) = expr.as_call().unwrap();
+            let (base, member) = callee.as_member().unwrap();
+            assert_eq!(base.as_ident().unwrap().as_str(), "a");
+            assert_eq!(member.as_str(), "f");
+            assert_eq!(args.len(), 2);
+
+            let args = args.exprs().collect::<Vec<_>>();
+            let (lhs, op, rhs) = args[0].as_binary().unwrap();
+            assert_eq!(lhs.as_ident().unwrap().as_str(), "b");
+            assert_eq!(op, BinOpKind::Add);
+            let (lit, sub) = rhs.as_lit().unwrap();
+            assert_eq!(lit.symbol.as_str(), "2");
+            assert_eq!(sub, Some(SubDenomination::Ether(EtherSubDenomination::Ether)));
+            assert_eq!(args[1].as_ident().unwrap().as_str(), "c");
+
+            // Accessors only match their own variant.
+            assert!(expr.as_member().is_none());
+            assert!(expr.as_binary().is_none());
+            assert!(callee.as_call().is_none());
+            assert!(args[0].as_lit().is_none());
+            assert!(rhs.as_ident().is_none());
+
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn expr_pool() {
+        test_util::enter(|t| -> Result {
+            // `2 ** 10` copies of the same 9-node sub-expression.
+            let mut src = "(a.b[i] + c * 2)".to_string();
+            for _ in 0..10 {
+                src = format!("({src} - {src})");
+            }
+            let expr = t.parse_expr(&src)?;
+
+            let mut pool = ExprPool::new();
+            let id = pool.intern(&expr).unwrap();
+            // 9 nodes for the innermost expression, plus a binary and a tuple node per level.
+            assert_eq!(pool.len(), 9 + 2 * 10);
+            assert!(
+                pool.approx_memory_bytes() * 50 < expr.approx_memory_bytes(),
+                "{} {}",
+                pool.approx_memory_bytes(),
+                expr.approx_memory_bytes()
+            );
+
+            // Both operands are the same pooled expression, with the span of the first one.
+            let PooledExprKind::Tuple(components) = pool.get(id) else { panic!() };
+            let PooledExprKind::Binary(lhs, BinOpKind::Sub, rhs) = pool.get(components[0].unwrap())
+            else {
+                panic!()
+            };
+            assert_eq!(lhs, rhs);
+            let ExprKind::Tuple(components) = &expr.kind else { panic!() };
+            let (expr_lhs, _, _) = components[0].as_ref().unwrap().as_binary().unwrap();
+            assert_eq!(pool.span(*lhs), expr_lhs.span);
+
+            // Interning again does not add anything, and impure expressions are not interned.
+            let len = pool.len();
+            assert!(pool.intern(&t.parse_expr("(a.b[i] + c * 2)")?).is_some());
+            assert_eq!(pool.intern(&t.parse_expr("a.b[i] + f(c)")?), None);
+            assert_eq!(pool.intern(&t.parse_expr("x++ + c")?), None);
+            assert_eq!(pool.len(), len);
+
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn external_calls() {
+        test_util::enter(|t| -> Result {
+            let expr = t.parse_expr(
+                "x + IERC20(t).balanceOf(this) * uint(y).f() \
+                 + addr.call{value: 1 ether}(abi.encode(lib.g(1), to.send(2))).length",
+            )?;
+            let calls = expr.external_calls();
+            let kinds =
+                calls.iter().map(|call| call.as_external_call().unwrap()).collect::<Vec<_>>();
+            assert_eq!(
+                kinds,
+                [ExternalCallKind::Contract, ExternalCallKind::Call, ExternalCallKind::Send]
+            );
+            let (callee, _) = calls[1].as_call().unwrap();
+            assert!(matches!(callee.kind, ExprKind::CallOptions(..)));
+
+            for (src, kind) in [
+                ("a.delegatecall(data)", Some(ExternalCallKind::Delegatecall)),
+                ("a.staticcall(data)", Some(ExternalCallKind::Staticcall)),
+                ("payable(a).transfer(1)", Some(ExternalCallKind::Transfer)),
+                ("a.send(1)", Some(ExternalCallKind::Send)),
+                // Token transfers.
+                ("IERC20(t).transfer(to, amount)", Some(ExternalCallKind::Contract)),
+                ("I(t).send(to, amount)", Some(ExternalCallKind::Contract)),
+                ("token.transfer(to, amount)", None),
+                ("token.send(to, amount)", None),
+                ("token.transfer({to: to, amount: amount})", None),
+                ("(I(a)).f{gas: 1}()", Some(ExternalCallKind::Contract)),
+                ("a.f()", None),
+                ("a.call", None),
+                ("f(a, b).g()", None),
+            ] {
+                assert_eq!(t.parse_expr(src)?.as_external_call(), kind, "{src}");
+            }
+
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn index_chain() {
+        test_util::enter(|t| -> Result {
+            // Indices nest to the left: `((a[1])[2:3])[k]`.
+            let expr = t.parse_expr("a[1][2:3][k]")?;
+            let ExprKind::Index(inner, IndexKind::Index(Some(k))) = &expr.kind else { panic!() };
+            assert_eq!(k.as_ident().unwrap().as_str(), "k");
+            let ExprKind::Index(inner, IndexKind::Range(Some(_), Some(_))) = &inner.kind else {
+                panic!()
+            };
+            let ExprKind::Index(a, IndexKind::Index(Some(_))) = &inner.kind else { panic!() };
+            assert_eq!(a.as_ident().unwrap().as_str(), "a");
+
+            let (base, indices) = expr.index_chain().unwrap();
+            assert_eq!(base.as_ident().unwrap().as_str(), "a");
+            let [first, second, third] = indices[..] else { panic!("{indices:?}") };
+            let IndexKind::Index(Some(one)) = first else { panic!() };
+            let IndexKind::Range(Some(l), Some(r)) = second else { panic!() };
+            let IndexKind::Index(Some(k)) = third else { panic!() };
+            assert!(one.is_one_literal());
+            assert_eq!(l.as_lit().unwrap().0.symbol.as_str(), "2");
+            assert_eq!(r.as_lit().unwrap().0.symbol.as_str(), "3");
+            assert_eq!(k.as_ident().unwrap().as_str(), "k");
+
+            // Slices with missing bounds and parenthesized bases.
+            let expr = t.parse_expr("(m[i])[:][j:]")?;
+            let (base, indices) = expr.index_chain().unwrap();
+            assert!(matches!(base.kind, ExprKind::Tuple(_)));
+            assert!(matches!(
+                indices[..],
+                [IndexKind::Range(None, None), IndexKind::Range(Some(_), None)]
+            ));
+            assert_eq!(base.strip_parens().index_chain().unwrap().1.len(), 1);
+
+            assert!(t.parse_expr("a.b")?.index_chain().is_none());
+            assert!(t.parse_expr("f(a[1])")?.index_chain().is_none());
+
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn expr_matches() {
+        use solar_ast::expr_matches;
+
+        test_util::enter(|t| -> Result {
+            let snippet = |expr: &Expr<'_>| t.snippet(expr.span);
+
+            // A lint for comparisons and assignments of an expression to itself.
+            let is_self_op = |expr: &Expr<'_>| {
+                expr_matches!(expr, Binary(lhs, Eq, rhs) if lhs.equiv(rhs))
+                    || expr_matches!(expr, Assign(lhs, =, rhs) if lhs.equiv(rhs))
+            };
+            for src in ["a.b == a.b", "x = x", "f(1) == f(1)"] {
+                assert!(is_self_op(&t.parse_expr(src)?), "{src}");
+            }
+            for src in ["a == b", "x += x", "x != x", "x = y"] {
+                assert!(!is_self_op(&t.parse_expr(src)?), "{src}");
+            }
+
+            // A lint for double negations, returning the negated expression.
+            let double_negation = |expr: &Expr<'_>| {
+                expr_matches!(expr, Unary(Not, inner) => inner)
+                    .and_then(|inner| expr_matches!(inner.strip_parens(), Unary(Not, x) => x))
+                    .map(snippet)
+            };
+            assert_eq!(double_negation(&t.parse_expr("!!x")?).as_deref(), Some("x"));
+            assert_eq!(double_negation(&t.parse_expr("!(!f(a))")?).as_deref(), Some("f(a)"));
+            assert_eq!(double_negation(&t.parse_expr("!-x")?), None);
+
+            // Increments by a literal, with a nested pattern for the literal.
+            let increment = |expr: &Expr<'_>| {
+                expr_matches!(expr, Assign(
+                    target,
+                    Add,
+                    Expr { kind: ExprKind::Lit(Lit { kind: LitKind::Number(n), .. }, None), .. }
+                ) => (snippet(target), n.to_string()))
+            };
+            assert_eq!(
+                increment(&t.parse_expr("a[i] += 2")?),
+                Some(("a[i]".to_string(), "2".into()))
+            );
+            assert_eq!(increment(&t.parse_expr("a[i] -= 2")?), None);
+            assert_eq!(increment(&t.parse_expr("a[i] += 2 ether")?), None);
+            assert_eq!(increment(&t.parse_expr("a[i] = 2")?), None);
+
+            // Other variants bind their fields as they are.
+            let expr = t.parse_expr("x.length")?;
+            let member =
+                expr_matches!(&*expr, Member(base, name) => (snippet(base), name.as_str()));
+            assert_eq!(member, Some(("x".to_string(), "length")));
+            assert!(expr_matches!(&*t.parse_expr("f(a, b)")?, Call(_, args) if args.len() == 2));
+            assert!(expr_matches!(&*t.parse_expr("foo")?, Ident(ident) if ident.as_str() == "foo"));
+            assert!(!expr_matches!(&*t.parse_expr("(foo)")?, Ident(_)));
+
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn matcher() {
+        use solar_ast::matcher::*;
+        use solar_interface::Symbol;
+
+        test_util::enter(|t| -> Result {
+            // `x == true`, with the operands in any order and parentheses around the literal.
+            let bool_comparison = bin_op_commutative(
+                BinOpKind::Eq,
+                capture(not(strip_parens(any_lit_bool()))),
+                strip_parens(capture(any_lit_bool())),
+            );
+            for (src, value, lit) in [
+                ("x == true", "x", true),
+                ("(false) == a.b", "a.b", false),
+                ("f() == true", "f()", true),
+            ] {
+                let expr = t.parse_expr(src)?;
+                let captures = bool_comparison.match_expr(&expr).expect(src);
+                assert_eq!(captures.len(), 2);
+                let snippet = t.snippet(captures[0].span);
+                assert_eq!(snippet, value);
+                assert_eq!(captures[1].is_true_literal(), lit);
+            }
+            for src in ["x != true", "true == false", "x == 1", "x"] {
+                assert!(!bool_comparison.is_match(&t.parse_expr(src)?), "{src}");
+            }
+
+            // Nested captures are in the order of the patterns, and wildcards capture nothing.
+            let pattern = bin_op(
+                BinOpKind::Add,
+                capture(member(capture(ident(Symbol::intern("a"))), Symbol::intern("b"))),
+                either(unary(UnOpKind::Neg, capture(lit_zero())), call(any())),
+            );
+            let expr = t.parse_expr("a.b + -0")?;
+            let captures = pattern.match_expr(&expr).unwrap();
+            assert_eq!(captures.len(), 3);
+            assert!(captures[0].as_member().is_some());
+            assert_eq!(captures[1].as_ident().unwrap().as_str(), "a");
+            assert!(captures[2].is_zero_literal());
+
+            // Captures of a failed alternative are discarded.
+            let captures = pattern.match_expr(&t.parse_expr("a.b + f(1)")?).unwrap();
+            assert_eq!(captures.len(), 2);
+            assert!(pattern.match_expr(&t.parse_expr("a.c + f()")?).is_none());
+            assert!(pattern.match_expr(&t.parse_expr("a.b + -1")?).is_none());
+            assert!(predicate(|e| e.is_pure()).is_match(&t.parse_expr("a + 1")?));
+
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn minify() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a + b * c", "a+b*c"),
+                ("f(x, y)", "f(x,y)"),
+                ("a - -b", "a- -b"),
+                ("a - (-b)", "a-(-b)"),
+                ("x-- - --y", "x-- - --y"),
+                ("i++ + ++j", "i++ + ++j"),
+                ("!(a == b) && c != d", "!(a==b)&&c!=d"),
+                ("a < -1 ? b : c << 2", "a<-1?b:c<<2"),
+                ("x >>= 1 >> y", "x>>=1>>y"),
+                ("delete m[k]", "delete m[k]"),
+                ("type(uint8).max + 1 ether", "type(uint8).max+1 ether"),
+                ("new uint[][2](n)", "new uint256[][2](n)"),
+                (
+                    r#"x += -a[i:] * f(1 ether, (b, , "s"), g{value: 1}({k: 2}))[0]"#,
+                    r#"x+=-a[i:]*f(1 ether,(b,,"s"),g{value:1}({k:2}))[0]"#,
+                ),
+                (
+                    r#"payable(addr).call(hex"00ff", "a\"b")"#,
+                    r#"payable(addr).call(hex"00ff","a\"b")"#,
+                ),
+                (
+                    "abi.decode(data, (uint, address, bytes32[]))",
+                    "abi.decode(data,(uint256,address,bytes32[]))",
+                ),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let minified = expr.to_minified().unwrap();
+                assert_eq!(minified, expected, "{src:?}");
+                // The minified expression parses back to the same tree.
+                let reparsed = t.parse_expr(&minified)?;
+                assert_eq!(reparsed.to_sexpr(), expr.to_sexpr(), "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn const_value_set() {
+        test_util::enter(|t| -> Result {
+            let tests: &[(&str, Option<&[&str]>)] = &[
+                ("1", Some(&["1"])),
+                ("(2 - 1)", Some(&["1"])),
+                ("c ? 1 : 2", Some(&["1", "2"])),
+                ("c ? 1 : 2 - 1", Some(&["1"])),
+                ("a ? 1 : b ? 2 : 3", Some(&["1", "2", "3"])),
+                ("true ? 1 : 2", Some(&["1"])),
+                ("c ? \"a\" : \"b\"", Some(&["\"a\"", "\"b\""])),
+                ("c ? 1 : x", None),
+                ("true ? 1 : x", None),
+                ("x", None),
+                ("f()", None),
+            ];
+            for &(src, expected) in tests.iter() {
+                let expr = t.parse_expr(src)?;
+                let set = expr.const_value_set();
+                let values = set.map(|set| set.iter().map(|v| v.to_string()).collect::<Vec<_>>());
+                let expected =
+                    expected.map(|e| e.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+                assert_eq!(values, expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn duplicate_constants() {
+        /// Returns the indices of the `x == c` comparisons in a `||` chain whose constant was
+        /// already compared with.
+        fn duplicates(expr: &Expr<'_>) -> Vec<usize> {
+            let mut comparisons = Vec::new();
+            let mut expr = expr;
+            while let Some((lhs, BinOpKind::Or, rhs)) = expr.as_binary() {
+                comparisons.push(rhs);
+                expr = lhs;
+            }
+            comparisons.push(expr);
+            comparisons.reverse();
+
+            let mut seen = ValueSet::new();
+            let mut duplicates = Vec::new();
+            for (i, comparison) in comparisons.into_iter().enumerate() {
+                let Some((_, BinOpKind::Eq, value)) = comparison.as_binary() else { continue };
+                let Some(set) = value.const_value_set() else { continue };
+                if seen.intersects(&set) {
+                    duplicates.push(i);
+                }
+                seen.extend(set);
+            }
+            duplicates
+        }
+
+        test_util::enter(|t| -> Result {
+            let tests: &[(&str, &[usize])] = &[
+                ("x == 1 || x == 2", &[]),
+                ("x == 1 || x == 1", &[1]),
+                ("x == 1 || x == 2 - 1", &[1]),
+                ("x == 1 || x == 2 || x == (1)", &[2]),
+                ("x == (c ? 1 : 2) || x == 2", &[1]),
+                ("x == 1 || x == y || x == y", &[]),
+                ("x == \"a\" || x == \"a\"", &[1]),
+            ];
+            for &(src, expected) in tests.iter() {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(duplicates(&expr), expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn minify_parens() {
+        test_util::enter(|t| -> Result {
+            // Source, minified with parentheses preserved, minified with redundant parentheses
+            // stripped.
+            let tests = [
+                ("(a + b)", "(a+b)", "a+b"),
+                ("((x))", "((x))", "x"),
+                ("(a + b) * c", "(a+b)*c", "(a+b)*c"),
+                ("f((a), (b + c))", "f((a),(b+c))", "f(a,b+c)"),
+                ("(a, (b))", "(a,(b))", "(a,b)"),
+                ("x[(i)]", "x[(i)]", "x[i]"),
+                ("-(-x)", "-(-x)", "- -x"),
+                ("(-x) + (y++)", "(-x)+(y++)", "-x+y++"),
+                ("(x.y)++", "(x.y)++", "x.y++"),
+                ("(-x)++", "(-x)++", "(-x)++"),
+                ("(f)(x).y", "(f)(x).y", "f(x).y"),
+                ("(new C).x", "(new C).x", "(new C).x"),
+                ("c ? (a = 1) : (b)", "c?(a=1):(b)", "c?a=1:b"),
+                ("(c ? a : b) ? d : e", "(c?a:b)?d:e", "(c?a:b)?d:e"),
+            ];
+            for (src, kept, stripped) in tests {
+                let expr = t.parse_expr(src)?;
+
+                let options = MinifyOptions { strip_redundant_parens: false, ..Default::default() };
+                let minified = expr.to_minified_with(options).unwrap();
+                assert_eq!(minified, kept, "{src:?}");
+                let reparsed = t.parse_expr(&minified)?;
+                assert_eq!(reparsed.to_sexpr(), expr.to_sexpr(), "{src:?}");
+
+                let options = MinifyOptions { strip_redundant_parens: true, ..Default::default() };
+                let minified = expr.to_minified_with(options).unwrap();
+                assert_eq!(minified, stripped, "{src:?}");
+                let reparsed = t.parse_expr(&minified)?;
+                assert!(reparsed.struct_eq(&expr), "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn minify_sorted_named_args() {
+        test_util::enter(|t| -> Result {
+            // Source, minified in source order, minified with sorted named arguments.
+            let tests = [
+                ("f({b: 1, a: 2})", "f({b:1,a:2})", "f({a:2,b:1})"),
+                ("f({a: 1, b: 2})", "f({a:1,b:2})", "f({a:1,b:2})"),
+                (
+                    "f({to: t, amount: g({z: 1, y: 2}), data: d})",
+                    "f({to:t,amount:g({z:1,y:2}),data:d})",
+                    "f({amount:g({y:2,z:1}),data:d,to:t})",
+                ),
+                ("f(b, a)", "f(b,a)", "f(b,a)"),
+                ("f(g({b: 1, a: 2}), c)", "f(g({b:1,a:2}),c)", "f(g({a:2,b:1}),c)"),
+                (
+                    "c.f{value: v, gas: g}({y: 1, x: 2})",
+                    "c.f{value:v,gas:g}({y:1,x:2})",
+                    "c.f{value:v,gas:g}({x:2,y:1})",
+                ),
+                ("f({B: 1, a: 2, _c: 3})", "f({B:1,a:2,_c:3})", "f({B:1,_c:3,a:2})"),
+            ];
+            for (src, source_order, sorted) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(expr.to_minified().unwrap(), source_order, "{src:?}");
+                let options = MinifyOptions { sort_named_args: true, ..Default::default() };
+                assert_eq!(expr.to_minified_with(options).unwrap(), sorted, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn to_string_truncated() {
+        test_util::enter(|t| -> Result {
+            // Source, maximum length, truncated output.
+            let tests = [
+                ("foo(a, bb, cc)", 100, "foo(a,bb,cc)"),
+                ("foo(a, bb, cc)", 12, "foo(a,bb,cc)"),
+                ("foo(a, bb, cc)", 11, "foo(a,...)"),
+                ("foo(a, bb, cc)", 8, "foo(...)"),
+                ("foo(a, bb, cc)", 5, "..."),
+                ("foo(a, bb, cc)", 0, "..."),
+                ("a + someLongName", 10, "a+..."),
+                ("someLongName + a", 10, "..."),
+                ("f(g(x, y), [1, 2, 3])", 14, "f(g(x,y),...)"),
+                ("f(g(x, y), [1, 2, 3])", 16, "f(g(x,y),[1...])"),
+                ("a.b.c[i].d", 8, "a.b.c..."),
+            ];
+            for (src, max_len, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let truncated = expr.to_string_truncated(max_len);
+                assert_eq!(truncated, expected, "{src:?} at {max_len}");
+                assert!(truncated.len() <= max_len.max(3), "{src:?} at {max_len}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn struct_eq() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a", "(a)", true),
+                ("(a + b) * c", "((a + b)) * (c)", true),
+                ("f(x, [y])", "(f)((x), [(y)])", true),
+                ("(a + b) * c", "a + b * c", false),
+                ("(a, b)", "((a), b)", true),
+                ("(a, b)", "((a, b))", true),
+                ("(a, b)", "(a, b, c)", false),
+                ("1 + 1", "2", false),
+            ];
+            for (a, b, eq) in tests {
+                let a_expr = t.parse_expr(a)?;
+                let b_expr = t.parse_expr(b)?;
+                assert_eq!(a_expr.struct_eq(&b_expr), eq, "{a:?} {b:?}");
+                assert_eq!(b_expr.struct_eq(&a_expr), eq, "{a:?} {b:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn lvalue_roots() {
+        test_util::enter(|t| -> Result {
+            // Source, identifier roots, non-variable roots.
+            let tests: &[(&str, &[&str], &[&str])] = &[
+                ("a", &["a"], &[]),
+                ("(a)", &["a"], &[]),
+                ("a.b.c", &["a"], &[]),
+                ("arr[i]", &["arr"], &[]),
+                ("m[k].v[i:j]", &["m"], &[]),
+                ("s.arr[i].x", &["s"], &[]),
+                ("(x, y)", &["x", "y"], &[]),
+                ("(x, , (y.a, z[0]))", &["x", "y", "z"], &[]),
+                ("f().x", &[], &["(call (ident f))"]),
+                ("(a, f()[0])", &["a"], &["(call (ident f))"]),
+                ("[a, b][i]", &[], &["(array (ident a) (ident b))"]),
+            ];
+            for &(src, idents, others) in tests.iter() {
+                let expr = t.parse_expr(src)?;
+                let roots: Vec<_> =
+                    expr.lvalue_roots().iter().map(|ident| ident.as_str()).collect();
+                assert_eq!(roots, idents, "{src:?}");
+                let non_variable: Vec<_> =
+                    expr.lvalue_non_variable_roots().iter().map(|e| e.to_sexpr()).collect();
+                assert_eq!(non_variable, others, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn invariant_subexprs() {
+        test_util::enter(|t| -> Result {
+            let modified: std::collections::HashSet<_> =
+                ["i", "sum"].into_iter().map(Symbol::intern).collect();
+            // Source, whether it is invariant, and the candidates for hoisting.
+            let tests: &[(&str, bool, &[&str])] = &[
+                ("a * b", true, &["a*b"]),
+                ("x.length - 1", true, &["x.length-1"]),
+                ("a", true, &[]),
+                ("1 + 2", true, &["1+2"]),
+                ("i", false, &[]),
+                ("arr[i]", false, &[]),
+                ("sum + a * b", false, &["a*b"]),
+                ("arr[i] + len * 2", false, &["len*2"]),
+                ("(a + b) * i", false, &["(a+b)"]),
+                ("c ? a : sum", false, &[]),
+                ("s.x[i + 1] == s.x[k + 1]", false, &["s.x", "s.x[k+1]"]),
+                ("f(a + b)", false, &["a+b"]),
+                ("a++ + b", false, &[]),
+                ("x = a - b", false, &["a-b"]),
+            ];
+            for &(src, invariant, candidates) in tests.iter() {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(expr.is_invariant(&modified), invariant, "{src:?}");
+                let found: Vec<_> = expr
+                    .invariant_subexprs(&modified)
+                    .iter()
+                    .map(|e| e.to_minified().unwrap())
+                    .collect();
+                assert_eq!(found, candidates, "{src:?}");
+            }
+
+            let src = "a.x + f(b, a)";
+            let expr = t.parse_expr(src)?;
+            let idents: Vec<_> = expr.free_identifiers().iter().map(|i| i.as_str()).collect();
+            assert_eq!(idents, ["a", "f", "b", "a"]);
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn special_identifier() {
+        use solar_ast::SpecialIdent;
+
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("this", Some(SpecialIdent::This)),
+                ("super", Some(SpecialIdent::Super)),
+                ("msg", Some(SpecialIdent::Msg)),
+                ("tx", Some(SpecialIdent::Tx)),
+                ("block", Some(SpecialIdent::Block)),
+                ("user", None),
+                ("abi", None),
+                ("msg.sender", None),
+                ("(this)", None),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(expr.special_identifier(), expected, "{src:?}");
+                if let Some(special) = expected {
+                    assert_eq!(special.to_str(), src);
+                }
+            }
+
+            let tests = [
+                ("this", true),
+                ("(this)", true),
+                ("address(this)", true),
+                ("payable(this)", true),
+                ("payable(address((this)))", true),
+                ("this.f", false),
+                ("address(0)", false),
+                ("address(this, 1)", false),
+                ("uint160(address(this))", false),
+                ("super", false),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(expr.is_addressable_this(), expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn type_members() {
+        use solar_ast::TypeMember;
+
+        test_util::enter(|t| -> Result {
+            // Source, type argument, member, and whether the member applies to the argument if it
+            // is an elementary type.
+            let tests: &[(&str, &str, Option<TypeMember>, Option<bool>)] = &[
+                ("type(C).creationCode", "C", Some(TypeMember::CreationCode), None),
+                ("type(C).runtimeCode", "C", Some(TypeMember::RuntimeCode), None),
+                ("(type(a.C)).name", "a.C", Some(TypeMember::Name), None),
+                ("type(I).interfaceId", "I", Some(TypeMember::InterfaceId), None),
+                ("type(uint8).min", "uint8", Some(TypeMember::Min), Some(true)),
+                ("type(int).max", "int256", Some(TypeMember::Max), Some(true)),
+                ("type(uint).creationCode", "uint256", Some(TypeMember::CreationCode), Some(false)),
+                ("type(bool).max", "bool", Some(TypeMember::Max), Some(false)),
+                ("type(C).foo", "C", None, None),
+            ];
+            for &(src, ty, member, applies) in tests.iter() {
+                let expr = t.parse_expr(src)?;
+                let (arg, name) = expr.as_type_member().expect(src);
+                assert_eq!(arg.to_sexpr(), ty, "{src:?}");
+                assert_eq!(TypeMember::from_member(name.name), member, "{src:?}");
+                let elementary = match arg.kind {
+                    TypeKind::Elementary(ty) => Some(ty),
+                    _ => None,
+                };
+                let applies_to_elementary =
+                    elementary.zip(member).map(|(ty, member)| member.applies_to_elementary(ty));
+                assert_eq!(applies_to_elementary, applies, "{src:?}");
+            }
+
+            for src in ["type(C)", "C.name", "f(type(C)).name"] {
+                let expr = t.parse_expr(src)?;
+                assert!(expr.as_type_member().is_none(), "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn simplify_negations() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("!!x", "x"),
+                ("!!!x", "!x"),
+                ("!(a == b)", "(a!=b)"),
+                ("!(a < b)", "(a>=b)"),
+                ("!((a >= b))", "((a<b))"),
+                ("!(a && b)", "(!a||!b)"),
+                ("!(a || b == c)", "(!a&&(b!=c))"),
+                ("!(a && b || c)", "((!a||!b)&&!c)"),
+                ("!!(a && b)", "(a&&b)"),
+                // The operands are still evaluated in the same order, and `g()` is still only
+                // evaluated if `f()` is `true`.
+                ("!(f() && !g())", "(!f()||g())"),
+                ("c ? !(x != y) : !!z", "c?(x==y):z"),
+                ("!(a == b) == c", "(a!=b)==c"),
+                ("f(!(a > b), x[!!i])", "f((a<=b),x[i])"),
+                // Not simplified.
+                ("!a", "!a"),
+                ("!(a + b)", "!(a+b)"),
+                ("!(c ? a : b)", "!(c?a:b)"),
+                ("-(-a)", "-(-a)"),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                expr.simplify_negations(&t.arena);
+                let minified = expr.to_minified().unwrap();
+                assert_eq!(minified, expected, "{src:?}");
+
+                // The simplified tree is printed as it is structured.
+                let reparsed = t.parse_expr(&minified)?;
+                assert_eq!(reparsed.to_sexpr(), expr.to_sexpr(), "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn expand_compound_assign() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a += b", Some("a=a+b")),
+                ("a -= 1", Some("a=a-1")),
+                ("a <<= n", Some("a=a<<n")),
+                ("a *= b + c", Some("a=a*(b+c)")),
+                ("a |= c ? 1 : 2", Some("a=a|(c?1:2)")),
+                ("x[i] += 1", Some("x[i]=x[i]+1")),
+                ("s.x[i + 1].y %= -z", Some("s.x[i+1].y=s.x[i+1].y%-z")),
+                ("(a) /= 2", Some("(a)=(a)/2")),
+                // Impure left-hand sides.
+                ("a[f()] += 1", None),
+                ("a[i++] += 1", None),
+                ("f().x += 1", None),
+                ("a[b = 1] += 1", None),
+                // Impure right-hand sides.
+                ("a += f()", None),
+                ("a += b = c", None),
+                // Not compound assignments.
+                ("a = b", None),
+                ("a + b", None),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let before = expr.to_sexpr();
+                let expanded = expr.expand_compound_assign(&t.arena);
+                // The original expression is left untouched.
+                assert_eq!(expr.to_sexpr(), before, "{src:?}");
+                let Some(expected) = expected else {
+                    assert!(expanded.is_none(), "{src:?}");
+                    continue;
+                };
+                let expanded = expanded.unwrap_or_else(|| panic!("{src:?}"));
+                let minified = expanded.to_minified().unwrap();
+                assert_eq!(minified, expected, "{src:?}");
+
+                // The expanded tree is printed as it is structured.
+                let reparsed = t.parse_expr(&minified)?;
+                assert_eq!(reparsed.to_sexpr(), expanded.to_sexpr(), "{src:?}");
+            }
+
+            // The copy of the left-hand side keeps its spans.
+            let src = "x[i] += 1";
+            let expr = t.parse_expr(src)?;
+            let ExprKind::Assign(lhs, _, rhs) = &expr.kind else { panic!("{expr:?}") };
+            let expanded = expr.expand_compound_assign(&t.arena).unwrap();
+            let ExprKind::Assign(target, None, value) = &expanded.kind else {
+                panic!("{expanded:?}")
+            };
+            let ExprKind::Binary(read, op, one) = &value.kind else { panic!("{value:?}") };
+            assert_eq!(op.kind, BinOpKind::Add);
+            assert_eq!((target.span, read.span, one.span), (lhs.span, lhs.span, rhs.span));
+            assert_eq!((expanded.span, value.span), (expr.span, expr.span));
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn cost() {
+        use solar_ast::visit::Visit;
+        use solar_data_structures::Never;
+        use std::ops::ControlFlow;
+
+        struct ExprCounter(usize);
+
+        impl<'ast> Visit<'ast> for ExprCounter {
+            type BreakValue = Never;
+
+            fn visit_expr(&mut self, expr: &'ast Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+                self.0 += 1;
+                self.walk_expr(expr)
+            }
+        }
+
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a", 1, 1),
+                ("(a)", 2, 1),
+                ("a + b * c", 5, 3),
+                ("f(x, y)", 4, 3),
+                ("f{value: 1}(x)", 5, 2),
+                ("g({a: 1})", 3, 1),
+                ("a[i][j:]", 5, 3),
+                ("c ? -x : [1, 2]", 7, 2),
+                ("(a, , b) = t", 5, 3),
+                ("new uint[](n)", 3, 1),
+                ("payable(x).balance", 3, 1),
+                ("type(uint).max", 2, 0),
+            ];
+            for (src, nodes, idents) in tests {
+                let expr = t.parse_expr(src)?;
+                assert_eq!(expr.node_count(), nodes, "{src:?}");
+                assert_eq!(expr.cost(|_| 1), nodes as u64, "{src:?}");
+                let mut counter = ExprCounter(0);
+                let _ = counter.visit_expr(expr);
+                assert_eq!(counter.0, nodes, "{src:?}");
+                let cost = expr.cost(|kind| matches!(kind, ExprKind::Ident(_)) as u64);
+                assert_eq!(cost, idents, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn find_map() {
+        test_util::enter(|t| -> Result {
+            let tests = [
+                ("a + f(b(c)) + g(d)", Some("f(b(c))"), 4),
+                ("(x)[h(1)]", Some("h(1)"), 4),
+                ("c ? -x : [y, k()]", Some("k()"), 7),
+                ("a + b * c", None, 5),
+            ];
+            for (src, expected, visits) in tests {
+                let expr = t.parse_expr(src)?;
+                let mut visited = 0;
+                let call = expr.find_map(|e| {
+                    visited += 1;
+                    matches!(e.kind, ExprKind::Call(..)).then_some(e.span)
+                });
+                let call = call.map(|span| t.snippet(span));
+                assert_eq!(call.as_deref(), expected, "{src:?}");
+                assert_eq!(visited, visits, "{src:?}");
+                assert_eq!(expr.any(|e| matches!(e.kind, ExprKind::Call(..))), expected.is_some());
+
+                let mut visited = 0;
+                assert!(expr.any(|_| {
+                    visited += 1;
+                    true
+                }));
+                assert_eq!(visited, 1, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn with_indexed() {
+        use solar_interface::Symbol;
+
+        test_util::enter(|t| -> Result {
+            // `event Transfer(address indexed from, address indexed to, uint256 value);`
+            let params = [
+                (Some(Symbol::intern("from")), true),
+                (Some(Symbol::intern("to")), true),
+                (Some(Symbol::intern("value")), false),
+            ];
+            let tests: [(&str, &[(&str, Option<bool>)]); 5] = [
+                ("Transfer(a, b, v)", &[("a", Some(true)), ("b", Some(true)), ("v", Some(false))]),
+                (
+                    "Transfer({value: v, from: a, to: b})",
+                    &[("v", Some(false)), ("a", Some(true)), ("b", Some(true))],
+                ),
+                (
+                    "Transfer(a, b, v, w)",
+                    &[("a", Some(true)), ("b", Some(true)), ("v", Some(false)), ("w", None)],
+                ),
+                ("Transfer({to: b, amount: v})", &[("b", Some(true)), ("v", None)]),
+                ("Transfer()", &[]),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let ExprKind::Call(_, args) = &expr.kind else { panic!("not a call: {src:?}") };
+                let paired = args
+                    .with_indexed(&params)
+                    .map(|(arg, indexed)| (t.snippet(arg.span), indexed))
+                    .collect::<Vec<_>>();
+                let expected = expected
+                    .iter()
+                    .map(|&(arg, indexed)| (arg.to_string(), indexed))
+                    .collect::<Vec<_>>();
+                assert_eq!(paired, expected, "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn collect_spans() {
+        test_util::enter(|t| -> Result {
+            let tests: [(&str, &[&str]); 3] = [
+                (
+                    "a + -b * f(c)",
+                    &[
+                        "a + -b * f(c)",
+                        "a",
+                        "+",
+                        "-b * f(c)",
+                        "-b",
+                        "-",
+                        "b",
+                        "*",
+                        "f(c)",
+                        "f",
+                        "(c)",
+                        "c",
+                    ],
+                ),
+                ("x.y[i]++", &["x.y[i]++", "x.y[i]", "x.y", "x", "y", "i", "++"]),
+                (
+                    "g({k: 1 ether})",
+                    &[
+                        "g({k: 1 ether})",
+                        "g",
+                        "({k: 1 ether})",
+                        "{k: 1 ether}",
+                        "k",
+                        "1 ether",
+                        "1",
+                    ],
+                ),
+            ];
+            for (src, expected) in tests {
+                let expr = t.parse_expr(src)?;
+                let mut spans = Vec::new();
+                expr.collect_spans(&mut spans);
+                let snippets = spans.iter().map(|&span| t.snippet(span)).collect::<Vec<_>>();
+                assert_eq!(snippets, expected, "{src:?}");
+                let unique = spans.iter().collect::<std::collections::HashSet<_>>();
+                assert_eq!(unique.len(), spans.len(), "{src:?}");
+            }
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn deep_nesting() {
+        use solar_interface::{Ident, Span};
+
+        // Deeper than the parser allows, to check that the traversals do not recurse.
+        const DEPTH: usize = 100_000;
+        let arena = Arena::new();
+        let mut expr = arena.alloc(Expr { span: Span::DUMMY, kind: ExprKind::Ident(Ident::DUMMY) });
+        for _ in 0..DEPTH {
+            let op = UnOp { span: Span::DUMMY, kind: UnOpKind::Neg };
+            expr = arena.alloc(Expr { span: Span::DUMMY, kind: ExprKind::Unary(op, expr) });
+        }
+        assert_eq!(expr.node_count(), DEPTH + 1);
+        assert!(expr.any(|e| matches!(e.kind, ExprKind::Ident(_))));
+        assert_eq!(expr.find_map(|e| matches!(e.kind, ExprKind::Lit(..)).then_some(())), None);
+        let mut spans = Vec::new();
+        expr.collect_spans(&mut spans);
+        assert_eq!(spans.len(), 2 * DEPTH + 1);
+    }
+
+    /// Wrappers that do not change the purity of the wrapped expression `E`.
+    const PURE_WRAPPERS: &[&str] =
+        &["(E)", "(E, 1)", "[E]", "E + 1", "c ? E : 0", "c ? 0 : E", "!E", "E.length", "x[E]"];
+
+    proptest::proptest! {
+        #[test]
+        fn is_pure_under_pure_wrapper(
+            tree in solar_ast::strategy::expr_tree(),
+            wrapper in proptest::sample::select(PURE_WRAPPERS),
+        ) {
+            test_util::enter(|t| -> Result {
+                let src = tree.to_string();
+                let wrapped_src = wrapper.replace('E', &format!("({src})"));
+                let pure = t.parse_expr(&src)?.is_pure();
+                let wrapped = t.parse_expr(&wrapped_src)?.is_pure();
+                assert_eq!(pure, wrapped, "{src:?} -> {wrapped_src:?}");
+                t.has_errors()
+            })
+            .unwrap();
+        }
+    }
 }
@@ -2,7 +2,10 @@ use super::{ExpectedToken, SeqSep};
 use crate::{PResult, Parser};
 use itertools::Itertools;
 use solar_ast::{token::*, *};
-use solar_interface::{diagnostics::DiagMsg, error_code, kw, sym, Ident, Span};
+use solar_interface::{
+    diagnostics::{codes, DiagMsg},
+    kw, sym, Ident, Span,
+};
 
 impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// Parses a source unit.
@@ -31,7 +34,12 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         };
 
         let mut items = Vec::new();
-        while let Some(item) = self.parse_item()? {
+        loop {
+            if self.check_node_limit() {
+                self.skip_until(&[end]);
+                break;
+            }
+            let Some(item) = self.parse_item()? else { break };
             if self.in_contract && !item.is_allowed_in_contract() {
                 let msg = format!("{}s are not allowed in contracts", item.description());
                 let (_, note) = get_msg_note(self);
@@ -179,7 +187,11 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 let kw_span = self.prev_token.span;
                 ident = self.parse_ident_any()?;
                 let msg = format!("function named `{ident}`");
-                let mut warn = self.dcx().warn(msg).span(ident.span).code(error_code!(3445));
+                let mut warn = self
+                    .dcx()
+                    .warn(msg)
+                    .span(ident.span)
+                    .code(codes::FUNCTION_NAMED_FALLBACK_OR_RECEIVE);
                 if self.in_contract {
                     let help = format!("remove the `function` keyword if you intend this to be a contract's {ident} function");
                     warn = warn.span_help(kw_span, help);
@@ -207,7 +219,9 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         loop {
             // This is needed to skip parsing surrounding variable's visibility in function types.
             // E.g. in `function(uint) external internal e;` the `internal` is the surrounding
-            // variable's visibility, not the function's.
+            // variable's visibility, not the function's. Function types in expressions
+            // (`FUNCTION_TY_EXPR`) are never followed by a variable, so this does not apply to
+            // them.
             // HACK: Ugly way to add an extra guard to `if let` without the unstable `let-chains`.
             // Ideally this would be `if let Some(_) = _ && guard { ... }`.
             let vis_guard = (!(flags == FunctionFlags::FUNCTION_TY && header.visibility.is_some()))
@@ -731,7 +745,11 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             if flags.contains(VarFlags::NAME_WARN) {
                 debug_assert!(!flags.contains(VarFlags::NAME));
                 let msg = "named function type parameters are deprecated";
-                self.dcx().warn(msg).code(error_code!(6162)).span(name.span).emit();
+                self.dcx()
+                    .warn(msg)
+                    .code(codes::NAMED_FUNCTION_TYPE_PARAMETER)
+                    .span(name.span)
+                    .emit();
             }
         }
 
@@ -806,7 +824,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         let arguments = if self.token.kind == TokenKind::OpenDelim(Delimiter::Parenthesis) {
             self.parse_call_args()?
         } else {
-            CallArgs::empty()
+            CallArgs::empty(name.span().shrink_to_hi())
         };
         Ok(Modifier { name, arguments })
     }
@@ -1106,6 +1124,9 @@ bitflags::bitflags! {
         const RETURNS          = 1 << 13;
         /// Must be implemented, meaning it must end in a `{}` implementation block.
         const ONLY_BLOCK       = 1 << 14;
+        /// Function type in an expression, which cannot be followed by the visibility of a
+        /// variable, so all of the visibilities are parsed as its own.
+        const EXPR             = 1 << 15;
 
         // https://docs.soliditylang.org/en/latest/grammar.html#a4.SolidityParser.constructorDefinition
         const CONSTRUCTOR = Self::MODIFIERS.bits()
@@ -1149,6 +1170,8 @@ bitflags::bitflags! {
                           | Self::VISIBILITY.bits()
                           | Self::STATE_MUTABILITY.bits()
                           | Self::RETURNS.bits();
+
+        const FUNCTION_TY_EXPR = Self::FUNCTION_TY.bits() | Self::EXPR.bits();
     }
 }
 
@@ -1302,7 +1325,7 @@ fn common_flags_error<T: std::fmt::Display>(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use solar_interface::{source_map::FileName, Result, Session};
+    use solar_interface::{source_map::FileName, Result, Session, Symbol};
 
     fn assert_version_matches(tests: &[(&str, &str, bool)]) {
         let sess = Session::builder().with_test_emitter().build();
@@ -1499,4 +1522,371 @@ mod tests {
             ("0.8.1", "0.8 || 0.9", true),
         ]);
     }
+
+    #[test]
+    fn parallel_interning() {
+        let sess = Session::builder().with_test_emitter().threads(4).build();
+        sess.enter_parallel(|| {
+            let results = std::sync::Mutex::new(Vec::new());
+            sess.scope(|scope| {
+                for i in 0..16 {
+                    let (sess, results) = (&sess, &results);
+                    scope.spawn(move |_| {
+                        let src = format!(
+                            "contract C{} {{ uint256 shared; function f{i}() {{}} }}",
+                            i % 4
+                        );
+                        let arena = Arena::new();
+                        let name = FileName::custom(format!("parallel{i}"));
+                        let mut parser = Parser::from_source_code(sess, &arena, name, src).unwrap();
+                        let unit = parser.parse_file().map_err(|e| e.emit()).unwrap();
+                        let Some(ItemKind::Contract(contract)) =
+                            unit.items.iter().next().map(|item| &item.kind)
+                        else {
+                            panic!("expected a contract");
+                        };
+                        let members = contract.body.iter().filter_map(|item| item.name());
+                        let members = members.map(|ident| ident.name).collect::<Vec<_>>();
+                        results.lock().unwrap().push((i, contract.name.name, members));
+                    });
+                }
+            });
+
+            let mut results = results.into_inner().unwrap();
+            results.sort_by_key(|&(i, ..)| i);
+            assert_eq!(results.len(), 16);
+            let shared = Symbol::intern("shared");
+            for &(i, contract, ref members) in &results {
+                assert_eq!(contract.as_str(), format!("C{}", i % 4));
+                assert_eq!(contract, results[i % 4].1);
+                assert_eq!(members[0], shared);
+                assert_eq!(members[1].as_str(), format!("f{i}"));
+                for &(j, _, ref other) in &results {
+                    assert_eq!(members[1] == other[1], i == j);
+                }
+            }
+        });
+        assert!(sess.dcx.has_errors().is_ok());
+    }
+
+    #[test]
+    fn arena_reuse() {
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| -> Result {
+            let contract = |n: usize| {
+                let header = "(uint a) public pure returns (uint)";
+                let functions = (0..n)
+                    .map(|i| format!("function f{i}{header} {{ return a * {i} + 0x{i}; }}"))
+                    .collect::<String>();
+                format!("contract C {{ string s = \"{n}\"; {functions} }}")
+            };
+            let parse = |arena: &Arena, name: &str, src: String| -> Result<usize> {
+                let name = FileName::Custom(name.into());
+                let mut parser = Parser::from_source_code(&sess, arena, name, src)?;
+                let unit = parser.parse_file().map_err(|e| e.emit())?;
+                Ok(unit.items.len())
+            };
+
+            let mut arena = Arena::new();
+            assert_eq!(parse(&arena, "a.sol", contract(200))?, 1);
+            let allocated = arena.allocated_bytes();
+            let used = arena.used_bytes();
+            assert!(used > 0);
+
+            arena.reset();
+            assert_eq!(arena.used_bytes(), 0);
+            assert_eq!(parse(&arena, "b.sol", contract(20))?, 1);
+            // The second file fits in the memory kept from the first one.
+            assert!(arena.used_bytes() < used);
+            assert!(arena.allocated_bytes() <= allocated);
+
+            let pool = ArenaPool::new();
+            assert!(pool.is_empty());
+            pool.put(arena);
+            assert_eq!(pool.len(), 1);
+            let items = pool.with(|arena| {
+                assert_eq!(arena.used_bytes(), 0);
+                parse(arena, "c.sol", contract(1))
+            })?;
+            assert_eq!(items, 1);
+            assert_eq!(pool.len(), 1);
+            assert_eq!(pool.take().used_bytes(), 0);
+            assert!(pool.is_empty());
+
+            sess.dcx.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn max_nodes() {
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| -> Result {
+            let stmts = (0..100).map(|i| format!("x += {i};")).collect::<String>();
+            let src = format!(
+                "contract C {{ function f(uint x) public {{ {stmts} }} function g() public {{}} }}
+                 contract D {{}}"
+            );
+            let parse = |name: &str, max_nodes: Option<usize>| -> Result<(usize, usize, usize)> {
+                let arena = Arena::new();
+                let name = FileName::Custom(name.into());
+                let mut parser = Parser::from_source_code(&sess, &arena, name, src.clone())?;
+                parser.set_max_nodes(max_nodes);
+                let unit = parser.parse_file().map_err(|e| e.emit())?;
+                let ItemKind::Contract(c) = &unit.items[0].kind else { panic!() };
+                let ItemKind::Function(f) = &c.body[0].kind else { panic!() };
+                let stmts = f.body.as_ref().unwrap().len();
+                Ok((unit.items.len(), c.body.len(), stmts))
+            };
+
+            assert_eq!(parse("unlimited.sol", None)?, (2, 2, 100));
+            assert_eq!(sess.dcx.err_count(), 0);
+
+            // The partial AST is returned along with a single error.
+            let (items, functions, stmts) = parse("limited.sol", Some(50))?;
+            assert_eq!((items, functions), (1, 1));
+            assert!(stmts > 0 && stmts < 100, "{stmts}");
+            assert_eq!(sess.dcx.err_count(), 1);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn max_nodes_expression() {
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| -> Result {
+            let exprs = [
+                vec!["1"; 10_000].join(" + "),
+                format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000)),
+                format!("a{}", ".b".repeat(10_000)),
+                format!("{}x", "!".repeat(10_000)),
+                format!("f({})", vec!["g(1 + 2)"; 10_000].join(", ")),
+            ];
+            for (i, expr) in exprs.iter().enumerate() {
+                let src = format!(
+                    "contract C {{ function f() public {{ x = {expr}; y = 1; }} function g() {{}} }}"
+                );
+                let arena = Arena::new();
+                let name = FileName::Custom(format!("{i}.sol"));
+                let mut parser = Parser::from_source_code(&sess, &arena, name, src)?;
+                parser.set_max_nodes(Some(50));
+                let unit = parser.parse_file().map_err(|e| e.emit())?;
+                assert!(parser.node_count() < 200, "{i}: {}", parser.node_count());
+
+                // The expression ends early, and so does the rest of the file.
+                assert_eq!(unit.items.len(), 1, "{i}");
+                let ItemKind::Contract(c) = &unit.items[0].kind else { panic!() };
+                assert_eq!(c.body.len(), 1, "{i}");
+                let ItemKind::Function(f) = &c.body[0].kind else { panic!() };
+                assert_eq!(f.body.as_ref().unwrap().len(), 1, "{i}");
+                assert_eq!(sess.dcx.err_count(), i + 1, "{i}");
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn using_directives() {
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| -> Result {
+            let src = "
+                using L for uint;
+                using {f, g} for T;
+                using {eq as ==, L.neg as -} for T global;
+                contract C { using L for *; }
+            ";
+            let arena = Arena::new();
+            let name = FileName::Custom("using.sol".into());
+            let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+            let unit = parser.parse_file().map_err(|e| e.emit())?;
+            let ItemKind::Contract(c) = &unit.items[3].kind else { panic!() };
+            let usings = unit.items[..3].iter().chain(&c.body[..]).map(|item| {
+                let ItemKind::Using(using) = &item.kind else { panic!() };
+                using
+            });
+
+            let summary = usings
+                .map(|using| {
+                    let paths = match &using.list {
+                        UsingList::Single(path) => vec![path.to_string()],
+                        UsingList::Multiple(paths) => {
+                            paths.iter().map(|(path, _)| path.to_string()).collect()
+                        }
+                    };
+                    let ops = using
+                        .operators()
+                        .map(|(path, op)| {
+                            format!(
+                                "{path} as {}",
+                                op.to_op().either(UnOpKind::to_str, BinOpKind::to_str)
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    (paths, using.ty.is_some(), using.global, ops)
+                })
+                .collect::<Vec<_>>();
+            let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+            assert_eq!(
+                summary,
+                [
+                    (strings(&["L"]), true, false, vec![]),
+                    (strings(&["f", "g"]), true, false, vec![]),
+                    (strings(&["eq", "L.neg"]), true, true, strings(&["eq as ==", "L.neg as -"])),
+                    (strings(&["L"]), false, false, vec![]),
+                ]
+            );
+            sess.dcx.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn visit_scopes() {
+        use solar_ast::visit::{ScopeKind, Visit};
+        use std::ops::ControlFlow;
+
+        /// Records the scopes and the variables, and breaks at the variable named `stop`, if any.
+        struct Recorder {
+            events: Vec<String>,
+            stop: Option<&'static str>,
+        }
+
+        impl<'ast> Visit<'ast> for Recorder {
+            type BreakValue = ();
+
+            fn enter_scope(&mut self, kind: ScopeKind) {
+                self.events.push(format!("enter {kind:?}"));
+            }
+
+            fn exit_scope(&mut self, kind: ScopeKind) {
+                self.events.push(format!("exit {kind:?}"));
+            }
+
+            fn visit_variable_definition(
+                &mut self,
+                var: &'ast VariableDefinition<'ast>,
+            ) -> ControlFlow<Self::BreakValue> {
+                let name = var.name.unwrap();
+                self.events.push(format!("var {name}"));
+                if self.stop == Some(name.as_str()) {
+                    return ControlFlow::Break(());
+                }
+                self.walk_variable_definition(var)
+            }
+        }
+
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| -> Result {
+            let src = "
+                contract C {
+                    uint s;
+                    function f(uint a) public {
+                        uint b;
+                        for (uint i; i < a; i++) { uint c; }
+                        unchecked { uint d; }
+                        try this.g() returns (uint e) {} catch Error(string memory r) {
+                            uint x;
+                            uint y;
+                        }
+                    }
+                }
+            ";
+            let arena = Arena::new();
+            let name = FileName::Custom("scopes.sol".into());
+            let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+            let unit = parser.parse_file().map_err(|e| e.emit())?;
+
+            let visit = |stop| {
+                let mut recorder = Recorder { events: Vec::new(), stop };
+                let _ = recorder.visit_source_unit(&unit);
+                recorder.events
+            };
+            assert_eq!(
+                visit(None),
+                [
+                    "var s",
+                    "enter Function",
+                    "var a",
+                    "enter Block",
+                    "var b",
+                    "enter For",
+                    "var i",
+                    "enter Block",
+                    "var c",
+                    "exit Block",
+                    "exit For",
+                    "enter Block",
+                    "var d",
+                    "exit Block",
+                    "enter TryCatchClause",
+                    "var e",
+                    "enter Block",
+                    "exit Block",
+                    "exit TryCatchClause",
+                    "enter TryCatchClause",
+                    "var r",
+                    "enter Block",
+                    "var x",
+                    "var y",
+                    "exit Block",
+                    "exit TryCatchClause",
+                    "exit Block",
+                    "exit Function",
+                ]
+            );
+
+            // Breaking inside nested scopes still exits all of them.
+            assert_eq!(
+                visit(Some("x")),
+                [
+                    "var s",
+                    "enter Function",
+                    "var a",
+                    "enter Block",
+                    "var b",
+                    "enter For",
+                    "var i",
+                    "enter Block",
+                    "var c",
+                    "exit Block",
+                    "exit For",
+                    "enter Block",
+                    "var d",
+                    "exit Block",
+                    "enter TryCatchClause",
+                    "var e",
+                    "enter Block",
+                    "exit Block",
+                    "exit TryCatchClause",
+                    "enter TryCatchClause",
+                    "var r",
+                    "enter Block",
+                    "var x",
+                    "exit Block",
+                    "exit TryCatchClause",
+                    "exit Block",
+                    "exit Function",
+                ]
+            );
+            assert_eq!(
+                visit(Some("i")),
+                [
+                    "var s",
+                    "enter Function",
+                    "var a",
+                    "enter Block",
+                    "var b",
+                    "enter For",
+                    "var i",
+                    "exit For",
+                    "exit Block",
+                    "exit Function",
+                ]
+            );
+            sess.dcx.has_errors()
+        })
+        .unwrap();
+    }
 }
@@ -1,10 +1,13 @@
-use crate::{unescape, PResult, Parser};
+use crate::{unescape, LanguageVersion, PResult, Parser};
 use alloy_primitives::Address;
 use num_bigint::BigInt;
 use num_rational::BigRational;
 use num_traits::Num;
 use solar_ast::{token::*, *};
-use solar_interface::{diagnostics::ErrorGuaranteed, kw, Symbol};
+use solar_interface::{
+    diagnostics::{codes, Applicability, ErrorGuaranteed},
+    kw, Symbol,
+};
 use std::{borrow::Cow, fmt};
 
 impl<'sess, 'ast> Parser<'sess, 'ast> {
@@ -21,6 +24,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// display reasons.
     ///
     /// Returns None if no subdenomination was parsed or if the literal is not a number or rational.
+    /// Any subdenomination after the first one is parsed and reported as an error.
     pub fn parse_lit_with_subdenomination(
         &mut self,
     ) -> PResult<'sess, (&'ast mut Lit, Option<SubDenomination>)> {
@@ -40,9 +44,25 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 _ => {
                     *opt = None;
                     let msg = "sub-denominations are only allowed on number and rational literals";
-                    self.dcx().err(msg).span(lit.span.to(self.prev_token.span)).emit();
+                    self.dcx()
+                        .err(msg)
+                        .code(codes::SUBDENOMINATION_ON_NON_NUMBER)
+                        .span(lit.span.to(self.prev_token.span))
+                        .emit();
                 }
             }
+
+            let first = self.prev_token.span;
+            while self.subdenomination().is_some() {
+                self.bump();
+                let msg = "literals can only have one sub-denomination";
+                self.dcx()
+                    .err(msg)
+                    .code(codes::MULTIPLE_SUBDENOMINATIONS)
+                    .span(self.prev_token.span)
+                    .span_note(first, "first sub-denomination here")
+                    .emit();
+            }
         }
         Ok((lit, sub))
     }
@@ -53,6 +73,11 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         if sub.is_some() {
             self.bump();
         }
+        if sub == Some(SubDenomination::Time(TimeSubDenomination::Years)) {
+            let help = "use `365 days` instead";
+            let span = self.prev_token.span;
+            self.check_removed_syntax("`years` units", LanguageVersion::V0_5_0, span, help);
+        }
         sub
     }
 
@@ -110,7 +135,12 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     fn parse_lit_int(&mut self, symbol: Symbol) -> PResult<'sess, LitKind> {
         use LitError::*;
         match parse_integer(symbol) {
-            Ok(l) => Ok(l),
+            Ok(l) => {
+                if !self.in_yul {
+                    self.check_address_lit(symbol);
+                }
+                Ok(l)
+            }
             // User error.
             Err(e @ IntegerLeadingZeros) => Err(self.dcx().err(e.to_string())),
             // User error, but already emitted.
@@ -125,6 +155,24 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         }
     }
 
+    /// Emits an error if the integer literal that was just parsed looks like an address, but is not
+    /// a valid one.
+    fn check_address_lit(&mut self, symbol: Symbol) {
+        let Err(e) = check_address_lit(&strip_underscores(&symbol)) else { return };
+        let span = self.prev_token.span;
+        let mut diag =
+            self.dcx().err(e.to_string()).code(codes::INVALID_ADDRESS_LITERAL).span(span);
+        if let AddressLitError::InvalidChecksum(address) = e {
+            diag = diag.span_suggestion(
+                span,
+                "use the checksummed address",
+                address.to_checksum(None),
+                Applicability::MachineApplicable,
+            );
+        }
+        diag.help("if this is not used as an address, prepend `00`").emit();
+    }
+
     /// Parses a rational literal.
     fn parse_lit_rational(&mut self, symbol: Symbol) -> PResult<'sess, LitKind> {
         use LitError::*;
@@ -247,6 +295,54 @@ fn parse_integer(symbol: Symbol) -> Result<LitKind, LitError> {
     BigInt::from_str_radix(s, base).map(LitKind::Number).map_err(LitError::ParseInteger)
 }
 
+/// An invalid address literal.
+#[derive(Debug, PartialEq, Eq)]
+enum AddressLitError {
+    /// The literal has 39 or 41 hexadecimal digits.
+    InvalidLength(usize),
+    /// The literal has 40 hexadecimal digits, but not a valid EIP-55 checksum. Contains the
+    /// address, whose checksummed form should be used instead.
+    InvalidChecksum(Address),
+}
+
+impl fmt::Display for AddressLitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength(digits) => {
+                write!(f, "this looks like an address but is not exactly 40 hex digits; ")?;
+                write!(f, "it is {digits} hex digits")
+            }
+            Self::InvalidChecksum(_) => {
+                write!(f, "this looks like an address but has an invalid checksum")
+            }
+        }
+    }
+}
+
+/// Checks an integer literal, without underscores, that may be an address.
+///
+/// Like in solc, hexadecimal literals with 39 to 41 digits look like addresses. They must have
+/// exactly 40 digits and a valid [EIP-55](https://eips.ethereum.org/EIPS/eip-55) checksum, even if
+/// all of their letters have the same case: `0xde709f2102306220921060314715629080e2fb77` is valid,
+/// since it is its own checksummed form, but most lowercase addresses are not.
+fn check_address_lit(s: &str) -> Result<(), AddressLitError> {
+    let Some(digits) = s.strip_prefix("0x") else { return Ok(()) };
+    if !(39..=41).contains(&digits.len()) {
+        return Ok(());
+    }
+    if digits.len() != 40 {
+        return Err(AddressLitError::InvalidLength(digits.len()));
+    }
+    match Address::parse_checksummed(s, None) {
+        Ok(_) => Ok(()),
+        Err(alloy_primitives::AddressError::InvalidChecksum) => {
+            Err(AddressLitError::InvalidChecksum(digits.parse().unwrap()))
+        }
+        // Not a hexadecimal number, which is reported by the lexer.
+        Err(alloy_primitives::AddressError::Hex(_)) => Ok(()),
+    }
+}
+
 fn parse_rational(symbol: Symbol) -> Result<LitKind, LitError> {
     let s = &strip_underscores(&symbol)[..];
     debug_assert!(!s.is_empty());
@@ -478,6 +574,55 @@ mod tests {
         });
     }
 
+    #[test]
+    fn address_checksum() {
+        use solar_interface::source_map::FileName;
+        use AddressLitError::*;
+
+        let checksummed = address!("52908400098527886E0F7030069857D2E4169EE7");
+        for (src, expected) in [
+            // Valid checksums, including an address without letters and a lowercase address that
+            // is its own checksummed form.
+            ("0x52908400098527886E0F7030069857D2E4169EE7", Ok(())),
+            ("0x0000000000000000000000000000000000000001", Ok(())),
+            ("0xde709f2102306220921060314715629080e2fb77", Ok(())),
+            // Invalid checksums.
+            ("0x52908400098527886E0F7030069857D2E4169Ee7", Err(InvalidChecksum(checksummed))),
+            ("0x52908400098527886e0f7030069857d2e4169ee7", Err(InvalidChecksum(checksummed))),
+            (
+                "0xDE709F2102306220921060314715629080E2FB77",
+                Err(InvalidChecksum(address!("de709f2102306220921060314715629080e2fb77"))),
+            ),
+            // Not quite addresses.
+            ("0x000000000000000000000000000000000000001", Err(InvalidLength(39))),
+            ("0x00000000000000000000000000000000000000001", Err(InvalidLength(41))),
+            // Not addresses.
+            ("0x00000000000000000000000000000000000001", Ok(())),
+            ("0x000000000000000000000000000000000000000001", Ok(())),
+            ("1000000000000000000000000000000000000000", Ok(())),
+        ] {
+            assert_eq!(check_address_lit(src), expected, "{src:?}");
+        }
+        assert_eq!(checksummed.to_checksum(None), "0x52908400098527886E0F7030069857D2E4169EE7");
+
+        // Only Solidity literals are checked, after removing underscores.
+        let has_errors = |src: &str, yul: bool| {
+            let sess = Session::builder().with_test_emitter().build();
+            sess.enter(|| {
+                let arena = Arena::new();
+                let name = FileName::custom("address_checksum");
+                let mut parser = Parser::from_source_code(&sess, &arena, name, src.into()).unwrap();
+                let lit = if yul { parser.in_yul(|p| p.parse_lit()) } else { parser.parse_lit() };
+                lit.map_err(|e| e.emit()).unwrap();
+                sess.dcx.has_errors().is_err()
+            })
+        };
+        assert!(!has_errors("0x52908400098527886E0F7030069857D2E4169EE7", false));
+        assert!(has_errors("0x52908400098527886E0F7030069857D2E4169Ee7", false));
+        assert!(has_errors("0x5290_8400_0985_2788_6E0F_7030_0698_57D2_E416_9Ee7", false));
+        assert!(!has_errors("0x52908400098527886E0F7030069857D2E4169Ee7", true));
+    }
+
     #[test]
     fn rational() {
         use LitError::*;
@@ -596,4 +741,137 @@ mod tests {
             check_rat("1.20", Ok("12/10"));
         });
     }
+
+    #[test]
+    fn format_int() {
+        const PRESERVE: DigitSeparators = DigitSeparators::Preserve;
+        const REMOVE: DigitSeparators = DigitSeparators::Remove;
+        const GROUP: DigitSeparators = DigitSeparators::Group { decimal: 3, hexadecimal: 2 };
+
+        #[track_caller]
+        fn check(
+            src: &str,
+            sub: Option<SubDenomination>,
+            base: IntBase,
+            separators: DigitSeparators,
+            expected: Option<&str>,
+        ) {
+            let symbol = lex_literal(src);
+            let kind = if src.starts_with("0x") || !src.contains(['.', 'e']) {
+                parse_integer(symbol)
+            } else {
+                parse_rational(symbol)
+            };
+            let lit = Lit { span: Span::DUMMY, symbol, kind: kind.unwrap() };
+            let formatted = lit.format_int(sub, IntFormat { base, separators });
+            assert_eq!(formatted.as_deref(), expected, "{src:?} {base:?} {separators:?}");
+        }
+
+        solar_interface::enter(|| {
+            use IntBase::*;
+
+            // Preserve the base.
+            check("1_000", None, Preserve, PRESERVE, Some("1_000"));
+            check("1_000", None, Preserve, REMOVE, Some("1000"));
+            check("10_00000", None, Preserve, GROUP, Some("1_000_000"));
+            check("0xDEAD_beef", None, Preserve, PRESERVE, Some("0xDEAD_beef"));
+            check("0xDEAD_beef", None, Preserve, REMOVE, Some("0xDEADbeef"));
+            check("0xabcde", None, Preserve, GROUP, Some("0xa_bc_de"));
+            check("0", None, Preserve, GROUP, Some("0"));
+
+            // Force decimal.
+            check("0xff", None, Decimal, PRESERVE, Some("255"));
+            check("0x1_0000", None, Decimal, GROUP, Some("65_536"));
+            check("123", None, Decimal, PRESERVE, Some("123"));
+            check(
+                "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                None,
+                Decimal,
+                GROUP,
+                Some(
+                    "115_792_089_237_316_195_423_570_985_008_687_907_853_269_984_665_640_564_039_457_\
+                     584_007_913_129_639_935",
+                ),
+            );
+
+            // Force hexadecimal.
+            check("255", None, Hexadecimal, PRESERVE, Some("0xff"));
+            check("1_000_000", None, Hexadecimal, GROUP, Some("0xf_42_40"));
+            check("0xAB", None, Hexadecimal, PRESERVE, Some("0xAB"));
+            check(
+                "2",
+                Some(SubDenomination::Time(TimeSubDenomination::Days)),
+                Hexadecimal,
+                PRESERVE,
+                Some("2"),
+            );
+            // `2 ** 156` has 40 hexadecimal digits, which would be an address literal.
+            check(
+                "91343852333181432387730302044767688728495783936",
+                None,
+                Hexadecimal,
+                REMOVE,
+                Some("0x01000000000000000000000000000000000000000"),
+            );
+
+            // Other literals are left as is.
+            check("1e18", None, Hexadecimal, GROUP, None);
+            check("1.5", None, Decimal, GROUP, None);
+            check("0x52908400098527886E0F7030069857D2E4169EE7", None, Decimal, GROUP, None);
+            for kind in [StrKind::Str, StrKind::Unicode, StrKind::Hex] {
+                let lit = Lit {
+                    span: Span::DUMMY,
+                    symbol: Symbol::intern("1234"),
+                    kind: LitKind::Str(kind, Default::default()),
+                };
+                let format = IntFormat { base: Decimal, separators: GROUP };
+                assert_eq!(lit.format_int(None, format), None);
+            }
+        });
+    }
+
+    #[test]
+    fn to_const_value() {
+        use solar_interface::{source_map::FileName, Result};
+        use std::collections::HashMap;
+
+        solar_interface::enter(|| -> Result {
+            let sess = Session::builder().with_test_emitter().build();
+            let arena = Arena::new();
+            let value = |src: &str| -> Result<Option<ConstValue>> {
+                let name = FileName::Custom(src.into());
+                let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+                let (lit, sub) = parser.parse_lit_with_subdenomination().map_err(|e| e.emit())?;
+                Ok(lit.to_const_value(sub))
+            };
+
+            let mut map = HashMap::new();
+            for (src, key) in [
+                ("1", "one"),
+                ("0x01", "one"),
+                ("1 wei", "one"),
+                ("10e-1", "one"),
+                ("1 gwei", "gwei"),
+                ("1000000000", "gwei"),
+                ("0.5", "half"),
+                ("5e-1", "half"),
+                ("true", "true"),
+                ("\"a\"", "a"),
+                ("'a'", "a"),
+                ("\"\\x61\"", "a"),
+                ("unicode\"a\"", "a"),
+                ("hex\"61\"", "0x61"),
+                ("hex'61'", "0x61"),
+            ] {
+                let value = value(src)?.unwrap_or_else(|| panic!("{src:?} is not constant"));
+                let prev = *map.entry(value).or_insert(key);
+                assert_eq!(prev, key, "{src:?}");
+            }
+            assert_eq!(map.len(), 6);
+
+            assert_eq!(value("0x52908400098527886E0F7030069857D2E4169EE7")?, None);
+            sess.dcx.has_errors()
+        })
+        .unwrap();
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Lexer, PErr, PResult};
+use crate::{Dialect, LanguageVersion, Lexer, PErr, PResult};
 use smallvec::SmallVec;
 use solar_ast::{
     self as ast,
@@ -7,11 +7,11 @@ use solar_ast::{
 };
 use solar_data_structures::{fmt::or_list, BumpExt};
 use solar_interface::{
-    diagnostics::DiagCtxt,
+    diagnostics::{codes, DiagCtxt, ErrorGuaranteed},
     source_map::{FileName, SourceFile},
     Ident, Result, Session, Span, Symbol,
 };
-use std::{fmt, path::Path};
+use std::{cell::Cell, fmt, path::Path};
 
 mod expr;
 mod item;
@@ -47,6 +47,18 @@ pub struct Parser<'sess, 'ast> {
     in_yul: bool,
     /// Whether the parser is currently parsing a contract block.
     in_contract: bool,
+    /// The language dialect.
+    dialect: Dialect,
+
+    /// The number of AST nodes allocated so far. See [`set_max_nodes`](Self::set_max_nodes).
+    node_count: Cell<usize>,
+    /// The maximum number of AST nodes.
+    max_nodes: Option<usize>,
+    /// The error reported when the node limit was exceeded, if it was.
+    node_limit_exceeded: Option<ErrorGuaranteed>,
+    /// The nesting depth of the expression that is being parsed. Each level allocates at least
+    /// one node once it is parsed, so it already counts towards the node limit.
+    expr_depth: usize,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -131,6 +143,11 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             tokens: tokens.into_iter(),
             in_yul: false,
             in_contract: false,
+            dialect: Dialect::new(),
+            node_count: Cell::new(0),
+            max_nodes: None,
+            node_limit_exceeded: None,
+            expr_depth: 0,
         };
         parser.bump();
         parser
@@ -151,7 +168,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// The file will not be read if it has already been added into the source map.
     pub fn from_file(sess: &'sess Session, arena: &'ast ast::Arena, path: &Path) -> Result<Self> {
         Self::from_lazy_source_code(sess, arena, FileName::Real(path.to_path_buf()), || {
-            std::fs::read_to_string(path).map_err(|e| {
+            solar_interface::source_map::read_to_string(path).map_err(|e| {
                 std::io::Error::new(
                     e.kind(),
                     solar_interface::source_map::ResolveError::ReadFile(path.to_path_buf(), e),
@@ -194,6 +211,111 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         Self::new(lexer.sess, arena, lexer.into_tokens())
     }
 
+    /// Returns the language dialect.
+    #[inline]
+    pub fn dialect(&self) -> &Dialect {
+        &self.dialect
+    }
+
+    /// Sets the language dialect.
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    /// Returns the maximum number of AST nodes that will be parsed, if any.
+    #[inline]
+    pub fn max_nodes(&self) -> Option<usize> {
+        self.max_nodes
+    }
+
+    /// Sets the maximum number of AST nodes that will be parsed. Defaults to no limit.
+    ///
+    /// This bounds the memory used by the AST of adversarial or enormous inputs. Every value
+    /// allocated in the AST arena counts as a node, including each element of a list.
+    ///
+    /// The limit is checked before parsing each element of a sequence, such as an item, a
+    /// statement, or a call argument, and before each operand and postfix operation of an
+    /// expression, so a single large expression cannot bypass it. Each nesting level of the
+    /// expression that is being parsed counts as a node, which also bounds the depth of deeply
+    /// nested expressions, like `((((x))))` or `!!!!x`. The limit can be exceeded by at most a few
+    /// nodes. Once it is exceeded, a "node limit exceeded" error is emitted, and the rest of
+    /// the input is skipped: the expression that is being parsed ends with an
+    /// [`ExprKind::Err`](ast::ExprKind::Err) placeholder, every sequence that is being parsed
+    /// ends early, and the partial AST parsed until then is returned.
+    pub fn set_max_nodes(&mut self, max_nodes: Option<usize>) {
+        self.max_nodes = max_nodes;
+    }
+
+    /// Returns the number of AST nodes allocated so far. See
+    /// [`set_max_nodes`](Self::set_max_nodes).
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.node_count.get()
+    }
+
+    #[inline]
+    fn count_nodes(&self, n: usize) {
+        self.node_count.set(self.node_count.get() + n);
+    }
+
+    /// Returns `true` if the [node limit](Self::set_max_nodes) has been exceeded, emitting an
+    /// error the first time.
+    fn check_node_limit(&mut self) -> bool {
+        self.node_limit_error().is_some()
+    }
+
+    /// Returns the error reported for exceeding the [node limit](Self::set_max_nodes), if it has
+    /// been exceeded, emitting it the first time.
+    fn node_limit_error(&mut self) -> Option<ErrorGuaranteed> {
+        if let Some(guar) = self.node_limit_exceeded {
+            return Some(guar);
+        }
+        let max_nodes = self.max_nodes?;
+        if self.node_count.get() + self.expr_depth <= max_nodes {
+            return None;
+        }
+        let guar = self
+            .dcx()
+            .err("node limit exceeded")
+            .span(self.token.span)
+            .note(format!("the limit is {max_nodes} AST nodes; the rest of the file is ignored"))
+            .emit();
+        self.node_limit_exceeded = Some(guar);
+        Some(guar)
+    }
+
+    /// Skips tokens until one of `kets` that is not nested in delimiters, an unbalanced closing
+    /// delimiter, or EOF is reached.
+    fn skip_until(&mut self, kets: &[&TokenKind]) {
+        let mut depth = 0usize;
+        loop {
+            match &self.token.kind {
+                kind if depth == 0 && kets.contains(&kind) => break,
+                TokenKind::Eof => break,
+                TokenKind::OpenDelim(_) => depth += 1,
+                TokenKind::CloseDelim(_) if depth == 0 => break,
+                TokenKind::CloseDelim(_) => depth -= 1,
+                _ => {}
+            }
+            self.bump();
+        }
+    }
+
+    /// Emits an error if `what`, which was removed in the `removed_in` language version, is not
+    /// accepted by the language version of the current [`Dialect`].
+    fn check_removed_syntax(
+        &self,
+        what: &str,
+        removed_in: LanguageVersion,
+        span: Span,
+        help: &'static str,
+    ) {
+        if !self.dialect.allows_removed_in(removed_in) {
+            let msg = format!("{what} were removed in version {removed_in}");
+            self.dcx().err(msg).code(codes::REMOVED_SYNTAX).span(span).help(help).emit();
+        }
+    }
+
     /// Returns the diagnostic context.
     #[inline]
     pub fn dcx(&self) -> &'sess DiagCtxt {
@@ -202,6 +324,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
 
     /// Allocates an object on the AST arena.
     pub fn alloc<T>(&self, value: T) -> Box<'ast, T> {
+        self.count_nodes(1);
         self.arena.alloc(value)
     }
 
@@ -211,16 +334,19 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     ///
     /// Panics if the list is empty.
     pub fn alloc_path(&self, values: &[Ident]) -> AstPath<'ast> {
+        self.count_nodes(1);
         PathSlice::from_mut_slice(self.arena.alloc_slice_copy(values))
     }
 
     /// Allocates a list of objects on the AST arena.
     pub fn alloc_vec<T>(&self, values: Vec<T>) -> Box<'ast, [T]> {
+        self.count_nodes(values.len());
         self.arena.alloc_vec(values)
     }
 
     /// Allocates a list of objects on the AST arena.
     pub fn alloc_smallvec<A: smallvec::Array>(&self, values: SmallVec<A>) -> Box<'ast, [A::Item]> {
+        self.count_nodes(values.len());
         self.arena.alloc_smallvec(values)
     }
 
@@ -662,6 +788,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         let mut first = true;
         let mut recovered = false;
         let mut trailing = false;
+        let mut truncated = false;
         let mut v = SmallVec::<[T; 8]>::new();
 
         if !allow_empty {
@@ -674,6 +801,12 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 break;
             }
 
+            if self.check_node_limit() {
+                self.skip_until(kets);
+                truncated = true;
+                break;
+            }
+
             if let Some(sep_kind) = &sep.sep {
                 if first {
                     // no separator for the first element
@@ -700,7 +833,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             v.push(f(self)?);
         }
 
-        if let Some(sep_kind) = &sep.sep {
+        if let Some(sep_kind) = sep.sep.as_ref().filter(|_| !truncated) {
             let open_close_delim = first && allow_empty;
             if !open_close_delim && sep.trailing_sep_required && !trailing {
                 if let Err(e) = self.expect(sep_kind) {
@@ -829,6 +962,42 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         }
     }
 
+    /// Parses the entire input with `f`, expecting the end of the input afterwards.
+    ///
+    /// This allows parsing a standalone sub-grammar, like a single expression, without a
+    /// surrounding source unit. See [`parse_standalone_expr`](Self::parse_standalone_expr).
+    pub fn parse_standalone<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> PResult<'sess, T>,
+    ) -> PResult<'sess, T> {
+        let t = f(self)?;
+        self.expect(&TokenKind::Eof)?;
+        Ok(t)
+    }
+
+    /// Parses the entire input as a single expression.
+    ///
+    /// Spans are relative to the source map, so only the first file of a session yields spans
+    /// that index directly into its source code. [`parse_expr`](crate::parse_expr) parses a
+    /// string in a session of its own instead.
+    pub fn parse_standalone_expr(&mut self) -> PResult<'sess, Box<'ast, ast::Expr<'ast>>> {
+        self.parse_standalone(Self::parse_expr)
+    }
+
+    /// Parses the entire input as a single type.
+    ///
+    /// See [`parse_standalone_expr`](Self::parse_standalone_expr) for more details.
+    pub fn parse_standalone_type(&mut self) -> PResult<'sess, ast::Type<'ast>> {
+        self.parse_standalone(Self::parse_type)
+    }
+
+    /// Parses the entire input as a single statement.
+    ///
+    /// See [`parse_standalone_expr`](Self::parse_standalone_expr) for more details.
+    pub fn parse_standalone_stmt(&mut self) -> PResult<'sess, ast::Stmt<'ast>> {
+        self.parse_standalone(Self::parse_stmt)
+    }
+
     /// Parses contiguous doc comments. Can be empty.
     #[inline]
     pub fn parse_doc_comments(&mut self) -> DocComments<'ast> {
@@ -1,5 +1,5 @@
 use super::item::VarFlags;
-use crate::{parser::SeqSep, PResult, Parser};
+use crate::{parser::SeqSep, LanguageVersion, PResult, Parser};
 use smallvec::SmallVec;
 use solar_ast::{token::*, *};
 use solar_data_structures::BumpExt;
@@ -46,8 +46,10 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
             let expr = if self.check(&TokenKind::Semi) { None } else { Some(self.parse_expr()?) };
             Ok(StmtKind::Return(expr))
         } else if self.eat_keyword(kw::Throw) {
-            let msg = "`throw` statements have been removed; use `revert`, `require`, or `assert` instead";
-            Err(self.dcx().err(msg).span(self.prev_token.span))
+            let help = "use `revert`, `require`, or `assert` instead";
+            let span = self.prev_token.span;
+            self.check_removed_syntax("`throw` statements", LanguageVersion::V0_5_0, span, help);
+            Ok(StmtKind::Throw)
         } else if self.eat_keyword(kw::Try) {
             semi = false;
             self.parse_stmt_try().map(|stmt| StmtKind::Try(self.alloc(stmt)))
@@ -145,11 +147,13 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         self.expect_keyword(kw::Catch)?;
         loop {
             let name = self.parse_ident_opt()?;
-            let args = if self.check(&TokenKind::OpenDelim(Delimiter::Parenthesis)) {
-                self.parse_parameter_list(false, VarFlags::FUNCTION)?
-            } else {
-                Default::default()
-            };
+            // A name must be followed by its parameters: `catch Error(string memory reason)`.
+            let args =
+                if name.is_some() || self.check(&TokenKind::OpenDelim(Delimiter::Parenthesis)) {
+                    self.parse_parameter_list(false, VarFlags::FUNCTION)?
+                } else {
+                    Default::default()
+                };
             let block = self.parse_block()?;
             clauses.push(TryCatchClause { name, args, block });
             if !self.eat_keyword(kw::Catch) {
@@ -188,7 +192,9 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// Also used in the for loop initializer. Does not parse the trailing semicolon.
     fn parse_simple_stmt_kind(&mut self) -> PResult<'sess, StmtKind<'ast>> {
         let lo = self.token.span;
-        if self.eat(&TokenKind::OpenDelim(Delimiter::Parenthesis)) {
+        if self.eat_keyword(kw::Var) {
+            self.parse_var_decl()
+        } else if self.eat(&TokenKind::OpenDelim(Delimiter::Parenthesis)) {
             let mut empty_components = 0usize;
             while self.eat(&TokenKind::Comma) {
                 empty_components += 1;
@@ -244,6 +250,37 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
         }
     }
 
+    /// Parses a `var` declaration, after the `var` keyword: `var x = 1`, `var (a, , b) = f()`.
+    fn parse_var_decl(&mut self) -> PResult<'sess, StmtKind<'ast>> {
+        let var_span = self.prev_token.span;
+        let help = "use an explicit type instead";
+        self.check_removed_syntax("`var` declarations", LanguageVersion::V0_5_0, var_span, help);
+
+        let ty = Type { span: var_span, kind: TypeKind::Var };
+        if !self.check(&TokenKind::OpenDelim(Delimiter::Parenthesis)) {
+            let var = self.parse_variable_definition_with(VarFlags::VAR, Some(ty))?;
+            return Ok(StmtKind::DeclSingle(self.alloc(var)));
+        }
+
+        let variables = self.parse_optional_items_seq(Delimiter::Parenthesis, |this| {
+            let name = this.parse_ident()?;
+            Ok(VariableDefinition {
+                span: name.span,
+                ty: Type { span: var_span, kind: TypeKind::Var },
+                visibility: None,
+                mutability: None,
+                data_location: None,
+                override_: None,
+                indexed: false,
+                name: Some(name),
+                initializer: None,
+            })
+        })?;
+        self.expect(&TokenKind::Eq)?;
+        let expr = self.parse_expr()?;
+        Ok(StmtKind::DeclMulti(variables, expr))
+    }
+
     /// Parses a `delim`-delimited, comma-separated list of maybe-optional items.
     /// E.g. `(a, b) => [Some, Some]`, `(, a,, b,) => [None, Some, None, Some, None]`.
     pub(super) fn parse_optional_items_seq<T>(
@@ -467,6 +504,7 @@ fn smallvec_repeat_none<T>(n: usize) -> SmallVec<[Option<T>; 8]> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Dialect;
     use solar_interface::{source_map::FileName, Result, Session};
 
     #[test]
@@ -511,4 +549,264 @@ mod tests {
             ("(a,,c)", &[Some("a"), None, Some("c")]),
         ]);
     }
+
+    #[test]
+    fn legacy_syntax() {
+        fn is_var(var: &VariableDefinition<'_>, name: &str) -> bool {
+            matches!(var.ty.kind, TypeKind::Var) && var.name.is_some_and(|n| n.as_str() == name)
+        }
+
+        let tests: &[(&str, bool, fn(&StmtKind<'_>) -> bool)] = &[
+            (
+                "var x = 1;",
+                true,
+                |kind| matches!(kind, StmtKind::DeclSingle(var) if is_var(var, "x") && var.initializer.is_some()),
+            ),
+            ("var (a, , b) = f();", true, |kind| match kind {
+                StmtKind::DeclMulti(vars, _) => match &vars[..] {
+                    [Some(a), None, Some(b)] => is_var(a, "a") && is_var(b, "b"),
+                    _ => false,
+                },
+                _ => false,
+            }),
+            ("throw;", true, |kind| matches!(kind, StmtKind::Throw)),
+            ("x = 1 years;", true, |kind| {
+                let StmtKind::Expr(expr) = kind else { return false };
+                let ExprKind::Assign(_, None, rhs) = &expr.kind else { return false };
+                matches!(
+                    rhs.kind,
+                    ExprKind::Lit(_, Some(SubDenomination::Time(TimeSubDenomination::Years)))
+                )
+            }),
+            // Removed builtins are plain identifiers, and are only rejected during resolution.
+            ("sha3(x);", false, |kind| matches!(kind, StmtKind::Expr(_))),
+            ("suicide(a);", false, |kind| matches!(kind, StmtKind::Expr(_))),
+        ];
+
+        solar_interface::enter(|| -> Result {
+            let sess = Session::builder().with_test_emitter().build();
+            let legacy = Dialect::new().with_language_version(LanguageVersion::new(0, 4, 26));
+            for (i, &(src, removed, check)) in tests.iter().enumerate() {
+                for dialect in [legacy.clone(), Dialect::new()] {
+                    let arena = Arena::new();
+                    let name = FileName::Custom(format!("{i}-{:?}", dialect.language_version()));
+                    let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+                    parser.set_dialect(dialect.clone());
+
+                    let errors = sess.dcx.err_count();
+                    let stmt = parser.parse_stmt().map_err(|e| e.emit())?;
+                    assert!(check(&stmt.kind), "{src:?}: {:?}", stmt.kind);
+                    let expected = usize::from(removed && dialect.language_version().is_none());
+                    assert_eq!(sess.dcx.err_count() - errors, expected, "{src:?}: {dialect:?}");
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn revert() {
+        // The kind of the revert, the error path, and the arguments.
+        let tests: &[(&str, Option<(&str, &str, &[&str])>)] = &[
+            ("revert();", Some(("bare", "", &[]))),
+            ("revert(\"reason\");", Some(("reason", "", &["\"reason\""]))),
+            ("revert(string.concat(a, b));", Some(("reason", "", &["string.concat(a,b)"]))),
+            ("revert Unauthorized();", Some(("custom", "Unauthorized", &[]))),
+            ("revert Errors.Invalid(x, 1);", Some(("custom", "Errors.Invalid", &["x", "1"]))),
+            ("revert Invalid({value: x});", Some(("custom", "Invalid", &["x"]))),
+            ("revert(a, b);", None),
+            ("revert.selector;", None),
+            ("f(\"reason\");", None),
+            ("return;", None),
+        ];
+
+        solar_interface::enter(|| -> Result {
+            let sess = Session::builder().with_test_emitter().build();
+            for (i, &(src, expected)) in tests.iter().enumerate() {
+                let arena = Arena::new();
+                let name = FileName::Custom(i.to_string());
+                let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+                let stmt = parser.parse_stmt().map_err(|e| e.emit())?;
+                let revert = stmt.as_revert().map(|revert| {
+                    let print = |expr: &Expr<'_>| expr.to_minified().unwrap();
+                    match revert {
+                        RevertKind::Bare => ("bare", String::new(), vec![]),
+                        RevertKind::Reason(reason) => {
+                            ("reason", String::new(), vec![print(reason)])
+                        }
+                        RevertKind::CustomError(path, args) => {
+                            ("custom", path.to_string(), args.exprs().map(print).collect())
+                        }
+                    }
+                });
+                let expected = expected.map(|(kind, path, args)| {
+                    (kind, path.to_string(), args.iter().map(ToString::to_string).collect())
+                });
+                assert_eq!(revert, expected, "{src:?}");
+            }
+            sess.dcx.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn walk_exprs_in_statements() {
+        let src = "{
+            uint256 x = a + 1;
+            (bool ok, ) = f(x);
+            if (x > 0) { emit E(x); } else revert Err(x, y);
+            for (uint256 i = 0; i < n; ++i) { x += i; }
+            while (c) continue;
+            do { x--; } while (d);
+            unchecked { x *= 2; }
+            try g() returns (uint256 r) { return r; } catch { return; }
+            assembly { let z := 1 }
+        }";
+
+        solar_interface::enter(|| -> Result {
+            let sess = Session::builder().with_test_emitter().build();
+            let arena = Arena::new();
+            let name = FileName::custom("body");
+            let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+            let stmt = parser.parse_stmt().map_err(|e| e.emit())?;
+            let StmtKind::Block(body) = &stmt.kind else { panic!("not a block") };
+
+            let mut count = 0;
+            let mut outermost = Vec::new();
+            solar_ast::walk_exprs_in_statements(body, |expr, path| {
+                count += 1;
+                if path.is_empty() {
+                    outermost.push(expr.to_minified().unwrap());
+                }
+            });
+            assert_eq!(
+                outermost,
+                [
+                    "a+1", "f(x)", "x>0", "x", "x", "y", "0", "i<n", "++i", "x+=i", "c", "x--",
+                    "d", "x*=2", "g()", "r",
+                ]
+            );
+            assert_eq!(count, 31);
+
+            // A single statement.
+            let mut count = 0;
+            stmt.walk_exprs(|_, _| count += 1);
+            assert_eq!(count, 31);
+            sess.dcx.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn try_catch() {
+        type Param<'a> = (&'a str, Option<DataLocation>, &'a str);
+        const MEMORY: Option<DataLocation> = Some(DataLocation::Memory);
+        let tests: &[(&str, &[(CatchKind, &[Param<'_>])])] = &[
+            ("try f() {} catch {}", &[(CatchKind::Any, &[])]),
+            (
+                "try f() {} catch (bytes memory data) {}",
+                &[(CatchKind::Any, &[("data", MEMORY, "bytes")])],
+            ),
+            (
+                "try f() {} catch Error(string memory reason) {}",
+                &[(CatchKind::Error, &[("reason", MEMORY, "string")])],
+            ),
+            (
+                "try f() {} catch Panic(uint code) {}",
+                &[(CatchKind::Panic, &[("code", None, "uint256")])],
+            ),
+            (
+                "try f() returns (uint x) {} catch Error(string memory) {} catch Panic(uint) {} \
+                 catch {}",
+                &[
+                    (CatchKind::Error, &[("", MEMORY, "string")]),
+                    (CatchKind::Panic, &[("", None, "uint256")]),
+                    (CatchKind::Any, &[]),
+                ],
+            ),
+        ];
+
+        solar_interface::enter(|| -> Result {
+            let sess = Session::builder().with_test_emitter().build();
+            for (i, &(src, expected)) in tests.iter().enumerate() {
+                let arena = Arena::new();
+                let name = FileName::Custom(i.to_string());
+                let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+                let stmt = parser.parse_stmt().map_err(|e| e.emit())?;
+                let StmtKind::Try(try_) = &stmt.kind else { panic!("{src:?}: {:?}", stmt.kind) };
+                assert_eq!(try_.returns_clause().name, None, "{src:?}");
+                assert_eq!(try_.catch_clauses().len(), expected.len(), "{src:?}");
+                for (clause, &(kind, params)) in try_.catch_clauses().iter().zip(expected) {
+                    assert_eq!(clause.catch_kind(), kind, "{src:?}");
+                    let args = clause.args.iter().map(|arg| {
+                        let TypeKind::Elementary(ty) = arg.ty.kind else { panic!("{arg:?}") };
+                        let name = arg.name.as_ref().map_or("", |name| name.as_str());
+                        (name, arg.data_location, ty.to_abi_str().into_owned())
+                    });
+                    assert!(
+                        args.eq(params.iter().map(|&(n, l, ty)| (n, l, ty.to_string()))),
+                        "{src:?}"
+                    );
+                }
+            }
+            sess.dcx.has_errors()?;
+
+            // Custom names are parsed, and rejected later.
+            let arena = Arena::new();
+            let src = "try f() {} catch Failure(bytes memory data) {}";
+            let name = FileName::Custom("custom".into());
+            let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+            let stmt = parser.parse_stmt().map_err(|e| e.emit())?;
+            let StmtKind::Try(try_) = &stmt.kind else { panic!("{:?}", stmt.kind) };
+            let CatchKind::Custom(name) = try_.catch_clauses()[0].catch_kind() else {
+                panic!("{:?}", try_.catch_clauses()[0].catch_kind())
+            };
+            assert_eq!(name.as_str(), "Failure");
+            sess.dcx.has_errors()?;
+
+            // Names must be followed by parameters.
+            let src = "try f() {} catch Error {}";
+            let name = FileName::Custom("no_params".into());
+            let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+            let _ = parser.parse_stmt().map_err(|e| e.emit());
+            assert!(sess.dcx.has_errors().is_err());
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn assembly_flags() {
+        let tests: &[(&str, Option<&str>, &[&str])] = &[
+            ("assembly {}", None, &[]),
+            ("assembly \"evmasm\" {}", Some("evmasm"), &[]),
+            ("assembly (\"memory-safe\") {}", None, &["memory-safe"]),
+            ("assembly \"evmasm\" (\"memory-safe\") {}", Some("evmasm"), &["memory-safe"]),
+            (
+                "assembly (\"memory-safe\", \"other\") { let x := 1 }",
+                None,
+                &["memory-safe", "other"],
+            ),
+        ];
+
+        solar_interface::enter(|| -> Result {
+            let sess = Session::builder().with_test_emitter().build();
+            for (i, &(src, dialect, flags)) in tests.iter().enumerate() {
+                let arena = Arena::new();
+                let name = FileName::Custom(i.to_string());
+                let mut parser = Parser::from_source_code(&sess, &arena, name, src.into())?;
+                let stmt = parser.parse_stmt().map_err(|e| e.emit())?;
+                let StmtKind::Assembly(assembly) = &stmt.kind else {
+                    panic!("{src:?}: {:?}", stmt.kind)
+                };
+                assert_eq!(assembly.dialect.as_ref().map(|d| d.value.as_str()), dialect, "{src:?}");
+                let parsed: Vec<_> = assembly.flags.iter().map(|f| f.value.as_str()).collect();
+                assert_eq!(parsed, flags, "{src:?}");
+                assert_eq!(assembly.is_memory_safe(), flags.contains(&"memory-safe"), "{src:?}");
+            }
+            sess.dcx.has_errors()
+        })
+        .unwrap();
+    }
 }
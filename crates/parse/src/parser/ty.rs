@@ -8,8 +8,16 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// Parses a type.
     #[instrument(level = "debug", skip_all)]
     pub fn parse_type(&mut self) -> PResult<'sess, Type<'ast>> {
+        self.parse_type_with(FunctionFlags::FUNCTION_TY)
+    }
+
+    /// Parses a type, with the given flags for the header of a function type.
+    pub(super) fn parse_type_with(
+        &mut self,
+        function_flags: FunctionFlags,
+    ) -> PResult<'sess, Type<'ast>> {
         let mut ty = self
-            .parse_spanned(Self::parse_basic_ty_kind)
+            .parse_spanned(|this| this.parse_basic_ty_kind(function_flags))
             .map(|(span, kind)| Type { span, kind })?;
 
         // Parse suffixes.
@@ -30,11 +38,14 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
     }
 
     /// Parses a type kind. Does not parse suffixes.
-    fn parse_basic_ty_kind(&mut self) -> PResult<'sess, TypeKind<'ast>> {
+    fn parse_basic_ty_kind(
+        &mut self,
+        function_flags: FunctionFlags,
+    ) -> PResult<'sess, TypeKind<'ast>> {
         if self.check_elementary_type() {
             self.parse_elementary_type().map(TypeKind::Elementary)
         } else if self.eat_keyword(kw::Function) {
-            self.parse_function_header(FunctionFlags::FUNCTION_TY).map(|f| {
+            self.parse_function_header(function_flags).map(|f| {
                 let FunctionHeader {
                     name: _,
                     parameters,
@@ -2,7 +2,7 @@ use super::SeqSep;
 use crate::{PResult, Parser};
 use smallvec::SmallVec;
 use solar_ast::{token::*, yul::*, AstPath, Box, DocComments, LitKind, PathSlice, StrKind, StrLit};
-use solar_interface::{error_code, kw, sym, Ident};
+use solar_interface::{diagnostics::codes, kw, sym, Ident};
 
 impl<'sess, 'ast> Parser<'sess, 'ast> {
     /// Parses a Yul object or plain block.
@@ -216,7 +216,7 @@ impl<'sess, 'ast> Parser<'sess, 'ast> {
                 self.dcx()
                     .warn("`switch` statement has only a default case")
                     .span(span)
-                    .code(error_code!(9592))
+                    .code(codes::SWITCH_ONLY_DEFAULT_CASE)
                     .emit();
             }
         }
@@ -0,0 +1,206 @@
+use crate::{Dialect, Parser};
+use solar_ast as ast;
+use solar_interface::{
+    source_map::{FileName, SourceFile},
+    Result, Session,
+};
+use std::path::Path;
+
+/// A parsing session: the [`Session`] that owns the source map and the diagnostic context, and the
+/// configuration that every [`Parser`] created from it starts with.
+///
+/// A parsing session can be reused across any number of files. All of them are added to the same
+/// source map, and report their diagnostics to the same diagnostic context, so the spans of the
+/// ASTs of all the files can be resolved with [`Session::source_map`], and errors can be checked
+/// once at the end with [`DiagCtxt::has_errors`].
+///
+/// The `parse_*` methods emit the parser errors and return the AST parsed so far on recoverable
+/// errors, like [`Parser`] does, so an `Ok` result does not mean that the source is valid.
+///
+/// # Examples
+///
+/// ```
+/// use solar_parse::{
+///     ast::Arena,
+///     interface::{source_map::FileName, Session},
+///     Dialect, LanguageVersion, ParseSession,
+/// };
+///
+/// let sess = Session::builder().with_test_emitter().build();
+/// sess.enter(|| -> solar_parse::interface::Result {
+///     let version = LanguageVersion::new(0, 4, 26);
+///     let dialect = Dialect::new().with_language_version(version);
+///     let psess = ParseSession::new(&sess).with_dialect(dialect);
+///     let arena = Arena::new();
+///     let a = "contract A {}";
+///     let a = psess.parse_source_code(&arena, FileName::custom("a.sol"), a.into())?;
+///     // `throw` was removed in 0.5.0.
+///     let b = "contract B { function f() { throw; } }";
+///     let b = psess.parse_source_code(&arena, FileName::custom("b.sol"), b.into())?;
+///     assert_eq!((a.items.len(), b.items.len()), (1, 1));
+///     assert_eq!(sess.source_map().files().len(), 2);
+///     sess.dcx.has_errors()
+/// })?;
+/// # Ok::<_, solar_parse::interface::diagnostics::ErrorGuaranteed>(())
+/// ```
+///
+/// [`DiagCtxt::has_errors`]: solar_interface::diagnostics::DiagCtxt::has_errors
+#[derive(Clone, Debug)]
+pub struct ParseSession<'sess> {
+    sess: &'sess Session,
+    dialect: Dialect,
+    max_nodes: Option<usize>,
+}
+
+impl<'sess> ParseSession<'sess> {
+    /// Creates a new parsing session with the default configuration.
+    pub fn new(sess: &'sess Session) -> Self {
+        Self { sess, dialect: Dialect::new(), max_nodes: None }
+    }
+
+    /// Sets the language dialect. See [`Parser::set_dialect`].
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Sets the maximum number of AST nodes parsed in each file. See [`Parser::set_max_nodes`].
+    pub fn with_max_nodes(mut self, max_nodes: Option<usize>) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// Returns the session.
+    #[inline]
+    pub fn sess(&self) -> &'sess Session {
+        self.sess
+    }
+
+    /// Returns the language dialect.
+    #[inline]
+    pub fn dialect(&self) -> &Dialect {
+        &self.dialect
+    }
+
+    /// Returns the maximum number of AST nodes parsed in each file, if any.
+    #[inline]
+    pub fn max_nodes(&self) -> Option<usize> {
+        self.max_nodes
+    }
+
+    /// Creates a parser for a source file, with the configuration of this session.
+    ///
+    /// Note that the source file must be added to the source map before calling this function.
+    pub fn parser<'ast>(&self, arena: &'ast ast::Arena, file: &SourceFile) -> Parser<'sess, 'ast> {
+        let mut parser = Parser::from_source_file(self.sess, arena, file);
+        parser.set_dialect(self.dialect.clone());
+        parser.set_max_nodes(self.max_nodes);
+        parser
+    }
+
+    /// Creates a parser for a source code string, with the configuration of this session.
+    ///
+    /// The source will not be added again if a file with the same name has already been added into
+    /// the source map.
+    pub fn parser_from_source_code<'ast>(
+        &self,
+        arena: &'ast ast::Arena,
+        filename: FileName,
+        src: String,
+    ) -> Result<Parser<'sess, 'ast>> {
+        let mut parser = Parser::from_source_code(self.sess, arena, filename, src)?;
+        parser.set_dialect(self.dialect.clone());
+        parser.set_max_nodes(self.max_nodes);
+        Ok(parser)
+    }
+
+    /// Creates a parser for a file, with the configuration of this session.
+    ///
+    /// The file will not be read if it has already been added into the source map.
+    pub fn parser_from_file<'ast>(
+        &self,
+        arena: &'ast ast::Arena,
+        path: &Path,
+    ) -> Result<Parser<'sess, 'ast>> {
+        let mut parser = Parser::from_file(self.sess, arena, path)?;
+        parser.set_dialect(self.dialect.clone());
+        parser.set_max_nodes(self.max_nodes);
+        Ok(parser)
+    }
+
+    /// Parses a source code string as a source unit.
+    pub fn parse_source_code<'ast>(
+        &self,
+        arena: &'ast ast::Arena,
+        filename: FileName,
+        src: String,
+    ) -> Result<ast::SourceUnit<'ast>> {
+        self.parser_from_source_code(arena, filename, src)?.parse_file().map_err(|e| e.emit())
+    }
+
+    /// Parses a file as a source unit.
+    pub fn parse_file<'ast>(
+        &self,
+        arena: &'ast ast::Arena,
+        path: &Path,
+    ) -> Result<ast::SourceUnit<'ast>> {
+        self.parser_from_file(arena, path)?.parse_file().map_err(|e| e.emit())
+    }
+
+    /// Parses a source code string as a single expression.
+    pub fn parse_expr<'ast>(
+        &self,
+        arena: &'ast ast::Arena,
+        filename: FileName,
+        src: String,
+    ) -> Result<ast::Box<'ast, ast::Expr<'ast>>> {
+        self.parser_from_source_code(arena, filename, src)?
+            .parse_standalone_expr()
+            .map_err(|e| e.emit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_ast::{BinOpKind, ItemKind};
+
+    #[test]
+    fn two_files() {
+        let sess = Session::builder().with_test_emitter().build();
+        sess.enter(|| {
+            let psess = ParseSession::new(&sess)
+                .with_dialect(Dialect::new().with_bin_op(BinOpKind::Pow, false));
+            let arena = ast::Arena::new();
+
+            let a = "contract A { uint x = 1; }";
+            let b = "import \"a.sol\";\ncontract B is A { function f() { x = 2; } }";
+            let a_ast = psess.parse_source_code(&arena, FileName::custom("a.sol"), a.into());
+            let b_ast = psess.parse_source_code(&arena, FileName::custom("b.sol"), b.into());
+            let (a_ast, b_ast) = (a_ast.unwrap(), b_ast.unwrap());
+            sess.dcx.has_errors().unwrap();
+
+            // Both files are in the same source map, after one another.
+            let files = sess.source_map().files().clone();
+            assert_eq!(files.len(), 2);
+            assert!(files[0].end_position() <= files[1].start_pos);
+            let contract_name = |ast: &ast::SourceUnit<'_>| {
+                let item = ast.items.iter().find(|item| matches!(item.kind, ItemKind::Contract(_)));
+                let ItemKind::Contract(contract) = &item.unwrap().kind else { unreachable!() };
+                sess.source_map().span_to_snippet(contract.name.span).unwrap()
+            };
+            assert_eq!(contract_name(&a_ast), "A");
+            assert_eq!(contract_name(&b_ast), "B");
+            let b_file = sess.source_map().lookup_source_file(b_ast.items[1].span.lo());
+            assert_eq!(b_file.name, FileName::custom("b.sol"));
+
+            // The configuration applies to every file.
+            let expr = psess.parse_expr(&arena, FileName::custom("c"), "2 ** 3".into()).unwrap();
+            assert!(
+                matches!(expr.kind, ast::ExprKind::Binary(_, op, _) if op.kind == BinOpKind::Pow)
+            );
+            assert!(sess.dcx.has_errors().is_err());
+            assert_eq!(sess.source_map().files().len(), 3);
+        });
+    }
+}
@@ -0,0 +1,133 @@
+//! Parsing a single expression, type, or statement from a string.
+
+use crate::{PResult, Parser};
+use solar_ast as ast;
+use solar_interface::{
+    diagnostics::EmittedDiagnostics, source_map::FileName, ColorChoice, Session,
+};
+
+/// Parses the source code as a single expression.
+///
+/// This parses the source code in a new [`Session`] of its own, so the spans of the nodes index
+/// directly into `src`, like with [`Span::to_range`](solar_interface::Span::to_range). If any
+/// errors are emitted, they are returned with the other diagnostics, rendered without colors.
+/// Warnings are discarded otherwise.
+///
+/// Identifiers are interned in the current session globals, or in new ones that only live for
+/// the duration of the call if there are none. Call this inside of [`solar_interface::enter`] to
+/// get the strings of the identifiers of the expression after this returns.
+///
+/// # Examples
+///
+/// ```
+/// use solar_parse::{ast::Arena, interface::enter};
+///
+/// enter(|| {
+///     let arena = Arena::new();
+///     let src = "a + b * c";
+///     let expr = solar_parse::parse_expr(&arena, src).unwrap();
+///     assert_eq!(&src[expr.span.to_range()], "a + b * c");
+///     let (lhs, _, _) = expr.as_binary().unwrap();
+///     assert_eq!(lhs.as_ident().unwrap().as_str(), "a");
+///
+///     let err = solar_parse::parse_expr(&arena, "a +").unwrap_err();
+///     assert!(err.to_string().contains("expected expression"), "{err}");
+/// });
+/// ```
+pub fn parse_expr<'ast>(
+    arena: &'ast ast::Arena,
+    src: &str,
+) -> Result<ast::Box<'ast, ast::Expr<'ast>>, EmittedDiagnostics> {
+    parse_standalone(arena, src, |parser| parser.parse_standalone_expr())
+}
+
+/// Parses the source code as a single type.
+///
+/// See [`parse_expr`] for more details.
+pub fn parse_type<'ast>(
+    arena: &'ast ast::Arena,
+    src: &str,
+) -> Result<ast::Type<'ast>, EmittedDiagnostics> {
+    parse_standalone(arena, src, |parser| parser.parse_standalone_type())
+}
+
+/// Parses the source code as a single statement.
+///
+/// See [`parse_expr`] for more details.
+pub fn parse_stmt<'ast>(
+    arena: &'ast ast::Arena,
+    src: &str,
+) -> Result<ast::Stmt<'ast>, EmittedDiagnostics> {
+    parse_standalone(arena, src, |parser| parser.parse_standalone_stmt())
+}
+
+fn parse_standalone<'ast, T>(
+    arena: &'ast ast::Arena,
+    src: &str,
+    f: impl for<'sess> FnOnce(&mut Parser<'sess, 'ast>) -> PResult<'sess, T>,
+) -> Result<T, EmittedDiagnostics> {
+    let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).build();
+    sess.enter(|| {
+        let res = Parser::from_source_code(&sess, arena, FileName::custom("input"), src.into())
+            .and_then(|mut parser| f(&mut parser).map_err(|e| e.emit()));
+        sess.emitted_errors().unwrap()?;
+        Ok(res.expect("the error was emitted"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_ast::{BinOpKind, ExprKind, StmtKind, TypeKind};
+
+    #[test]
+    fn standalone() {
+        solar_interface::enter(|| {
+            let arena = ast::Arena::new();
+
+            let src = "a + b * c";
+            let expr = parse_expr(&arena, src).unwrap();
+            assert_eq!(&src[expr.span.to_range()], "a + b * c");
+            let ExprKind::Binary(lhs, op, rhs) = &expr.kind else { panic!("{expr:?}") };
+            assert_eq!(op.kind, BinOpKind::Add);
+            assert_eq!(&src[lhs.span.to_range()], "a");
+            let ExprKind::Binary(lhs, op, rhs) = &rhs.kind else { panic!("{rhs:?}") };
+            assert_eq!(op.kind, BinOpKind::Mul);
+            assert_eq!(&src[lhs.span.to_range()], "b");
+            assert_eq!(&src[rhs.span.to_range()], "c");
+            assert_eq!(lhs.as_ident().unwrap().as_str(), "b");
+
+            // Every call starts a new source map.
+            let src = "f( x )";
+            let expr = parse_expr(&arena, src).unwrap();
+            assert_eq!(&src[expr.span.to_range()], "f( x )");
+            let (callee, args) = expr.as_call().unwrap();
+            assert_eq!(&src[callee.span.to_range()], "f");
+            assert_eq!(&src[args.span.to_range()], "( x )");
+
+            let src = "mapping(uint => bool)[]";
+            let ty = parse_type(&arena, src).unwrap();
+            assert!(matches!(ty.kind, TypeKind::Array(_)), "{ty:?}");
+            assert_eq!(ty.span.to_range(), 0..src.len());
+
+            let src = "return x;";
+            let stmt = parse_stmt(&arena, src).unwrap();
+            assert!(matches!(stmt.kind, StmtKind::Return(Some(_))), "{stmt:?}");
+            assert_eq!(stmt.span.to_range(), 0..src.len());
+        });
+    }
+
+    #[test]
+    fn standalone_errors() {
+        solar_interface::enter(|| {
+            let arena = ast::Arena::new();
+            let err = parse_expr(&arena, "a + b c").unwrap_err().to_string();
+            assert!(err.starts_with("error"), "{err}");
+            // Errors that the parser recovers from are returned too.
+            let err = parse_expr(&arena, "[a, , b]").unwrap_err().to_string();
+            assert!(err.contains("array expression components cannot be empty"), "{err}");
+            assert!(parse_type(&arena, "uint +").is_err());
+            assert!(parse_stmt(&arena, "return x").is_err());
+        });
+    }
+}
@@ -0,0 +1,225 @@
+//! Utilities for writing parser tests.
+//!
+//! Requires the `test-util` feature.
+//!
+//! A [`TestSession`] parses any number of sources in one session, each as its own file, and
+//! resolves the spans of the parsed nodes relative to their file.
+//!
+//! Snapshots are the [span-free debug trees](solar_ast::Expr::debug_tree) of the parsed
+//! expressions, pretty-printed with one node per line, so they only change when the structure of
+//! the tree changes, and not when the source is reformatted. They can be compared to an expected
+//! string with [`assert_expr_snapshot`], or passed to a golden test library like `expect-test` or
+//! `insta`.
+
+use crate::Parser;
+use solar_ast::{self as ast, Arena, Expr};
+use solar_interface::{source_map::FileName, Result, Session, Span};
+use std::{cell::Cell, ops::Range};
+
+/// Runs `f` with a new [`TestSession`], with its session globals set.
+pub fn enter<R>(f: impl FnOnce(&TestSession) -> R) -> R {
+    TestSession::new().enter(f)
+}
+
+/// A session for parser tests, in which every source is parsed as a new file.
+///
+/// The source map returns the file it already has when a file name is added again, without
+/// reading the new source, so parsing several sources under one name would parse the first one
+/// every time. A test session gives every source a unique name instead. Since the files follow
+/// one another in the source map, only the first one starts at position zero, so spans should be
+/// resolved with [`range`](Self::range) or [`snippet`](Self::snippet).
+pub struct TestSession {
+    /// The session.
+    pub sess: Session,
+    /// The arena that the parsed nodes are allocated in.
+    pub arena: Arena,
+    files: Cell<usize>,
+}
+
+impl Default for TestSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestSession {
+    /// Creates a new test session that emits its diagnostics with the test emitter.
+    pub fn new() -> Self {
+        Self::with_session(Session::builder().with_test_emitter().build())
+    }
+
+    /// Creates a new test session with the given session.
+    pub fn with_session(sess: Session) -> Self {
+        Self { sess, arena: Arena::new(), files: Cell::new(0) }
+    }
+
+    /// Runs `f` with the session globals of the session set.
+    pub fn enter<R>(&self, f: impl FnOnce(&Self) -> R) -> R {
+        self.sess.enter(|| f(self))
+    }
+
+    /// Creates a parser for the source code, as a new file.
+    pub fn parser(&self, src: &str) -> Parser<'_, '_> {
+        let filename = FileName::custom(format!("test-{}", self.files.get()));
+        self.files.set(self.files.get() + 1);
+        Parser::from_source_code(&self.sess, &self.arena, filename, src.into())
+            .expect("failed to add the source file")
+    }
+
+    /// Parses the source code as a standalone expression, emitting the error if it fails.
+    pub fn parse_expr(&self, src: &str) -> Result<ast::Box<'_, Expr<'_>>> {
+        self.parser(src).parse_standalone_expr().map_err(|e| e.emit())
+    }
+
+    /// Parses the source code as a standalone type, emitting the error if it fails.
+    pub fn parse_type(&self, src: &str) -> Result<ast::Type<'_>> {
+        self.parser(src).parse_standalone_type().map_err(|e| e.emit())
+    }
+
+    /// Parses the source code as a standalone statement, emitting the error if it fails.
+    pub fn parse_stmt(&self, src: &str) -> Result<ast::Stmt<'_>> {
+        self.parser(src).parse_standalone_stmt().map_err(|e| e.emit())
+    }
+
+    /// Returns the range of the span in the source code of its file.
+    pub fn range(&self, span: Span) -> Range<usize> {
+        let start = self.sess.source_map().lookup_source_file(span.lo()).start_pos.to_usize();
+        span.lo().to_usize() - start..span.hi().to_usize() - start
+    }
+
+    /// Returns the source code of the span.
+    #[track_caller]
+    pub fn snippet(&self, span: Span) -> String {
+        self.sess.source_map().span_to_snippet(span).unwrap()
+    }
+
+    /// Returns `Err` if any errors have been emitted.
+    pub fn has_errors(&self) -> Result {
+        self.sess.dcx.has_errors()
+    }
+}
+
+/// Returns the snapshot of an expression.
+///
+/// This includes the names of identifiers and members, and literals as written in the source
+/// code, but no spans.
+pub fn expr_snapshot(expr: &Expr<'_>) -> String {
+    format!("{:#?}", expr.debug_tree())
+}
+
+/// Parses the source code as a standalone expression and returns its
+/// [snapshot](expr_snapshot).
+///
+/// # Panics
+///
+/// Panics if the source code does not parse without errors.
+#[track_caller]
+pub fn parse_expr_snapshot(src: &str) -> String {
+    let snapshot = enter(|t| {
+        let expr = t.parse_expr(src).ok()?;
+        t.has_errors().ok()?;
+        Some(expr_snapshot(&expr))
+    });
+    snapshot.unwrap_or_else(|| panic!("failed to parse {src:?}"))
+}
+
+/// Asserts that the source code parses as a standalone expression with the given
+/// [snapshot](expr_snapshot).
+///
+/// Leading and trailing whitespace in `expected` is ignored, so that it can be written as an
+/// indented raw string literal.
+///
+/// # Panics
+///
+/// Panics if the source code does not parse without errors, or if its snapshot is different.
+#[track_caller]
+pub fn assert_expr_snapshot(src: &str, expected: &str) {
+    let actual = parse_expr_snapshot(src);
+    let expected = unindent(expected);
+    assert!(
+        actual == expected,
+        "snapshot mismatch for {src:?}\n\n--- expected\n{expected}\n\n--- actual\n{actual}\n"
+    );
+}
+
+/// Trims the string and removes the common indentation of its lines.
+fn unindent(s: &str) -> String {
+    let s = s.trim_matches('\n').trim_end();
+    let indent = s
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    s.lines().map(|line| line.get(indent..).unwrap_or("")).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot() {
+        assert_expr_snapshot(
+            "f(a,\n  1 ether)[i] + x.y",
+            r#"
+            Binary(
+                Index(
+                    Call(
+                        Ident(
+                            "f",
+                        ),
+                        [
+                            Ident(
+                                "a",
+                            ),
+                            Lit(
+                                "1",
+                                ether,
+                            ),
+                        ],
+                    ),
+                    Some(
+                        Ident(
+                            "i",
+                        ),
+                    ),
+                ),
+                Add,
+                Member(
+                    Ident(
+                        "x",
+                    ),
+                    "y",
+                ),
+            )
+            "#,
+        );
+        // Whitespace does not matter.
+        assert_eq!(
+            parse_expr_snapshot("f(a, 1 ether)[i]+x . y"),
+            parse_expr_snapshot("f(a,\n1 ether)[i] + x.y")
+        );
+    }
+
+    #[test]
+    fn test_session() {
+        enter(|t| -> Result {
+            let a = t.parse_expr("a + b")?;
+            let b = t.parse_expr("(c)")?;
+            assert_eq!(t.sess.source_map().files().len(), 2);
+            assert_eq!((t.snippet(a.span), t.snippet(b.span)), ("a + b".into(), "(c)".into()));
+            assert_eq!((t.range(a.span), t.range(b.span)), (0..5, 0..3));
+            let ast::ExprKind::Tuple(elems) = &b.kind else { panic!("{b:?}") };
+            assert_eq!(t.range(elems[0].as_ref().unwrap().span), 1..2);
+            t.has_errors()
+        })
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic = "failed to parse \"a +\""]
+    fn snapshot_parse_error() {
+        parse_expr_snapshot("a +");
+    }
+}
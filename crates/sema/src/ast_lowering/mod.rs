@@ -8,7 +8,10 @@ use solar_data_structures::{
     map::FxHashMap,
     trustme,
 };
-use solar_interface::{diagnostics::DiagCtxt, Session};
+use solar_interface::{
+    diagnostics::{DiagCtxt, ErrorGuaranteed},
+    Session,
+};
 
 mod lower;
 
@@ -62,6 +65,10 @@ struct LoweringContext<'sess, 'ast, 'hir> {
     current_contract_id: Option<hir::ContractId>,
 
     resolver: SymbolResolver<'sess>,
+
+    /// An error emitted before lowering, if any. The parser reports syntax that was removed from
+    /// the language, unless its dialect accepts it.
+    parse_error: Option<ErrorGuaranteed>,
 }
 
 impl<'sess, 'hir> LoweringContext<'sess, '_, 'hir> {
@@ -74,6 +81,7 @@ impl<'sess, 'hir> LoweringContext<'sess, '_, 'hir> {
             current_contract_id: None,
             hir_to_ast: FxHashMap::default(),
             resolver: SymbolResolver::new(&sess.dcx),
+            parse_error: sess.dcx.has_errors().err(),
         }
     }
 
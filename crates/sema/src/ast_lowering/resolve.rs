@@ -255,6 +255,7 @@ impl<'hir> super::LoweringContext<'_, '_, 'hir> {
                     hir: &mut self.hir,
                     resolver: &self.resolver,
                     next_id,
+                    parse_error: self.parse_error,
                 }
             };
         }
@@ -645,6 +646,7 @@ struct ResolveContext<'sess, 'hir, 'a> {
     scopes: SymbolResolverScopes,
     function_id: Option<hir::FunctionId>,
     next_id: &'a AtomicUsize,
+    parse_error: Option<ErrorGuaranteed>,
 }
 
 impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
@@ -662,6 +664,7 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
             scopes,
             function_id,
             next_id,
+            parse_error: lcx.parse_error,
         }
     }
 
@@ -770,6 +773,11 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
                     ),
                 }))
             }
+            ast::StmtKind::Throw => hir::StmtKind::Err(self.removed_syntax_err(
+                "`throw` statements are not supported",
+                stmt.span,
+                "use `revert()` instead",
+            )),
             ast::StmtKind::Placeholder => hir::StmtKind::Placeholder,
         };
         hir::Stmt { span: stmt.span, kind }
@@ -978,6 +986,7 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
                 )
             }
             ast::ExprKind::Delete(expr) => hir::ExprKind::Delete(self.lower_expr(expr)),
+            ast::ExprKind::Err(guar) => hir::ExprKind::Err(*guar),
             ast::ExprKind::Ident(name) => {
                 match self.resolve_paths(ast::PathSlice::from_ref(name)) {
                     Ok(decls) => hir::ExprKind::Ident(
@@ -1005,7 +1014,7 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
             }
             ast::ExprKind::New(ty) => hir::ExprKind::New(self.lower_type(ty)),
             ast::ExprKind::Payable(args) => 'b: {
-                if let ast::CallArgs::Unnamed(args) = args {
+                if let ast::CallArgsKind::Unnamed(args) = &args.kind {
                     if let [arg] = &args[..] {
                         break 'b hir::ExprKind::Payable(self.lower_expr(arg));
                     }
@@ -1039,9 +1048,9 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
     }
 
     fn lower_call_args(&mut self, args: &ast::CallArgs<'_>) -> hir::CallArgs<'hir> {
-        match args {
-            ast::CallArgs::Unnamed(args) => hir::CallArgs::Unnamed(self.lower_exprs(&**args)),
-            ast::CallArgs::Named(args) => hir::CallArgs::Named(self.lower_named_args(args)),
+        match &args.kind {
+            ast::CallArgsKind::Unnamed(args) => hir::CallArgs::Unnamed(self.lower_exprs(&**args)),
+            ast::CallArgsKind::Named(args, _) => hir::CallArgs::Named(self.lower_named_args(args)),
         }
     }
 
@@ -1073,10 +1082,25 @@ impl<'sess, 'hir, 'a> ResolveContext<'sess, 'hir, 'a> {
                 Ok(id) => hir::TypeKind::Custom(id),
                 Err(guar) => hir::TypeKind::Err(guar),
             },
+            ast::TypeKind::Var => hir::TypeKind::Err(self.removed_syntax_err(
+                "`var` declarations are not supported",
+                ty.span,
+                "use an explicit type instead",
+            )),
         };
         hir::Type { kind, span: ty.span }
     }
 
+    /// Reports syntax that was removed from the language, unless the parser already did.
+    fn removed_syntax_err(
+        &self,
+        msg: &'static str,
+        span: Span,
+        help: &'static str,
+    ) -> ErrorGuaranteed {
+        self.parse_error.unwrap_or_else(|| self.sess.dcx.err(msg).span(span).help(help).emit())
+    }
+
     fn next_id<I: Idx>(&self) -> I {
         I::from_usize(self.next_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
     }
@@ -1121,6 +1145,18 @@ impl ResolverError {
             }
         }
     }
+
+    /// Returns a help message for unresolved builtins that were removed from the language.
+    fn help(&self) -> Option<&'static str> {
+        if !matches!(self.kind, ResolverErrorKind::Unresolved) {
+            return None;
+        }
+        match self.name.as_str() {
+            "sha3" => Some("`sha3` was removed in version 0.5.0; use `keccak256` instead"),
+            "suicide" => Some("`suicide` was removed in version 0.5.0; use `selfdestruct` instead"),
+            _ => None,
+        }
+    }
 }
 
 pub(crate) struct SymbolResolver<'sess> {
@@ -1158,7 +1194,13 @@ impl<'sess> SymbolResolver<'sess> {
     }
 
     fn emit_resolver_error(&self) -> impl Fn(ResolverError) -> ErrorGuaranteed + '_ {
-        move |e| self.dcx.err(e.format()).span(e.span()).emit()
+        move |e| {
+            let mut diag = self.dcx.err(e.format()).span(e.span());
+            if let Some(help) = e.help() {
+                diag = diag.help(help);
+            }
+            diag.emit()
+        }
     }
 
     fn resolve_path(
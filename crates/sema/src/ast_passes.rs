@@ -1,20 +1,83 @@
 //! AST-related passes.
 
+use crate::suppressions::Suppressions;
 use solar_ast::{self as ast, visit::Visit};
 use solar_data_structures::Never;
-use solar_interface::{diagnostics::DiagCtxt, sym, Session, Span};
-use std::ops::ControlFlow;
+use solar_interface::{
+    diagnostics::{codes, Applicability, DiagBuilder, DiagCtxt, DiagId, DiagMsg},
+    kw,
+    source_map::{SourceFile, SourceMap},
+    sym, Ident, Session, Span, Symbol,
+};
+use std::{collections::HashSet, hash::BuildHasher, ops::ControlFlow, sync::Arc};
 
+/// Runs the AST passes, and returns the suppression comments of the file, which are also used by
+/// the later passes.
 #[instrument(name = "ast_passes", level = "debug", skip_all)]
-pub(crate) fn run(sess: &Session, ast: &ast::SourceUnit<'_>) {
-    validate(sess, ast);
+pub(crate) fn run(
+    sess: &Session,
+    file: &Arc<SourceFile>,
+    ast: &ast::SourceUnit<'_>,
+) -> Suppressions {
+    let mut validator = AstValidator::new(sess, Suppressions::new(file.clone()));
+    validator.visit_source_unit(ast);
+    validator.suppressions
 }
 
 /// Performs AST validation.
 #[instrument(name = "validate", level = "debug", skip_all)]
-pub fn validate(sess: &Session, ast: &ast::SourceUnit<'_>) {
-    let mut validator = AstValidator::new(sess);
-    validator.visit_source_unit(ast);
+pub fn validate(sess: &Session, file: &Arc<SourceFile>, ast: &ast::SourceUnit<'_>) {
+    run(sess, file, ast).report_unused(&sess.dcx);
+}
+
+/// Warns about the uses of builtin names that are shadowed by a local declaration, like a call
+/// to `require` in a function that declares a local variable named `require`.
+///
+/// The AST does not resolve names, so `shadowed` is the set of names declared locally, like the
+/// parameters and local variables of the function whose body is `stmts`, and is collected by the
+/// caller. Only identifiers whose name is both in `shadowed` and the name of a global builtin, like
+/// `require`, `assert`, `keccak256`, `ecrecover`, or `msg`, are reported, at the span of the
+/// use. Member names, like in `s.require`, are not uses of the builtin.
+///
+/// Uses can be silenced with suppression comments in `file`, the source file of `stmts`, like
+/// `// solar-disable-next-line 0511`, as for the lints reported by [`validate`]. Unused
+/// suppression comments are not reported.
+pub fn lint_shadowed_builtins<'ast, S: BuildHasher>(
+    sess: &Session,
+    file: &Arc<SourceFile>,
+    stmts: &'ast [ast::Stmt<'ast>],
+    shadowed: &HashSet<Symbol, S>,
+) {
+    let validator = AstValidator::new(sess, Suppressions::new(file.clone()));
+    for ident in shadowed_builtin_uses(stmts, shadowed) {
+        let msg = format!("use of shadowed builtin `{ident}`");
+        let Some(lint) = validator.lint(codes::SHADOWED_BUILTIN, msg, ident.span) else {
+            continue;
+        };
+        lint.note(format!("`{ident}` refers to a local declaration, not to the builtin `{ident}`"))
+            .help("rename the local declaration")
+            .emit();
+    }
+}
+
+/// Returns the identifiers in the statements that are uses of a shadowed builtin name, in
+/// source order. See [`lint_shadowed_builtins`].
+fn shadowed_builtin_uses<'ast, S: BuildHasher>(
+    stmts: &'ast [ast::Stmt<'ast>],
+    shadowed: &HashSet<Symbol, S>,
+) -> Vec<&'ast Ident> {
+    let mut uses = Vec::new();
+    if !shadowed.iter().any(|&name| name.is_builtin_global()) {
+        return uses;
+    }
+    ast::walk_exprs_in_statements(stmts, |expr, _| {
+        if let ast::ExprKind::Ident(ident) = &expr.kind {
+            if shadowed.contains(&ident.name) && ident.is_builtin_global() {
+                uses.push(ident);
+            }
+        }
+    });
+    uses
 }
 
 /// AST validator.
@@ -24,20 +87,26 @@ struct AstValidator<'sess, 'ast> {
     contract: Option<&'ast ast::ItemContract<'ast>>,
     function_kind: Option<ast::FunctionKind>,
     in_unchecked_block: bool,
+    /// Whether the expression being visited is the target of an assignment, or a component of
+    /// one, in which case it may be a tuple with missing components.
+    in_assign_target: bool,
     loop_depth: u32,
     placeholder_count: u32,
+    suppressions: Suppressions,
 }
 
 impl<'sess> AstValidator<'sess, '_> {
-    fn new(sess: &'sess Session) -> Self {
+    fn new(sess: &'sess Session, suppressions: Suppressions) -> Self {
         Self {
             item_span: Span::DUMMY,
             dcx: &sess.dcx,
             contract: None,
             function_kind: None,
             in_unchecked_block: false,
+            in_assign_target: false,
             loop_depth: 0,
             placeholder_count: 0,
+            suppressions,
         }
     }
 
@@ -47,6 +116,20 @@ impl<'sess> AstValidator<'sess, '_> {
         self.dcx
     }
 
+    /// Creates a lint warning with the given code, unless it has been suppressed at `span` with a
+    /// [suppression comment](crate::suppressions).
+    fn lint(
+        &self,
+        code: DiagId,
+        msg: impl Into<DiagMsg>,
+        span: Span,
+    ) -> Option<DiagBuilder<'sess, ()>> {
+        if self.suppressions.is_suppressed(&code, span) {
+            return None;
+        }
+        Some(self.dcx().warn(msg).code(code).span(span))
+    }
+
     fn in_loop(&self) -> bool {
         self.loop_depth != 0
     }
@@ -92,6 +175,417 @@ impl<'sess> AstValidator<'sess, '_> {
                 .emit();
         }
     }
+
+    fn check_chained_comparison(&self, expr: &ast::Expr<'_>) {
+        let ast::ExprKind::Binary(lhs, op, rhs) = &expr.kind else {
+            return;
+        };
+        if !op.kind.is_comparison() {
+            return;
+        }
+        let is_comparison = |e: &ast::Expr<'_>| match &e.kind {
+            ast::ExprKind::Binary(_, op, _) => op.kind.is_comparison(),
+            _ => false,
+        };
+        if !is_comparison(lhs) && !is_comparison(rhs) {
+            return;
+        }
+        let msg = "comparison operators cannot be chained";
+        if let Some(lint) = self.lint(codes::CHAINED_COMPARISON, msg, expr.span) {
+            lint.help("split the comparison into two and combine them with `&&`").emit();
+        }
+    }
+
+    fn check_small_power(&self, expr: &ast::Expr<'_>) {
+        const MAX_EXPONENT: u32 = 3;
+
+        let ast::ExprKind::Binary(base, op, exponent) = &expr.kind else {
+            return;
+        };
+        if op.kind != ast::BinOpKind::Pow {
+            return;
+        }
+        let ast::ExprKind::Lit(ast::Lit { kind: ast::LitKind::Number(n), .. }, None) =
+            &exponent.strip_parens().kind
+        else {
+            return;
+        };
+        let Some(n) = u32::try_from(n).ok().filter(|n| (2..=MAX_EXPONENT).contains(n)) else {
+            return;
+        };
+        // Literal bases are evaluated at compile time, and impure bases cannot be duplicated.
+        if matches!(base.strip_parens().kind, ast::ExprKind::Lit(..)) || !base.is_pure() {
+            return;
+        }
+        let msg = "exponentiation with a small constant exponent is more expensive than \
+                   multiplication";
+        let Some(lint) = self.lint(codes::SMALL_POWER, msg, expr.span) else { return };
+        let base = self.dcx().source_map().and_then(|sm| sm.span_to_snippet(base.span).ok());
+        let help = match base.filter(|base| !base.contains('\n')) {
+            Some(base) => format!("use `{}` instead", vec![base; n as usize].join(" * ")),
+            None => "use repeated multiplication instead".to_string(),
+        };
+        lint.help(help).emit();
+    }
+
+    fn check_division_by_zero(&self, expr: &ast::Expr<'_>) {
+        let (op, divisor) = match &expr.kind {
+            ast::ExprKind::Binary(_, op, rhs) | ast::ExprKind::Assign(_, Some(op), rhs) => {
+                (op.kind, rhs)
+            }
+            _ => return,
+        };
+        let msg = match op {
+            ast::BinOpKind::Div => "division by zero",
+            ast::BinOpKind::Rem => "modulo by zero",
+            _ => return,
+        };
+        let is_zero = divisor.is_zero_literal()
+            || divisor.const_eval().is_some_and(|v| v.as_int().is_some_and(ast::ConstInt::is_zero));
+        if is_zero {
+            self.dcx().err(msg).span(expr.span).emit();
+        }
+    }
+
+    /// Checks the options of a call: `new C{salt: s}()`, `addr.call{value: v, gas: g}("")`.
+    ///
+    /// Whether `value` is allowed depends on the type of the callee, which is not known here.
+    fn check_call_options(&self, expr: &ast::Expr<'_>) {
+        let ast::ExprKind::Call(callee, _) = &expr.kind else { return };
+        let ast::ExprKind::CallOptions(target, options) = &callee.kind else { return };
+        let is_new = matches!(target.kind, ast::ExprKind::New(_));
+        for (i, option) in options.iter().enumerate() {
+            let name = option.name;
+            if !matches!(name.name, kw::Gas | sym::salt | sym::value) {
+                self.dcx()
+                    .err(format!("unknown call option `{name}`"))
+                    .span(name.span)
+                    .help("valid options are `gas`, `salt` and `value`")
+                    .emit();
+                continue;
+            }
+            if let Some(first) = options[..i].iter().find(|first| first.name.name == name.name) {
+                self.dcx()
+                    .err(format!("duplicate call option `{name}`"))
+                    .span(name.span)
+                    .span_note(first.name.span, "previously specified here")
+                    .emit();
+                continue;
+            }
+            if name.name == sym::salt && !is_new {
+                self.dcx()
+                    .err("call option `salt` can only be used with `new`")
+                    .span(name.span)
+                    .emit();
+            } else if name.name == kw::Gas && is_new {
+                self.dcx()
+                    .err("call option `gas` cannot be used with `new`")
+                    .span(name.span)
+                    .emit();
+            }
+        }
+    }
+
+    /// Checks that the components of a tuple assignment target are assignable, and that tuples
+    /// are not used in compound assignments: `(a, 1) = t`, `(a, b) += t`.
+    fn check_assign_target(&self, lhs: &ast::Expr<'_>, op: Option<ast::BinOp>) {
+        let ast::ExprKind::Tuple(exprs) = &lhs.strip_parens().kind else { return };
+        if let Some(op) = op {
+            let msg =
+                format!("compound assignment `{}=` is not allowed for tuples", op.kind.to_str());
+            self.dcx().err(msg).span(lhs.span).emit();
+            return;
+        }
+        for expr in exprs.iter().flatten() {
+            if let ast::ExprKind::Tuple(_) = expr.strip_parens().kind {
+                self.check_assign_target(expr, None);
+            } else if !expr.is_lvalue() {
+                self.dcx().err("tuple component is not assignable").span(expr.span).emit();
+            }
+        }
+    }
+
+    /// Checks that tuples outside of assignment targets have no missing components:
+    /// `(a, , b) + c`.
+    fn check_tuple_components(&self, expr: &ast::Expr<'_>) {
+        let ast::ExprKind::Tuple(exprs) = &expr.kind else { return };
+        if exprs.iter().any(Option::is_none) {
+            self.dcx()
+                .err("tuple component cannot be empty")
+                .span(expr.span)
+                .help("empty components are only allowed on the left-hand side of an assignment")
+                .emit();
+        }
+    }
+
+    fn check_builtin_shadowing(&self, name: ast::Ident) {
+        if name.is_builtin_global() {
+            let msg = format!("`{name}` shadows a builtin global");
+            if let Some(lint) = self.lint(codes::BUILTIN_SHADOWING, msg, name.span) {
+                lint.emit();
+            }
+        }
+    }
+
+    fn check_bool_comparison(&self, expr: &ast::Expr<'_>) {
+        use ast::matcher::*;
+
+        let comparison = |op| {
+            bin_op_commutative(
+                op,
+                capture(not(strip_parens(any_lit_bool()))),
+                strip_parens(capture(any_lit_bool())),
+            )
+        };
+        let (eq, captures) = match [ast::BinOpKind::Eq, ast::BinOpKind::Ne]
+            .map(|op| comparison(op).match_expr(expr))
+        {
+            [Some(captures), _] => (true, captures),
+            [_, Some(captures)] => (false, captures),
+            _ => return,
+        };
+        let (value, b) = (captures[0], captures[1].is_true_literal());
+
+        let msg = "comparison to a boolean literal is redundant";
+        let Some(mut lint) = self.lint(codes::BOOL_COMPARISON, msg, expr.span) else { return };
+        let snippet = self.dcx().source_map().and_then(|sm| sm.span_to_snippet(value.span).ok());
+        if let Some(snippet) = snippet {
+            let (msg, replacement) = if b == eq {
+                ("use the value directly", snippet)
+            } else if matches!(
+                value.kind,
+                ast::ExprKind::Assign(..) | ast::ExprKind::Binary(..) | ast::ExprKind::Ternary(..)
+            ) {
+                ("negate the value instead", format!("!({snippet})"))
+            } else {
+                ("negate the value instead", format!("!{snippet}"))
+            };
+            lint =
+                lint.span_suggestion(expr.span, msg, replacement, Applicability::MachineApplicable);
+        }
+        lint.emit();
+    }
+
+    /// Warns about logical negations that can be removed, like `!(a == b)`, which is `a != b`.
+    ///
+    /// Negations of `&&` and `||` are only reported if De Morgan's laws remove all the negations,
+    /// like in `!(a == b && !c)`, which is `a != b || c`.
+    fn check_negation(&self, expr: &ast::Expr<'_>) {
+        let ast::ExprKind::Unary(op, operand) = &expr.kind else { return };
+        if op.kind != ast::UnOpKind::Not || !is_simplifiable_negation(operand) {
+            return;
+        }
+
+        let msg = "negation can be simplified";
+        let Some(mut lint) = self.lint(codes::SIMPLIFIABLE_NEGATION, msg, expr.span) else {
+            return;
+        };
+        if let Some((replacement, _)) =
+            self.dcx().source_map().and_then(|sm| simplified_negation(sm, operand))
+        {
+            // Removing a double negation never changes how the surrounding expression is
+            // parsed, but replacing a negation with a binary operation may.
+            let applicability = if matches!(operand.strip_parens().kind, ast::ExprKind::Unary(..)) {
+                Applicability::MachineApplicable
+            } else {
+                Applicability::MaybeIncorrect
+            };
+            lint =
+                lint.span_suggestion(expr.span, "remove the negation", replacement, applicability);
+        }
+        lint.emit();
+    }
+
+    /// Checks an expression used as a boolean condition.
+    ///
+    /// `is_operand` is `true` for the conditions of conditional expressions and the operands of
+    /// logical operators, and `false` for the conditions of statements.
+    fn check_condition(&self, cond: &ast::Expr<'_>, is_operand: bool) {
+        // Like in C, an assignment in extra parentheses is intended: `if ((a = b))`. Operands
+        // need one level of parentheses to be assignments, so it does not count.
+        let assign = match &cond.kind {
+            ast::ExprKind::Tuple(exprs) if is_operand => match &**exprs {
+                [Some(inner)] => &**inner,
+                _ => cond,
+            },
+            _ => cond,
+        };
+        if let ast::ExprKind::Assign(lhs, None, rhs) = &assign.kind {
+            self.check_assignment_in_condition(assign, lhs, rhs);
+        }
+        self.check_bitwise_logical_operation(cond.strip_parens(), true);
+    }
+
+    /// Warns about `&` and `|` operations that are likely meant to be `&&` and `||`.
+    ///
+    /// These are the operations with two operands that look like booleans, like
+    /// `(a < b) & (c > d)`, and, if `in_condition` is `true`, the operations with no operand that
+    /// looks like a number, like `if (a & b)`, since the result of a bitwise operation on
+    /// integers cannot be used as a condition. Bitwise operations on numbers elsewhere, like
+    /// `x & 0xff`, are never reported.
+    fn check_bitwise_logical_operation(&self, expr: &ast::Expr<'_>, in_condition: bool) {
+        let ast::ExprKind::Binary(lhs, op, rhs) = &expr.kind else { return };
+        let logical = match op.kind {
+            ast::BinOpKind::BitAnd => ast::BinOpKind::And,
+            ast::BinOpKind::BitOr => ast::BinOpKind::Or,
+            _ => return,
+        };
+        let (lhs, rhs) = (Boolness::of(lhs), Boolness::of(rhs));
+        let both_bool = lhs == Boolness::Bool && rhs == Boolness::Bool;
+        // Operations on booleans are reported when visiting them, wherever they are.
+        let report = if in_condition {
+            !both_bool && lhs != Boolness::NonBool && rhs != Boolness::NonBool
+        } else {
+            both_bool
+        };
+        if !report {
+            return;
+        }
+
+        let msg = format!("bitwise `{}` used as a logical operator", op.kind.to_str());
+        let Some(mut lint) = self.lint(codes::BITWISE_LOGICAL_OPERATION, msg, expr.span) else {
+            return;
+        };
+        let snippet = self.dcx().source_map().and_then(|sm| sm.span_to_snippet(expr.span).ok());
+        if let Some(snippet) = snippet {
+            let op_range = op.span.to_range();
+            let start = op_range.start - expr.span.lo().to_usize();
+            let end = op_range.end - expr.span.lo().to_usize();
+            let replacement =
+                format!("{}{}{}", &snippet[..start], logical.to_str(), &snippet[end..]);
+            // `&&` and `||` have a lower precedence than `&` and `|`, which may change how the
+            // surrounding expression is parsed.
+            lint = lint.span_suggestion(
+                expr.span,
+                format!("use `{}` instead", logical.to_str()),
+                replacement,
+                Applicability::MaybeIncorrect,
+            );
+        }
+        lint.emit();
+    }
+
+    /// Warns about an assignment used as a condition, like `if (a = b)`, which is likely meant
+    /// to be the comparison `a == b`.
+    fn check_assignment_in_condition(
+        &self,
+        expr: &ast::Expr<'_>,
+        lhs: &ast::Expr<'_>,
+        rhs: &ast::Expr<'_>,
+    ) {
+        let msg = "assignment used as a condition";
+        let Some(mut lint) = self.lint(codes::ASSIGNMENT_IN_CONDITION, msg, expr.span) else {
+            return;
+        };
+        let snippets = self.dcx().source_map().and_then(|sm| {
+            Some((sm.span_to_snippet(lhs.span).ok()?, sm.span_to_snippet(rhs.span).ok()?))
+        });
+        if let Some((lhs, mut rhs_snippet)) = snippets {
+            // Keep the value grouped if `==` binds tighter than its operator.
+            let needs_parens = match &rhs.kind {
+                ast::ExprKind::Assign(..) | ast::ExprKind::Ternary(..) => true,
+                ast::ExprKind::Binary(_, op, _) => {
+                    op.kind.is_comparison()
+                        || matches!(op.kind, ast::BinOpKind::And | ast::BinOpKind::Or)
+                }
+                _ => false,
+            };
+            if needs_parens {
+                rhs_snippet = format!("({rhs_snippet})");
+            }
+            lint = lint.span_suggestion(
+                expr.span,
+                "use `==` to compare the values",
+                format!("{lhs} == {rhs_snippet}"),
+                Applicability::MaybeIncorrect,
+            );
+        }
+        lint.emit();
+    }
+}
+
+/// Whether an expression looks like a boolean, judging only from its syntax.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Boolness {
+    /// A comparison, a logical operation, or a boolean literal.
+    Bool,
+    /// A variable, member, call, or index, whose type is not known.
+    Unknown,
+    /// Any other expression, like a number literal or an arithmetic operation.
+    NonBool,
+}
+
+impl Boolness {
+    fn of(expr: &ast::Expr<'_>) -> Self {
+        match &expr.strip_parens().kind {
+            ast::ExprKind::Binary(_, op, _)
+                if op.kind.is_comparison()
+                    || matches!(op.kind, ast::BinOpKind::And | ast::BinOpKind::Or) =>
+            {
+                Self::Bool
+            }
+            ast::ExprKind::Unary(op, _) if op.kind == ast::UnOpKind::Not => Self::Bool,
+            ast::ExprKind::Lit(lit, _) if matches!(lit.kind, ast::LitKind::Bool(_)) => Self::Bool,
+            ast::ExprKind::Ident(_)
+            | ast::ExprKind::Member(..)
+            | ast::ExprKind::Call(..)
+            | ast::ExprKind::Index(..) => Self::Unknown,
+            _ => Self::NonBool,
+        }
+    }
+}
+
+/// Returns `true` if `!expr` can be written without a negation.
+fn is_simplifiable_negation(expr: &ast::Expr<'_>) -> bool {
+    match &expr.strip_parens().kind {
+        ast::ExprKind::Unary(op, _) => op.kind == ast::UnOpKind::Not,
+        ast::ExprKind::Binary(lhs, op, rhs) => match op.kind {
+            ast::BinOpKind::And | ast::BinOpKind::Or => {
+                is_simplifiable_negation(lhs) && is_simplifiable_negation(rhs)
+            }
+            op => op.is_comparison(),
+        },
+        _ => false,
+    }
+}
+
+/// Returns the source code of `!expr` without negations, and its top-level operator if it is
+/// `&&` or `||`.
+///
+/// See [`is_simplifiable_negation`] and [`ast::Expr::simplify_negations`].
+fn simplified_negation(
+    sm: &SourceMap,
+    expr: &ast::Expr<'_>,
+) -> Option<(String, Option<ast::BinOpKind>)> {
+    let snippet = |expr: &ast::Expr<'_>| sm.span_to_snippet(expr.span).ok();
+    match &expr.strip_parens().kind {
+        ast::ExprKind::Unary(op, operand) if op.kind == ast::UnOpKind::Not => {
+            Some((snippet(operand)?, None))
+        }
+        ast::ExprKind::Binary(lhs, op, rhs) => match op.kind {
+            ast::BinOpKind::And | ast::BinOpKind::Or => {
+                let outer = if op.kind == ast::BinOpKind::And {
+                    ast::BinOpKind::Or
+                } else {
+                    ast::BinOpKind::And
+                };
+                // `&&` binds tighter than `||`.
+                let operand = |expr: &ast::Expr<'_>| {
+                    let (s, top) = simplified_negation(sm, expr)?;
+                    let needs_parens =
+                        outer == ast::BinOpKind::And && top == Some(ast::BinOpKind::Or);
+                    Some(if needs_parens { format!("({s})") } else { s })
+                };
+                let s = format!("{} {} {}", operand(lhs)?, outer.to_str(), operand(rhs)?);
+                Some((s, Some(outer)))
+            }
+            op => {
+                let s = format!("{} {} {}", snippet(lhs)?, op.negate()?.to_str(), snippet(rhs)?);
+                Some((s, None))
+            }
+        },
+        _ => None,
+    }
 }
 
 impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
@@ -99,9 +593,25 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
 
     fn visit_item(&mut self, item: &'ast ast::Item<'ast>) -> ControlFlow<Self::BreakValue> {
         self.item_span = item.span;
+        // Variables are checked in `visit_variable_definition`.
+        if !matches!(item.kind, ast::ItemKind::Variable(_)) {
+            if let Some(name) = item.name() {
+                self.check_builtin_shadowing(name);
+            }
+        }
         self.walk_item(item)
     }
 
+    fn visit_variable_definition(
+        &mut self,
+        var: &'ast ast::VariableDefinition<'ast>,
+    ) -> ControlFlow<Self::BreakValue> {
+        if let Some(name) = var.name {
+            self.check_builtin_shadowing(name);
+        }
+        self.walk_variable_definition(var)
+    }
+
     fn visit_item_struct(
         &mut self,
         item: &'ast ast::ItemStruct<'ast>,
@@ -162,6 +672,13 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
     }
 
     fn visit_stmt(&mut self, stmt: &'ast ast::Stmt<'ast>) -> ControlFlow<Self::BreakValue> {
+        if let ast::StmtKind::If(cond, ..)
+        | ast::StmtKind::While(cond, _)
+        | ast::StmtKind::DoWhile(_, cond)
+        | ast::StmtKind::For { cond: Some(cond), .. } = &stmt.kind
+        {
+            self.check_condition(cond, false);
+        }
         match &stmt.kind {
             ast::StmtKind::While(_, body)
             | ast::StmtKind::DoWhile(body, _)
@@ -200,6 +717,20 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
                 self.in_unchecked_block = prev;
                 return r;
             }
+            ast::StmtKind::Try(try_) => {
+                for clause in try_.catch_clauses() {
+                    if let ast::CatchKind::Custom(name) = clause.catch_kind() {
+                        self.dcx()
+                            .err("invalid catch clause name")
+                            .code(codes::INVALID_CATCH_CLAUSE_NAME)
+                            .span(name.span)
+                            .help(
+                                "expected `catch (...)`, `catch Error(...)`, or `catch Panic(...)`",
+                            )
+                            .emit();
+                    }
+                }
+            }
             ast::StmtKind::Placeholder => {
                 self.placeholder_count += 1;
                 if !self.function_kind.is_some_and(|k| k.is_modifier()) {
@@ -373,6 +904,12 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
         if *global && self.contract.is_some() {
             self.dcx().err("`global` can only be used at file level").span(self.item_span).emit();
         }
+        if !*global && using.operators().next().is_some() {
+            self.dcx()
+                .err("user-defined operators can only be defined in a global `using for` directive")
+                .span(self.item_span)
+                .emit();
+        }
         if let Some(contract) = self.contract {
             if contract.kind.is_interface() {
                 self.dcx()
@@ -385,10 +922,48 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
     }
 
     fn visit_expr(&mut self, expr: &'ast ast::Expr<'ast>) -> ControlFlow<Self::BreakValue> {
+        let in_assign_target = std::mem::take(&mut self.in_assign_target);
         let ast::Expr { kind, .. } = expr;
         if let ast::ExprKind::Lit(lit, _) = kind {
             self.check_underscores_in_number_literals(lit);
         }
+        self.check_chained_comparison(expr);
+        self.check_small_power(expr);
+        self.check_bool_comparison(expr);
+        self.check_negation(expr);
+        self.check_division_by_zero(expr);
+        self.check_call_options(expr);
+        self.check_bitwise_logical_operation(expr, false);
+        match kind {
+            ast::ExprKind::Binary(lhs, op, rhs)
+                if matches!(op.kind, ast::BinOpKind::And | ast::BinOpKind::Or) =>
+            {
+                self.check_condition(lhs, true);
+                self.check_condition(rhs, true);
+            }
+            ast::ExprKind::Unary(op, operand) if op.kind == ast::UnOpKind::Not => {
+                self.check_condition(operand, true)
+            }
+            ast::ExprKind::Ternary(cond, ..) => self.check_condition(cond, true),
+            _ => {}
+        }
+        match kind {
+            ast::ExprKind::Assign(lhs, op, rhs) => {
+                self.check_assign_target(lhs, *op);
+                self.in_assign_target = true;
+                self.visit_expr(lhs)?;
+                return self.visit_expr(rhs);
+            }
+            ast::ExprKind::Tuple(exprs) if in_assign_target => {
+                for expr in exprs.iter().flatten() {
+                    self.in_assign_target = true;
+                    self.visit_expr(expr)?;
+                }
+                return ControlFlow::Continue(());
+            }
+            ast::ExprKind::Tuple(_) => self.check_tuple_components(expr),
+            _ => {}
+        }
         self.walk_expr(expr)
     }
 
@@ -407,3 +982,64 @@ impl<'ast> Visit<'ast> for AstValidator<'_, 'ast> {
         self.walk_ty(ty)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::{source_map::FileName, ColorChoice};
+    use solar_parse::Parser;
+
+    #[test]
+    fn shadowed_builtins() {
+        let src = "{
+            function(bool) pure require = check;
+            require(x > 0);
+            assert(x < 10);
+            y = s.require + keccak256;
+            if (ok) require(ok);
+            // solar-disable-next-line 0511
+            require(ok);
+        }";
+
+        solar_interface::enter(|| {
+            let sess = Session::builder().with_buffer_emitter(ColorChoice::Never).build();
+            let arena = ast::Arena::new();
+            let name = FileName::custom("body");
+            let mut parser = Parser::from_source_code(&sess, &arena, name, src.into()).unwrap();
+            let stmt = parser.parse_stmt().map_err(|e| e.emit()).unwrap();
+            let ast::StmtKind::Block(body) = &stmt.kind else { panic!("not a block") };
+            let shadowed = HashSet::from([sym::require, Symbol::intern("x")]);
+
+            // Not shadowed.
+            assert!(shadowed_builtin_uses(body, &HashSet::new()).is_empty());
+            assert!(shadowed_builtin_uses(body, &HashSet::from([Symbol::intern("x")])).is_empty());
+
+            // The two calls to `require`, but not the declaration or the member.
+            let sm = sess.source_map();
+            let uses = shadowed_builtin_uses(body, &shadowed)
+                .iter()
+                .map(|ident| {
+                    let snippet = sm.span_to_snippet(ident.span).unwrap();
+                    (snippet, sm.lookup_char_pos(ident.span.lo()).line)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(uses, [3, 6, 8].map(|line| ("require".to_string(), line)));
+
+            // The last call is suppressed.
+            let file = sm.lookup_source_file(stmt.span.lo());
+            lint_shadowed_builtins(&sess, &file, body, &shadowed);
+            let diags = sess.emitted_diagnostics().unwrap().to_string();
+            assert_eq!(
+                diags.matches("warning[0511]: use of shadowed builtin `require`").count(),
+                2
+            );
+            assert!(
+                diags.contains(
+                    "`require` refers to a local declaration, not to the builtin `require`"
+                ),
+                "{diags}"
+            );
+            assert!(!diags.contains("error"), "{diags}");
+        });
+    }
+}
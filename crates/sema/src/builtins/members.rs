@@ -52,7 +52,7 @@ pub(crate) fn members_of<'gcx>(gcx: Gcx<'gcx>, ty: Ty<'gcx>) -> MemberList<'gcx>
             .map(|&b| Member::of_builtin(gcx, b))
             .collect(),
         TyKind::Type(_ty) => type_type(gcx, ty),
-        TyKind::Meta(_ty) => meta(gcx, ty),
+        TyKind::Meta(ty) => meta(gcx, ty),
         TyKind::Err(_guar) => Default::default(),
     })
 }
@@ -306,4 +306,11 @@ mod tests {
         assert_eq!(slice[1] as usize, 1);
         assert_eq!(slice[2] as usize, 2);
     }
+
+    #[test]
+    fn global_names() {
+        for builtin in Builtin::global().iter().copied().chain([Builtin::This, Builtin::Super]) {
+            assert!(builtin.name().is_builtin_global(), "{builtin:?}");
+        }
+    }
 }
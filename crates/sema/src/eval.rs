@@ -52,9 +52,23 @@ impl<'gcx> ConstantEvaluator<'gcx> {
     pub fn emit_eval_error(&self, expr: &hir::Expr<'_>, err: EvalError) -> ErrorGuaranteed {
         match err.kind {
             EE::AlreadyEmitted(guar) => guar,
+            // Point at the operation that overflows, not at the whole expression.
+            EE::ArithmeticOverflow(overflow) => self
+                .gcx
+                .dcx()
+                .err("evaluation of constant value failed")
+                .span(err.span)
+                .span_label(err.span, overflow.to_string())
+                .note(Overflow::RANGE_NOTE)
+                .emit(),
             _ => {
                 let msg = "evaluation of constant value failed";
-                self.gcx.dcx().err(msg).span(expr.span).span_note(err.span, err.kind.msg()).emit()
+                self.gcx
+                    .dcx()
+                    .err(msg)
+                    .span(expr.span)
+                    .span_note(err.span, err.kind.to_string())
+                    .emit()
             }
         }
     }
@@ -67,7 +81,10 @@ impl<'gcx> ConstantEvaluator<'gcx> {
             hir::ExprKind::Binary(l, bin_op, r) => {
                 let l = self.try_eval(l)?;
                 let r = self.try_eval(r)?;
-                l.binop(&r, bin_op.kind).map_err(Into::into)
+                l.binop(&r, bin_op.kind).map_err(|kind| match kind {
+                    EE::ArithmeticOverflow(_) => kind.spanned(expr.span),
+                    _ => kind.into(),
+                })
             }
             // hir::ExprKind::Call(_, _) => unimplemented!(),
             // hir::ExprKind::CallOptions(_, _) => unimplemented!(),
@@ -168,6 +185,7 @@ impl IntScalar {
     /// Applies the given binary operation to this value.
     pub fn binop(&self, r: &Self, op: hir::BinOpKind) -> Result<Self, EE> {
         let l = self;
+        let overflow = || EE::ArithmeticOverflow(Overflow::new(op, l.data, r.data));
         Ok(match op {
             hir::BinOpKind::Lt => Self::from_bool(l.data < r.data),
             hir::BinOpKind::Le => Self::from_bool(l.data <= r.data),
@@ -187,18 +205,10 @@ impl IntScalar {
             hir::BinOpKind::Sar => {
                 Self::new(l.data.arithmetic_shr(r.data.try_into().unwrap_or(usize::MAX)))
             }
-            hir::BinOpKind::Add => {
-                Self::new(l.data.checked_add(r.data).ok_or(EE::ArithmeticOverflow)?)
-            }
-            hir::BinOpKind::Sub => {
-                Self::new(l.data.checked_sub(r.data).ok_or(EE::ArithmeticOverflow)?)
-            }
-            hir::BinOpKind::Pow => {
-                Self::new(l.data.checked_pow(r.data).ok_or(EE::ArithmeticOverflow)?)
-            }
-            hir::BinOpKind::Mul => {
-                Self::new(l.data.checked_mul(r.data).ok_or(EE::ArithmeticOverflow)?)
-            }
+            hir::BinOpKind::Add => Self::new(l.data.checked_add(r.data).ok_or_else(overflow)?),
+            hir::BinOpKind::Sub => Self::new(l.data.checked_sub(r.data).ok_or_else(overflow)?),
+            hir::BinOpKind::Pow => Self::new(l.data.checked_pow(r.data).ok_or_else(overflow)?),
+            hir::BinOpKind::Mul => Self::new(l.data.checked_mul(r.data).ok_or_else(overflow)?),
             hir::BinOpKind::Div => Self::new(l.data.checked_div(r.data).ok_or(EE::DivisionByZero)?),
             hir::BinOpKind::Rem => Self::new(l.data.checked_rem(r.data).ok_or(EE::DivisionByZero)?),
         })
@@ -208,7 +218,7 @@ impl IntScalar {
 #[derive(Debug)]
 pub enum EvalErrorKind {
     RecursionLimitReached,
-    ArithmeticOverflow,
+    ArithmeticOverflow(Overflow),
     IntTooBig,
     DivisionByZero,
     UnsupportedLiteral,
@@ -223,11 +233,13 @@ impl EvalErrorKind {
     pub fn spanned(self, span: Span) -> EvalError {
         EvalError { kind: self, span }
     }
+}
 
-    fn msg(&self) -> &'static str {
-        match self {
+impl fmt::Display for EvalErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
             Self::RecursionLimitReached => "recursion limit reached",
-            Self::ArithmeticOverflow => "arithmetic overflow",
+            Self::ArithmeticOverflow(overflow) => return overflow.fmt(f),
             Self::IntTooBig => "integer value is too big",
             Self::DivisionByZero => "division by zero",
             Self::UnsupportedLiteral => "unsupported literal",
@@ -235,7 +247,44 @@ impl EvalErrorKind {
             Self::UnsupportedExpr => "unsupported expression",
             Self::NonConstantVar => "only constant variables are allowed",
             Self::AlreadyEmitted(_) => "error already emitted",
-        }
+        })
+    }
+}
+
+/// An arithmetic operation whose result is out of the range of `uint256`, like `0 - 1`.
+///
+/// Constants are evaluated without their types, so every operation is checked as `uint256`.
+///
+/// Displayed as the operation and the type: `` `0 - 1` underflows `uint256` ``.
+#[derive(Debug)]
+pub struct Overflow {
+    /// The operation.
+    pub op: hir::BinOpKind,
+    /// The value of the left operand.
+    pub lhs: U256,
+    /// The value of the right operand.
+    pub rhs: U256,
+}
+
+impl Overflow {
+    /// A note with the range of `uint256`.
+    pub const RANGE_NOTE: &'static str = "the range of `uint256` is 0 to 2**256 - 1";
+
+    fn new(op: hir::BinOpKind, lhs: U256, rhs: U256) -> Self {
+        Self { op, lhs, rhs }
+    }
+
+    /// Returns `true` if the result is below the range of the type.
+    pub fn is_underflow(&self) -> bool {
+        self.op == hir::BinOpKind::Sub && self.lhs < self.rhs
+    }
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { op, lhs, rhs } = self;
+        let flows = if self.is_underflow() { "underflows" } else { "overflows" };
+        write!(f, "`{lhs} {op} {rhs}` {flows} `uint256`", op = op.to_str())
     }
 }
 
@@ -253,8 +302,42 @@ impl From<EE> for EvalError {
 
 impl fmt::Display for EvalError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.kind.msg().fmt(f)
+        self.kind.fmt(f)
     }
 }
 
 impl std::error::Error for EvalError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binop(l: u64, op: hir::BinOpKind, r: u64) -> Result<U256, EE> {
+        IntScalar::new(U256::from(l)).binop(&IntScalar::new(U256::from(r)), op).map(|v| v.data)
+    }
+
+    #[test]
+    fn overflow() {
+        use hir::BinOpKind::*;
+
+        let max = U256::MAX;
+        let Err(EE::ArithmeticOverflow(overflow)) =
+            IntScalar::new(max).binop(&IntScalar::new(U256::from(256)), Mul)
+        else {
+            panic!("expected an overflow");
+        };
+        assert_eq!((overflow.op, overflow.lhs, overflow.rhs), (Mul, max, U256::from(256)));
+        assert!(!overflow.is_underflow());
+        assert_eq!(overflow.to_string(), format!("`{max} * 256` overflows `uint256`"));
+
+        let Err(EE::ArithmeticOverflow(overflow)) = binop(0, Sub, 1) else {
+            panic!("expected an underflow");
+        };
+        assert!(overflow.is_underflow());
+        assert_eq!(overflow.to_string(), "`0 - 1` underflows `uint256`");
+
+        assert!(matches!(binop(2, Pow, 256), Err(EE::ArithmeticOverflow(_))));
+        assert_eq!(binop(256, Mul, 256).unwrap(), U256::from(65536));
+        assert!(matches!(binop(1, Div, 0), Err(EE::DivisionByZero)));
+    }
+}
@@ -0,0 +1,362 @@
+//! Classification of the call expressions in the HIR.
+
+use super::*;
+use solar_data_structures::{map::FxHashMap, Never};
+use solar_interface::Symbol;
+
+/// What a call expression calls, as determined from name resolution alone.
+///
+/// Calls through members of values, like `x.f()`, can only be resolved with type information,
+/// which includes functions bound with `using for`. These are all classified as
+/// [`Member`](Self::Member).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CallResolution {
+    /// A call to a free function by name: `f()`, or `M.f()` where `M` is an import namespace.
+    Free,
+    /// A call to a function of a contract, including inherited functions, by name: `f()` inside
+    /// of the contract, or `C.f()` where `C` is a contract or an interface.
+    Internal,
+    /// A call to a function of a library through the library name: `Lib.f()`.
+    Library(ContractId),
+    /// A call to a member of a value: `x.f()`, `this.f()`, `a.push()`.
+    Member,
+    /// A call to a builtin function: `keccak256(x)`, `abi.encode(x)`.
+    Builtin(Builtin),
+    /// A call through a variable of a function type: `callback()`.
+    Variable,
+    /// Any other call, like a type conversion, a struct constructor, or a `new` expression.
+    Other,
+}
+
+impl CallResolution {
+    /// Classifies a call to `callee`.
+    ///
+    /// Overloaded names are classified by their first candidate, since every candidate of an
+    /// overloaded name is of the same kind.
+    pub fn of(callee: &Expr<'_>, resolver: &impl CallResolver) -> Self {
+        match callee.peel_parens().kind {
+            ExprKind::Ident(res) => match res.first() {
+                Some(&Res::Item(ItemId::Function(id))) => {
+                    if resolver.is_free_function(id) {
+                        Self::Free
+                    } else {
+                        Self::Internal
+                    }
+                }
+                Some(Res::Item(ItemId::Variable(_))) => Self::Variable,
+                Some(&Res::Builtin(builtin)) => Self::Builtin(builtin),
+                _ => Self::Other,
+            },
+            ExprKind::Member(base, member) => match base.peel_parens().kind {
+                ExprKind::Ident(&[Res::Item(ItemId::Contract(id))]) => {
+                    if !resolver.contract_has_function(id, member.name) {
+                        Self::Other
+                    } else if resolver.is_library(id) {
+                        Self::Library(id)
+                    } else {
+                        Self::Internal
+                    }
+                }
+                ExprKind::Ident(&[Res::Namespace(id)]) => {
+                    if resolver.source_has_function(id, member.name) {
+                        Self::Free
+                    } else {
+                        Self::Other
+                    }
+                }
+                ExprKind::Ident(&[Res::Builtin(builtin)]) => {
+                    match builtin.members().and_then(|members| {
+                        members.iter().find(|b| b.name() == member.name).copied()
+                    }) {
+                        Some(builtin) => Self::Builtin(builtin),
+                        None => Self::Member,
+                    }
+                }
+                _ => Self::Member,
+            },
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The information about items needed to [classify](CallResolution::of) calls.
+///
+/// This is implemented for [`Hir`].
+pub trait CallResolver {
+    /// Returns `true` if the function is a free function.
+    fn is_free_function(&self, id: FunctionId) -> bool;
+
+    /// Returns `true` if the contract is a library.
+    fn is_library(&self, id: ContractId) -> bool;
+
+    /// Returns `true` if the contract declares or inherits a function with the given name.
+    fn contract_has_function(&self, id: ContractId, name: Symbol) -> bool;
+
+    /// Returns `true` if the source declares a free function with the given name.
+    fn source_has_function(&self, id: SourceId, name: Symbol) -> bool;
+}
+
+impl CallResolver for Hir<'_> {
+    fn is_free_function(&self, id: FunctionId) -> bool {
+        self.function(id).is_free()
+    }
+
+    fn is_library(&self, id: ContractId) -> bool {
+        self.contract(id).kind.is_library()
+    }
+
+    fn contract_has_function(&self, id: ContractId, name: Symbol) -> bool {
+        self.contract_item_ids(id)
+            .filter_map(ItemId::as_function)
+            .any(|f| self.function(f).name.is_some_and(|n| n.name == name))
+    }
+
+    fn source_has_function(&self, id: SourceId, name: Symbol) -> bool {
+        self.source(id).items.iter().filter_map(ItemId::as_function).any(|f| {
+            let f = self.function(f);
+            f.is_free() && f.name.is_some_and(|n| n.name == name)
+        })
+    }
+}
+
+/// The [resolutions](CallResolution) of the call expressions in a program, by expression ID.
+#[derive(Clone, Debug, Default)]
+pub struct CallResolutions {
+    map: FxHashMap<ExprId, CallResolution>,
+}
+
+impl CallResolutions {
+    /// Classifies all the calls in the function bodies and variable initializers of `hir`.
+    pub fn new<'hir>(hir: &'hir Hir<'hir>) -> Self {
+        let mut collector = Collector { hir, resolutions: Self::default() };
+        for id in hir.function_ids() {
+            let _ = collector.visit_nested_function(id);
+        }
+        for id in hir.variable_ids() {
+            if let Some(init) = hir.variable(id).initializer {
+                let _ = collector.visit_expr(init);
+            }
+        }
+        collector.resolutions
+    }
+
+    /// Classifies the calls in `expr` and its subexpressions.
+    pub fn add_expr(&mut self, expr: &Expr<'_>, resolver: &impl CallResolver) {
+        let mut stack = vec![expr];
+        while let Some(expr) = stack.pop() {
+            self.add_call(expr, resolver);
+            subexprs(expr, |sub| stack.push(sub));
+        }
+    }
+
+    fn add_call(&mut self, expr: &Expr<'_>, resolver: &impl CallResolver) {
+        if let ExprKind::Call(callee, ..) = expr.kind {
+            self.map.insert(expr.id, CallResolution::of(callee, resolver));
+        }
+    }
+
+    /// Returns the resolution of the given call expression. Returns `None` if the expression is
+    /// not a call.
+    pub fn get(&self, id: ExprId) -> Option<CallResolution> {
+        self.map.get(&id).copied()
+    }
+
+    /// Returns the number of calls.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if there are no calls.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the calls and their resolutions, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (ExprId, CallResolution)> + '_ {
+        self.map.iter().map(|(&id, &res)| (id, res))
+    }
+}
+
+/// Calls `f` with the direct subexpressions of `expr`, including call arguments, which the
+/// [visitor](Visit) does not visit. Expressions in types are not included.
+pub(super) fn subexprs<'a, 'hir>(expr: &'a Expr<'hir>, mut f: impl FnMut(&'a Expr<'hir>)) {
+    match expr.kind {
+        ExprKind::Call(callee, ref args, opts) => {
+            f(callee);
+            args.exprs().for_each(&mut f);
+            opts.into_iter().flatten().for_each(|opt| f(&opt.value));
+        }
+        ExprKind::Delete(expr)
+        | ExprKind::Member(expr, _)
+        | ExprKind::Payable(expr)
+        | ExprKind::Unary(_, expr) => f(expr),
+        ExprKind::Assign(lhs, _, rhs) | ExprKind::Binary(lhs, _, rhs) => {
+            f(lhs);
+            f(rhs);
+        }
+        ExprKind::Index(expr, index) => {
+            f(expr);
+            index.into_iter().for_each(f);
+        }
+        ExprKind::Slice(expr, start, end) => {
+            f(expr);
+            start.into_iter().chain(end).for_each(f);
+        }
+        ExprKind::Ternary(cond, true_, false_) => {
+            f(cond);
+            f(true_);
+            f(false_);
+        }
+        ExprKind::Array(exprs) => exprs.iter().for_each(f),
+        ExprKind::Tuple(exprs) => exprs.iter().flatten().copied().for_each(f),
+        ExprKind::Ident(_)
+        | ExprKind::Lit(_)
+        | ExprKind::New(_)
+        | ExprKind::TypeCall(_)
+        | ExprKind::Type(_)
+        | ExprKind::Err(_) => {}
+    }
+}
+
+struct Collector<'hir> {
+    hir: &'hir Hir<'hir>,
+    resolutions: CallResolutions,
+}
+
+impl<'hir> Visit<'hir> for Collector<'hir> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'hir Hir<'hir> {
+        self.hir
+    }
+
+    fn visit_expr(&mut self, expr: &'hir Expr<'hir>) -> ControlFlow<Self::BreakValue> {
+        // Only the outermost expressions are visited, and they are classified in full here.
+        self.resolutions.add_expr(expr, self.hir);
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::sym;
+
+    /// A resolver with free function `0`, contract function `1`, library `0` with function `f`,
+    /// contract `1` with function `g`, and source `0` with free function `h`.
+    struct MockResolver;
+
+    impl CallResolver for MockResolver {
+        fn is_free_function(&self, id: FunctionId) -> bool {
+            id == FunctionId::new(0)
+        }
+
+        fn is_library(&self, id: ContractId) -> bool {
+            id == ContractId::new(0)
+        }
+
+        fn contract_has_function(&self, id: ContractId, name: Symbol) -> bool {
+            match id.index() {
+                0 => name.as_str() == "f",
+                1 => name.as_str() == "g",
+                _ => false,
+            }
+        }
+
+        fn source_has_function(&self, id: SourceId, name: Symbol) -> bool {
+            id == SourceId::new(0) && name.as_str() == "h"
+        }
+    }
+
+    fn ident<'a>(arena: &'a Arena, res: Res) -> ExprKind<'a> {
+        ExprKind::Ident(arena.alloc_slice_copy(&[res]))
+    }
+
+    fn member<'a>(arena: &'a Arena, base: Res, name: &str) -> &'a Expr<'a> {
+        let base =
+            arena.alloc(Expr { id: ExprId::new(0), kind: ident(arena, base), span: Span::DUMMY });
+        arena.alloc(Expr {
+            id: ExprId::new(0),
+            kind: ExprKind::Member(base, Ident::from_str(name)),
+            span: Span::DUMMY,
+        })
+    }
+
+    fn classify(callee: &Expr<'_>) -> CallResolution {
+        CallResolution::of(callee, &MockResolver)
+    }
+
+    #[test]
+    fn call_resolution() {
+        solar_interface::enter(|| {
+            let arena = &Arena::new();
+            let mk = |kind| Expr { id: ExprId::new(0), kind, span: Span::DUMMY };
+            let free = Res::Item(ItemId::Function(FunctionId::new(0)));
+            let internal = Res::Item(ItemId::Function(FunctionId::new(1)));
+            let library = Res::Item(ItemId::Contract(ContractId::new(0)));
+            let contract = Res::Item(ItemId::Contract(ContractId::new(1)));
+            let namespace = Res::Namespace(SourceId::new(0));
+            let variable = Res::Item(ItemId::Variable(VariableId::new(0)));
+
+            assert_eq!(classify(&mk(ident(arena, free))), CallResolution::Free);
+            assert_eq!(classify(&mk(ident(arena, internal))), CallResolution::Internal);
+            assert_eq!(classify(&mk(ident(arena, variable))), CallResolution::Variable);
+            assert_eq!(classify(&mk(ident(arena, contract))), CallResolution::Other);
+            assert_eq!(
+                classify(&mk(ident(arena, Res::Builtin(Builtin::Keccak256)))),
+                CallResolution::Builtin(Builtin::Keccak256)
+            );
+
+            // `Lib.f()`
+            assert_eq!(
+                classify(member(arena, library, "f")),
+                CallResolution::Library(ContractId::new(0))
+            );
+            // `(Lib).f()`
+            let parens = arena.alloc_slice_copy(&[Some(member(arena, library, "f"))]);
+            assert_eq!(
+                classify(&mk(ExprKind::Tuple(parens))),
+                CallResolution::Library(ContractId::new(0))
+            );
+            // `Lib.S()`, a struct constructor.
+            assert_eq!(classify(member(arena, library, "S")), CallResolution::Other);
+            // `C.g()`
+            assert_eq!(classify(member(arena, contract, "g")), CallResolution::Internal);
+            // `M.h()`
+            assert_eq!(classify(member(arena, namespace, "h")), CallResolution::Free);
+            // `x.f()`
+            assert_eq!(classify(member(arena, variable, "f")), CallResolution::Member);
+            // `abi.encode()`
+            assert_eq!(
+                classify(member(arena, Res::Builtin(Builtin::Abi), sym::encode.as_str())),
+                CallResolution::Builtin(Builtin::AbiEncode)
+            );
+        });
+    }
+
+    #[test]
+    fn side_table() {
+        solar_interface::enter(|| {
+            let arena = &Arena::new();
+            let mk = |id, kind| Expr { id: ExprId::new(id), kind, span: Span::DUMMY };
+            let library = Res::Item(ItemId::Contract(ContractId::new(0)));
+            let free = Res::Item(ItemId::Function(FunctionId::new(0)));
+
+            // `f(Lib.f())`
+            let inner = mk(1, ExprKind::Call(member(arena, library, "f"), CallArgs::empty(), None));
+            let args = arena.alloc_slice_fill_iter([inner]);
+            let callee = arena.alloc(mk(2, ident(arena, free)));
+            let outer = mk(3, ExprKind::Call(callee, CallArgs::Unnamed(args), None));
+
+            let mut table = CallResolutions::default();
+            table.add_expr(&outer, &MockResolver);
+            assert_eq!(table.len(), 2);
+            assert_eq!(table.get(ExprId::new(3)), Some(CallResolution::Free));
+            assert_eq!(
+                table.get(ExprId::new(1)),
+                Some(CallResolution::Library(ContractId::new(0)))
+            );
+            assert_eq!(table.get(ExprId::new(2)), None);
+        });
+    }
+}
@@ -0,0 +1,334 @@
+//! Resolution of the member access expressions in the HIR to the declarations they refer to.
+
+use super::{calls::subexprs, *};
+use solar_data_structures::{map::FxHashMap, Never};
+use solar_interface::Symbol;
+
+/// The declaration that a member access expression refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeclRef {
+    /// An item: a struct field, a contract member, or an item of an import namespace.
+    Item(ItemId),
+    /// A variant of an enum, by index: `E.A`.
+    EnumVariant(EnumId, usize),
+}
+
+impl DeclRef {
+    /// Resolves a member access expression, `base.member`, to the declaration it refers to.
+    ///
+    /// Only members that can be resolved without type checking are resolved:
+    /// - fields of struct variables, including nested fields: `s.field`, `s.inner.field`;
+    /// - members of contract variables and contract names: `c.f`, `C.f`;
+    /// - enum variants: `E.A`;
+    /// - items of import namespaces: `M.f`.
+    ///
+    /// Returns `None` for any other expression, like builtin members (`msg.sender`, `a.length`),
+    /// members of function results or of array elements, and overloaded members.
+    pub fn of(expr: &Expr<'_>, resolver: &impl MemberResolver) -> Option<Self> {
+        let ExprKind::Member(base, member) = expr.kind else { return None };
+        let name = member.name;
+        let base = base.peel_parens();
+        let item = match base.kind {
+            ExprKind::Ident(&[Res::Item(ItemId::Variable(id))]) => resolver.variable_type(id)?,
+            ExprKind::Ident(&[Res::Item(ItemId::Enum(id))]) => {
+                return resolver.enum_variant(id, name).map(|i| Self::EnumVariant(id, i));
+            }
+            ExprKind::Ident(&[Res::Item(ItemId::Contract(id))]) => {
+                return resolver.contract_member(id, name).map(Self::Item);
+            }
+            ExprKind::Ident(&[Res::Namespace(id)]) => {
+                return resolver.source_member(id, name).map(Self::Item);
+            }
+            ExprKind::Member(..) => match Self::of(base, resolver)? {
+                Self::Item(ItemId::Variable(id)) => resolver.variable_type(id)?,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        match item {
+            ItemId::Struct(id) => resolver.struct_field(id, name).map(|f| Self::Item(f.into())),
+            ItemId::Contract(id) => resolver.contract_member(id, name).map(Self::Item),
+            _ => None,
+        }
+    }
+}
+
+/// The information about items needed to [resolve](DeclRef::of) member accesses.
+///
+/// This is implemented for [`Hir`].
+pub trait MemberResolver {
+    /// Returns the item that the type of the variable refers to, if it is a user-defined type
+    /// like a struct or a contract.
+    fn variable_type(&self, id: VariableId) -> Option<ItemId>;
+
+    /// Returns the field of the struct with the given name.
+    fn struct_field(&self, id: StructId, name: Symbol) -> Option<VariableId>;
+
+    /// Returns the index of the variant of the enum with the given name.
+    fn enum_variant(&self, id: EnumId, name: Symbol) -> Option<usize>;
+
+    /// Returns the item of the contract, including inherited items, with the given name.
+    ///
+    /// Returns `None` if the name is overloaded.
+    fn contract_member(&self, id: ContractId, name: Symbol) -> Option<ItemId>;
+
+    /// Returns the item declared in the source with the given name.
+    ///
+    /// Returns `None` if the name is overloaded.
+    fn source_member(&self, id: SourceId, name: Symbol) -> Option<ItemId>;
+}
+
+impl MemberResolver for Hir<'_> {
+    fn variable_type(&self, id: VariableId) -> Option<ItemId> {
+        match self.variable(id).ty.kind {
+            TypeKind::Custom(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    fn struct_field(&self, id: StructId, name: Symbol) -> Option<VariableId> {
+        self.strukt(id)
+            .fields
+            .iter()
+            .copied()
+            .find(|&f| self.variable(f).name.is_some_and(|n| n.name == name))
+    }
+
+    fn enum_variant(&self, id: EnumId, name: Symbol) -> Option<usize> {
+        self.enumm(id).variants.iter().position(|v| v.name == name)
+    }
+
+    fn contract_member(&self, id: ContractId, name: Symbol) -> Option<ItemId> {
+        // The most derived declaration shadows the inherited ones, like an overriding function.
+        self.contract(id)
+            .linearized_bases
+            .iter()
+            .find_map(|&base| unique(self, self.contract(base).items, name))
+            .flatten()
+    }
+
+    fn source_member(&self, id: SourceId, name: Symbol) -> Option<ItemId> {
+        unique(self, self.source(id).items, name).flatten()
+    }
+}
+
+/// Returns the only item with the given name. Returns `Some(None)` if more than one item has the
+/// name, and `None` if none does.
+fn unique(hir: &Hir<'_>, items: &[ItemId], name: Symbol) -> Option<Option<ItemId>> {
+    let mut named =
+        items.iter().copied().filter(|&id| hir.item(id).name().is_some_and(|n| n.name == name));
+    let first = named.next()?;
+    Some(named.next().is_none().then_some(first))
+}
+
+/// The [declarations](DeclRef) that the member access expressions in a program refer to, by
+/// expression ID.
+#[derive(Clone, Debug, Default)]
+pub struct MemberTargets {
+    map: FxHashMap<ExprId, DeclRef>,
+}
+
+impl MemberTargets {
+    /// Resolves all the member accesses in the function bodies and variable initializers of
+    /// `hir`.
+    pub fn new<'hir>(hir: &'hir Hir<'hir>) -> Self {
+        let mut collector = Collector { hir, targets: Self::default() };
+        for id in hir.function_ids() {
+            let _ = collector.visit_nested_function(id);
+        }
+        for id in hir.variable_ids() {
+            if let Some(init) = hir.variable(id).initializer {
+                let _ = collector.visit_expr(init);
+            }
+        }
+        collector.targets
+    }
+
+    /// Resolves the member accesses in `expr` and its subexpressions.
+    pub fn add_expr(&mut self, expr: &Expr<'_>, resolver: &impl MemberResolver) {
+        let mut stack = vec![expr];
+        while let Some(expr) = stack.pop() {
+            if let Some(target) = DeclRef::of(expr, resolver) {
+                self.map.insert(expr.id, target);
+            }
+            subexprs(expr, |sub| stack.push(sub));
+        }
+    }
+
+    /// Returns the declaration that the given member access expression refers to. Returns `None`
+    /// if the expression is not a member access, or if its member could not be resolved.
+    pub fn member_target(&self, id: ExprId) -> Option<DeclRef> {
+        self.map.get(&id).copied()
+    }
+
+    /// Returns the number of resolved member accesses.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if there are no resolved member accesses.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the resolved member accesses and their declarations, in no
+    /// particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (ExprId, DeclRef)> + '_ {
+        self.map.iter().map(|(&id, &target)| (id, target))
+    }
+}
+
+struct Collector<'hir> {
+    hir: &'hir Hir<'hir>,
+    targets: MemberTargets,
+}
+
+impl<'hir> Visit<'hir> for Collector<'hir> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'hir Hir<'hir> {
+        self.hir
+    }
+
+    fn visit_expr(&mut self, expr: &'hir Expr<'hir>) -> ControlFlow<Self::BreakValue> {
+        // Only the outermost expressions are visited, and they are resolved in full here.
+        self.targets.add_expr(expr, self.hir);
+        ControlFlow::Continue(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A resolver with variable `0` of struct `0`, which has field `field` (variable `1`) of
+    /// struct `1`, which has field `inner` (variable `2`); variable `3` of contract `0`, which
+    /// has member `f` (function `0`); enum `0` with variants `A` and `B`; and source `0` with
+    /// item `g` (function `1`).
+    struct MockResolver;
+
+    impl MemberResolver for MockResolver {
+        fn variable_type(&self, id: VariableId) -> Option<ItemId> {
+            match id.index() {
+                0 => Some(StructId::new(0).into()),
+                1 => Some(StructId::new(1).into()),
+                3 => Some(ContractId::new(0).into()),
+                _ => None,
+            }
+        }
+
+        fn struct_field(&self, id: StructId, name: Symbol) -> Option<VariableId> {
+            match (id.index(), name.as_str()) {
+                (0, "field") => Some(VariableId::new(1)),
+                (1, "inner") => Some(VariableId::new(2)),
+                _ => None,
+            }
+        }
+
+        fn enum_variant(&self, id: EnumId, name: Symbol) -> Option<usize> {
+            if id != EnumId::new(0) {
+                return None;
+            }
+            ["A", "B"].iter().position(|&v| v == name.as_str())
+        }
+
+        fn contract_member(&self, id: ContractId, name: Symbol) -> Option<ItemId> {
+            (id == ContractId::new(0) && name.as_str() == "f").then(|| FunctionId::new(0).into())
+        }
+
+        fn source_member(&self, id: SourceId, name: Symbol) -> Option<ItemId> {
+            (id == SourceId::new(0) && name.as_str() == "g").then(|| FunctionId::new(1).into())
+        }
+    }
+
+    fn mk(id: usize, kind: ExprKind<'_>) -> Expr<'_> {
+        Expr { id: ExprId::new(id), kind, span: Span::DUMMY }
+    }
+
+    fn ident<'a>(arena: &'a Arena, res: Res) -> &'a Expr<'a> {
+        arena.alloc(mk(0, ExprKind::Ident(arena.alloc_slice_copy(&[res]))))
+    }
+
+    fn member<'a>(arena: &'a Arena, base: &'a Expr<'a>, name: &str) -> &'a Expr<'a> {
+        arena.alloc(mk(0, ExprKind::Member(base, Ident::from_str(name))))
+    }
+
+    fn resolve(expr: &Expr<'_>) -> Option<DeclRef> {
+        DeclRef::of(expr, &MockResolver)
+    }
+
+    #[test]
+    fn decl_ref() {
+        solar_interface::enter(|| {
+            let arena = &Arena::new();
+            let var = |id| ident(arena, Res::Item(ItemId::Variable(VariableId::new(id))));
+            let field = |id| Some(DeclRef::Item(ItemId::Variable(VariableId::new(id))));
+            let s = var(0);
+
+            // `s.field`
+            assert_eq!(resolve(member(arena, s, "field")), field(1));
+            // `(s).field`
+            let parens = arena.alloc_slice_copy(&[Some(s)]);
+            let parens = arena.alloc(mk(0, ExprKind::Tuple(parens)));
+            assert_eq!(resolve(member(arena, parens, "field")), field(1));
+            // `s.field.inner`
+            let nested = member(arena, member(arena, s, "field"), "inner");
+            assert_eq!(resolve(nested), field(2));
+            // `c.f`
+            let function = Some(DeclRef::Item(ItemId::Function(FunctionId::new(0))));
+            assert_eq!(resolve(member(arena, var(3), "f")), function);
+            // `C.f`
+            let contract = ident(arena, Res::Item(ItemId::Contract(ContractId::new(0))));
+            assert_eq!(resolve(member(arena, contract, "f")), function);
+            // `E.B`
+            let enumm = ident(arena, Res::Item(ItemId::Enum(EnumId::new(0))));
+            assert_eq!(
+                resolve(member(arena, enumm, "B")),
+                Some(DeclRef::EnumVariant(EnumId::new(0), 1))
+            );
+            // `M.g`
+            let namespace = ident(arena, Res::Namespace(SourceId::new(0)));
+            assert_eq!(
+                resolve(member(arena, namespace, "g")),
+                Some(DeclRef::Item(ItemId::Function(FunctionId::new(1))))
+            );
+
+            // Unresolved members.
+            assert_eq!(resolve(member(arena, s, "missing")), None);
+            assert_eq!(resolve(member(arena, member(arena, s, "missing"), "inner")), None);
+            assert_eq!(resolve(member(arena, enumm, "C")), None);
+            // `s.field.inner.x`, where `inner` is not of a struct type.
+            assert_eq!(resolve(member(arena, nested, "x")), None);
+            // `x.length`, where `x` is not of a user-defined type.
+            assert_eq!(resolve(member(arena, var(4), "length")), None);
+            // `msg.sender`
+            let msg = ident(arena, Res::Builtin(Builtin::Msg));
+            assert_eq!(resolve(member(arena, msg, "sender")), None);
+            // Not a member access.
+            assert_eq!(resolve(s), None);
+        });
+    }
+
+    #[test]
+    fn side_table() {
+        solar_interface::enter(|| {
+            let arena = &Arena::new();
+            let s = ident(arena, Res::Item(ItemId::Variable(VariableId::new(0))));
+
+            // `s.field = s.missing`
+            let lhs = arena.alloc(mk(1, ExprKind::Member(s, Ident::from_str("field"))));
+            let rhs = arena.alloc(mk(2, ExprKind::Member(s, Ident::from_str("missing"))));
+            let assign = mk(3, ExprKind::Assign(lhs, None, rhs));
+
+            let mut table = MemberTargets::default();
+            table.add_expr(&assign, &MockResolver);
+            assert_eq!(table.len(), 1);
+            assert_eq!(
+                table.member_target(ExprId::new(1)),
+                Some(DeclRef::Item(ItemId::Variable(VariableId::new(1))))
+            );
+            assert_eq!(table.member_target(ExprId::new(2)), None);
+            assert_eq!(table.member_target(ExprId::new(3)), None);
+        });
+    }
+}
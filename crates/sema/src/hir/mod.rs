@@ -11,10 +11,19 @@ use std::{fmt, ops::ControlFlow, sync::Arc};
 use strum::EnumIs;
 
 pub use ast::{
-    BinOp, BinOpKind, ContractKind, DataLocation, ElementaryType, FunctionKind, Lit,
+    BinOp, BinOpKind, ContractKind, DataLocation, ElementaryType, FunctionKind, Lit, SpecialIdent,
     StateMutability, UnOp, UnOpKind, VarMut, Visibility,
 };
 
+mod calls;
+pub use calls::{CallResolution, CallResolutions, CallResolver};
+
+mod members;
+pub use members::{DeclRef, MemberResolver, MemberTargets};
+
+mod storage;
+pub use storage::{Aliasing, StoragePath, StorageProjection};
+
 mod visit;
 pub use visit::Visit;
 
@@ -255,6 +264,16 @@ impl<'hir> Hir<'hir> {
     pub fn contract_items(&self, id: ContractId) -> impl Iterator<Item = Item<'_, 'hir>> + Clone {
         self.contract_item_ids(id).map(move |id| self.item(id))
     }
+
+    /// Returns the kind of the variable that the expression refers to, if it is an identifier
+    /// that resolves to a variable.
+    ///
+    /// This tells named return values, which are [`VarKind::FunctionReturn`], apart from
+    /// parameters, which are [`VarKind::FunctionParam`], and local variables, which are
+    /// [`VarKind::Statement`], including in `return` statements.
+    pub fn variable_kind_of(&self, expr: &Expr<'_>) -> Option<VarKind> {
+        expr.as_variable().map(|id| self.variable(id).kind)
+    }
 }
 
 newtype_index! {
@@ -1128,6 +1147,33 @@ impl Expr<'_> {
         }
         expr
     }
+
+    /// Returns the variable that the expression refers to, if it is an identifier that resolves
+    /// to a variable.
+    pub fn as_variable(&self) -> Option<VariableId> {
+        match self.kind {
+            ExprKind::Ident([res]) => res.as_variable(),
+            _ => None,
+        }
+    }
+
+    /// Returns the special identifier if the expression is an identifier that resolves to one of
+    /// the builtins `this`, `super`, `msg`, `tx`, or `block`.
+    ///
+    /// Unlike [`ast::Expr::special_identifier`], this uses name resolution, so identifiers that
+    /// refer to a declaration that shadows the builtin, like a local variable named `msg`, are not
+    /// classified.
+    pub fn special_identifier(&self) -> Option<SpecialIdent> {
+        let ExprKind::Ident([Res::Builtin(builtin)]) = self.kind else { return None };
+        match builtin {
+            Builtin::This => Some(SpecialIdent::This),
+            Builtin::Super => Some(SpecialIdent::Super),
+            Builtin::Msg => Some(SpecialIdent::Msg),
+            Builtin::Tx => Some(SpecialIdent::Tx),
+            Builtin::Block => Some(SpecialIdent::Block),
+            _ => None,
+        }
+    }
 }
 
 /// A kind of expression.
@@ -1358,3 +1404,143 @@ pub struct TypeMapping<'hir> {
     pub value: Type<'hir>,
     pub value_name: Option<Ident>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ast_lowering, ParsingContext};
+    use solar_interface::{source_map::FileName, Session};
+    use thread_local::ThreadLocal;
+
+    #[test]
+    fn variable_kind_of() {
+        struct Collector<'a, 'hir> {
+            hir: &'hir Hir<'hir>,
+            vars: &'a mut Vec<(String, VarKind)>,
+        }
+
+        impl<'hir> Visit<'hir> for Collector<'_, 'hir> {
+            type BreakValue = ();
+
+            fn hir(&self) -> &'hir Hir<'hir> {
+                self.hir
+            }
+
+            fn visit_expr(&mut self, expr: &'hir Expr<'hir>) -> ControlFlow<Self::BreakValue> {
+                if let (Some(id), Some(kind)) =
+                    (expr.as_variable(), self.hir.variable_kind_of(expr))
+                {
+                    let name = self.hir.variable(id).name.unwrap().to_string();
+                    self.vars.push((name, kind));
+                }
+                self.walk_expr(expr)
+            }
+        }
+
+        let sess = Session::builder().with_test_emitter().single_threaded().build();
+        sess.enter(|| {
+            let src = "
+                contract C {
+                    uint s;
+                    function f(uint p) public returns (uint r, uint) {
+                        uint l = p;
+                        r = l + s;
+                        return (r + 1, r);
+                    }
+                }
+            ";
+            let file = sess
+                .source_map()
+                .new_source_file(FileName::custom("variable_kind_of"), || Ok(src.to_string()))
+                .unwrap();
+            let mut pcx = ParsingContext::new(&sess);
+            pcx.add_file(file);
+            let ast_arenas = ThreadLocal::new();
+            let mut sources = pcx.parse(&ast_arenas);
+            sources.topo_sort();
+            let arena = Arena::new();
+            let (hir, _) = ast_lowering::lower(&sess, &sources, &arena);
+            sess.dcx.has_errors().unwrap();
+
+            let f = hir.functions().find(|f| f.name.is_some_and(|n| n.as_str() == "f")).unwrap();
+            let mut vars = Vec::new();
+            let _ = Collector { hir: &hir, vars: &mut vars }.visit_function(f);
+            let expected = [
+                ("p", VarKind::FunctionParam),
+                ("r", VarKind::FunctionReturn),
+                ("l", VarKind::Statement),
+                ("s", VarKind::State),
+                ("r", VarKind::FunctionReturn),
+                ("r", VarKind::FunctionReturn),
+            ];
+            let expected = expected.map(|(name, kind)| (name.to_string(), kind));
+            assert_eq!(vars, expected);
+        });
+    }
+
+    #[test]
+    fn special_identifier() {
+        struct Collector<'a, 'hir> {
+            hir: &'hir Hir<'hir>,
+            sess: &'a Session,
+            idents: &'a mut Vec<(String, Option<SpecialIdent>)>,
+        }
+
+        impl<'hir> Visit<'hir> for Collector<'_, 'hir> {
+            type BreakValue = ();
+
+            fn hir(&self) -> &'hir Hir<'hir> {
+                self.hir
+            }
+
+            fn visit_expr(&mut self, expr: &'hir Expr<'hir>) -> ControlFlow<Self::BreakValue> {
+                if let ExprKind::Ident(_) = expr.kind {
+                    let name = self.sess.source_map().span_to_snippet(expr.span).unwrap();
+                    self.idents.push((name, expr.special_identifier()));
+                }
+                self.walk_expr(expr)
+            }
+        }
+
+        let sess = Session::builder().with_test_emitter().single_threaded().build();
+        sess.enter(|| {
+            let src = "
+                contract C {
+                    function f(uint user) public view {
+                        this;
+                        msg.sender;
+                        user;
+                        uint block = user;
+                        block;
+                    }
+                }
+            ";
+            let file = sess
+                .source_map()
+                .new_source_file(FileName::custom("special_identifier"), || Ok(src.to_string()))
+                .unwrap();
+            let mut pcx = ParsingContext::new(&sess);
+            pcx.add_file(file);
+            let ast_arenas = ThreadLocal::new();
+            let mut sources = pcx.parse(&ast_arenas);
+            sources.topo_sort();
+            let arena = Arena::new();
+            let (hir, _) = ast_lowering::lower(&sess, &sources, &arena);
+            sess.dcx.has_errors().unwrap();
+
+            let f = hir.functions().find(|f| f.name.is_some_and(|n| n.as_str() == "f")).unwrap();
+            let mut idents = Vec::new();
+            let _ = Collector { hir: &hir, sess: &sess, idents: &mut idents }.visit_function(f);
+            let expected = [
+                ("this", Some(SpecialIdent::This)),
+                ("msg", Some(SpecialIdent::Msg)),
+                ("user", None),
+                ("user", None),
+                // The local variable shadows the builtin.
+                ("block", None),
+            ];
+            let expected = expected.map(|(name, special)| (name.to_string(), special));
+            assert_eq!(idents, expected);
+        });
+    }
+}
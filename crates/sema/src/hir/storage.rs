@@ -0,0 +1,215 @@
+//! Storage locations referenced by expressions, and whether two of them may alias.
+
+use super::*;
+use solar_data_structures::smallvec::SmallVec;
+use solar_interface::Symbol;
+
+/// A storage location, as the path from a state variable through struct members and indices:
+/// `s.arr[i]` is the `i`-th element of the member `arr` of the state variable `s`.
+#[derive(Clone, Debug)]
+pub struct StoragePath<'hir> {
+    /// The state variable the path starts at.
+    pub var: VariableId,
+    /// The members and indices applied to the variable, in order.
+    pub projections: SmallVec<[StorageProjection<'hir>; 4]>,
+}
+
+/// A step of a [`StoragePath`].
+#[derive(Clone, Copy, Debug)]
+pub enum StorageProjection<'hir> {
+    /// A member of a struct: `.field`.
+    Member(Symbol),
+    /// An element of an array, or a value of a mapping: `[index]`.
+    Index(&'hir Expr<'hir>),
+}
+
+/// Whether two storage locations overlap.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Aliasing {
+    /// The locations are disjoint.
+    No,
+    /// The locations may overlap, depending on values that are not known at compile time.
+    May,
+    /// The locations are the same, or one contains the other.
+    Must,
+}
+
+impl<'hir> StoragePath<'hir> {
+    /// Returns the storage path of the expression, if it is a state variable or a member or index
+    /// of one. `is_storage_var` returns `true` for variables that live in storage, that is state
+    /// variables that are neither constant nor immutable.
+    ///
+    /// This is purely syntactic: the path may end at a value type, like `s.len`, or at a member
+    /// that is not a struct field, like `s.arr.length`. Use [`Gcx::storage_ref`] to only get
+    /// paths that yield storage pointers.
+    ///
+    /// [`Gcx::storage_ref`]: crate::ty::Gcx::storage_ref
+    pub fn of(expr: &'hir Expr<'hir>, is_storage_var: impl Fn(VariableId) -> bool) -> Option<Self> {
+        let mut projections = SmallVec::new();
+        let mut expr = expr.peel_parens();
+        let var = loop {
+            match expr.kind {
+                ExprKind::Ident(&[Res::Item(ItemId::Variable(id))]) if is_storage_var(id) => {
+                    break id
+                }
+                ExprKind::Member(base, member) => {
+                    projections.push(StorageProjection::Member(member.name));
+                    expr = base;
+                }
+                ExprKind::Index(base, Some(index)) => {
+                    projections.push(StorageProjection::Index(index));
+                    expr = base;
+                }
+                _ => return None,
+            }
+            expr = expr.peel_parens();
+        };
+        projections.reverse();
+        Some(Self { var, projections })
+    }
+
+    /// Returns whether the two locations may overlap.
+    ///
+    /// Different state variables never overlap. Otherwise, the paths are compared step by step:
+    /// different members are disjoint, and indices are compared by value if they are constants,
+    /// or structurally otherwise, so `a[i]` and `a[i]` are the same location, assuming that `i`
+    /// has the same value in both, while `a[i]` and `a[j]` may alias, and `a[0]` and `a[1]` do
+    /// not. If one path is a prefix of the other, the shorter one contains the longer one.
+    pub fn alias(&self, other: &Self) -> Aliasing {
+        if self.var != other.var {
+            return Aliasing::No;
+        }
+        let mut aliasing = Aliasing::Must;
+        for (a, b) in self.projections.iter().zip(&other.projections) {
+            match (a, b) {
+                (StorageProjection::Member(a), StorageProjection::Member(b)) => {
+                    if a != b {
+                        return Aliasing::No;
+                    }
+                }
+                (StorageProjection::Index(a), StorageProjection::Index(b)) => {
+                    match same_value(a, b) {
+                        Some(true) => {}
+                        Some(false) => return Aliasing::No,
+                        None => aliasing = Aliasing::May,
+                    }
+                }
+                // Only possible if one of them is not a struct member or an index of the type.
+                _ => aliasing = Aliasing::May,
+            }
+        }
+        aliasing
+    }
+}
+
+/// Returns whether the two expressions definitely have the same value, or definitely different
+/// values, or `None` if this is not known.
+fn same_value(a: &Expr<'_>, b: &Expr<'_>) -> Option<bool> {
+    let (a, b) = (a.peel_parens(), b.peel_parens());
+    match (&a.kind, &b.kind) {
+        (ExprKind::Lit(a), ExprKind::Lit(b)) => {
+            let (a, b) = (a.to_const_value(None)?, b.to_const_value(None)?);
+            // Constants of different types may be converted to the same key.
+            (std::mem::discriminant(&a) == std::mem::discriminant(&b)).then_some(a == b)
+        }
+        (ExprKind::Ident([a]), ExprKind::Ident([b])) => (a == b).then_some(true),
+        (ExprKind::Member(a, ma), ExprKind::Member(b, mb)) => {
+            (ma.name == mb.name && same_value(a, b)?).then_some(true)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solar_interface::enter;
+
+    struct Builder<'a> {
+        arena: &'a Arena,
+    }
+
+    impl<'a> Builder<'a> {
+        fn expr(&self, kind: ExprKind<'a>) -> &'a Expr<'a> {
+            self.arena.alloc(Expr { id: ExprId::new(0), kind, span: Span::DUMMY })
+        }
+
+        fn var(&self, id: usize) -> &'a Expr<'a> {
+            let res =
+                self.arena.alloc_slice_copy(&[Res::Item(ItemId::Variable(VariableId::new(id)))]);
+            self.expr(ExprKind::Ident(res))
+        }
+
+        fn member(&self, base: &'a Expr<'a>, name: &str) -> &'a Expr<'a> {
+            self.expr(ExprKind::Member(base, Ident::from_str(name)))
+        }
+
+        fn index(&self, base: &'a Expr<'a>, index: &'a Expr<'a>) -> &'a Expr<'a> {
+            self.expr(ExprKind::Index(base, Some(index)))
+        }
+
+        fn int(&self, value: u64) -> &'a Expr<'a> {
+            let lit = self.arena.literals.alloc(Lit {
+                span: Span::DUMMY,
+                symbol: Symbol::intern(&value.to_string()),
+                kind: ast::LitKind::Number(value.into()),
+            });
+            self.expr(ExprKind::Lit(lit))
+        }
+    }
+
+    /// Variables `0` and `1` are state variables, `2` and `3` are locals.
+    fn path<'a>(expr: &'a Expr<'a>) -> StoragePath<'a> {
+        StoragePath::of(expr, |id| id.index() < 2).unwrap()
+    }
+
+    #[test]
+    fn storage_path() {
+        enter(|| {
+            let arena = Arena::new();
+            let b = Builder { arena: &arena };
+            let (s, i) = (b.var(0), b.var(2));
+            assert!(StoragePath::of(i, |id| id.index() < 2).is_none());
+            assert!(StoragePath::of(b.index(s, b.int(0)), |_| false).is_none());
+
+            let p = path(b.index(b.member(s, "arr"), i));
+            assert_eq!(p.var, VariableId::new(0));
+            assert!(matches!(
+                p.projections[..],
+                [StorageProjection::Member(arr), StorageProjection::Index(index)]
+                    if arr.as_str() == "arr" && std::ptr::eq(index, i)
+            ));
+        });
+    }
+
+    #[test]
+    fn alias() {
+        enter(|| {
+            let arena = Arena::new();
+            let b = Builder { arena: &arena };
+            let (s, t, i, j) = (b.var(0), b.var(1), b.var(2), b.var(3));
+            let arr = |index| b.index(b.member(s, "arr"), index);
+            let alias = |x, y| path(x).alias(&path(y));
+
+            // Definitely the same.
+            assert_eq!(alias(arr(i), arr(i)), Aliasing::Must);
+            assert_eq!(alias(arr(b.int(1)), arr(b.int(1))), Aliasing::Must);
+            assert_eq!(alias(b.member(s, "arr"), arr(j)), Aliasing::Must);
+            assert_eq!(alias(s, b.member(s, "x")), Aliasing::Must);
+            let nested = |index| b.index(b.member(arr(i), "inner"), index);
+            assert_eq!(alias(nested(b.member(i, "k")), nested(b.member(i, "k"))), Aliasing::Must);
+
+            // Definitely different.
+            assert_eq!(alias(arr(i), b.index(t, i)), Aliasing::No);
+            assert_eq!(alias(b.member(s, "arr"), b.member(s, "other")), Aliasing::No);
+            assert_eq!(alias(arr(b.int(0)), arr(b.int(1))), Aliasing::No);
+            assert_eq!(alias(b.member(arr(i), "x"), b.member(arr(j), "y")), Aliasing::No);
+
+            // Unknown.
+            assert_eq!(alias(arr(i), arr(j)), Aliasing::May);
+            assert_eq!(alias(arr(i), arr(b.int(0))), Aliasing::May);
+            assert_eq!(alias(b.member(arr(i), "x"), b.member(arr(j), "x")), Aliasing::May);
+            assert_eq!(alias(b.member(arr(i), "x"), arr(j)), Aliasing::May);
+        });
+    }
+}
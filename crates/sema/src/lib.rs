@@ -10,7 +10,7 @@
 extern crate tracing;
 
 use rayon::prelude::*;
-use solar_data_structures::{trustme, OnDrop};
+use solar_data_structures::{index::IndexVec, trustme, OnDrop};
 use solar_interface::{config::CompilerStage, Result, Session};
 use thread_local::ThreadLocal;
 use ty::Gcx;
@@ -23,6 +23,9 @@ pub use solar_interface as interface;
 
 mod ast_lowering;
 mod ast_passes;
+pub use ast_passes::lint_shadowed_builtins;
+mod suppressions;
+use suppressions::Suppressions;
 
 mod parse;
 pub use parse::{ParsedSource, ParsedSources, ParsingContext};
@@ -76,7 +79,7 @@ pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
         debug!(hir_allocated = hir_arena.get_or_default().allocated_bytes());
         debug_span!("dropping_hir_arena").in_scope(|| drop(hir_arena));
     });
-    let (hir, symbol_resolver) = lower(sess, &sources, hir_arena.get_or_default())?;
+    let (hir, symbol_resolver, suppressions) = lower(sess, &sources, hir_arena.get_or_default())?;
 
     // Drop the ASTs and AST arenas in a separate thread.
     sess.spawn({
@@ -90,10 +93,12 @@ pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
         }
     });
 
-    let global_context =
-        OnDrop::new(ty::GlobalCtxt::new(sess, &hir_arena, hir, symbol_resolver), |gcx| {
+    let global_context = OnDrop::new(
+        ty::GlobalCtxt::new(sess, &hir_arena, hir, symbol_resolver, suppressions),
+        |gcx| {
             debug_span!("drop_gcx").in_scope(|| drop(gcx));
-        });
+        },
+    );
     let gcx = ty::Gcx::new(unsafe { trustme::decouple_lt(&global_context) });
     analysis(gcx)?;
 
@@ -101,20 +106,30 @@ pub fn parse_and_resolve(pcx: ParsingContext<'_>) -> Result<()> {
 }
 
 /// Lowers the parsed ASTs into the HIR.
+///
+/// Also returns the suppression comments of each source, which are used by the AST passes and
+/// type checking.
 fn lower<'sess, 'hir>(
     sess: &'sess Session,
     sources: &ParsedSources<'_>,
     arena: &'hir hir::Arena,
-) -> Result<(hir::Hir<'hir>, ast_lowering::SymbolResolver<'sess>)> {
-    debug_span!("all_ast_passes").in_scope(|| {
-        sources.par_asts().for_each(|ast| {
-            ast_passes::run(sess, ast);
-        });
+) -> Result<(
+    hir::Hir<'hir>,
+    ast_lowering::SymbolResolver<'sess>,
+    IndexVec<hir::SourceId, Option<Suppressions>>,
+)> {
+    let suppressions = debug_span!("all_ast_passes").in_scope(|| {
+        sources
+            .as_raw_slice()
+            .par_iter()
+            .map(|source| source.ast.as_ref().map(|ast| ast_passes::run(sess, &source.file, ast)))
+            .collect::<Vec<_>>()
     });
 
     sess.dcx.has_errors()?;
 
-    Ok(ast_lowering::lower(sess, sources, arena))
+    let (hir, symbol_resolver) = ast_lowering::lower(sess, sources, arena);
+    Ok((hir, symbol_resolver, IndexVec::from_vec(suppressions)))
 }
 
 #[instrument(level = "debug", skip_all)]
@@ -137,6 +152,10 @@ fn analysis(gcx: Gcx<'_>) -> Result<()> {
     gcx.sess.dcx.has_errors()?;
 
     typeck::check(gcx);
+    // Type checking runs the last lints.
+    for suppressions in gcx.suppressions.iter().flatten() {
+        suppressions.report_unused(gcx.dcx());
+    }
     gcx.sess.dcx.has_errors()?;
 
     if !gcx.sess.opts.emit.is_empty() {
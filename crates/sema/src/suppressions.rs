@@ -0,0 +1,113 @@
+//! Lint suppression comments.
+//!
+//! Lints can be silenced with line comments that list the codes of the lints to suppress:
+//! - `// solar-disable-next-line 0501` suppresses the lints on the next line;
+//! - `// solar-disable-line 0501` suppresses the lints on the same line as the comment.
+//!
+//! Multiple codes can be separated by commas or whitespace. If no codes are given, all lints are
+//! suppressed. Only the line on which a lint starts is taken into account. Suppression comments
+//! that do not suppress any lint are reported.
+
+use solar_interface::{
+    diagnostics::{codes, DiagCtxt, DiagId},
+    source_map::SourceFile,
+    RelativeBytePos, Span,
+};
+use solar_parse::{lexer::token::RawTokenKind, Cursor};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+const DISABLE_NEXT_LINE: &str = "solar-disable-next-line";
+const DISABLE_LINE: &str = "solar-disable-line";
+
+/// The suppression comments of a source file.
+pub(crate) struct Suppressions {
+    file: Arc<SourceFile>,
+    directives: Vec<Directive>,
+}
+
+/// A single suppression comment.
+struct Directive {
+    /// The span of the comment.
+    span: Span,
+    /// The 0-based index of the line that the comment applies to.
+    line: usize,
+    /// The codes of the suppressed lints. Empty if all lints are suppressed.
+    codes: Vec<String>,
+    /// Whether the comment suppressed at least one lint.
+    used: AtomicBool,
+}
+
+impl Suppressions {
+    /// Collects the suppression comments of the given source file.
+    pub(crate) fn new(file: Arc<SourceFile>) -> Self {
+        let mut directives = Vec::new();
+        let mut pos = 0;
+        for token in Cursor::new(&file.src) {
+            let start = pos;
+            pos += token.len as usize;
+            if token.kind != (RawTokenKind::LineComment { is_doc: false }) {
+                continue;
+            }
+            let content = file.src[start + 2..pos].trim();
+            let (rest, offset) = if let Some(rest) = content.strip_prefix(DISABLE_NEXT_LINE) {
+                (rest, 1)
+            } else if let Some(rest) = content.strip_prefix(DISABLE_LINE) {
+                (rest, 0)
+            } else {
+                continue;
+            };
+            if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+                continue;
+            }
+
+            let lo = file.absolute_position(RelativeBytePos::from_usize(start));
+            let hi = file.absolute_position(RelativeBytePos::from_usize(pos));
+            let Some(line) = file.lookup_line(RelativeBytePos::from_usize(start)) else { continue };
+            let codes = rest
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|code| !code.is_empty())
+                .map(String::from)
+                .collect();
+            let span = Span::new(lo, hi);
+            let used = AtomicBool::new(false);
+            directives.push(Directive { span, line: line + offset, codes, used });
+        }
+        Self { file, directives }
+    }
+
+    /// Returns `true` if the lint with the given code is suppressed at the given span, marking
+    /// the corresponding suppression comments as used.
+    pub(crate) fn is_suppressed(&self, code: &DiagId, span: Span) -> bool {
+        if self.directives.is_empty() || !self.file.contains(span.lo()) {
+            return false;
+        }
+        let Some(line) = self.file.lookup_line(self.file.relative_position(span.lo())) else {
+            return false;
+        };
+        let code = code.as_string();
+        let mut suppressed = false;
+        for directive in &self.directives {
+            if directive.line == line
+                && (directive.codes.is_empty() || directive.codes.contains(&code))
+            {
+                directive.used.store(true, Ordering::Relaxed);
+                suppressed = true;
+            }
+        }
+        suppressed
+    }
+
+    /// Reports the suppression comments that did not suppress any lint.
+    pub(crate) fn report_unused(&self, dcx: &DiagCtxt) {
+        for directive in self.directives.iter().filter(|d| !d.used.load(Ordering::Relaxed)) {
+            dcx.warn("unused suppression comment")
+                .code(codes::UNUSED_SUPPRESSION)
+                .span(directive.span)
+                .help("no matching lint was reported on the targeted line; remove the comment")
+                .emit();
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! The common type of two types, used for the branches of a conditional expression `c ? a : b`.
+//!
+//! The rules are independent of how types are represented, which is abstracted by the
+//! [`TypeLattice`] trait.
+
+use alloy_primitives::U256;
+use solar_ast::{ElementaryType, TypeSize};
+
+/// The type queries needed to compute a [common type](common_type).
+pub trait TypeLattice {
+    /// A type.
+    type Ty: Copy + PartialEq;
+
+    /// Returns the value of the type if it is an integer literal type, as its sign and its
+    /// absolute value.
+    fn int_literal(&self, ty: Self::Ty) -> Option<(bool, U256)>;
+
+    /// Returns the type of the given elementary type.
+    fn elementary(&self, ty: ElementaryType) -> Self::Ty;
+
+    /// Returns `true` if values of type `from` are implicitly convertible to type `to`.
+    fn is_implicitly_convertible(&self, from: Self::Ty, to: Self::Ty) -> bool;
+}
+
+/// An error returned by [`common_type`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommonTypeError<T> {
+    /// The integer literal does not fit in any integer type.
+    LiteralTooLarge(T),
+    /// Neither type is implicitly convertible to the other.
+    Incompatible(T, T),
+}
+
+/// Returns the common type of `a` and `b`, which is the type of `c ? a : b`.
+///
+/// Integer literals are first converted to the [smallest integer type](smallest_int_type) that
+/// can hold their value. The common type is then the first of the two types that the other one
+/// is implicitly convertible to.
+pub fn common_type<L: TypeLattice + ?Sized>(
+    lattice: &L,
+    a: L::Ty,
+    b: L::Ty,
+) -> Result<L::Ty, CommonTypeError<L::Ty>> {
+    let a = mobile_type(lattice, a)?;
+    let b = mobile_type(lattice, b)?;
+    if lattice.is_implicitly_convertible(b, a) {
+        Ok(a)
+    } else if lattice.is_implicitly_convertible(a, b) {
+        Ok(b)
+    } else {
+        Err(CommonTypeError::Incompatible(a, b))
+    }
+}
+
+/// Returns the type that a value of the given type has when it is stored in a variable.
+fn mobile_type<L: TypeLattice + ?Sized>(
+    lattice: &L,
+    ty: L::Ty,
+) -> Result<L::Ty, CommonTypeError<L::Ty>> {
+    let Some((negative, abs)) = lattice.int_literal(ty) else { return Ok(ty) };
+    match smallest_int_type(negative, abs) {
+        Some(int) => Ok(lattice.elementary(int)),
+        None => Err(CommonTypeError::LiteralTooLarge(ty)),
+    }
+}
+
+/// Returns the smallest integer type that can hold the given value: `uintN` for non-negative
+/// values, and `intN` for negative ones.
+///
+/// Returns `None` if the value does not fit in 256 bits.
+pub fn smallest_int_type(negative: bool, abs: U256) -> Option<ElementaryType> {
+    if negative && !abs.is_zero() {
+        // `intN` holds values down to `-2^(N-1)`.
+        let bits = (abs - U256::from(1)).bit_len() + 1;
+        if bits > 256 {
+            return None;
+        }
+        Some(ElementaryType::Int(int_size(bits)))
+    } else {
+        Some(ElementaryType::UInt(int_size(abs.bit_len())))
+    }
+}
+
+/// Returns the size of the smallest integer type with at least `bits` bits.
+fn int_size(bits: usize) -> TypeSize {
+    TypeSize::new_int_bits((bits.div_ceil(8).max(1) * 8) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum MockTy {
+        Bool,
+        Int(u16),
+        UInt(u16),
+        Literal(bool, U256),
+    }
+
+    struct MockLattice;
+
+    impl TypeLattice for MockLattice {
+        type Ty = MockTy;
+
+        fn int_literal(&self, ty: MockTy) -> Option<(bool, U256)> {
+            match ty {
+                MockTy::Literal(negative, abs) => Some((negative, abs)),
+                _ => None,
+            }
+        }
+
+        fn elementary(&self, ty: ElementaryType) -> MockTy {
+            match ty {
+                ElementaryType::Bool => MockTy::Bool,
+                ElementaryType::Int(size) => MockTy::Int(size.bits()),
+                ElementaryType::UInt(size) => MockTy::UInt(size.bits()),
+                _ => unreachable!(
+                    "the mock lattice only supports `bool` and integer types, not {ty:?}"
+                ),
+            }
+        }
+
+        fn is_implicitly_convertible(&self, from: MockTy, to: MockTy) -> bool {
+            match (from, to) {
+                (MockTy::UInt(a), MockTy::UInt(b)) | (MockTy::Int(a), MockTy::Int(b)) => a <= b,
+                (MockTy::UInt(a), MockTy::Int(b)) => a < b,
+                (a, b) => a == b,
+            }
+        }
+    }
+
+    fn lit(value: i64) -> MockTy {
+        MockTy::Literal(value < 0, U256::from(value.unsigned_abs()))
+    }
+
+    #[test]
+    fn smallest_int() {
+        let uint = |bits| Some(ElementaryType::UInt(TypeSize::new_int_bits(bits)));
+        let int = |bits| Some(ElementaryType::Int(TypeSize::new_int_bits(bits)));
+        let one = U256::from(1);
+        assert_eq!(smallest_int_type(false, U256::ZERO), uint(8));
+        assert_eq!(smallest_int_type(true, U256::ZERO), uint(8));
+        assert_eq!(smallest_int_type(false, U256::from(255)), uint(8));
+        assert_eq!(smallest_int_type(false, U256::from(256)), uint(16));
+        assert_eq!(smallest_int_type(false, U256::MAX), uint(256));
+        assert_eq!(smallest_int_type(true, U256::from(128)), int(8));
+        assert_eq!(smallest_int_type(true, U256::from(129)), int(16));
+        assert_eq!(smallest_int_type(true, one << 255), int(256));
+        assert_eq!(smallest_int_type(true, (one << 255) + one), None);
+    }
+
+    #[test]
+    fn compatible() {
+        let common = |a, b| common_type(&MockLattice, a, b);
+        assert_eq!(common(MockTy::UInt(8), MockTy::UInt(16)), Ok(MockTy::UInt(16)));
+        assert_eq!(common(MockTy::UInt(16), MockTy::UInt(8)), Ok(MockTy::UInt(16)));
+        assert_eq!(common(MockTy::UInt(8), MockTy::Int(16)), Ok(MockTy::Int(16)));
+        assert_eq!(common(MockTy::Bool, MockTy::Bool), Ok(MockTy::Bool));
+        assert_eq!(common(lit(1), lit(300)), Ok(MockTy::UInt(16)));
+        assert_eq!(common(lit(1), MockTy::UInt(256)), Ok(MockTy::UInt(256)));
+        assert_eq!(common(lit(-1), lit(-200)), Ok(MockTy::Int(16)));
+        assert_eq!(common(lit(-129), MockTy::UInt(8)), Ok(MockTy::Int(16)));
+    }
+
+    #[test]
+    fn incompatible() {
+        let common = |a, b| common_type(&MockLattice, a, b);
+        assert_eq!(
+            common(MockTy::Bool, MockTy::UInt(8)),
+            Err(CommonTypeError::Incompatible(MockTy::Bool, MockTy::UInt(8)))
+        );
+        assert_eq!(
+            common(MockTy::Int(8), MockTy::UInt(8)),
+            Err(CommonTypeError::Incompatible(MockTy::Int(8), MockTy::UInt(8)))
+        );
+        // `-1` is an `int8` and `255` is a `uint8`.
+        assert_eq!(
+            common(lit(-1), lit(255)),
+            Err(CommonTypeError::Incompatible(MockTy::Int(8), MockTy::UInt(8)))
+        );
+        let too_large = MockTy::Literal(true, U256::MAX);
+        assert_eq!(common(too_large, lit(1)), Err(CommonTypeError::LiteralTooLarge(too_large)));
+    }
+}
@@ -2,12 +2,14 @@ use crate::{
     ast_lowering::SymbolResolver,
     builtins::{members, Builtin},
     hir::{self, Hir},
+    suppressions::Suppressions,
 };
 use alloy_primitives::{keccak256, Selector, B256, U256};
 use either::Either;
 use solar_ast::{DataLocation, StateMutability, TypeSize, Visibility};
 use solar_data_structures::{
     fmt::{from_fn, or_list},
+    index::IndexVec,
     map::{FxBuildHasher, FxHashMap, FxHashSet},
     smallvec::SmallVec,
     BumpExt,
@@ -20,6 +22,7 @@ use std::{
     fmt,
     hash::{BuildHasher, Hash},
     ops::ControlFlow,
+    sync::OnceLock,
 };
 use thread_local::ThreadLocal;
 
@@ -32,6 +35,9 @@ pub use common::{CommonTypes, EachDataLoc};
 mod interner;
 use interner::Interner;
 
+mod lattice;
+pub use lattice::{common_type, smallest_int_type, CommonTypeError, TypeLattice};
+
 #[allow(clippy::module_inception)]
 mod ty;
 pub use ty::{Ty, TyData, TyFlags, TyFnPtr, TyKind};
@@ -147,9 +153,12 @@ pub struct GlobalCtxt<'gcx> {
     pub types: CommonTypes<'gcx>,
     pub hir: Hir<'gcx>,
     pub(crate) symbol_resolver: SymbolResolver<'gcx>,
+    /// The suppression comments of each source.
+    pub(crate) suppressions: IndexVec<hir::SourceId, Option<Suppressions>>,
 
     interner: Interner<'gcx>,
     cache: Cache<'gcx>,
+    call_resolutions: OnceLock<hir::CallResolutions>,
 }
 
 impl<'gcx> GlobalCtxt<'gcx> {
@@ -158,6 +167,7 @@ impl<'gcx> GlobalCtxt<'gcx> {
         arena: &'gcx ThreadLocal<hir::Arena>,
         hir: Hir<'gcx>,
         symbol_resolver: SymbolResolver<'gcx>,
+        suppressions: IndexVec<hir::SourceId, Option<Suppressions>>,
     ) -> Self {
         let interner = Interner::new(arena);
         Self {
@@ -165,8 +175,10 @@ impl<'gcx> GlobalCtxt<'gcx> {
             types: CommonTypes::new(&interner),
             hir,
             symbol_resolver,
+            suppressions,
             interner,
             cache: Cache::default(),
+            call_resolutions: OnceLock::new(),
         }
     }
 }
@@ -451,6 +463,50 @@ impl<'gcx> Gcx<'gcx> {
             hir::Res::Err(guar) => self.mk_ty_err(guar),
         }
     }
+
+    /// Returns the resolutions of all the call expressions in the program.
+    ///
+    /// These are computed on the first call.
+    pub fn call_resolutions(self) -> &'gcx hir::CallResolutions {
+        self.0.call_resolutions.get_or_init(|| hir::CallResolutions::new(&self.0.hir))
+    }
+
+    /// Returns the resolution of the given call expression. Returns `None` if the expression is
+    /// not a call.
+    pub fn call_resolution(self, id: hir::ExprId) -> Option<hir::CallResolution> {
+        self.call_resolutions().get(id)
+    }
+
+    /// Returns the storage location of the expression, if it yields a storage pointer: a state
+    /// variable of a reference type, or a struct member or an index of one that is itself of a
+    /// reference type, like `s.arr[i]` in an array of structs.
+    ///
+    /// Constants and immutable variables are not stored in storage.
+    pub fn storage_ref(self, expr: &'gcx hir::Expr<'gcx>) -> Option<hir::StoragePath<'gcx>> {
+        let path = hir::StoragePath::of(expr, |id| {
+            let var = self.hir.variable(id);
+            var.is_state_variable() && var.mutability.is_none()
+        })?;
+        let mut ty = self.type_of_item(path.var.into()).peel_refs();
+        for projection in &path.projections {
+            ty = match (*projection, ty.kind) {
+                (hir::StorageProjection::Member(name), TyKind::Struct(id)) => {
+                    let fields = self.hir.strukt(id).fields;
+                    let is_field = |&f: &hir::VariableId| {
+                        self.hir.variable(f).name.is_some_and(|n| n.name == name)
+                    };
+                    self.struct_field_types(id)[fields.iter().position(is_field)?]
+                }
+                (
+                    hir::StorageProjection::Index(_),
+                    TyKind::Array(elem, _) | TyKind::DynArray(elem) | TyKind::Mapping(_, elem),
+                ) => elem,
+                _ => return None,
+            }
+            .peel_refs();
+        }
+        (ty.is_reference_type() || matches!(ty.kind, TyKind::Mapping(..))).then_some(path)
+    }
 }
 
 macro_rules! cached {
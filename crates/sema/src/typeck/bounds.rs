@@ -0,0 +1,181 @@
+//! Comparisons that are always true or always false because of the range of the compared type.
+
+use super::OperandTypes;
+use crate::{
+    hir::{self, Visit},
+    ty::Gcx,
+};
+use alloy_primitives::U256;
+use solar_ast::{ElementaryType, LitKind};
+use solar_data_structures::Never;
+use solar_interface::{diagnostics::codes, sym};
+use std::{cmp::Ordering, fmt, ops::ControlFlow};
+
+/// Checks the comparisons in the given function.
+pub(super) fn check<'gcx>(gcx: Gcx<'gcx>, func: &'gcx hir::Function<'gcx>) {
+    let _ = BoundsChecker { gcx, source: func.source }.visit_function(func);
+}
+
+struct BoundsChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    source: hir::SourceId,
+}
+
+impl<'gcx> Visit<'gcx> for BoundsChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let hir::ExprKind::Binary(lhs, op, rhs) = expr.kind {
+            self.check_comparison(expr, lhs, op.kind, rhs);
+        }
+        self.walk_expr(expr)
+    }
+}
+
+impl<'gcx> BoundsChecker<'gcx> {
+    /// Warns if the comparison of a variable with a constant is always true or always false.
+    ///
+    /// Only variables of integer types are considered, and only literals and `type(T).min` or
+    /// `type(T).max` are considered constant.
+    fn check_comparison(
+        &self,
+        expr: &hir::Expr<'_>,
+        lhs: &hir::Expr<'_>,
+        op: hir::BinOpKind,
+        rhs: &hir::Expr<'_>,
+    ) {
+        if !op.is_comparison() {
+            return;
+        }
+        let (ty, op, value) = if let Some(ty) = self.int_var_type(lhs) {
+            let Some(value) = const_int(rhs) else { return };
+            (ty, op, value)
+        } else if let Some(ty) = self.int_var_type(rhs) {
+            let Some(value) = const_int(lhs) else { return };
+            (ty, op.flip_comparison().unwrap(), value)
+        } else {
+            return;
+        };
+
+        let (min, max) = int_bounds(ty).unwrap();
+        let Some(result) = always(op, value, min, max) else { return };
+        let msg = format!("comparison is always {result}");
+        let code = codes::TAUTOLOGICAL_COMPARISON;
+        let Some(lint) = super::lint(self.gcx, self.source, code, msg, expr.span) else { return };
+        lint.note(format!("values of type `{ty}` range from {min} to {max}")).emit();
+    }
+
+    /// Returns the type of the expression if it is a variable of an integer type.
+    fn int_var_type(&self, expr: &hir::Expr<'_>) -> Option<ElementaryType> {
+        self.gcx
+            .elementary_type_of(expr.peel_parens())
+            .filter(|ty| matches!(ty, ElementaryType::Int(_) | ElementaryType::UInt(_)))
+    }
+}
+
+/// Returns the result of `x op value` for every `x` in `min..=max`, if it is the same for all of
+/// them.
+fn always(op: hir::BinOpKind, value: Int, min: Int, max: Int) -> Option<bool> {
+    use hir::BinOpKind::*;
+
+    match op {
+        Lt if value <= min => Some(false),
+        Lt if value > max => Some(true),
+        Le if value < min => Some(false),
+        Le if value >= max => Some(true),
+        Gt if value >= max => Some(false),
+        Gt if value < min => Some(true),
+        Ge if value > max => Some(false),
+        Ge if value <= min => Some(true),
+        Eq if value < min || value > max => Some(false),
+        Ne if value < min || value > max => Some(true),
+        _ => None,
+    }
+}
+
+/// Evaluates integer literals, their negations, and `type(T).min` and `type(T).max`.
+fn const_int(expr: &hir::Expr<'_>) -> Option<Int> {
+    match expr.peel_parens().kind {
+        hir::ExprKind::Lit(hir::Lit { kind: LitKind::Number(n), .. }) => {
+            let (_, bytes) = n.to_bytes_be();
+            U256::try_from_be_slice(&bytes).map(Int::positive)
+        }
+        hir::ExprKind::Unary(op, operand) if op.kind == hir::UnOpKind::Neg => {
+            const_int(operand).map(Int::neg)
+        }
+        hir::ExprKind::Member(base, member) => {
+            let hir::ExprKind::TypeCall(ty) = &base.peel_parens().kind else { return None };
+            let hir::TypeKind::Elementary(ty) = ty.kind else { return None };
+            let (min, max) = int_bounds(ty)?;
+            match member.name {
+                sym::min => Some(min),
+                sym::max => Some(max),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the smallest and the largest values of an integer type.
+fn int_bounds(ty: ElementaryType) -> Option<(Int, Int)> {
+    match ty {
+        ElementaryType::UInt(size) => {
+            let max = U256::MAX >> (256 - size.bits() as usize);
+            Some((Int::positive(U256::ZERO), Int::positive(max)))
+        }
+        ElementaryType::Int(size) => {
+            let abs_min = U256::from(1) << (size.bits() as usize - 1);
+            Some((Int::positive(abs_min).neg(), Int::positive(abs_min - U256::from(1))))
+        }
+        _ => None,
+    }
+}
+
+/// An integer that can hold the values of every integer type, and their negations.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Int {
+    /// Whether the value is negative. Zero is never negative.
+    negative: bool,
+    abs: U256,
+}
+
+impl Int {
+    fn positive(abs: U256) -> Self {
+        Self { negative: false, abs }
+    }
+
+    fn neg(self) -> Self {
+        Self { negative: !self.negative && !self.abs.is_zero(), abs: self.abs }
+    }
+}
+
+impl PartialOrd for Int {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Int {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.abs.cmp(&other.abs),
+            (true, true) => other.abs.cmp(&self.abs),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl fmt::Display for Int {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            f.write_str("-")?;
+        }
+        write!(f, "{}", self.abs)
+    }
+}
@@ -0,0 +1,130 @@
+//! Explicit conversions to the type that the converted value already has.
+
+use super::OperandTypes;
+use crate::{
+    hir::{self, Visit},
+    ty::Gcx,
+};
+use solar_ast::ElementaryType;
+use solar_data_structures::Never;
+use solar_interface::diagnostics::{codes, Applicability};
+use std::ops::ControlFlow;
+
+/// Checks the conversions in the given function.
+pub(super) fn check<'gcx>(gcx: Gcx<'gcx>, func: &'gcx hir::Function<'gcx>) {
+    let _ = ConversionChecker { gcx, source: func.source }.visit_function(func);
+}
+
+struct ConversionChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    source: hir::SourceId,
+}
+
+impl<'gcx> Visit<'gcx> for ConversionChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        if let Some((ty, arg)) = redundant_conversion(&self.gcx, expr) {
+            self.report(expr, ty, arg);
+        }
+        self.walk_expr(expr)
+    }
+}
+
+impl ConversionChecker<'_> {
+    /// Warns about a redundant conversion, and suggests replacing it with its argument.
+    fn report(&self, expr: &hir::Expr<'_>, ty: ElementaryType, arg: &hir::Expr<'_>) {
+        let msg = format!("redundant conversion to `{ty}`");
+        let code = codes::REDUNDANT_CONVERSION;
+        let Some(lint) = super::lint(self.gcx, self.source, code, msg, expr.span) else { return };
+        let mut lint = lint.note(format!("the argument already has type `{ty}`"));
+        if let Ok(snippet) = self.gcx.sess.source_map().span_to_snippet(arg.span) {
+            // The conversion call binds tighter than any operator in its argument.
+            let replacement = match arg.kind {
+                hir::ExprKind::Assign(..)
+                | hir::ExprKind::Binary(..)
+                | hir::ExprKind::Ternary(..)
+                | hir::ExprKind::Unary(..) => format!("({snippet})"),
+                _ => snippet,
+            };
+            lint = lint.span_suggestion(
+                expr.span,
+                "remove the conversion",
+                replacement,
+                Applicability::MachineApplicable,
+            );
+        }
+        lint.emit();
+    }
+}
+
+/// Returns the type and the argument of the expression if it is an explicit conversion to an
+/// elementary type, like `uint256(x)`, and the argument already has that type.
+///
+/// Conversions between types of different sizes or signedness, like `uint256(x)` for a `uint8`
+/// `x`, are not redundant. Neither are conversions of arguments of unknown types, like
+/// `address(this)`.
+fn redundant_conversion<'hir>(
+    types: &impl OperandTypes,
+    expr: &hir::Expr<'hir>,
+) -> Option<(ElementaryType, &'hir hir::Expr<'hir>)> {
+    let hir::ExprKind::Call(callee, hir::CallArgs::Unnamed([arg]), None) = expr.kind else {
+        return None;
+    };
+    let hir::ExprKind::Type(hir::Type { kind: hir::TypeKind::Elementary(ty), .. }) = callee.kind
+    else {
+        return None;
+    };
+    (types.elementary_type_of(arg.peel_parens())? == ty).then_some((ty, arg))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hir::{Arena, ExprId},
+        typeck::mock::{int, uint, Builder, MockTypes},
+    };
+    use solar_interface::{enter, Span};
+
+    #[test]
+    fn redundant_conversion() {
+        enter(|| {
+            let arena = Arena::new();
+            let b = Builder { arena: &arena };
+            let (x, small, signed, addr, unknown) =
+                (b.var(1), b.var(2), b.var(0), b.var(5), b.var(6));
+            let check = |expr| super::redundant_conversion(&MockTypes, expr).map(|(ty, _)| ty);
+            let address = ElementaryType::Address(false);
+
+            // Redundant.
+            assert_eq!(check(b.cast(uint(256), x)), Some(uint(256)));
+            assert_eq!(check(b.cast(uint(256), b.parens(x))), Some(uint(256)));
+            assert_eq!(check(b.cast(uint(8), small)), Some(uint(8)));
+            assert_eq!(check(b.cast(address, addr)), Some(address));
+
+            // Widening, narrowing, and sign conversions.
+            assert_eq!(check(b.cast(uint(256), small)), None);
+            assert_eq!(check(b.cast(uint(8), x)), None);
+            assert_eq!(check(b.cast(uint(256), signed)), None);
+            assert_eq!(check(b.cast(int(16), signed)), None);
+            assert_eq!(check(b.cast(ElementaryType::Address(true), addr)), None);
+            // Unknown types, like `address(this)`.
+            assert_eq!(check(b.cast(address, unknown)), None);
+            assert_eq!(check(b.cast(uint(256), b.cast(uint(256), unknown))), None);
+            // Not a conversion.
+            assert_eq!(check(x), None);
+            assert_eq!(check(b.call(unknown, std::slice::from_ref(x))), None);
+            let args = arena.alloc_slice_fill_with(2, |_| hir::Expr {
+                id: ExprId::new(0),
+                kind: b.var_kind(1),
+                span: Span::DUMMY,
+            });
+            assert_eq!(check(b.call(b.ty(uint(256)), args)), None);
+        });
+    }
+}
@@ -0,0 +1,79 @@
+//! Mock types and HIR expressions for testing the checks that implement [`OperandTypes`].
+
+use super::OperandTypes;
+use crate::hir::{self, Arena, ExprId, ExprKind, Res, VariableId};
+use solar_ast::{ElementaryType, TypeSize};
+use solar_interface::Span;
+
+/// Variable `0` is an `int256`, `1` a `uint256`, `2` a `uint8`, `3` an `int16`, `4` a `bool`,
+/// and `5` an `address`. The types of the other expressions are not known.
+pub(super) struct MockTypes;
+
+impl OperandTypes for MockTypes {
+    fn elementary_type_of(&self, expr: &hir::Expr<'_>) -> Option<ElementaryType> {
+        let ExprKind::Ident(&[Res::Item(hir::ItemId::Variable(id))]) = expr.kind else {
+            return None;
+        };
+        Some(match id.index() {
+            0 => int(256),
+            1 => uint(256),
+            2 => uint(8),
+            3 => int(16),
+            4 => ElementaryType::Bool,
+            5 => ElementaryType::Address(false),
+            _ => return None,
+        })
+    }
+}
+
+pub(super) fn int(bits: u16) -> ElementaryType {
+    ElementaryType::Int(TypeSize::new_int_bits(bits))
+}
+
+pub(super) fn uint(bits: u16) -> ElementaryType {
+    ElementaryType::UInt(TypeSize::new_int_bits(bits))
+}
+
+/// Builds HIR expressions in an arena.
+pub(super) struct Builder<'a> {
+    pub(super) arena: &'a Arena,
+}
+
+impl<'a> Builder<'a> {
+    pub(super) fn expr(&self, kind: ExprKind<'a>) -> &'a hir::Expr<'a> {
+        self.arena.alloc(hir::Expr { id: ExprId::new(0), kind, span: Span::DUMMY })
+    }
+
+    pub(super) fn var_kind(&self, id: usize) -> ExprKind<'a> {
+        let res =
+            self.arena.alloc_slice_copy(&[Res::Item(hir::ItemId::Variable(VariableId::new(id)))]);
+        ExprKind::Ident(res)
+    }
+
+    /// Returns a use of the variable with the given ID. See [`MockTypes`] for its type.
+    pub(super) fn var(&self, id: usize) -> &'a hir::Expr<'a> {
+        self.expr(self.var_kind(id))
+    }
+
+    pub(super) fn ty(&self, ty: ElementaryType) -> &'a hir::Expr<'a> {
+        let ty = hir::Type { span: Span::DUMMY, kind: hir::TypeKind::Elementary(ty) };
+        self.expr(ExprKind::Type(ty))
+    }
+
+    pub(super) fn call(
+        &self,
+        callee: &'a hir::Expr<'a>,
+        args: &'a [hir::Expr<'a>],
+    ) -> &'a hir::Expr<'a> {
+        self.expr(ExprKind::Call(callee, hir::CallArgs::Unnamed(args), None))
+    }
+
+    /// Returns an explicit conversion: `ty(arg)`.
+    pub(super) fn cast(&self, ty: ElementaryType, arg: &'a hir::Expr<'a>) -> &'a hir::Expr<'a> {
+        self.call(self.ty(ty), std::slice::from_ref(arg))
+    }
+
+    pub(super) fn parens(&self, expr: &'a hir::Expr<'a>) -> &'a hir::Expr<'a> {
+        self.expr(ExprKind::Tuple(self.arena.alloc_slice_copy(&[Some(expr)])))
+    }
+}
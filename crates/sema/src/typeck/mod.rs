@@ -1,10 +1,22 @@
 use crate::{
     ast_lowering::resolve::{Declaration, Declarations},
     hir::{self, Res},
-    ty::{Gcx, Ty},
+    ty::{Gcx, Ty, TyKind},
 };
 use rayon::prelude::*;
+use solar_ast::ElementaryType;
 use solar_data_structures::{map::FxHashSet, parallel};
+use solar_interface::{
+    diagnostics::{DiagBuilder, DiagId, DiagMsg},
+    Span,
+};
+
+mod bounds;
+mod conversions;
+#[cfg(test)]
+mod mock;
+mod signedness;
+mod type_members;
 
 pub(crate) fn check(gcx: Gcx<'_>) {
     parallel!(
@@ -15,9 +27,63 @@ pub(crate) fn check(gcx: Gcx<'_>) {
         gcx.hir.par_source_ids().for_each(|id| {
             check_duplicate_definitions(gcx, &gcx.symbol_resolver.source_scopes[id]);
         }),
+        gcx.hir.par_functions().for_each(|func| {
+            bounds::check(gcx, func);
+            conversions::check(gcx, func);
+            signedness::check(gcx, func);
+            type_members::check(gcx, func);
+        }),
+        gcx.hir.par_variables().for_each(|var| {
+            // Local variables are checked with their function.
+            if var.is_state_variable() || var.is_file_level_variable() {
+                type_members::check_var(gcx, var);
+            }
+        }),
     );
 }
 
+/// Creates a lint warning with the given code, unless it has been suppressed at `span` with a
+/// suppression comment in the given source. See [`crate::suppressions`].
+fn lint<'gcx>(
+    gcx: Gcx<'gcx>,
+    source: hir::SourceId,
+    code: DiagId,
+    msg: impl Into<DiagMsg>,
+    span: Span,
+) -> Option<DiagBuilder<'gcx, ()>> {
+    let suppressions = gcx.suppressions[source].as_ref();
+    if suppressions.is_some_and(|suppressions| suppressions.is_suppressed(&code, span)) {
+        return None;
+    }
+    Some(gcx.dcx().warn(msg).code(code).span(span))
+}
+
+/// The type queries needed by the checks that only look at the types of some operands.
+///
+/// This abstracts over how the types of expressions are known, so that the checks can be tested
+/// with mock types, without lowering and type checking a source. [`TypeLattice`] is a different
+/// abstraction: it abstracts over the representation of types, and relates types to each other
+/// rather than to expressions, so the two are kept separate.
+///
+/// [`TypeLattice`]: crate::ty::TypeLattice
+trait OperandTypes {
+    /// Returns the elementary type of the expression, or `None` if it is not known.
+    fn elementary_type_of(&self, expr: &hir::Expr<'_>) -> Option<ElementaryType>;
+}
+
+impl OperandTypes for Gcx<'_> {
+    /// Only the types of variables are known.
+    fn elementary_type_of(&self, expr: &hir::Expr<'_>) -> Option<ElementaryType> {
+        let hir::ExprKind::Ident(&[Res::Item(hir::ItemId::Variable(id))]) = expr.kind else {
+            return None;
+        };
+        match self.type_of_item(id.into()).kind {
+            TyKind::Elementary(ty) => Some(ty),
+            _ => None,
+        }
+    }
+}
+
 /// Checks for definitions that have the same name and parameter types in the given scope.
 fn check_duplicate_definitions(gcx: Gcx<'_>, scope: &Declarations) {
     let is_duplicate = |a: Declaration, b: Declaration| -> bool {
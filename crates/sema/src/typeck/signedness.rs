@@ -0,0 +1,183 @@
+//! Comparisons and arithmetic between signed and unsigned integers.
+
+use super::OperandTypes;
+use crate::{
+    hir::{self, Visit},
+    ty::Gcx,
+};
+use solar_ast::ElementaryType;
+use solar_data_structures::Never;
+use solar_interface::{diagnostics::codes, Span};
+use std::ops::ControlFlow;
+
+/// Checks the operations in the given function.
+pub(super) fn check<'gcx>(gcx: Gcx<'gcx>, func: &'gcx hir::Function<'gcx>) {
+    let _ = SignednessChecker { gcx, source: func.source }.visit_function(func);
+}
+
+struct SignednessChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+    source: hir::SourceId,
+}
+
+impl<'gcx> Visit<'gcx> for SignednessChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            hir::ExprKind::Binary(lhs, op, rhs) | hir::ExprKind::Assign(lhs, Some(op), rhs) => {
+                self.check_operation(expr.span, lhs, op.kind, rhs);
+            }
+            _ => {}
+        }
+        self.walk_expr(expr)
+    }
+}
+
+impl SignednessChecker<'_> {
+    /// Warns if the operation mixes signed and unsigned integers.
+    fn check_operation(
+        &self,
+        span: Span,
+        lhs: &hir::Expr<'_>,
+        op: hir::BinOpKind,
+        rhs: &hir::Expr<'_>,
+    ) {
+        let Some(mix) = mixed_signedness(&self.gcx, lhs, op, rhs) else { return };
+        let msg = if op.is_comparison() {
+            "comparison of signed and unsigned integers"
+        } else {
+            "arithmetic operation on signed and unsigned integers"
+        };
+        let code = codes::MIXED_SIGNEDNESS;
+        let Some(lint) = super::lint(self.gcx, self.source, code, msg, span) else { return };
+        match mix.common {
+            Some(common) => {
+                let unsigned = if common == mix.lhs { mix.rhs } else { mix.lhs };
+                lint.note(format!("the `{unsigned}` operand is implicitly converted to `{common}`"))
+            }
+            None => lint
+                .note(format!(
+                    "`{}` and `{}` are not implicitly convertible to each other",
+                    mix.lhs, mix.rhs
+                ))
+                .help("convert one of the operands explicitly"),
+        }
+        .emit();
+    }
+}
+
+/// An operation between a signed and an unsigned integer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct MixedSignedness {
+    /// The type of the left operand.
+    lhs: ElementaryType,
+    /// The type of the right operand.
+    rhs: ElementaryType,
+    /// The signed type that the unsigned operand is implicitly converted to, if it is large enough
+    /// to hold all of its values. Otherwise, the operation is rejected by solc.
+    common: Option<ElementaryType>,
+}
+
+/// Returns how `lhs op rhs` mixes signed and unsigned integers, if `op` is a comparison or an
+/// arithmetic operator other than `**`, and the types of both operands are known integer types of
+/// different signedness.
+fn mixed_signedness(
+    types: &impl OperandTypes,
+    lhs: &hir::Expr<'_>,
+    op: hir::BinOpKind,
+    rhs: &hir::Expr<'_>,
+) -> Option<MixedSignedness> {
+    use hir::BinOpKind::*;
+
+    if !(op.is_comparison() || matches!(op, Add | Sub | Mul | Div | Rem)) {
+        return None;
+    }
+    let (lhs, rhs) = (int_type(types, lhs)?, int_type(types, rhs)?);
+    let common = match (lhs, rhs) {
+        (ElementaryType::UInt(unsigned), ElementaryType::Int(signed)) => {
+            (unsigned.bits() < signed.bits()).then_some(rhs)
+        }
+        (ElementaryType::Int(signed), ElementaryType::UInt(unsigned)) => {
+            (unsigned.bits() < signed.bits()).then_some(lhs)
+        }
+        _ => return None,
+    };
+    Some(MixedSignedness { lhs, rhs, common })
+}
+
+/// Returns the type of the expression if it is a known integer type.
+///
+/// Explicit conversions to an elementary type, like `int256(x)`, have the type that they convert
+/// to, whatever the type of their argument.
+fn int_type(types: &impl OperandTypes, expr: &hir::Expr<'_>) -> Option<ElementaryType> {
+    let expr = expr.peel_parens();
+    let ty = match expr.kind {
+        hir::ExprKind::Call(
+            hir::Expr {
+                kind: hir::ExprKind::Type(hir::Type { kind: hir::TypeKind::Elementary(ty), .. }),
+                ..
+            },
+            ..,
+        ) => *ty,
+        _ => types.elementary_type_of(expr)?,
+    };
+    matches!(ty, ElementaryType::Int(_) | ElementaryType::UInt(_)).then_some(ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hir::Arena,
+        typeck::mock::{int, uint, Builder, MockTypes},
+    };
+    use solar_ast::BinOpKind::*;
+    use solar_interface::enter;
+
+    #[test]
+    fn mixed_signedness() {
+        enter(|| {
+            let arena = Arena::new();
+            let b = Builder { arena: &arena };
+            let (x, y, small, z, flag, unknown) =
+                (b.var(0), b.var(1), b.var(2), b.var(3), b.var(4), b.var(6));
+            let check = |lhs, op, rhs| super::mixed_signedness(&MockTypes, lhs, op, rhs);
+            let mix = |lhs, rhs, common| Some(MixedSignedness { lhs, rhs, common });
+
+            // Rejected by solc.
+            assert_eq!(check(x, Lt, y), mix(int(256), uint(256), None));
+            assert_eq!(check(y, Add, x), mix(uint(256), int(256), None));
+            assert_eq!(check(b.parens(x), Ne, y), mix(int(256), uint(256), None));
+            // Implicitly converted to the signed type.
+            assert_eq!(check(x, Ge, small), mix(int(256), uint(8), Some(int(256))));
+            assert_eq!(check(small, Mul, z), mix(uint(8), int(16), Some(int(16))));
+
+            // Same signedness.
+            assert_eq!(check(x, Lt, z), None);
+            assert_eq!(check(y, Sub, small), None);
+            // Not a comparison or an arithmetic operation.
+            assert_eq!(check(x, Pow, y), None);
+            assert_eq!(check(x, Shl, y), None);
+            assert_eq!(check(x, And, y), None);
+            // Unknown or non-integer types.
+            assert_eq!(check(x, Lt, unknown), None);
+            assert_eq!(check(unknown, Lt, y), None);
+            assert_eq!(check(flag, Eq, y), None);
+
+            // Explicit conversions.
+            assert_eq!(check(b.cast(int(256), y), Lt, x), None);
+            assert_eq!(check(x, Lt, b.cast(int(256), unknown)), None);
+            assert_eq!(check(b.cast(uint(256), x), Lt, y), None);
+            assert_eq!(check(b.cast(uint(256), y), Lt, x), mix(uint(256), int(256), None));
+            assert_eq!(
+                check(b.cast(int(16), unknown), Lt, small),
+                mix(int(16), uint(8), Some(int(16)))
+            );
+        });
+    }
+}
@@ -0,0 +1,84 @@
+//! The arguments and members of `type(T)` expressions.
+
+use crate::{
+    hir::{self, Visit},
+    ty::Gcx,
+};
+use solar_data_structures::Never;
+use solar_interface::{diagnostics::codes, Ident, Span};
+use std::ops::ControlFlow;
+
+/// Checks the `type(T)` expressions in the given function.
+pub(super) fn check<'gcx>(gcx: Gcx<'gcx>, func: &'gcx hir::Function<'gcx>) {
+    let _ = TypeMembersChecker { gcx }.visit_function(func);
+}
+
+/// Checks the `type(T)` expressions in the type and initializer of the given variable.
+pub(super) fn check_var<'gcx>(gcx: Gcx<'gcx>, var: &'gcx hir::Variable<'gcx>) {
+    let _ = TypeMembersChecker { gcx }.visit_var(var);
+}
+
+struct TypeMembersChecker<'gcx> {
+    gcx: Gcx<'gcx>,
+}
+
+impl<'gcx> Visit<'gcx> for TypeMembersChecker<'gcx> {
+    type BreakValue = Never;
+
+    fn hir(&self) -> &'gcx hir::Hir<'gcx> {
+        &self.gcx.hir
+    }
+
+    fn visit_expr(&mut self, expr: &'gcx hir::Expr<'gcx>) -> ControlFlow<Self::BreakValue> {
+        match expr.kind {
+            hir::ExprKind::Member(base, member) => {
+                if let hir::ExprKind::TypeCall(ref ty) = base.peel_parens().kind {
+                    // Don't visit `type(T)` again.
+                    self.check_type_call(ty, Some((base.span, member)));
+                    return ControlFlow::Continue(());
+                }
+            }
+            hir::ExprKind::TypeCall(ref ty) => self.check_type_call(ty, None),
+            _ => {}
+        }
+        self.walk_expr(expr)
+    }
+}
+
+impl TypeMembersChecker<'_> {
+    /// Checks `type(T)`, and the member accessed on it, if any.
+    ///
+    /// Errors if `type(T)` is not supported for `T`, which must be a contract, an interface, a
+    /// library, an enum, or an integer type; or if the accessed member does not exist.
+    fn check_type_call(&self, ty: &hir::Type<'_>, member: Option<(Span, Ident)>) {
+        let ty_span = ty.span;
+        let ty = self.gcx.type_of_hir_ty(ty);
+        if ty.has_error().is_err() {
+            return;
+        }
+        let members = self.gcx.members_of(ty.make_meta(self.gcx));
+        if members.is_empty() {
+            self.gcx
+                .dcx()
+                .err("invalid type argument for `type`")
+                .code(codes::INVALID_TYPE_ARGUMENT)
+                .span(ty_span)
+                .help("`type` is only supported for contract, enum, and integer types")
+                .emit();
+            return;
+        }
+
+        let Some((span, member)) = member else { return };
+        if members.iter().any(|m| m.name == member.name) {
+            return;
+        }
+        let snippet = self.gcx.sess.source_map().span_to_snippet(span).ok();
+        let msg = match &snippet {
+            Some(snippet) => format!("member `{member}` not found in `{snippet}`"),
+            None => format!("member `{member}` not found"),
+        };
+        let names = members.iter().map(|m| format!("`{}`", m.name)).collect::<Vec<_>>();
+        let help = format!("the available members are {}", names.join(", "));
+        self.gcx.dcx().err(msg).code(codes::MEMBER_NOT_FOUND).span(member.span).help(help).emit();
+    }
+}
@@ -0,0 +1,6 @@
+function f(uint256 a, uint256 b) pure {
+    uint256[2] memory x = [a, b];
+    uint256[3] memory y = [a, , b]; //~ ERROR: array expression components cannot be empty
+    uint256[3] memory z = [a, b, ]; //~ ERROR: array expression components cannot be empty
+    (uint256 c, , uint256 d) = (a, , b); //~ ERROR: tuple component cannot be empty
+}
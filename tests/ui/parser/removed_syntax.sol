@@ -0,0 +1,7 @@
+function f(uint256 a) {
+    var x = a; //~ ERROR: `var` declarations were removed in version 0.5.0
+    var (y, , z) = (a, a, a); //~ ERROR: `var` declarations were removed in version 0.5.0
+    for (var i = 0; i < a; i++) {} //~ ERROR: `var` declarations were removed in version 0.5.0
+    uint256 t = 1 years; //~ ERROR: `years` units were removed in version 0.5.0
+    throw; //~ ERROR: `throw` statements were removed in version 0.5.0
+}
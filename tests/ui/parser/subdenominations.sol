@@ -0,0 +1,8 @@
+function f() {
+    uint256 a = 1 ether;
+    uint256 b = 2.5 gwei;
+    uint256 c = 5 days seconds; //~ ERROR: literals can only have one sub-denomination
+    uint256 d = 1 ether ether; //~ ERROR: literals can only have one sub-denomination
+    string memory e = "str" ether; //~ ERROR: sub-denominations are only allowed on number and rational literals
+    bool g = true days; //~ ERROR: sub-denominations are only allowed on number and rational literals
+}
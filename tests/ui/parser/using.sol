@@ -11,3 +11,8 @@ interface I2 {
     using L for int; //~ ERROR: the `using for` directive is not allowed inside interfaces
     function g() external;
 }
+
+type T is uint;
+function eq(T, T) pure returns (bool) {}
+using {eq as ==} for T global;
+using {eq as !=} for T; //~ ERROR: user-defined operators can only be defined in a global `using for` directive
@@ -0,0 +1,12 @@
+function f(bool a, uint256 b, bool[] memory c) pure {
+    bool x;
+    x = a == true; //~ WARN: comparison to a boolean literal is redundant
+    x = false != a; //~ WARN: comparison to a boolean literal is redundant
+    x = c[b] == false; //~ WARN: comparison to a boolean literal is redundant
+    x = (b > 1) != (true); //~ WARN: comparison to a boolean literal is redundant
+    x = (a || x) == false; //~ WARN: comparison to a boolean literal is redundant
+
+    x = a == x;
+    x = true == false;
+    x = a && true;
+}
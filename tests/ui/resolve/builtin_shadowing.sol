@@ -0,0 +1,19 @@
+contract C {
+    uint256 block; //~ WARN: `block` shadows a builtin global
+
+    function now() public {} //~ WARN: `now` shadows a builtin global
+
+    function f(address msg) public pure returns (uint256) { //~ WARN: `msg` shadows a builtin global
+        uint256 tx = 1; //~ WARN: `tx` shadows a builtin global
+        uint256 blocks = tx;
+        return blocks;
+    }
+
+    function g(bytes32 keccak256) public pure returns (bytes32) { //~ WARN: `keccak256` shadows a builtin global
+        return keccak256;
+    }
+}
+
+struct abi { //~ WARN: `abi` shadows a builtin global
+    uint256 x;
+}
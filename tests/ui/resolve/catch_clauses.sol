@@ -0,0 +1,23 @@
+interface I {
+    function f() external returns (uint256);
+}
+
+contract C {
+    function g(I i) public {
+        try i.f() returns (uint256 x) {
+            x;
+        } catch Error(string memory reason) {
+            reason;
+        } catch Panic(uint256 code) {
+            code;
+        } catch (bytes memory data) {
+            data;
+        }
+
+        try i.f() {} catch {}
+
+        try i.f() {} catch Failure(bytes memory data) { //~ ERROR: invalid catch clause name
+            data;
+        }
+    }
+}
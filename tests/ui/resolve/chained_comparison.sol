@@ -0,0 +1,10 @@
+function f(uint256 a, uint256 b, uint256 c) pure {
+    bool x;
+    x = a < b < c; //~ WARN: comparison operators cannot be chained
+    x = a <= b == true; //~ WARN: comparison operators cannot be chained
+    //~^ WARN: comparison to a boolean literal is redundant
+
+    x = a < b && b < c;
+    x = (a < b) == (b < c);
+    x = a + b < c;
+}
@@ -0,0 +1,20 @@
+function f(uint256 a, uint256 b, uint256 c) pure {
+    bool x;
+    // solar-disable-next-line 0501
+    x = a < b < c;
+    x = a < b < c; //~ WARN: comparison operators cannot be chained
+    x = a < b < c; // solar-disable-line
+
+    // solar-disable-next-line 0001, 0501
+    x = a <= b == true; //~ WARN: comparison to a boolean literal is redundant
+    x = a <= b == true; //~ WARN: comparison operators cannot be chained
+    //~^ WARN: comparison to a boolean literal is redundant
+
+    // Lints reported during type checking.
+    // solar-disable-next-line 0505
+    x = a < 0;
+    x = a >= 0; // solar-disable-line 0505
+
+    x = a < b && b < c; // solar-disable-line 0501
+    //~^ WARN: unused suppression comment
+}
@@ -0,0 +1,26 @@
+function f(bool a, bool b, uint256 x, uint256 y) pure {
+    bool c;
+    if ((x < 1) & (y > 2)) {} //~ WARN: bitwise `&` used as a logical operator
+    c = (x == 1) | !a; //~ WARN: bitwise `|` used as a logical operator
+    if (a & b) {} //~ WARN: bitwise `&` used as a logical operator
+    while (a | true) {} //~ WARN: bitwise `|` used as a logical operator
+    c = a && (a & b); //~ WARN: bitwise `&` used as a logical operator
+    c = !(a | b) ? a : b; //~ WARN: bitwise `|` used as a logical operator
+
+    if (a = b) {} //~ WARN: assignment used as a condition
+    while (c = x < y) {} //~ WARN: assignment used as a condition
+    c = !(c = a); //~ WARN: assignment used as a condition
+    c = a || (c = b); //~ WARN: assignment used as a condition
+
+    // Bitwise operations on numbers are not reported.
+    x = x & 0xff;
+    x = x | y;
+    c = (x & y) != 0;
+    if ((x & 1) == 0) {}
+    if (x & y > 0) {}
+    x |= y;
+
+    // Assignments in extra parentheses are intended.
+    if ((c = a)) {}
+    while ((c = b)) {}
+}
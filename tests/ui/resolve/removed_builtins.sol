@@ -0,0 +1,4 @@
+function f(bytes memory b, address payable a) {
+    sha3(b); //~ ERROR: unresolved symbol `sha3`
+    suicide(a); //~ ERROR: unresolved symbol `suicide`
+}
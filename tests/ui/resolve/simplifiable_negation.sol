@@ -0,0 +1,15 @@
+function f(bool a, uint256 b, uint256 c) pure {
+    bool x;
+    x = !!a; //~ WARN: negation can be simplified
+    x = !(b == c); //~ WARN: negation can be simplified
+    x = !(b < c); //~ WARN: negation can be simplified
+    x = !((b >= c)); //~ WARN: negation can be simplified
+    x = !(b == c && b < 1); //~ WARN: negation can be simplified
+    x = !(b != c || !a); //~ WARN: negation can be simplified
+    x = !((b == 1 && b == 2) || c > 3); //~ WARN: negation can be simplified
+
+    x = !a;
+    x = !(a && x);
+    x = !(b == c || a);
+    x = !(b + c > 0 ? a : x);
+}
@@ -0,0 +1,16 @@
+function g() returns (uint256) {}
+
+function f(uint256 x, uint256[] memory a) {
+    uint256 y;
+    y = x ** 2; //~ WARN: exponentiation with a small constant exponent is more expensive than multiplication
+    y = a[0] ** (3); //~ WARN: exponentiation with a small constant exponent is more expensive than multiplication
+    y = (x + 1) ** 2; //~ WARN: exponentiation with a small constant exponent is more expensive than multiplication
+
+    y = g() ** 2;
+    y = a[g()] ** 2;
+    y = (x = 1) ** 2;
+    y = x ** 1;
+    y = x ** 4;
+    y = x ** y;
+    y = 10 ** 2;
+}
@@ -0,0 +1,22 @@
+contract C {
+    constructor() payable {}
+}
+
+contract D {
+    function f(address payable a, bytes32 s) public {
+        new C{salt: s}();
+        new C{value: 1, salt: s}();
+        a.call{value: 1, gas: 2}("");
+        this.g{gas: 1}();
+
+        a.call{salt: s}(""); //~ ERROR: call option `salt` can only be used with `new`
+        new C{gas: 1}(); //~ ERROR: call option `gas` cannot be used with `new`
+        a.call{value: 1, fee: 2}(""); //~ ERROR: unknown call option `fee`
+        a.call{
+            value: 1,
+            value: 2 //~ ERROR: duplicate call option `value`
+        }("");
+    }
+
+    function g() public {}
+}
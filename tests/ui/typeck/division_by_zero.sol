@@ -0,0 +1,17 @@
+function f(uint256 x, uint256 y) pure returns (uint256 z) {
+    z = x / 0; //~ ERROR: division by zero
+    z = x / (1 - 1); //~ ERROR: division by zero
+    z = x % 0; //~ ERROR: modulo by zero
+    z = x % 0x0; //~ ERROR: modulo by zero
+    z = x / 0 ether; //~ ERROR: division by zero
+    z = x / (10 % 3 - 1); //~ ERROR: division by zero
+    z /= 2 - 2; //~ ERROR: division by zero
+    z %= (0); //~ ERROR: modulo by zero
+
+    z = x / y;
+    z = x % (y - y);
+    z = x / (1 - 2);
+    z = x / 1 wei;
+    z = 0 / x;
+    z = x / (true ? 1 : 0);
+}
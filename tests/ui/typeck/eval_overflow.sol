@@ -0,0 +1,10 @@
+uint constant big = 2**255;
+
+contract C {
+    function f(uint[big * 2] memory) public {} //~ ERROR: evaluation of constant value failed
+    function g(uint[2 ** 256] memory) public {} //~ ERROR: evaluation of constant value failed
+    function h(uint[1 - 2 + 3] memory) public {} //~ ERROR: evaluation of constant value failed
+
+    function i(uint[big * 1] memory) public {}
+    function j(uint[2 ** 255 + (2 ** 255 - 1)] memory) public {}
+}
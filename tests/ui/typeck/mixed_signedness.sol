@@ -0,0 +1,23 @@
+contract C {
+    function f(int256 x, uint256 y, uint8 small, int16 z) public pure {
+        bool b;
+        int256 r;
+        b = x < y; //~ WARN: comparison of signed and unsigned integers
+        b = (y) == x; //~ WARN: comparison of signed and unsigned integers
+        r = x + small; //~ WARN: arithmetic operation on signed and unsigned integers
+        r += small; //~ WARN: arithmetic operation on signed and unsigned integers
+
+        // Explicit conversions.
+        b = x < int256(y);
+        b = uint256(x) < y;
+        // Same signedness.
+        b = x < z;
+        b = y > small;
+        // Only operands of known integer types are checked.
+        b = x < 1;
+        b = x + 1 < y;
+        // Only comparisons and arithmetic operations are checked.
+        r = x ** y;
+        r = x >> y;
+    }
+}
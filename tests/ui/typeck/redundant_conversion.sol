@@ -0,0 +1,21 @@
+contract C {
+    uint256 total;
+
+    function f(uint256 x, uint8 small, int256 s, address a, bool flag) public view {
+        uint256 r;
+        r = uint256(x); //~ WARN: redundant conversion to `uint256`
+        r = uint(total) + 1; //~ WARN: redundant conversion to `uint256`
+        r = uint256((x)); //~ WARN: redundant conversion to `uint256`
+        address b = address(a); //~ WARN: redundant conversion to `address`
+        flag = bool(flag); //~ WARN: redundant conversion to `bool`
+
+        // Widening, narrowing, and sign conversions.
+        r = uint256(small);
+        small = uint8(x);
+        s = int256(x);
+        // Not a variable.
+        b = address(this);
+        r = uint256(1);
+        r = uint256(x + 1);
+    }
+}
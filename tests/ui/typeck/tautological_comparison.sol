@@ -0,0 +1,26 @@
+contract C {
+    uint8 small;
+
+    function f(uint256 x, int8 y, uint16 z) public view {
+        bool b;
+        b = x < 0; //~ WARN: comparison is always false
+        b = x >= 0; //~ WARN: comparison is always true
+        b = 0 > x; //~ WARN: comparison is always false
+        b = x <= type(uint256).max; //~ WARN: comparison is always true
+        b = (small) > type(uint8).max; //~ WARN: comparison is always false
+        b = y < -128; //~ WARN: comparison is always false
+        b = y != 200; //~ WARN: comparison is always true
+        b = z == 65536; //~ WARN: comparison is always false
+
+        // Not constant.
+        b = x > 0;
+        b = x == type(uint256).max;
+        b = y <= -1;
+        b = y < type(int8).max;
+        b = z < type(uint8).max;
+        b = x < z;
+        // Only variables of known integer types are checked.
+        b = uint8(x) < 256;
+        b = x + 1 < 0;
+    }
+}
@@ -0,0 +1,25 @@
+contract C {
+    uint256 s;
+    uint256[] arr;
+
+    function t() internal pure returns (uint256, uint256, uint256) {
+        return (1, 2, 3);
+    }
+
+    function f(uint256 a, uint256 b) public {
+        (a, b) = (b, a);
+        (, a) = (b, a);
+        (a, , b) = t();
+        (s, arr[0], , ) = (1, 2, 3, 4);
+        ((a, ), b) = ((1, 2), 3);
+        (a) = b;
+        (a) += b;
+
+        (a, , b) + a; //~ ERROR: tuple component cannot be empty
+        (a, b) = (a, , b); //~ ERROR: tuple component cannot be empty
+        arr[(a, , b)] = 1; //~ ERROR: tuple component cannot be empty
+        (a, , b) += t(); //~ ERROR: compound assignment `+=` is not allowed for tuples
+        (a, 1) = (1, 2); //~ ERROR: tuple component is not assignable
+        (a, (b, a + b)) = (1, (2, 3)); //~ ERROR: tuple component is not assignable
+    }
+}
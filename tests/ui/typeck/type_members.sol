@@ -0,0 +1,32 @@
+interface I {}
+
+contract C {}
+
+library L {}
+
+enum E { X, Y }
+
+contract D {
+    function f() public pure {
+        type(C).creationCode;
+        type(C).runtimeCode;
+        type(C).name;
+        type(L).name;
+        type(I).interfaceId;
+        type(I).name;
+        type(uint8).min;
+        type(int256).max;
+        type(E).min;
+        (type(E)).max;
+
+        type(uint).creationCode; //~ ERROR: member `creationCode` not found in `type(uint)`
+        type(C).interfaceId; //~ ERROR: member `interfaceId` not found in `type(C)`
+        type(I).creationCode; //~ ERROR: member `creationCode` not found in `type(I)`
+        type(C).min; //~ ERROR: member `min` not found in `type(C)`
+        type(E).name; //~ ERROR: member `name` not found in `type(E)`
+
+        type(bool); //~ ERROR: invalid type argument for `type`
+        type(bool).max; //~ ERROR: invalid type argument for `type`
+        type(uint[]).min; //~ ERROR: invalid type argument for `type`
+    }
+}
@@ -0,0 +1,10 @@
+contract C {}
+
+uint8 constant MAX = type(uint8).max;
+uint8 constant FOO = type(uint8).foo; //~ ERROR: member `foo` not found in `type(uint8)`
+
+contract D {
+    string name = type(C).name;
+    uint x = type(C).foo; //~ ERROR: member `foo` not found in `type(C)`
+    bool y = type(bool).max; //~ ERROR: invalid type argument for `type`
+}